@@ -28,6 +28,10 @@ pub fn parse_claude_stream_json_with_tools(
             }
         }
         "assistant" => {
+            let parent_tool_id = json
+                .get("parent_tool_use_id")
+                .and_then(|p| p.as_str())
+                .map(String::from);
             if let Some(message) = json.get("message") {
                 if let Some(content) = message.get("content").and_then(|c| c.as_array()) {
                     let mut texts: Vec<String> = Vec::new();
@@ -52,6 +56,7 @@ pub fn parse_claude_stream_json_with_tools(
                                 tool_id: tool_id.clone(),
                                 tool_name: tool_name.to_string(),
                                 input: tool_input,
+                                parent_tool_id: parent_tool_id.clone(),
                             });
 
                             texts.push(format!("\x1b[33m[Using tool: {}]\x1b[0m", tool_name));
@@ -8,7 +8,9 @@ use crate::agents::format_parsers::parse_agent_json_output_with_tools;
 use crate::agents::log_collector::LogCollector;
 use crate::agents::path_resolver::CliPathResolver;
 use crate::agents::rate_limiter::{RateLimitDetector, RateLimitInfo};
+use crate::agents::transcript_capture::{TranscriptCapture, TranscriptCaptures};
 use crate::agents::{StreamingParser, SubagentEvent, SubagentTree};
+use crate::file_storage::recordings::{Recorder, RecordingMeta};
 use crate::models::{AgentType, LogEntry, LogLevel};
 use crate::utils::lock_mutex_recover;
 use anyhow::{anyhow, Result};
@@ -73,6 +75,10 @@ pub struct AgentManager {
     pty_ids: Arc<Mutex<HashMap<String, String>>>,
     /// Raw PTY output history per agent (ring buffer for replay)
     pty_history: Arc<Mutex<HashMap<String, RingBuffer>>>,
+    /// Unbounded raw output + tool calls per agent, taken for transcript persistence
+    transcripts: TranscriptCaptures,
+    /// Asciicast recorders of agents' PTY output
+    recordings: Arc<Mutex<HashMap<String, Recorder>>>,
     /// Event sender for subagent events
     subagent_tx: Option<mpsc::UnboundedSender<SubagentEvent>>,
     /// Trace parsers per agent
@@ -91,6 +97,8 @@ impl AgentManager {
             rate_limit_detector: RateLimitDetector::new(),
             pty_ids: Arc::new(Mutex::new(HashMap::new())),
             pty_history: Arc::new(Mutex::new(HashMap::new())),
+            transcripts: Arc::new(Mutex::new(HashMap::new())),
+            recordings: Arc::new(Mutex::new(HashMap::new())),
             subagent_tx: None,
             parsers: Arc::new(Mutex::new(HashMap::new())),
             subagent_trees: Arc::new(Mutex::new(HashMap::new())),
@@ -233,6 +241,29 @@ impl AgentManager {
             .unwrap_or_default()
     }
 
+    /// Take the captured raw output, tool calls and subagent events for an agent
+    ///
+    /// Removes the capture, so call this once the agent has exited.
+    pub fn take_transcript(&self, agent_id: &str) -> Option<TranscriptCapture> {
+        let mut capture = lock_mutex_recover(&self.transcripts).remove(agent_id)?;
+        if let Some(tree) = lock_mutex_recover(&self.subagent_trees).get(agent_id) {
+            capture.subagent_events = tree.events.clone();
+        }
+        Some(capture)
+    }
+
+    /// Record an agent's PTY output; start before spawning the agent to
+    /// capture all of it
+    pub fn start_recording(&self, agent_id: &str, recorder: Recorder) {
+        lock_mutex_recover(&self.recordings).insert(agent_id.to_string(), recorder);
+    }
+
+    /// Finish an agent's recording, if it has one
+    pub fn finish_recording(&self, agent_id: &str) -> Option<RecordingMeta> {
+        let mut recorder = lock_mutex_recover(&self.recordings).remove(agent_id)?;
+        recorder.finish()
+    }
+
     /// Clear PTY tracking data for an agent
     pub fn clear_pty_data(&self, agent_id: &str) {
         {
@@ -243,6 +274,8 @@ impl AgentManager {
             let mut pty_history = lock_mutex_recover(&self.pty_history);
             pty_history.remove(agent_id);
         }
+        lock_mutex_recover(&self.transcripts).remove(agent_id);
+        lock_mutex_recover(&self.recordings).remove(agent_id);
     }
 
     /// Register a PTY association for an agent
@@ -274,6 +307,8 @@ impl AgentManager {
             let mut pty_ids = lock_mutex_recover(&self.pty_ids);
             pty_ids.remove(agent_id)
         };
+        // The agent's terminal is gone, and so is anything left to record
+        self.finish_recording(agent_id);
         // Keep history for a while after exit (don't clear immediately)
         // The history can be cleared explicitly via clear_pty_data
         if let Some(id) = pty_id {
@@ -612,6 +647,8 @@ impl AgentManager {
             let agent_logs = self.log_collector.agent_logs.clone();
             let parsers = self.parsers.clone();
             let subagent_trees = self.subagent_trees.clone();
+            let transcripts = self.transcripts.clone();
+            let recordings = self.recordings.clone();
             let pty_history = if is_pty_mode {
                 Some(self.pty_history.clone())
             } else {
@@ -643,6 +680,28 @@ impl AgentManager {
                             let parsed = parse_agent_json_output_with_tools(&line);
                             let display_text = parsed.display_text.clone();
 
+                            // Record the unparsed line and tool activity for the transcript
+                            {
+                                let mut captures = lock_mutex_recover(&transcripts);
+                                let capture = captures.entry(agent_id_clone.clone()).or_default();
+                                capture.push_line(&line, false);
+                                for tool_call in &parsed.tool_calls {
+                                    capture.start_tool_call(
+                                        tool_call.tool_id.clone(),
+                                        tool_call.tool_name.clone(),
+                                        tool_call.input.clone(),
+                                        tool_call.parent_tool_id.clone(),
+                                    );
+                                }
+                                for tool_result in &parsed.tool_results {
+                                    capture.complete_tool_call(
+                                        &tool_result.tool_id,
+                                        tool_result.output.clone(),
+                                        tool_result.is_error,
+                                    );
+                                }
+                            }
+
                             // Emit tool call events
                             for tool_call in parsed.tool_calls {
                                 if let Some(ref tx) = tool_call_tx {
@@ -681,6 +740,12 @@ impl AgentManager {
                                 hist.entry(agent_id_clone.clone())
                                     .or_insert_with(|| RingBuffer::new(1024 * 1024))
                                     .write(text_with_newline.as_bytes());
+                                drop(hist);
+                                if let Some(recorder) =
+                                    lock_mutex_recover(&recordings).get_mut(&agent_id_clone)
+                                {
+                                    recorder.output(text_with_newline.as_bytes());
+                                }
 
                                 // Also send via PTY data channel for real-time updates
                                 if let Some(ref tx) = pty_data_tx {
@@ -772,6 +837,8 @@ impl AgentManager {
                 None
             };
             let rate_limit_detector = RateLimitDetector::new();
+            let transcripts = self.transcripts.clone();
+            let recordings = self.recordings.clone();
             let agent_id_clone = agent_id.to_string();
             log::debug!(
                 "[AgentManager] Spawning stderr reader thread for agent {}",
@@ -788,6 +855,11 @@ impl AgentManager {
                         Ok(line) => {
                             log::warn!("[Agent {}] stderr: {}", agent_id_clone, line);
 
+                            lock_mutex_recover(&transcripts)
+                                .entry(agent_id_clone.clone())
+                                .or_default()
+                                .push_line(&line, true);
+
                             // For PTY mode, also write to pty_history (stderr in red)
                             if let Some(ref pty_hist) = pty_history {
                                 let line_with_color = format!("\x1b[31m{}\x1b[0m\r\n", line);
@@ -795,6 +867,12 @@ impl AgentManager {
                                 hist.entry(agent_id_clone.clone())
                                     .or_insert_with(|| RingBuffer::new(1024 * 1024))
                                     .write(line_with_color.as_bytes());
+                                drop(hist);
+                                if let Some(recorder) =
+                                    lock_mutex_recover(&recordings).get_mut(&agent_id_clone)
+                                {
+                                    recorder.output(line_with_color.as_bytes());
+                                }
 
                                 if let Some(ref tx) = pty_data_tx {
                                     let _ = tx.send(AgentPtyDataEvent {
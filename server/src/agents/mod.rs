@@ -7,14 +7,18 @@ pub mod log_collector;
 pub mod manager;
 pub mod model_cache;
 pub mod models;
+pub mod orphans;
 pub mod output_parser;
 pub mod path_resolver;
 // Agent Plugin Trait Definition
 pub mod plugin;
 pub mod providers;
+pub mod rate_limit_ledger;
 pub mod rate_limiter;
 pub mod registry;
+pub mod trace_export;
 pub mod trace_parser;
+pub mod transcript_capture;
 
 // Re-export for convenience
 pub use config::{ConfigField, ConfigFieldType, PluginConfigSchema};
@@ -26,6 +30,7 @@ pub use plugin::AgentPlugin;
 pub use registry::AgentRegistry;
 // Note: CliPathResolver is used internally by providers, not re-exported
 // Note: RateLimitDetector, RateLimitInfo, RateLimitType accessible via full path (agents::rate_limiter::*)
+// Note: The shared RateLimitLedger is accessible via agents::rate_limit_ledger::global()
 // Note: Fallback orchestration is handled by ralph_loop::FallbackOrchestrator
 pub use model_cache::ModelCache;
 pub use models::ModelInfo;
@@ -0,0 +1,503 @@
+//! Agents left running by a crashed server
+//!
+//! While a Ralph loop iteration runs, its agent is recorded in
+//! `.ralph-ui/agents/` with its PID. If the server dies, the agent keeps
+//! running and editing files. On the next start, each recorded PID is checked
+//! against the live process (command line, working directory and start time)
+//! and the orphan is either terminated or adopted: monitored until it exits,
+//! after which the PRD is synced from its worktree and the iteration recorded.
+
+use crate::file_storage::agents::{self as agent_storage, AgentStateFile, LoopAgentInfo};
+use crate::file_storage::iterations as iteration_storage;
+use crate::ralph_loop::{sync_prd_files, IterationOutcome, IterationRecord};
+use crate::shutdown::ShutdownState;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::{
+    Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System, UpdateKind,
+};
+
+/// How far a process's start time may be from the recorded spawn time
+const START_TIME_TOLERANCE_SECS: i64 = 60;
+
+/// How long a terminated agent gets to exit before it is killed
+const TERMINATE_GRACE: Duration = Duration::from_secs(5);
+
+/// How often adopted agents are checked
+const ADOPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What to do with agents a crashed server left running
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrphanPolicy {
+    /// Let the agent finish, then reconcile the PRD
+    #[default]
+    Adopt,
+    /// Stop the agent and record the iteration as interrupted
+    Terminate,
+}
+
+impl std::fmt::Display for OrphanPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrphanPolicy::Adopt => write!(f, "adopt"),
+            OrphanPolicy::Terminate => write!(f, "terminate"),
+        }
+    }
+}
+
+impl std::str::FromStr for OrphanPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "adopt" => Ok(OrphanPolicy::Adopt),
+            "terminate" => Ok(OrphanPolicy::Terminate),
+            _ => Err(format!(
+                "Unknown orphan policy: {} (expected adopt or terminate)",
+                s
+            )),
+        }
+    }
+}
+
+/// What identifies a running process
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessIdentity {
+    pub program: String,
+    pub command_digest: String,
+    pub cwd: Option<PathBuf>,
+    /// Seconds since the epoch
+    pub start_time: u64,
+}
+
+/// An agent process that outlived the server that spawned it
+#[derive(Debug, Clone)]
+pub struct OrphanedAgent {
+    pub project_path: PathBuf,
+    pub agent_id: String,
+    pub pid: u32,
+    pub worktree_path: String,
+    pub info: LoopAgentInfo,
+}
+
+/// Hex-encoded SHA-256 of a command line
+fn command_digest(cmd: &[OsString]) -> String {
+    let mut hasher = Sha256::new();
+    for arg in cmd {
+        hasher.update(arg.as_encoded_bytes());
+        hasher.update([0]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn refresh_process(system: &mut System, pid: Pid) {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::new()
+            .with_cmd(UpdateKind::Always)
+            .with_cwd(UpdateKind::Always),
+    );
+}
+
+/// Identity of a live process (None if it isn't running)
+pub fn process_identity(pid: u32) -> Option<ProcessIdentity> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    refresh_process(&mut system, pid);
+    let process = system.process(pid)?;
+    if matches!(
+        process.status(),
+        ProcessStatus::Zombie | ProcessStatus::Dead
+    ) {
+        return None;
+    }
+    Some(ProcessIdentity {
+        program: process
+            .cmd()
+            .first()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        command_digest: command_digest(process.cmd()),
+        cwd: process.cwd().map(Path::to_path_buf),
+        start_time: process.start_time(),
+    })
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Whether a live process is the recorded agent, rather than an unrelated
+/// process that reused its PID
+fn is_recorded_agent(identity: &ProcessIdentity, record: &AgentStateFile) -> bool {
+    let Some(info) = &record.ralph_loop else {
+        return false;
+    };
+    let started_near_spawn = (identity.start_time as i64 - info.started_at.timestamp()).abs()
+        <= START_TIME_TOLERANCE_SECS;
+    let cwd_matches = identity.cwd.as_deref().map_or(false, |cwd| {
+        same_path(cwd, Path::new(&record.worktree_path))
+    });
+
+    identity.command_digest == info.command_digest && cwd_matches && started_near_spawn
+}
+
+fn is_alive(system: &mut System, pid: Pid) -> bool {
+    refresh_process(system, pid);
+    system.process(pid).map_or(false, |p| {
+        !matches!(p.status(), ProcessStatus::Zombie | ProcessStatus::Dead)
+    })
+}
+
+/// SIGTERM the process, then SIGKILL it if it's still running after the grace period
+fn terminate_process(pid: u32) {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    refresh_process(&mut system, pid);
+    let Some(process) = system.process(pid) else {
+        return;
+    };
+    if process.kill_with(Signal::Term).is_none() {
+        process.kill();
+    }
+
+    let deadline = std::time::Instant::now() + TERMINATE_GRACE;
+    while std::time::Instant::now() < deadline {
+        if !is_alive(&mut system, pid) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+    if let Some(process) = system.process(pid) {
+        log::warn!("[Orphans] Agent {} ignored SIGTERM, killing it", pid);
+        process.kill();
+    }
+}
+
+/// Reconcile after an orphan has exited (or been stopped): sync the PRD from
+/// its worktree, record the iteration and drop the agent record. The loop
+/// that spawned it died with the old server, so its execution state is
+/// cleared too.
+fn finish(orphan: &OrphanedAgent, outcome: IterationOutcome, error: Option<&str>) {
+    let project = orphan.project_path.as_path();
+    let worktree = Path::new(&orphan.worktree_path);
+    let info = &orphan.info;
+
+    if !same_path(worktree, project) {
+        if let Err(e) = sync_prd_files(worktree, project, &info.prd_name) {
+            log::warn!("[Orphans] Failed to sync PRD {}: {}", info.prd_name, e);
+        }
+    }
+
+    let completed_at = Utc::now();
+    let record = IterationRecord {
+        id: orphan.agent_id.clone(),
+        execution_id: info.execution_id.clone(),
+        iteration: info.iteration,
+        outcome: outcome.clone(),
+        duration_secs: (completed_at - info.started_at).num_milliseconds().max(0) as f64 / 1000.0,
+        agent_type: info.agent_type,
+        rate_limit_encountered: false,
+        error_message: error.map(String::from),
+        started_at: info.started_at.to_rfc3339(),
+        completed_at: Some(completed_at.to_rfc3339()),
+    };
+    if let Err(e) = iteration_storage::upsert_iteration(project, &record) {
+        log::warn!(
+            "[Orphans] Failed to record iteration {} of {}: {}",
+            info.iteration,
+            info.execution_id,
+            e
+        );
+    }
+    if let Err(e) = iteration_storage::delete_execution_state(project, &info.execution_id) {
+        log::warn!(
+            "[Orphans] Failed to clear execution state of {}: {}",
+            info.execution_id,
+            e
+        );
+    }
+    if let Err(e) = agent_storage::delete_agent_files(project, &orphan.agent_id) {
+        log::warn!(
+            "[Orphans] Failed to remove agent record {}: {}",
+            orphan.agent_id,
+            e
+        );
+    }
+
+    log::info!(
+        "[Orphans] Iteration {} of execution {} recorded as {}",
+        info.iteration,
+        info.execution_id,
+        outcome
+    );
+}
+
+/// Check the project's recorded loop agents. Agents that are still running
+/// are terminated or returned for adoption, depending on `policy`; records of
+/// agents that are gone are reconciled and removed.
+pub fn recover_orphaned_agents(project_path: &Path, policy: OrphanPolicy) -> Vec<OrphanedAgent> {
+    let records = match agent_storage::list_loop_agents(project_path) {
+        Ok(records) => records,
+        Err(e) => {
+            log::warn!(
+                "[Orphans] Failed to read agents of {}: {}",
+                project_path.display(),
+                e
+            );
+            return Vec::new();
+        }
+    };
+
+    let mut adopted = Vec::new();
+    for record in records {
+        let (Some(pid), Some(info)) = (record.process_id, record.ralph_loop.clone()) else {
+            continue;
+        };
+        let orphan = OrphanedAgent {
+            project_path: project_path.to_path_buf(),
+            agent_id: record.id.clone(),
+            pid,
+            worktree_path: record.worktree_path.clone(),
+            info,
+        };
+
+        let running = pid != std::process::id()
+            && process_identity(pid)
+                .map_or(false, |identity| is_recorded_agent(&identity, &record));
+        if !running {
+            log::info!(
+                "[Orphans] Agent {} (PID {}) exited while the server was down",
+                orphan.agent_id,
+                pid
+            );
+            finish(
+                &orphan,
+                IterationOutcome::Interrupted,
+                Some("Agent exited while the server was down"),
+            );
+            continue;
+        }
+
+        match policy {
+            OrphanPolicy::Terminate => {
+                log::warn!(
+                    "[Orphans] Terminating agent {} (PID {}) left running by a previous server",
+                    orphan.agent_id,
+                    pid
+                );
+                terminate_process(pid);
+                finish(
+                    &orphan,
+                    IterationOutcome::Interrupted,
+                    Some("Agent terminated after a server restart"),
+                );
+            }
+            OrphanPolicy::Adopt => {
+                log::info!(
+                    "[Orphans] Adopting agent {} (PID {}) left running by a previous server",
+                    orphan.agent_id,
+                    pid
+                );
+                adopted.push(orphan);
+            }
+        }
+    }
+    adopted
+}
+
+/// Monitor adopted agents until each exits, then reconcile it. On shutdown
+/// the remaining records are kept, so the next start picks them up again.
+pub async fn monitor_adopted_agents(mut agents: Vec<OrphanedAgent>, shutdown: ShutdownState) {
+    let mut system = System::new();
+    while !agents.is_empty() {
+        if shutdown.is_shutdown_requested() {
+            return;
+        }
+        agents.retain(|orphan| {
+            if is_alive(&mut system, Pid::from_u32(orphan.pid)) {
+                return true;
+            }
+            log::info!(
+                "[Orphans] Adopted agent {} (PID {}) finished",
+                orphan.agent_id,
+                orphan.pid
+            );
+            finish(orphan, IterationOutcome::Recovered, None);
+            false
+        });
+        tokio::time::sleep(ADOPT_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Agent, AgentStatus, AgentType};
+    use tempfile::TempDir;
+
+    fn save_record(project: &Path, agent_id: &str, pid: u32, worktree: &Path, digest: &str) {
+        let agent = Agent {
+            id: agent_id.to_string(),
+            session_id: "exec-1".to_string(),
+            task_id: agent_id.to_string(),
+            status: AgentStatus::Implementing,
+            process_id: Some(pid),
+            worktree_path: worktree.to_string_lossy().to_string(),
+            branch: "main".to_string(),
+            iteration_count: 1,
+            tokens: 0,
+            cost: 0.0,
+            logs: Vec::new(),
+            subagents: Vec::new(),
+        };
+        let info = LoopAgentInfo {
+            execution_id: "exec-1".to_string(),
+            prd_name: "dark-mode".to_string(),
+            iteration: 1,
+            agent_type: AgentType::Claude,
+            program: "sleep".to_string(),
+            command_digest: digest.to_string(),
+            started_at: Utc::now(),
+        };
+        agent_storage::save_loop_agent(project, &agent, info).unwrap();
+    }
+
+    #[test]
+    fn test_orphan_policy_parse() {
+        assert_eq!("adopt".parse::<OrphanPolicy>(), Ok(OrphanPolicy::Adopt));
+        assert_eq!(
+            "Terminate".parse::<OrphanPolicy>(),
+            Ok(OrphanPolicy::Terminate)
+        );
+        assert!("ignore".parse::<OrphanPolicy>().is_err());
+        assert_eq!(OrphanPolicy::Terminate.to_string(), "terminate");
+    }
+
+    #[test]
+    fn test_is_recorded_agent() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path();
+        save_record(project, "agent-1", 1234, project, "digest");
+        let record = agent_storage::read_agent_state(project, "agent-1").unwrap();
+        let identity = ProcessIdentity {
+            program: "sleep".to_string(),
+            command_digest: "digest".to_string(),
+            cwd: Some(project.to_path_buf()),
+            start_time: Utc::now().timestamp() as u64,
+        };
+        assert!(is_recorded_agent(&identity, &record));
+
+        // A different command, directory or start time means the PID was reused
+        let other_command = ProcessIdentity {
+            command_digest: "other".to_string(),
+            ..identity.clone()
+        };
+        assert!(!is_recorded_agent(&other_command, &record));
+        let other_cwd = ProcessIdentity {
+            cwd: Some(PathBuf::from("/")),
+            ..identity.clone()
+        };
+        assert!(!is_recorded_agent(&other_cwd, &record));
+        let older = ProcessIdentity {
+            start_time: identity.start_time - 3600,
+            ..identity
+        };
+        assert!(!is_recorded_agent(&older, &record));
+    }
+
+    #[test]
+    fn test_recover_orphaned_agents() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path();
+        let worktree = project.join("worktree");
+        std::fs::create_dir_all(worktree.join(".ralph-ui/prds")).unwrap();
+        std::fs::write(
+            worktree.join(".ralph-ui/prds/dark-mode.json"),
+            "{\"done\":1}",
+        )
+        .unwrap();
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .current_dir(&worktree)
+            .spawn()
+            .unwrap();
+        let identity = process_identity(child.id()).unwrap();
+        save_record(
+            project,
+            "agent-live",
+            child.id(),
+            &worktree,
+            &identity.command_digest,
+        );
+
+        // Adopting leaves the agent running
+        let adopted = recover_orphaned_agents(project, OrphanPolicy::Adopt);
+        assert_eq!(adopted.len(), 1);
+        assert_eq!(adopted[0].pid, child.id());
+        assert!(child.try_wait().unwrap().is_none());
+
+        // Once it has exited, its record is reconciled: the PRD is synced and
+        // the iteration recorded
+        let _ = child.kill();
+        let _ = child.wait();
+        finish(&adopted[0], IterationOutcome::Recovered, None);
+        assert!(!agent_storage::agent_file_exists(project, "agent-live"));
+        let prd = std::fs::read_to_string(project.join(".ralph-ui/prds/dark-mode.json")).unwrap();
+        assert_eq!(prd, "{\"done\":1}");
+        let iterations =
+            iteration_storage::get_iterations_for_execution(project, "exec-1").unwrap();
+        assert_eq!(iterations[0].outcome, IterationOutcome::Recovered);
+
+        // Terminating stops it
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .current_dir(&worktree)
+            .spawn()
+            .unwrap();
+        let identity = process_identity(child.id()).unwrap();
+        save_record(
+            project,
+            "agent-term",
+            child.id(),
+            &worktree,
+            &identity.command_digest,
+        );
+        assert!(recover_orphaned_agents(project, OrphanPolicy::Terminate).is_empty());
+        assert!(child.wait().is_ok());
+        assert!(!agent_storage::agent_file_exists(project, "agent-term"));
+
+        // A PID that now belongs to another process is never touched
+        let mut other = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        save_record(
+            project,
+            "agent-reused",
+            other.id(),
+            &worktree,
+            "not-this-command",
+        );
+        assert!(recover_orphaned_agents(project, OrphanPolicy::Terminate).is_empty());
+        assert!(other.try_wait().unwrap().is_none());
+        assert!(!agent_storage::agent_file_exists(project, "agent-reused"));
+        let iterations =
+            iteration_storage::get_iterations_for_execution(project, "exec-1").unwrap();
+        assert_eq!(iterations[0].outcome, IterationOutcome::Interrupted);
+        let _ = other.kill();
+        let _ = other.wait();
+    }
+}
@@ -12,6 +12,8 @@ pub struct ParsedToolCall {
     pub tool_name: String,
     /// Tool input parameters
     pub input: Option<serde_json::Value>,
+    /// ID of the Task tool call whose subagent made this call (Claude's parent_tool_use_id)
+    pub parent_tool_id: Option<String>,
 }
 
 /// Parsed tool result from agent JSON output
@@ -54,6 +56,7 @@ mod tests {
             tool_id: "123".to_string(),
             tool_name: "Read".to_string(),
             input: Some(serde_json::json!({"path": "/test"})),
+            parent_tool_id: None,
         };
 
         assert_eq!(tool_call.tool_id, "123");
@@ -3,9 +3,24 @@
 use std::path::PathBuf;
 use std::process::Command;
 
+use crate::models::AgentType;
+
 pub struct CliPathResolver;
 
 impl CliPathResolver {
+    /// Resolve the CLI binary for any agent type
+    pub fn resolve(agent_type: AgentType) -> Option<PathBuf> {
+        match agent_type {
+            AgentType::Claude => Self::resolve_claude(),
+            AgentType::Opencode => Self::resolve_opencode(),
+            AgentType::Cursor => Self::resolve_cursor(),
+            AgentType::Codex => Self::resolve_codex(),
+            AgentType::Qwen => Self::resolve_qwen(),
+            AgentType::Droid => Self::resolve_droid(),
+            AgentType::Gemini => Self::resolve_gemini(),
+        }
+    }
+
     /// Resolve OpenCode binary path
     pub fn resolve_opencode() -> Option<PathBuf> {
         Self::resolve_cli(
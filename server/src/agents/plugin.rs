@@ -29,6 +29,15 @@ pub trait AgentPlugin: Send + Sync {
         PluginConfigSchema::default()
     }
 
+    /// Arguments for a cheap, side-effect free invocation that fails when the
+    /// CLI is not authenticated (e.g. `codex login status`).
+    ///
+    /// Returns None when the provider has no such command; preflight then
+    /// skips the auth check for this agent.
+    fn auth_check_args(&self) -> Option<Vec<String>> {
+        None
+    }
+
     /// Build the command to spawn the agent process
     fn build_command(&self, config: &AgentSpawnConfig) -> Result<Command>;
 
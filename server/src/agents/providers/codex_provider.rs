@@ -138,6 +138,11 @@ impl AgentPlugin for CodexProvider {
         self.try_list_models()
     }
 
+    fn auth_check_args(&self) -> Option<Vec<String>> {
+        // Exits non-zero when no credentials are stored
+        Some(vec!["login".to_string(), "status".to_string()])
+    }
+
     fn build_command(&self, config: &AgentSpawnConfig) -> Result<Command> {
         // Resolve Codex CLI path
         let codex_path = CliPathResolver::resolve_codex()
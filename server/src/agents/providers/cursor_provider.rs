@@ -37,6 +37,11 @@ impl AgentPlugin for CursorProvider {
         CliPathResolver::resolve_cursor().is_some()
     }
 
+    fn auth_check_args(&self) -> Option<Vec<String>> {
+        // Reports login state without starting a session
+        Some(vec!["status".to_string()])
+    }
+
     fn discover_models(&self) -> Result<Vec<ModelInfo>> {
         // Cursor Agent doesn't have model listing capability
         // Return empty to use fallback models
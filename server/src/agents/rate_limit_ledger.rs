@@ -0,0 +1,437 @@
+//! Process-wide rate limit ledger
+//!
+//! Tracks which providers (and optionally which models) are currently
+//! rate-limited so that every Ralph loop, chat session and research agent
+//! backs off from a provider as soon as any one of them hits a limit.
+//!
+//! The ledger is persisted to `~/.ralph-ui/rate-limits.json` so limits with
+//! long reset windows (e.g. daily usage caps) survive a server restart.
+
+use crate::agents::rate_limiter::{RateLimitInfo, RateLimitType};
+use crate::file_storage::{get_global_ralph_ui_dir, read_json, write_json, FileResult};
+use crate::models::AgentType;
+use crate::utils::lock_mutex_recover;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Version of the rate limit ledger file format
+const LEDGER_FILE_VERSION: u32 = 1;
+
+/// Ledger file name inside `~/.ralph-ui/`
+const LEDGER_FILE_NAME: &str = "rate-limits.json";
+
+/// Backoff used when the provider gave no reset hint (1 minute)
+pub const DEFAULT_RATE_LIMIT_BACKOFF_MS: u64 = 60_000;
+
+/// Upper bound for escalating backoff without a reset hint (30 minutes)
+const MAX_UNHINTED_BACKOFF_MS: u64 = 30 * 60_000;
+
+/// A single rate limit window for a provider or provider/model pair
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitEntry {
+    /// Provider that is rate-limited
+    pub agent_type: AgentType,
+    /// Model that is rate-limited (None = all models for this provider)
+    pub model: Option<String>,
+    /// When the limit was first observed in the current window
+    pub limited_at: DateTime<Utc>,
+    /// When the provider is expected to accept requests again
+    pub available_at: DateTime<Utc>,
+    /// Whether `available_at` came from a provider reset hint (vs. our own backoff)
+    pub from_provider_hint: bool,
+    /// Type of limit that was detected
+    pub limit_type: Option<RateLimitType>,
+    /// What hit the limit (e.g. "ralph_loop:{execution_id}", "prd_chat", "research")
+    pub source: String,
+    /// Number of consecutive limit hits in this window
+    pub hits: u32,
+}
+
+impl RateLimitEntry {
+    /// Whether this entry still blocks the provider at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.available_at > now
+    }
+
+    /// Whether this entry applies to the given provider/model
+    ///
+    /// Provider-wide entries (no model) cover every model; model-specific
+    /// entries only cover that exact model.
+    pub fn covers(&self, agent_type: AgentType, model: Option<&str>) -> bool {
+        self.agent_type == agent_type
+            && match &self.model {
+                None => true,
+                Some(m) => model == Some(m.as_str()),
+            }
+    }
+
+    /// Milliseconds until the provider is available again (0 if already available)
+    pub fn remaining_ms(&self, now: DateTime<Utc>) -> u64 {
+        (self.available_at - now).num_milliseconds().max(0) as u64
+    }
+}
+
+/// On-disk ledger format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerFile {
+    version: u32,
+    updated_at: DateTime<Utc>,
+    entries: Vec<RateLimitEntry>,
+}
+
+/// Shared record of provider/model availability
+///
+/// All methods take `&self`; the ledger is meant to be shared via `Arc`.
+pub struct RateLimitLedger {
+    /// Backing file (None = in-memory only)
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<RateLimitEntry>>,
+}
+
+impl RateLimitLedger {
+    /// Create an in-memory ledger that is never persisted
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Load a ledger from `path`, dropping windows that have already expired.
+    ///
+    /// A missing or unreadable file yields an empty ledger.
+    pub fn load(path: PathBuf) -> Self {
+        let now = Utc::now();
+        let entries = if path.exists() {
+            match read_json::<LedgerFile>(&path) {
+                Ok(file) => file
+                    .entries
+                    .into_iter()
+                    .filter(|e| e.is_active(now))
+                    .collect(),
+                Err(e) => {
+                    log::warn!("[RateLimitLedger] Ignoring unreadable ledger: {}", e);
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Load the ledger stored in a base directory (the global dir in production)
+    pub fn load_from(base_dir: &Path) -> Self {
+        Self::load(base_dir.join(LEDGER_FILE_NAME))
+    }
+
+    /// Record a detected rate limit.
+    ///
+    /// Uses the provider's reset hint when present; otherwise backs off
+    /// `fallback_backoff_ms`, doubling for each consecutive hit in the same window.
+    pub fn record(
+        &self,
+        agent_type: AgentType,
+        model: Option<&str>,
+        info: Option<&RateLimitInfo>,
+        source: &str,
+        fallback_backoff_ms: u64,
+    ) -> RateLimitEntry {
+        let now = Utc::now();
+        let hint = info.and_then(|i| i.reset_at).filter(|at| *at > now);
+        let limit_type = info.and_then(|i| i.limit_type);
+
+        let entry = {
+            let mut entries = lock_mutex_recover(&self.entries);
+            entries.retain(|e| e.is_active(now));
+
+            let existing = entries
+                .iter()
+                .position(|e| e.agent_type == agent_type && e.model.as_deref() == model);
+            let hits = existing.map(|i| entries[i].hits + 1).unwrap_or(1);
+
+            let available_at = hint.unwrap_or_else(|| {
+                let multiplier = 2u64.saturating_pow(hits.saturating_sub(1));
+                let backoff = fallback_backoff_ms
+                    .saturating_mul(multiplier)
+                    .min(MAX_UNHINTED_BACKOFF_MS.max(fallback_backoff_ms));
+                now + Duration::milliseconds(backoff as i64)
+            });
+
+            let entry = RateLimitEntry {
+                agent_type,
+                model: model.map(|m| m.to_string()),
+                limited_at: existing.map(|i| entries[i].limited_at).unwrap_or(now),
+                available_at,
+                from_provider_hint: hint.is_some(),
+                limit_type,
+                source: source.to_string(),
+                hits,
+            };
+
+            match existing {
+                Some(i) => entries[i] = entry.clone(),
+                None => entries.push(entry.clone()),
+            }
+            entry
+        };
+
+        log::info!(
+            "[RateLimitLedger] {}{} rate-limited by {} until {} ({})",
+            agent_type,
+            model.map(|m| format!("/{}", m)).unwrap_or_default(),
+            source,
+            entry.available_at.to_rfc3339(),
+            if entry.from_provider_hint {
+                "provider hint"
+            } else {
+                "backoff"
+            }
+        );
+
+        self.persist();
+        entry
+    }
+
+    /// Get the active limit covering a provider/model, if any.
+    ///
+    /// When several entries apply, the one that reopens last wins.
+    pub fn check(&self, agent_type: AgentType, model: Option<&str>) -> Option<RateLimitEntry> {
+        let now = Utc::now();
+        lock_mutex_recover(&self.entries)
+            .iter()
+            .filter(|e| e.is_active(now) && e.covers(agent_type, model))
+            .max_by_key(|e| e.available_at)
+            .cloned()
+    }
+
+    /// Whether a provider/model can be used right now
+    pub fn is_available(&self, agent_type: AgentType, model: Option<&str>) -> bool {
+        self.check(agent_type, model).is_none()
+    }
+
+    /// When the first of the given providers becomes available again.
+    ///
+    /// Returns None if at least one of them is available right now.
+    pub fn earliest_available_at(
+        &self,
+        agents: &[AgentType],
+        model: Option<&str>,
+    ) -> Option<DateTime<Utc>> {
+        let mut earliest: Option<DateTime<Utc>> = None;
+        for agent in agents {
+            let entry = self.check(*agent, model)?;
+            if earliest.map_or(true, |t| entry.available_at < t) {
+                earliest = Some(entry.available_at);
+            }
+        }
+        earliest
+    }
+
+    /// Clear limits for a provider. With a model, only that model's entry is
+    /// removed; without one, every entry for the provider is removed.
+    ///
+    /// Returns the number of entries removed.
+    pub fn clear(&self, agent_type: AgentType, model: Option<&str>) -> usize {
+        let removed = {
+            let mut entries = lock_mutex_recover(&self.entries);
+            let before = entries.len();
+            entries.retain(|e| {
+                !(e.agent_type == agent_type && (model.is_none() || e.model.as_deref() == model))
+            });
+            before - entries.len()
+        };
+
+        if removed > 0 {
+            log::info!(
+                "[RateLimitLedger] Cleared {} limit(s) for {}",
+                removed,
+                agent_type
+            );
+            self.persist();
+        }
+        removed
+    }
+
+    /// All currently active limits
+    pub fn active_entries(&self) -> Vec<RateLimitEntry> {
+        let now = Utc::now();
+        lock_mutex_recover(&self.entries)
+            .iter()
+            .filter(|e| e.is_active(now))
+            .cloned()
+            .collect()
+    }
+
+    /// Write the ledger to disk (best effort)
+    fn persist(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let file = LedgerFile {
+            version: LEDGER_FILE_VERSION,
+            updated_at: Utc::now(),
+            entries: self.active_entries(),
+        };
+        if let Err(e) = write_json(path, &file) {
+            log::warn!("[RateLimitLedger] Failed to persist ledger: {}", e);
+        }
+    }
+}
+
+static GLOBAL_LEDGER: OnceLock<Arc<RateLimitLedger>> = OnceLock::new();
+
+/// Get the process-wide ledger backed by `~/.ralph-ui/rate-limits.json`
+pub fn global() -> Arc<RateLimitLedger> {
+    GLOBAL_LEDGER
+        .get_or_init(|| Arc::new(RateLimitLedger::load_from(&get_global_ralph_ui_dir())))
+        .clone()
+}
+
+/// Refuse to start an agent whose provider is currently rate-limited
+///
+/// Used by one-shot agent runs (chat, research) that have no fallback chain.
+pub fn ensure_available(agent_type: AgentType, model: Option<&str>) -> FileResult<()> {
+    match global().check(agent_type, model) {
+        Some(entry) => Err(format!(
+            "{} is rate-limited until {} (reported by {}). Try again later or choose another agent.",
+            agent_type,
+            entry.available_at.to_rfc3339(),
+            entry.source
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn info_with_reset(reset_at: DateTime<Utc>) -> RateLimitInfo {
+        RateLimitInfo {
+            is_rate_limited: true,
+            limit_type: Some(RateLimitType::QuotaExceeded),
+            retry_after_ms: None,
+            reset_at: Some(reset_at),
+            matched_pattern: None,
+            detected_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_record_uses_provider_hint() {
+        let ledger = RateLimitLedger::in_memory();
+        let reset = Utc::now() + Duration::hours(2);
+
+        let entry = ledger.record(
+            AgentType::Claude,
+            None,
+            Some(&info_with_reset(reset)),
+            "test",
+            1000,
+        );
+
+        assert!(entry.from_provider_hint);
+        assert_eq!(entry.available_at, reset);
+        assert!(!ledger.is_available(AgentType::Claude, None));
+        assert!(ledger.is_available(AgentType::Opencode, None));
+    }
+
+    #[test]
+    fn test_record_escalates_backoff_without_hint() {
+        let ledger = RateLimitLedger::in_memory();
+
+        let first = ledger.record(AgentType::Codex, None, None, "test", 10_000);
+        let second = ledger.record(AgentType::Codex, None, None, "test", 10_000);
+
+        assert!(!first.from_provider_hint);
+        assert_eq!(second.hits, 2);
+        assert_eq!(second.limited_at, first.limited_at);
+        let first_ms = (first.available_at - first.limited_at).num_milliseconds();
+        let second_ms = (second.available_at - second.limited_at).num_milliseconds();
+        assert!(second_ms >= first_ms + 10_000);
+    }
+
+    #[test]
+    fn test_model_scoped_entries() {
+        let ledger = RateLimitLedger::in_memory();
+        ledger.record(AgentType::Claude, Some("opus"), None, "test", 60_000);
+
+        assert!(!ledger.is_available(AgentType::Claude, Some("opus")));
+        assert!(ledger.is_available(AgentType::Claude, Some("sonnet")));
+        assert!(ledger.is_available(AgentType::Claude, None));
+
+        // A provider-wide limit covers every model
+        ledger.record(AgentType::Claude, None, None, "test", 60_000);
+        assert!(!ledger.is_available(AgentType::Claude, Some("sonnet")));
+    }
+
+    #[test]
+    fn test_earliest_available_at_requires_whole_chain_limited() {
+        let ledger = RateLimitLedger::in_memory();
+        let chain = [AgentType::Claude, AgentType::Opencode];
+
+        let soon = Utc::now() + Duration::minutes(5);
+        let later = Utc::now() + Duration::minutes(50);
+        ledger.record(
+            AgentType::Claude,
+            None,
+            Some(&info_with_reset(later)),
+            "t",
+            0,
+        );
+        assert_eq!(ledger.earliest_available_at(&chain, None), None);
+
+        ledger.record(
+            AgentType::Opencode,
+            None,
+            Some(&info_with_reset(soon)),
+            "t",
+            0,
+        );
+        assert_eq!(ledger.earliest_available_at(&chain, None), Some(soon));
+    }
+
+    #[test]
+    fn test_clear() {
+        let ledger = RateLimitLedger::in_memory();
+        ledger.record(AgentType::Claude, Some("opus"), None, "t", 60_000);
+        ledger.record(AgentType::Claude, Some("sonnet"), None, "t", 60_000);
+
+        assert_eq!(ledger.clear(AgentType::Claude, Some("opus")), 1);
+        assert!(ledger.is_available(AgentType::Claude, Some("opus")));
+        assert_eq!(ledger.clear(AgentType::Claude, None), 1);
+        assert!(ledger.active_entries().is_empty());
+    }
+
+    #[test]
+    fn test_persists_and_reloads_active_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let reset = Utc::now() + Duration::hours(1);
+
+        {
+            let ledger = RateLimitLedger::load_from(temp_dir.path());
+            ledger.record(
+                AgentType::Gemini,
+                None,
+                Some(&info_with_reset(reset)),
+                "research",
+                0,
+            );
+        }
+
+        let reloaded = RateLimitLedger::load_from(temp_dir.path());
+        let entry = reloaded.check(AgentType::Gemini, None).unwrap();
+        assert_eq!(entry.source, "research");
+        assert_eq!(entry.available_at, reset);
+    }
+}
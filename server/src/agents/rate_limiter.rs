@@ -2,7 +2,7 @@
 
 #![allow(dead_code)] // Rate limiting infrastructure (Phase 4)
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
@@ -14,8 +14,11 @@ pub struct RateLimitInfo {
     pub is_rate_limited: bool,
     /// The type of rate limit detected
     pub limit_type: Option<RateLimitType>,
-    /// Suggested retry delay in milliseconds (from retry-after header if available)
+    /// Suggested retry delay in milliseconds (from retry-after header or reset hint if available)
     pub retry_after_ms: Option<u64>,
+    /// Absolute time at which the provider said the limit resets (if a hint was found)
+    #[serde(default)]
+    pub reset_at: Option<DateTime<Utc>>,
     /// The matched pattern that triggered detection
     pub matched_pattern: Option<String>,
     /// Timestamp when the rate limit was detected
@@ -143,11 +146,186 @@ fn get_patterns() -> &'static Vec<CompiledPattern> {
     })
 }
 
-// Regex for extracting retry-after values
+// Regexes for extracting reset hints
 static RETRY_AFTER_REGEX: OnceLock<Regex> = OnceLock::new();
+static RELATIVE_RESET_REGEX: OnceLock<Regex> = OnceLock::new();
+static EPOCH_RESET_REGEX: OnceLock<Regex> = OnceLock::new();
+static ISO_RESET_REGEX: OnceLock<Regex> = OnceLock::new();
+static CLOCK_RESET_REGEX: OnceLock<Regex> = OnceLock::new();
+static DURATION_PART_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Unit suffixes accepted in relative durations ("42s", "5 minutes", "1h 30m")
+const DURATION_UNITS: &str =
+    r"(?:ms|milliseconds?|s|secs?|seconds?|m|mins?|minutes?|h|hrs?|hours?)";
 
 fn get_retry_after_regex() -> &'static Regex {
-    RETRY_AFTER_REGEX.get_or_init(|| Regex::new(r"(?i)retry[_\-\s]?after[:\s]*(\d+)").unwrap())
+    RETRY_AFTER_REGEX.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)retry[_\-\s]?after[:\s]*(\d+)(\s*{})?\b",
+            DURATION_UNITS
+        ))
+        .unwrap()
+    })
+}
+
+fn get_relative_reset_regex() -> &'static Regex {
+    RELATIVE_RESET_REGEX.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)(?:try\s+again|retry|resets?|available(?:\s+again)?|wait)\s+in\s+((?:\d+(?:\.\d+)?\s*{}\b[,\s]*(?:and\s+)?)+)",
+            DURATION_UNITS
+        ))
+        .unwrap()
+    })
+}
+
+fn get_epoch_reset_regex() -> &'static Regex {
+    EPOCH_RESET_REGEX.get_or_init(|| {
+        // Matches "x-ratelimit-reset: 1760000000", "\"resets_at\":1760000000",
+        // "reset=1760000000000" and Claude CLI's "usage limit reached|1760000000"
+        Regex::new(
+            r#"(?i)(?:reset(?:s)?(?:[_\-\s]?at)?|x-ratelimit-reset[\w\-]*|limit\s+reached)["']?\s*[:=|]\s*["']?(\d{10}|\d{13})\b"#,
+        )
+        .unwrap()
+    })
+}
+
+fn get_iso_reset_regex() -> &'static Regex {
+    ISO_RESET_REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?i)(?:resets?|try\s+again|available|retry)(?:\s+(?:at|after|on))?[:\s]+(\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+\-]\d{2}:?\d{2})?)",
+        )
+        .unwrap()
+    })
+}
+
+fn get_clock_reset_regex() -> &'static Regex {
+    CLOCK_RESET_REGEX.get_or_init(|| {
+        // "resets at 3pm", "reset at 3:30 PM (UTC)", "try again at 15:00"
+        Regex::new(
+            r"(?i)(?:resets?|try\s+again|available(?:\s+again)?)\s+at\s+(\d{1,2})(?::(\d{2}))?\s*(am|pm)?(?:\s*\(?\s*(utc|gmt)\b)?",
+        )
+        .unwrap()
+    })
+}
+
+fn get_duration_part_regex() -> &'static Regex {
+    DURATION_PART_REGEX.get_or_init(|| {
+        Regex::new(&format!(r"(?i)(\d+(?:\.\d+)?)\s*({})\b", DURATION_UNITS)).unwrap()
+    })
+}
+
+/// Convert a numeric value with a unit suffix to milliseconds.
+/// A missing unit is treated as seconds (the HTTP Retry-After convention).
+fn unit_to_ms(value: f64, unit: Option<&str>) -> u64 {
+    let unit = unit.map(|u| u.trim().to_lowercase()).unwrap_or_default();
+    let multiplier = match unit.as_str() {
+        "ms" | "millisecond" | "milliseconds" => 1.0,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60_000.0,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3_600_000.0,
+        _ => 1_000.0,
+    };
+    (value * multiplier).round() as u64
+}
+
+/// `now` plus a hint's milliseconds, or None when that's beyond what a timestamp can hold
+fn add_ms(now: DateTime<Utc>, ms: u64) -> Option<DateTime<Utc>> {
+    let ms = i64::try_from(ms).ok()?;
+    now.checked_add_signed(Duration::try_milliseconds(ms)?)
+}
+
+/// Parse a provider reset hint from agent output.
+///
+/// Understands relative hints ("try again in 42s", "resets in 1h 30m"),
+/// Retry-After values, epoch timestamps (seconds or milliseconds), ISO-8601
+/// timestamps and wall-clock times ("resets at 3pm"). Wall-clock times without
+/// an explicit UTC/GMT marker are interpreted in the server's local timezone
+/// and roll over to the next day if already past.
+pub fn parse_reset_hint(output: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let Some(caps) = get_epoch_reset_regex().captures(output) {
+        let raw: i64 = caps.get(1)?.as_str().parse().ok()?;
+        let reset = if raw >= 1_000_000_000_000 {
+            Utc.timestamp_millis_opt(raw).single()
+        } else {
+            Utc.timestamp_opt(raw, 0).single()
+        };
+        if let Some(reset) = reset {
+            return Some(reset);
+        }
+    }
+
+    if let Some(caps) = get_iso_reset_regex().captures(output) {
+        let raw = caps.get(1)?.as_str().replacen(' ', "T", 1);
+        if let Ok(parsed) = DateTime::parse_from_rfc3339(&raw) {
+            return Some(parsed.with_timezone(&Utc));
+        }
+        if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(&raw, "%Y-%m-%dT%H:%M:%S") {
+            return Some(naive.and_utc());
+        }
+        if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(&raw, "%Y-%m-%dT%H:%M") {
+            return Some(naive.and_utc());
+        }
+    }
+
+    if let Some(caps) = get_relative_reset_regex().captures(output) {
+        let total_ms: u64 = get_duration_part_regex()
+            .captures_iter(caps.get(1)?.as_str())
+            .filter_map(|part| {
+                let value: f64 = part.get(1)?.as_str().parse().ok()?;
+                Some(unit_to_ms(value, part.get(2).map(|m| m.as_str())))
+            })
+            .fold(0u64, u64::saturating_add);
+        if total_ms > 0 {
+            return add_ms(now, total_ms);
+        }
+    }
+
+    if let Some(caps) = get_retry_after_regex().captures(output) {
+        let value: f64 = caps.get(1)?.as_str().parse().ok()?;
+        let ms = unit_to_ms(value, caps.get(2).map(|m| m.as_str()));
+        return add_ms(now, ms);
+    }
+
+    if let Some(caps) = get_clock_reset_regex().captures(output) {
+        let mut hour: u32 = caps.get(1)?.as_str().parse().ok()?;
+        let minute: u32 = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        match caps.get(3).map(|m| m.as_str().to_lowercase()).as_deref() {
+            Some("pm") if hour < 12 => hour += 12,
+            Some("am") if hour == 12 => hour = 0,
+            _ => {}
+        }
+        let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+        let is_utc = caps.get(4).is_some();
+        return next_wall_clock(now, time, is_utc);
+    }
+
+    None
+}
+
+/// Find the next occurrence of a wall-clock time strictly after `now`
+fn next_wall_clock(now: DateTime<Utc>, time: NaiveTime, is_utc: bool) -> Option<DateTime<Utc>> {
+    if is_utc {
+        let mut candidate = now.date_naive().and_time(time).and_utc();
+        if candidate <= now {
+            candidate += Duration::days(1);
+        }
+        Some(candidate)
+    } else {
+        let local_now = now.with_timezone(&Local);
+        let mut candidate = Local
+            .from_local_datetime(&local_now.date_naive().and_time(time))
+            .earliest()?
+            .with_timezone(&Utc);
+        if candidate <= now {
+            candidate = Local
+                .from_local_datetime(&(local_now.date_naive() + Duration::days(1)).and_time(time))
+                .earliest()?
+                .with_timezone(&Utc);
+        }
+        Some(candidate)
+    }
 }
 
 impl RateLimitDetector {
@@ -170,15 +348,18 @@ impl RateLimitDetector {
 
         for pattern in patterns {
             if pattern.regex.is_match(output) {
-                let retry_after_ms = self.extract_retry_after(output);
+                let now = Utc::now();
+                let reset_at = parse_reset_hint(output, now);
+                let retry_after_ms = reset_at.map(|at| (at - now).num_milliseconds().max(0) as u64);
                 let matched = pattern.regex.find(output).map(|m| m.as_str().to_string());
 
                 return Some(RateLimitInfo {
                     is_rate_limited: true,
                     limit_type: Some(pattern.limit_type),
                     retry_after_ms,
+                    reset_at,
                     matched_pattern: matched,
-                    detected_at: Utc::now(),
+                    detected_at: now,
                 });
             }
         }
@@ -186,15 +367,6 @@ impl RateLimitDetector {
         None
     }
 
-    /// Extract retry-after delay from output
-    fn extract_retry_after(&self, output: &str) -> Option<u64> {
-        let regex = get_retry_after_regex();
-
-        regex
-            .captures(output)
-            .and_then(|caps| caps.get(1).and_then(|m| m.as_str().parse::<u64>().ok()))
-    }
-
     /// Check if output indicates a rate limit (convenience method)
     pub fn is_rate_limited(&self, stderr: &str) -> bool {
         self.detect_in_stderr(stderr).is_some()
@@ -259,7 +431,8 @@ mod tests {
 
         assert!(result.is_some());
         let info = result.unwrap();
-        assert_eq!(info.retry_after_ms, Some(30));
+        assert_retry_close(info.retry_after_ms, 30_000);
+        assert!(info.reset_at.is_some());
     }
 
     #[test]
@@ -380,16 +553,155 @@ mod tests {
         let info1 = detector
             .detect_in_stderr("rate limited, retry-after: 60")
             .unwrap();
-        assert_eq!(info1.retry_after_ms, Some(60));
+        assert_retry_close(info1.retry_after_ms, 60_000);
 
         let info2 = detector
             .detect_in_stderr("Rate limited. Retry_After: 120")
             .unwrap();
-        assert_eq!(info2.retry_after_ms, Some(120));
+        assert_retry_close(info2.retry_after_ms, 120_000);
 
         let info3 = detector
             .detect_in_stderr("rate limit hit, retry after 45 seconds")
             .unwrap();
-        assert_eq!(info3.retry_after_ms, Some(45));
+        assert_retry_close(info3.retry_after_ms, 45_000);
+    }
+
+    /// Retry delays are computed against the wall clock, so allow a little slack
+    fn assert_retry_close(actual: Option<u64>, expected_ms: u64) {
+        let actual = actual.expect("expected a retry-after value");
+        assert!(
+            actual <= expected_ms && actual + 1_000 >= expected_ms,
+            "expected ~{}ms, got {}ms",
+            expected_ms,
+            actual
+        );
+    }
+
+    fn fixed_now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_reset_hint_relative() {
+        let now = fixed_now();
+
+        assert_eq!(
+            parse_reset_hint("Rate limited, try again in 42s", now),
+            Some(now + Duration::seconds(42))
+        );
+        assert_eq!(
+            parse_reset_hint("Quota exceeded. Resets in 1h 30m.", now),
+            Some(now + Duration::minutes(90))
+        );
+        assert_eq!(
+            parse_reset_hint("overloaded - please try again in 500ms", now),
+            Some(now + Duration::milliseconds(500))
+        );
+        assert_eq!(
+            parse_reset_hint("429: retry-after: 2 minutes", now),
+            Some(now + Duration::minutes(2))
+        );
+    }
+
+    #[test]
+    fn test_parse_reset_hint_huge_values_do_not_panic() {
+        let now = fixed_now();
+
+        assert_eq!(
+            parse_reset_hint("try again in 99999999999999999999999h", now),
+            None
+        );
+        assert_eq!(
+            parse_reset_hint("try again in 9999999999999h 9999999999999h", now),
+            None
+        );
+        assert_eq!(
+            parse_reset_hint("retry-after: 9999999999999 minutes", now),
+            None
+        );
+        assert_eq!(
+            parse_reset_hint("retry-after: 99999999999999999 minutes", now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_reset_hint_epoch() {
+        let now = fixed_now();
+        let reset = Utc.with_ymd_and_hms(2026, 3, 10, 17, 0, 0).unwrap();
+
+        let secs = format!("Claude AI usage limit reached|{}", reset.timestamp());
+        assert_eq!(parse_reset_hint(&secs, now), Some(reset));
+
+        let header = format!("x-ratelimit-reset-requests: {}", reset.timestamp_millis());
+        assert_eq!(parse_reset_hint(&header, now), Some(reset));
+
+        let json = format!(
+            r#"{{"error":"rate_limit","resets_at":{}}}"#,
+            reset.timestamp()
+        );
+        assert_eq!(parse_reset_hint(&json, now), Some(reset));
+    }
+
+    #[test]
+    fn test_parse_reset_hint_iso_timestamp() {
+        let now = fixed_now();
+        assert_eq!(
+            parse_reset_hint("Usage limit reached, resets at 2026-03-10T18:15:00Z", now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 10, 18, 15, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_reset_hint_wall_clock_utc() {
+        let now = fixed_now();
+
+        // Later today
+        assert_eq!(
+            parse_reset_hint("Your limit resets at 3pm (UTC)", now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 10, 15, 0, 0).unwrap())
+        );
+        // Already past today, so it rolls over to tomorrow
+        assert_eq!(
+            parse_reset_hint("try again at 9:30 am UTC", now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 11, 9, 30, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_reset_hint_wall_clock_local() {
+        let now = Utc::now();
+        let reset = parse_reset_hint(
+            "Claude usage limit reached. Your limit will reset at 3pm",
+            now,
+        )
+        .expect("expected a reset time");
+
+        let local = reset.with_timezone(&Local);
+        assert_eq!(local.format("%H:%M").to_string(), "15:00");
+        assert!(reset > now);
+        assert!(reset <= now + Duration::days(1));
+    }
+
+    #[test]
+    fn test_parse_reset_hint_none() {
+        assert_eq!(parse_reset_hint("rate limit exceeded", fixed_now()), None);
+        assert_eq!(parse_reset_hint("session 1760000000", fixed_now()), None);
+    }
+
+    #[test]
+    fn test_detect_fills_reset_at() {
+        let detector = RateLimitDetector::new();
+        let info = detector
+            .detect_in_stderr("Error: 429 Too Many Requests. Please try again in 5 minutes.")
+            .unwrap();
+
+        assert_retry_close(info.retry_after_ms, 300_000);
+        let reset_at = info.reset_at.unwrap();
+        assert_eq!(
+            (reset_at - info.detected_at).num_seconds(),
+            300,
+            "reset_at should be relative to detection time"
+        );
     }
 }
@@ -0,0 +1,573 @@
+// Chrome Trace Event export for persisted agent runs
+//
+// Builds JSON loadable in Perfetto (ui.perfetto.dev) or chrome://tracing:
+// - one process per execution
+// - one track per agent run, with the iteration as its top-level slice
+// - tool calls as spans on the track of the agent or subagent that made them
+// - subagents as child tracks sorted directly below their parent
+//
+// Overlapping tool calls (parallel tool use) cannot nest on a single track, so
+// they spill onto extra "parallel" lanes of the same track.
+
+use crate::agents::transcript_capture::TranscriptToolCall;
+use crate::agents::{SubagentEvent, SubagentEventType};
+use crate::file_storage::transcripts::TranscriptMeta;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Tool input summaries longer than this are truncated in span args
+const MAX_ARG_CHARS: usize = 500;
+/// Sort-index gap between iterations (leaves room for subagent tracks and lanes)
+const ITERATION_SORT_STRIDE: u32 = 10_000;
+/// Sort-index gap between tracks of one iteration (leaves room for lanes)
+const TRACK_SORT_STRIDE: u32 = 100;
+
+/// Everything persisted for one iteration that contributes to its trace
+#[derive(Debug, Clone)]
+pub struct IterationTrace {
+    pub meta: TranscriptMeta,
+    pub tool_calls: Vec<TranscriptToolCall>,
+    pub subagent_events: Vec<SubagentEvent>,
+}
+
+/// A Chrome Trace Event file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChromeTrace {
+    pub trace_events: Vec<TraceEvent>,
+    pub display_time_unit: String,
+}
+
+/// A single trace event (complete span, instant or metadata)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEvent {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub cat: String,
+    /// Phase: "X" complete span, "i" instant, "M" metadata
+    pub ph: String,
+    /// Microseconds since the start of the trace
+    #[serde(default)]
+    pub ts: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dur: Option<u64>,
+    pub pid: u32,
+    pub tid: u32,
+    /// Instant event scope ("t" = thread)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub s: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub args: serde_json::Value,
+}
+
+impl TraceEvent {
+    fn span(name: String, cat: &str, pid: u32, tid: u32, ts: u64, end: u64) -> Self {
+        Self {
+            name,
+            cat: cat.to_string(),
+            ph: "X".to_string(),
+            ts,
+            dur: Some(end.saturating_sub(ts).max(1)),
+            pid,
+            tid,
+            s: None,
+            args: serde_json::Value::Null,
+        }
+    }
+
+    fn metadata(name: &str, pid: u32, tid: u32, args: serde_json::Value) -> Self {
+        Self {
+            name: name.to_string(),
+            cat: String::new(),
+            ph: "M".to_string(),
+            ts: 0,
+            dur: None,
+            pid,
+            tid,
+            s: None,
+            args,
+        }
+    }
+
+    fn with_args(mut self, args: serde_json::Value) -> Self {
+        self.args = args;
+        self
+    }
+}
+
+/// A named track, split into lanes so spans on each lane nest properly
+struct Track {
+    name: String,
+    sort_index: u32,
+    /// (tid, end of the last span) per lane
+    lanes: Vec<(u32, u64)>,
+}
+
+/// Allocates thread IDs and emits track metadata
+struct TraceBuilder {
+    origin: DateTime<Utc>,
+    events: Vec<TraceEvent>,
+    next_tid: u32,
+}
+
+impl TraceBuilder {
+    fn micros(&self, timestamp: &str) -> Option<u64> {
+        let at = parse_time(timestamp)?;
+        Some((at - self.origin).num_microseconds().unwrap_or(0).max(0) as u64)
+    }
+
+    fn new_track(&mut self, pid: u32, name: String, sort_index: u32) -> Track {
+        let mut track = Track {
+            name,
+            sort_index,
+            lanes: Vec::new(),
+        };
+        self.add_lane(pid, &mut track);
+        track
+    }
+
+    fn add_lane(&mut self, pid: u32, track: &mut Track) -> usize {
+        let lane = track.lanes.len();
+        let tid = self.next_tid;
+        self.next_tid += 1;
+
+        let name = if lane == 0 {
+            track.name.clone()
+        } else {
+            format!("{} (parallel {})", track.name, lane)
+        };
+        self.events.push(TraceEvent::metadata(
+            "thread_name",
+            pid,
+            tid,
+            json!({ "name": name }),
+        ));
+        self.events.push(TraceEvent::metadata(
+            "thread_sort_index",
+            pid,
+            tid,
+            json!({ "sort_index": track.sort_index + lane as u32 }),
+        ));
+        track.lanes.push((tid, 0));
+        lane
+    }
+
+    /// Place a span on the first lane where it does not overlap the previous span
+    fn place(&mut self, pid: u32, track: &mut Track, start: u64, end: u64) -> u32 {
+        let lane = match track
+            .lanes
+            .iter()
+            .position(|(_, last_end)| *last_end <= start)
+        {
+            Some(lane) => lane,
+            None => self.add_lane(pid, track),
+        };
+        track.lanes[lane].1 = end;
+        track.lanes[lane].0
+    }
+}
+
+fn parse_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(max).collect::<String>())
+    }
+}
+
+/// Human-readable name for a subagent spawned by a Task tool call
+fn task_description(call: &TranscriptToolCall) -> String {
+    call.input
+        .as_ref()
+        .and_then(|input| input.get("description").or_else(|| input.get("prompt")))
+        .and_then(|d| d.as_str())
+        .map(|d| truncate_chars(d, 60))
+        .unwrap_or_else(|| call.tool_id.clone())
+}
+
+/// Build a Chrome trace from persisted iterations
+pub fn build_chrome_trace(iterations: &[IterationTrace]) -> ChromeTrace {
+    let mut iterations: Vec<&IterationTrace> = iterations.iter().collect();
+    iterations.sort_by(|a, b| a.meta.started_at.cmp(&b.meta.started_at));
+
+    let origin = iterations
+        .iter()
+        .filter_map(|it| parse_time(&it.meta.started_at))
+        .min()
+        .unwrap_or_else(Utc::now);
+    let mut builder = TraceBuilder {
+        origin,
+        events: Vec::new(),
+        next_tid: 1,
+    };
+
+    let mut pids: HashMap<String, u32> = HashMap::new();
+    for (order, iteration) in iterations.iter().enumerate() {
+        let meta = &iteration.meta;
+        let pid = match pids.get(&meta.execution_id) {
+            Some(pid) => *pid,
+            None => {
+                let pid = pids.len() as u32 + 1;
+                pids.insert(meta.execution_id.clone(), pid);
+                builder.events.push(TraceEvent::metadata(
+                    "process_name",
+                    pid,
+                    0,
+                    json!({ "name": format!("Execution {}", meta.execution_id) }),
+                ));
+                pid
+            }
+        };
+
+        let start = builder.micros(&meta.started_at).unwrap_or(0);
+        let end = builder.micros(&meta.saved_at).unwrap_or(start).max(start);
+        let base_sort = order as u32 * ITERATION_SORT_STRIDE;
+
+        // Agent track with the iteration as its top-level slice
+        let mut agent_track = builder.new_track(
+            pid,
+            format!("Iteration {} · {}", meta.iteration, meta.agent_id),
+            base_sort,
+        );
+        let iteration_name = match &meta.story_id {
+            Some(story_id) => format!("Iteration {} ({})", meta.iteration, story_id),
+            None => format!("Iteration {}", meta.iteration),
+        };
+        builder.events.push(
+            TraceEvent::span(
+                iteration_name,
+                "iteration",
+                pid,
+                agent_track.lanes[0].0,
+                start,
+                end,
+            )
+            .with_args(json!({
+                "executionId": meta.execution_id,
+                "storyId": meta.story_id,
+                "attempt": meta.attempt,
+                "agentType": meta.agent_type,
+                "model": meta.model,
+                "exitCode": meta.exit_code,
+                "toolCalls": meta.tool_call_count,
+            })),
+        );
+
+        // Subagent tracks keyed by the Task tool call that spawned them
+        let calls_by_id: HashMap<&str, &TranscriptToolCall> = iteration
+            .tool_calls
+            .iter()
+            .map(|c| (c.tool_id.as_str(), c))
+            .collect();
+        let mut subagent_tracks: HashMap<String, Track> = HashMap::new();
+        let mut track_count = 0u32;
+
+        let mut calls: Vec<&TranscriptToolCall> = iteration.tool_calls.iter().collect();
+        calls.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+
+        for call in calls {
+            let call_start = builder.micros(&call.started_at).unwrap_or(start);
+            let call_end = call
+                .completed_at
+                .as_deref()
+                .and_then(|t| builder.micros(t))
+                .unwrap_or(end)
+                .max(call_start);
+
+            let parent = call
+                .parent_tool_id
+                .as_deref()
+                .and_then(|id| calls_by_id.get(id).map(|task| (id, *task)));
+            let track = match parent {
+                Some((parent_id, task)) => {
+                    if !subagent_tracks.contains_key(parent_id) {
+                        track_count += 1;
+                        let task_start = builder.micros(&task.started_at).unwrap_or(start);
+                        let task_end = task
+                            .completed_at
+                            .as_deref()
+                            .and_then(|t| builder.micros(t))
+                            .unwrap_or(end);
+                        let mut track = builder.new_track(
+                            pid,
+                            format!("  ↳ Subagent: {}", task_description(task)),
+                            base_sort + track_count * TRACK_SORT_STRIDE,
+                        );
+                        builder.events.push(TraceEvent::span(
+                            format!("Subagent: {}", task_description(task)),
+                            "subagent",
+                            pid,
+                            track.lanes[0].0,
+                            task_start,
+                            task_end,
+                        ));
+                        // The subagent slice is a container; tool spans nest inside it
+                        track.lanes[0].1 = task_start;
+                        subagent_tracks.insert(parent_id.to_string(), track);
+                    }
+                    subagent_tracks.get_mut(parent_id)
+                }
+                None => None,
+            };
+            let tid = match track {
+                Some(track) => builder.place(pid, track, call_start, call_end),
+                None => builder.place(pid, &mut agent_track, call_start, call_end),
+            };
+
+            let input = call
+                .input
+                .as_ref()
+                .map(|i| truncate_chars(&i.to_string(), MAX_ARG_CHARS));
+            builder.events.push(
+                TraceEvent::span(
+                    call.tool_name.clone(),
+                    "tool",
+                    pid,
+                    tid,
+                    call_start,
+                    call_end,
+                )
+                .with_args(json!({
+                    "toolId": call.tool_id,
+                    "isError": call.is_error,
+                    "incomplete": call.completed_at.is_none(),
+                    "input": input,
+                })),
+            );
+        }
+
+        // Subagents detected from display output (no tool call IDs)
+        let mut spawned: HashMap<&str, (u64, &SubagentEvent)> = HashMap::new();
+        let mut text_tracks: HashMap<&str, u32> = HashMap::new();
+        for event in &iteration.subagent_events {
+            let at = builder
+                .micros(&event.timestamp.to_rfc3339())
+                .unwrap_or(start);
+            let tid = match text_tracks.get(event.subagent_id.as_str()) {
+                Some(tid) => *tid,
+                None => {
+                    track_count += 1;
+                    let indent = "  ".repeat(event.depth as usize + 1);
+                    let track = builder.new_track(
+                        pid,
+                        format!("{}↳ Subagent: {}", indent, event.description),
+                        base_sort + track_count * TRACK_SORT_STRIDE,
+                    );
+                    text_tracks.insert(&event.subagent_id, track.lanes[0].0);
+                    track.lanes[0].0
+                }
+            };
+
+            match event.event_type {
+                SubagentEventType::Spawned => {
+                    spawned.insert(&event.subagent_id, (at, event));
+                }
+                SubagentEventType::Completed | SubagentEventType::Failed => {
+                    if let Some((spawn_at, spawn)) = spawned.remove(event.subagent_id.as_str()) {
+                        builder.events.push(
+                            TraceEvent::span(
+                                format!("Subagent: {}", spawn.description),
+                                "subagent",
+                                pid,
+                                tid,
+                                spawn_at,
+                                at,
+                            )
+                            .with_args(json!({
+                                "failed": event.event_type == SubagentEventType::Failed,
+                            })),
+                        );
+                    }
+                }
+                SubagentEventType::Progress => {
+                    builder.events.push(TraceEvent {
+                        name: event.description.clone(),
+                        cat: "subagent".to_string(),
+                        ph: "i".to_string(),
+                        ts: at,
+                        dur: None,
+                        pid,
+                        tid,
+                        s: Some("t".to_string()),
+                        args: serde_json::Value::Null,
+                    });
+                }
+            }
+        }
+        // Subagents still running when the iteration ended
+        for (subagent_id, (spawn_at, spawn)) in spawned {
+            if let Some(tid) = text_tracks.get(subagent_id) {
+                builder.events.push(
+                    TraceEvent::span(
+                        format!("Subagent: {}", spawn.description),
+                        "subagent",
+                        pid,
+                        *tid,
+                        spawn_at,
+                        end,
+                    )
+                    .with_args(json!({ "incomplete": true })),
+                );
+            }
+        }
+    }
+
+    ChromeTrace {
+        trace_events: builder.events,
+        display_time_unit: "ms".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AgentType;
+
+    fn meta(iteration: u32, started_at: &str, saved_at: &str) -> TranscriptMeta {
+        TranscriptMeta {
+            execution_id: "exec-1".to_string(),
+            iteration,
+            attempt: 1,
+            agent_id: format!("agent-{}", iteration),
+            agent_type: AgentType::Claude,
+            model: None,
+            prd_name: None,
+            story_id: Some("US-1".to_string()),
+            exit_code: 0,
+            started_at: started_at.to_string(),
+            saved_at: saved_at.to_string(),
+            output_bytes: 0,
+            compressed_bytes: 0,
+            line_count: 0,
+            tool_call_count: 0,
+            truncated: false,
+        }
+    }
+
+    fn call(
+        id: &str,
+        name: &str,
+        parent: Option<&str>,
+        started_at: &str,
+        completed_at: &str,
+    ) -> TranscriptToolCall {
+        TranscriptToolCall {
+            tool_id: id.to_string(),
+            tool_name: name.to_string(),
+            input: Some(json!({ "description": "explore repo" })),
+            parent_tool_id: parent.map(String::from),
+            output: None,
+            is_error: false,
+            started_at: started_at.to_string(),
+            completed_at: Some(completed_at.to_string()),
+        }
+    }
+
+    fn spans<'a>(trace: &'a ChromeTrace, cat: &str) -> Vec<&'a TraceEvent> {
+        trace
+            .trace_events
+            .iter()
+            .filter(|e| e.ph == "X" && e.cat == cat)
+            .collect()
+    }
+
+    #[test]
+    fn test_iteration_and_tool_spans() {
+        let trace = build_chrome_trace(&[IterationTrace {
+            meta: meta(1, "2026-01-01T00:00:00Z", "2026-01-01T00:00:10Z"),
+            tool_calls: vec![call(
+                "t1",
+                "Bash",
+                None,
+                "2026-01-01T00:00:01Z",
+                "2026-01-01T00:00:04Z",
+            )],
+            subagent_events: Vec::new(),
+        }]);
+
+        let iterations = spans(&trace, "iteration");
+        assert_eq!(iterations.len(), 1);
+        assert_eq!(iterations[0].ts, 0);
+        assert_eq!(iterations[0].dur, Some(10_000_000));
+
+        let tools = spans(&trace, "tool");
+        assert_eq!(tools[0].ts, 1_000_000);
+        assert_eq!(tools[0].dur, Some(3_000_000));
+        assert_eq!(tools[0].tid, iterations[0].tid);
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert!(json.get("traceEvents").is_some());
+    }
+
+    #[test]
+    fn test_subagent_calls_get_their_own_track() {
+        let trace = build_chrome_trace(&[IterationTrace {
+            meta: meta(1, "2026-01-01T00:00:00Z", "2026-01-01T00:00:10Z"),
+            tool_calls: vec![
+                call(
+                    "task",
+                    "Task",
+                    None,
+                    "2026-01-01T00:00:01Z",
+                    "2026-01-01T00:00:08Z",
+                ),
+                call(
+                    "read",
+                    "Read",
+                    Some("task"),
+                    "2026-01-01T00:00:02Z",
+                    "2026-01-01T00:00:03Z",
+                ),
+            ],
+            subagent_events: Vec::new(),
+        }]);
+
+        let tools = spans(&trace, "tool");
+        let task = tools.iter().find(|e| e.name == "Task").unwrap();
+        let read = tools.iter().find(|e| e.name == "Read").unwrap();
+        assert_ne!(task.tid, read.tid);
+
+        let subagent = &spans(&trace, "subagent")[0];
+        assert_eq!(subagent.tid, read.tid);
+        assert_eq!(subagent.name, "Subagent: explore repo");
+    }
+
+    #[test]
+    fn test_overlapping_calls_spill_to_parallel_lane() {
+        let trace = build_chrome_trace(&[IterationTrace {
+            meta: meta(1, "2026-01-01T00:00:00Z", "2026-01-01T00:00:10Z"),
+            tool_calls: vec![
+                call(
+                    "a",
+                    "Read",
+                    None,
+                    "2026-01-01T00:00:01Z",
+                    "2026-01-01T00:00:05Z",
+                ),
+                call(
+                    "b",
+                    "Grep",
+                    None,
+                    "2026-01-01T00:00:02Z",
+                    "2026-01-01T00:00:03Z",
+                ),
+            ],
+            subagent_events: Vec::new(),
+        }]);
+
+        let tools = spans(&trace, "tool");
+        assert_ne!(tools[0].tid, tools[1].tid);
+        assert!(trace.trace_events.iter().any(|e| e.ph == "M"
+            && e.args["name"]
+                .as_str()
+                .is_some_and(|n| n.ends_with("(parallel 1)"))));
+    }
+}
@@ -0,0 +1,122 @@
+// Raw agent output capture for persistent transcripts
+//
+// The PTY ring buffer only keeps display text and is capped, so the output
+// readers also record the unparsed stream and tool calls here until the
+// orchestrator takes them for persistence.
+
+use crate::agents::SubagentEvent;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Raw output above this size is dropped (the transcript is marked truncated)
+const MAX_CAPTURE_BYTES: usize = 64 * 1024 * 1024;
+
+/// A tool call observed in agent output, with its result once available
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptToolCall {
+    pub tool_id: String,
+    pub tool_name: String,
+    pub input: Option<serde_json::Value>,
+    /// Task tool call that spawned the subagent making this call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_tool_id: Option<String>,
+    pub output: Option<String>,
+    #[serde(default)]
+    pub is_error: bool,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// Everything captured from one agent run
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptCapture {
+    /// Unparsed stdout lines, with stderr lines prefixed by `[stderr] `
+    pub raw: Vec<u8>,
+    pub tool_calls: Vec<TranscriptToolCall>,
+    /// Subagent events parsed from display output
+    pub subagent_events: Vec<SubagentEvent>,
+    /// Whether raw output exceeded MAX_CAPTURE_BYTES
+    pub truncated: bool,
+}
+
+impl TranscriptCapture {
+    /// Append a raw output line
+    pub fn push_line(&mut self, line: &str, is_stderr: bool) {
+        let prefix = if is_stderr { "[stderr] " } else { "" };
+        if self.raw.len() + prefix.len() + line.len() + 1 > MAX_CAPTURE_BYTES {
+            self.truncated = true;
+            return;
+        }
+        self.raw.extend_from_slice(prefix.as_bytes());
+        self.raw.extend_from_slice(line.as_bytes());
+        self.raw.push(b'\n');
+    }
+
+    /// Record the start of a tool call
+    pub fn start_tool_call(
+        &mut self,
+        tool_id: String,
+        tool_name: String,
+        input: Option<serde_json::Value>,
+        parent_tool_id: Option<String>,
+    ) {
+        self.tool_calls.push(TranscriptToolCall {
+            tool_id,
+            tool_name,
+            input,
+            parent_tool_id,
+            output: None,
+            is_error: false,
+            started_at: Utc::now().to_rfc3339(),
+            completed_at: None,
+        });
+    }
+
+    /// Attach a result to the matching (most recent) tool call
+    pub fn complete_tool_call(&mut self, tool_id: &str, output: String, is_error: bool) {
+        if let Some(call) = self
+            .tool_calls
+            .iter_mut()
+            .rev()
+            .find(|c| c.tool_id == tool_id && c.completed_at.is_none())
+        {
+            call.output = Some(output);
+            call.is_error = is_error;
+            call.completed_at = Some(Utc::now().to_rfc3339());
+        }
+    }
+}
+
+/// Shared per-agent captures, keyed by agent ID
+pub type TranscriptCaptures = Arc<Mutex<HashMap<String, TranscriptCapture>>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_line_prefixes_stderr() {
+        let mut capture = TranscriptCapture::default();
+        capture.push_line("{\"type\":\"text\"}", false);
+        capture.push_line("boom", true);
+        assert_eq!(
+            String::from_utf8(capture.raw).unwrap(),
+            "{\"type\":\"text\"}\n[stderr] boom\n"
+        );
+    }
+
+    #[test]
+    fn test_complete_tool_call_matches_id() {
+        let mut capture = TranscriptCapture::default();
+        capture.start_tool_call("a".into(), "Read".into(), None, None);
+        capture.start_tool_call("b".into(), "Bash".into(), None, None);
+        capture.complete_tool_call("a", "contents".into(), false);
+
+        assert_eq!(capture.tool_calls[0].output.as_deref(), Some("contents"));
+        assert!(capture.tool_calls[0].completed_at.is_some());
+        assert!(capture.tool_calls[1].completed_at.is_none());
+    }
+}
@@ -0,0 +1,822 @@
+//! Headless command-line operations
+//!
+//! Backs the `ralph-ui` subcommands (`run`, `status`, `list-prds`,
+//! `list-executions`, `logs`, `import-prd`, `migrate`, `export-state`, `import-state`)
+//! used in CI and over SSH. They
+//! read and write the same `.ralph-ui/` files as the server, and `run` drives
+//! the loop through the same orchestrator the server uses.
+//!
+//! Every report implements `Display` for terminal output and `Serialize` for
+//! `--json`.
+
+mod run;
+
+pub use run::{run_loop, RunEvent, RunOutcome};
+
+use crate::commands::ralph_loop as ralph_commands;
+use crate::file_storage::iterations::{self as iteration_storage, IterationStats};
+use crate::file_storage::migrations::{self, MigrationReport};
+use crate::file_storage::project_archive::{
+    self, ArchiveSection, ExportOptions, ImportMode, ImportReport,
+};
+use crate::file_storage::transcripts::{self, TranscriptFilter, TranscriptPart};
+use crate::parsers::{parse_prd_auto, PRDDocument};
+use crate::ralph_loop::{
+    IterationOutcome, IterationRecord, PrdExecutor, PrdStatus, RalphLoopState, RalphPrd, RalphStory,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+
+/// Heartbeats older than this mean the execution's process is gone
+/// (running loops write one every 5 seconds)
+const ACTIVE_HEARTBEAT_SECS: i64 = 30;
+
+/// Print a report as text, or as pretty JSON with `--json`.
+/// Write errors (e.g. a closed pipe) are ignored.
+pub fn print_report<T: Serialize + fmt::Display>(report: &T, json: bool) {
+    let mut stdout = std::io::stdout().lock();
+    let _ = if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(out) => writeln!(stdout, "{}", out),
+            Err(e) => {
+                eprintln!("Failed to serialize output: {}", e);
+                return;
+            }
+        }
+    } else {
+        write!(stdout, "{}", report)
+    };
+}
+
+fn path_str(project_path: &Path) -> String {
+    project_path.to_string_lossy().to_string()
+}
+
+// =============================================================================
+// PRDs
+// =============================================================================
+
+/// A PRD with its progress
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrdSummary {
+    /// PRD file name (without extension)
+    pub name: String,
+    pub title: String,
+    pub branch: String,
+    pub status: PrdStatus,
+    pub last_execution_id: Option<String>,
+}
+
+/// The project's PRDs
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct PrdList(pub Vec<PrdSummary>);
+
+impl fmt::Display for PrdList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "No PRDs found");
+        }
+        for prd in &self.0 {
+            writeln!(
+                f,
+                "{:<32} {:>3}/{:<3} {:>5.1}%  {}",
+                prd.name,
+                prd.status.passed,
+                prd.status.total,
+                prd.status.progress_percent,
+                prd.title
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// List the project's PRDs with their status
+pub fn list_prds(project_path: &Path) -> Result<PrdList, String> {
+    let project = path_str(project_path);
+    let files = ralph_commands::get_ralph_files(project.clone())?;
+
+    let mut prds = Vec::new();
+    for name in files.prd_names {
+        // Other JSON files (e.g. extracted structures) live next to PRDs
+        let Ok(prd) = ralph_commands::get_ralph_prd(project.clone(), name.clone()) else {
+            log::debug!("[CLI] Skipping unreadable PRD {}", name);
+            continue;
+        };
+        let status = ralph_commands::get_ralph_prd_status(project.clone(), name.clone())?;
+        prds.push(PrdSummary {
+            name,
+            title: prd.title,
+            branch: prd.branch,
+            status,
+            last_execution_id: prd.metadata.and_then(|m| m.last_execution_id),
+        });
+    }
+    prds.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(PrdList(prds))
+}
+
+// =============================================================================
+// Executions
+// =============================================================================
+
+/// An execution recorded in the project's iteration history
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionSummary {
+    pub execution_id: String,
+    /// PRD whose latest execution this is
+    pub prd_name: Option<String>,
+    /// Last saved loop state, while the execution hasn't cleaned up after itself
+    pub state: Option<RalphLoopState>,
+    /// Whether the execution is still sending heartbeats
+    pub active: bool,
+    pub last_heartbeat: Option<String>,
+    pub started_at: Option<String>,
+    pub stats: IterationStats,
+}
+
+/// The project's executions, newest first
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct ExecutionList(pub Vec<ExecutionSummary>);
+
+fn state_label(state: &RalphLoopState) -> String {
+    match state {
+        RalphLoopState::Idle => "idle".to_string(),
+        RalphLoopState::Running { iteration } => format!("running (iteration {})", iteration),
+        RalphLoopState::Retrying {
+            iteration, attempt, ..
+        } => format!("retrying (iteration {}, attempt {})", iteration, attempt),
+        RalphLoopState::Paused { iteration, .. } => format!("paused (iteration {})", iteration),
+        RalphLoopState::Completed { .. } => "completed".to_string(),
+        RalphLoopState::Failed { .. } => "failed".to_string(),
+        RalphLoopState::Cancelled { .. } => "cancelled".to_string(),
+    }
+}
+
+impl fmt::Display for ExecutionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match (&self.state, self.active) {
+            (Some(state), true) => state_label(state),
+            (Some(_), false) => "stale".to_string(),
+            (None, _) => "finished".to_string(),
+        };
+        write!(
+            f,
+            "{}  {:<24} {:<26} {} iterations ({} ok, {} failed)  {}",
+            self.execution_id,
+            self.prd_name.as_deref().unwrap_or("-"),
+            state,
+            self.stats.total,
+            self.stats.successful,
+            self.stats.failed,
+            self.started_at.as_deref().unwrap_or("")
+        )
+    }
+}
+
+impl fmt::Display for ExecutionList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "No executions found");
+        }
+        for execution in &self.0 {
+            writeln!(f, "{}", execution)?;
+        }
+        Ok(())
+    }
+}
+
+/// IDs of executions with iteration history or transcripts
+fn execution_ids(project_path: &Path) -> Result<BTreeSet<String>, String> {
+    let mut ids = BTreeSet::new();
+
+    let iterations_dir = iteration_storage::get_iterations_dir(project_path);
+    if let Ok(entries) = std::fs::read_dir(&iterations_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(stem) = path.file_stem() {
+                    ids.insert(stem.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+
+    for transcript in transcripts::list_transcripts(project_path, &TranscriptFilter::default())? {
+        ids.insert(transcript.execution_id);
+    }
+    Ok(ids)
+}
+
+/// List the project's executions, newest first
+pub fn list_executions(project_path: &Path) -> Result<ExecutionList, String> {
+    let prds = list_prds(project_path)?;
+    let now = chrono::Utc::now();
+
+    let mut executions = Vec::new();
+    for execution_id in execution_ids(project_path)? {
+        let stats = iteration_storage::get_execution_stats(project_path, &execution_id)?;
+        let iterations =
+            iteration_storage::get_iterations_for_execution(project_path, &execution_id)?;
+        let saved = iteration_storage::get_execution_state(project_path, &execution_id)?;
+
+        let active = saved.as_ref().is_some_and(|s| {
+            chrono::DateTime::parse_from_rfc3339(&s.last_heartbeat).is_ok_and(|hb| {
+                (now - hb.with_timezone(&chrono::Utc)).num_seconds() < ACTIVE_HEARTBEAT_SECS
+            })
+        });
+
+        executions.push(ExecutionSummary {
+            prd_name: prds
+                .0
+                .iter()
+                .find(|p| p.last_execution_id.as_deref() == Some(&execution_id))
+                .map(|p| p.name.clone()),
+            state: saved
+                .as_ref()
+                .and_then(|s| serde_json::from_str(&s.state).ok()),
+            active,
+            last_heartbeat: saved.map(|s| s.last_heartbeat),
+            started_at: iterations.iter().map(|i| i.started_at.clone()).min(),
+            stats,
+            execution_id,
+        });
+    }
+
+    // Executions without iterations yet sort first (they just started)
+    executions.sort_by(|a, b| match (&a.started_at, &b.started_at) {
+        (Some(a), Some(b)) => b.cmp(a),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    });
+    Ok(ExecutionList(executions))
+}
+
+/// Resolve a (possibly abbreviated) execution ID
+pub fn resolve_execution_id(project_path: &Path, id: &str) -> Result<String, String> {
+    let ids = execution_ids(project_path)?;
+    if ids.contains(id) {
+        return Ok(id.to_string());
+    }
+    let matches: Vec<&String> = ids.iter().filter(|e| e.starts_with(id)).collect();
+    match matches.as_slice() {
+        [single] => Ok(single.to_string()),
+        [] => Err(format!("No execution found with ID: {}", id)),
+        _ => Err(format!(
+            "Execution ID {} is ambiguous ({} matches)",
+            id,
+            matches.len()
+        )),
+    }
+}
+
+// =============================================================================
+// Status
+// =============================================================================
+
+/// Overview of a project: PRD progress and running loops
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusReport {
+    pub project_path: String,
+    pub prds: PrdList,
+    pub active_executions: Vec<ExecutionSummary>,
+}
+
+impl fmt::Display for StatusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Project: {}", self.project_path)?;
+        writeln!(f)?;
+        writeln!(f, "PRDs:")?;
+        write!(f, "{}", self.prds)?;
+        writeln!(f)?;
+        if self.active_executions.is_empty() {
+            writeln!(f, "No running loops")
+        } else {
+            writeln!(f, "Running loops:")?;
+            for execution in &self.active_executions {
+                writeln!(f, "{}", execution)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Project status: PRD progress and loops that are still sending heartbeats
+pub fn status(project_path: &Path) -> Result<StatusReport, String> {
+    let active_executions = list_executions(project_path)?
+        .0
+        .into_iter()
+        .filter(|e| e.active)
+        .collect();
+    Ok(StatusReport {
+        project_path: path_str(project_path),
+        prds: list_prds(project_path)?,
+        active_executions,
+    })
+}
+
+// =============================================================================
+// Logs
+// =============================================================================
+
+/// One iteration's record and captured agent output
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IterationLog {
+    pub iteration: u32,
+    pub outcome: Option<IterationOutcome>,
+    pub story_id: Option<String>,
+    pub error_message: Option<String>,
+    pub started_at: Option<String>,
+    /// Agent output lines (empty when no transcript was saved)
+    pub lines: Vec<String>,
+}
+
+/// Logs of an execution's iterations
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionLogs {
+    pub execution_id: String,
+    pub iterations: Vec<IterationLog>,
+}
+
+impl fmt::Display for ExecutionLogs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.iterations.is_empty() {
+            return writeln!(f, "No iterations recorded for {}", self.execution_id);
+        }
+        for log in &self.iterations {
+            let outcome = log
+                .outcome
+                .as_ref()
+                .map(|o| format!("{:?}", o).to_lowercase())
+                .unwrap_or_else(|| "unknown".to_string());
+            write!(f, "=== Iteration {} ({})", log.iteration, outcome)?;
+            if let Some(story_id) = &log.story_id {
+                write!(f, " story {}", story_id)?;
+            }
+            writeln!(f, " ===")?;
+            for line in &log.lines {
+                writeln!(f, "{}", line)?;
+            }
+            if let Some(error) = &log.error_message {
+                writeln!(f, "Error: {}", error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Iteration records and agent output of an execution
+pub fn execution_logs(
+    project_path: &Path,
+    execution_id: &str,
+    iteration: Option<u32>,
+) -> Result<ExecutionLogs, String> {
+    let execution_id = resolve_execution_id(project_path, execution_id)?;
+    let records: Vec<IterationRecord> =
+        iteration_storage::get_iterations_for_execution(project_path, &execution_id)?;
+    let transcripts = transcripts::list_transcripts(
+        project_path,
+        &TranscriptFilter {
+            execution_id: Some(execution_id.clone()),
+            ..Default::default()
+        },
+    )?;
+
+    let numbers: BTreeSet<u32> = records
+        .iter()
+        .map(|r| r.iteration)
+        .chain(transcripts.iter().map(|t| t.iteration))
+        .filter(|n| iteration.map_or(true, |wanted| *n == wanted))
+        .collect();
+
+    let mut iterations = Vec::new();
+    for number in numbers {
+        let record = records.iter().find(|r| r.iteration == number);
+        let transcript = transcripts.iter().find(|t| t.iteration == number);
+        let lines = match transcript {
+            Some(_) => transcripts::read_transcript_part(
+                project_path,
+                &execution_id,
+                number,
+                TranscriptPart::Output,
+            )
+            .map(|data| {
+                String::from_utf8_lossy(&data)
+                    .lines()
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        iterations.push(IterationLog {
+            iteration: number,
+            outcome: record.map(|r| r.outcome.clone()),
+            story_id: transcript.and_then(|t| t.story_id.clone()),
+            error_message: record.and_then(|r| r.error_message.clone()),
+            started_at: record
+                .map(|r| r.started_at.clone())
+                .or_else(|| transcript.map(|t| t.started_at.clone())),
+            lines,
+        });
+    }
+
+    Ok(ExecutionLogs {
+        execution_id,
+        iterations,
+    })
+}
+
+// =============================================================================
+// Import
+// =============================================================================
+
+/// A PRD created from a document
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedPrd {
+    pub name: String,
+    pub title: String,
+    pub branch: String,
+    pub story_count: usize,
+}
+
+impl fmt::Display for ImportedPrd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Imported PRD {} ({}) with {} stories on branch {}",
+            self.name, self.title, self.story_count, self.branch
+        )
+    }
+}
+
+/// PRD name from a file name: lowercase words joined by dashes
+fn prd_name_from_file(file: &Path) -> String {
+    let stem = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name = stem
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if name.is_empty() {
+        "imported-prd".to_string()
+    } else {
+        name
+    }
+}
+
+/// Convert a parsed PRD document into a Ralph PRD
+fn document_to_prd(doc: PRDDocument, branch: String) -> RalphPrd {
+    let mut prd = RalphPrd::new(doc.title, branch);
+    prd.description = doc.description;
+    for (index, task) in doc.tasks.into_iter().enumerate() {
+        let id = task
+            .id
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| format!("story-{}", index + 1));
+        let acceptance = if task.description.trim().is_empty() {
+            task.title.clone()
+        } else {
+            task.description.clone()
+        };
+        let mut story = RalphStory::new(id, task.title, acceptance);
+        if !task.description.trim().is_empty() {
+            story.description = Some(task.description);
+        }
+        story.priority = task
+            .priority
+            .map(|p| p.max(0) as u32)
+            .unwrap_or(index as u32 + 1);
+        story.dependencies = task.dependencies;
+        story.tags = task.tags;
+        prd.add_story(story);
+    }
+    prd
+}
+
+/// Import a Markdown, YAML or JSON PRD document as `.ralph-ui/prds/<name>.json`
+pub fn import_prd(
+    project_path: &Path,
+    file: &Path,
+    name: Option<String>,
+    overwrite: bool,
+) -> Result<ImportedPrd, String> {
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let doc = parse_prd_auto(&content).map_err(|e| format!("Failed to parse PRD: {}", e))?;
+    if doc.tasks.is_empty() {
+        return Err(format!("No stories found in {}", file.display()));
+    }
+
+    let name = name.unwrap_or_else(|| prd_name_from_file(file));
+    let executor = PrdExecutor::new(project_path, &name);
+    if executor.prd_exists() && !overwrite {
+        return Err(format!(
+            "PRD {} already exists (use --force to replace it)",
+            name
+        ));
+    }
+
+    let prd = document_to_prd(doc, format!("ralph-{}", name));
+    executor.write_prd(&prd)?;
+    log::info!("[CLI] Imported PRD {} from {}", name, file.display());
+
+    Ok(ImportedPrd {
+        name,
+        title: prd.title,
+        branch: prd.branch,
+        story_count: prd.stories.len(),
+    })
+}
+
+// =============================================================================
+// Migrations
+// =============================================================================
+
+/// Outcome of a schema migration pass
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct MigrationSummary(pub Vec<MigrationReport>);
+
+impl fmt::Display for MigrationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dry_run = self.0.first().is_some_and(|r| r.dry_run);
+        let mut up_to_date = true;
+        for report in &self.0 {
+            if report.migrated.is_empty() && report.errors.is_empty() {
+                continue;
+            }
+            up_to_date = false;
+            writeln!(f, "{}", report.base_dir)?;
+            for doc in &report.migrated {
+                writeln!(
+                    f,
+                    "  {:<13} {:<40} v{} -> v{}  ({})",
+                    if dry_run { "would migrate" } else { "migrated" },
+                    doc.path,
+                    doc.from_version,
+                    doc.to_version,
+                    doc.steps.join(", ")
+                )?;
+            }
+            for error in &report.errors {
+                writeln!(f, "  {:<13} {:<40} {}", "error", error.path, error.error)?;
+            }
+            if let Some(backup_dir) = &report.backup_dir {
+                writeln!(f, "  originals backed up to {}", backup_dir)?;
+            }
+        }
+        if up_to_date {
+            writeln!(f, "All documents are up to date")?;
+        }
+        Ok(())
+    }
+}
+
+impl MigrationSummary {
+    /// Fail if any document couldn't be migrated
+    pub fn check(&self) -> Result<(), String> {
+        let failed: usize = self.0.iter().map(|r| r.errors.len()).sum();
+        if failed > 0 {
+            return Err(format!("{} document(s) could not be migrated", failed));
+        }
+        Ok(())
+    }
+}
+
+/// Migrate a project's stored documents, or the global ones and those of all
+/// registered projects
+pub fn migrate(project_path: Option<&Path>, dry_run: bool) -> MigrationSummary {
+    MigrationSummary(match project_path {
+        Some(project_path) => vec![migrations::migrate_project(project_path, dry_run)],
+        None => migrations::migrate_all(dry_run),
+    })
+}
+
+// =============================================================================
+// State export and import
+// =============================================================================
+
+/// An archive written by `export-state`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateExport {
+    pub path: String,
+    pub sections: Vec<ArchiveSection>,
+    pub file_count: usize,
+    pub size: usize,
+}
+
+impl fmt::Display for StateExport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections: Vec<String> = self
+            .sections
+            .iter()
+            .map(|s| format!("{:?}", s).to_lowercase())
+            .collect();
+        writeln!(
+            f,
+            "Exported {} file(s) ({}) to {} ({} bytes)",
+            self.file_count,
+            sections.join(", "),
+            self.path,
+            self.size
+        )
+    }
+}
+
+/// Export a project's state to a `.tar.gz` (default: a timestamped file in the
+/// current directory)
+pub fn export_state(
+    project_path: &Path,
+    output: Option<&Path>,
+    options: &ExportOptions,
+) -> Result<StateExport, String> {
+    let archive = project_archive::export_project_state(project_path, options)?;
+    let path = match output {
+        Some(output) => output.to_path_buf(),
+        None => Path::new(".").join(archive.file_name()),
+    };
+    std::fs::write(&path, &archive.data)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(StateExport {
+        path: path.display().to_string(),
+        sections: archive.manifest.sections,
+        file_count: archive.manifest.files.len(),
+        size: archive.data.len(),
+    })
+}
+
+/// Outcome of `import-state`
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct StateImport(pub ImportReport);
+
+impl fmt::Display for StateImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = &self.0;
+        let replace = report.mode == ImportMode::Replace;
+        writeln!(
+            f,
+            "{} {} from {} (exported {})",
+            if report.dry_run {
+                "Would import"
+            } else {
+                "Imported"
+            },
+            report.manifest.project_name,
+            report.manifest.project_path,
+            report.manifest.exported_at.format("%Y-%m-%d %H:%M UTC")
+        )?;
+        for path in &report.added {
+            writeln!(f, "  {:<9} {}", "add", path)?;
+        }
+        for path in &report.conflicts {
+            writeln!(
+                f,
+                "  {:<9} {}",
+                if replace { "replace" } else { "conflict" },
+                path
+            )?;
+        }
+        for path in &report.removed {
+            writeln!(f, "  {:<9} {}", "remove", path)?;
+        }
+        writeln!(
+            f,
+            "{} added, {} unchanged, {} {}, {} removed, {} with rewritten paths",
+            report.added.len(),
+            report.unchanged,
+            report.conflicts.len(),
+            if replace {
+                "replaced"
+            } else {
+                "kept (conflicting)"
+            },
+            report.removed.len(),
+            report.rewritten.len()
+        )?;
+        if let Some(backup_dir) = &report.backup_dir {
+            writeln!(f, "Originals backed up to {}", backup_dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Import a `.tar.gz` written by `export-state` into a project and register it
+pub fn import_state(
+    project_path: &Path,
+    file: &Path,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<StateImport, String> {
+    let data =
+        std::fs::read(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    crate::commands::projects::import_project_archive(project_path, &data, mode, dry_run)
+        .map(StateImport)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AgentType;
+    use crate::ralph_loop::ExecutionStateSnapshot;
+    use tempfile::TempDir;
+
+    const MARKDOWN_PRD: &str = "# Dark Mode\n\nAdd a dark theme.\n\n## Tasks\n\n### Add theme toggle\nA toggle in settings switches themes.\n\n### Persist theme choice\nThe choice survives reloads.\n";
+
+    #[test]
+    fn test_import_prd_and_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("Dark Mode.md");
+        std::fs::write(&file, MARKDOWN_PRD).unwrap();
+
+        let imported = import_prd(temp_dir.path(), &file, None, false).unwrap();
+        assert_eq!(imported.name, "dark-mode");
+        assert_eq!(imported.branch, "ralph-dark-mode");
+        assert!(imported.story_count >= 2);
+
+        // Importing again needs --force
+        assert!(import_prd(temp_dir.path(), &file, None, false).is_err());
+        assert!(import_prd(temp_dir.path(), &file, None, true).is_ok());
+
+        let prds = list_prds(temp_dir.path()).unwrap();
+        assert_eq!(prds.0.len(), 1);
+        assert_eq!(prds.0[0].status.passed, 0);
+        assert_eq!(prds.0[0].status.total, imported.story_count);
+    }
+
+    #[test]
+    fn test_list_executions_and_logs() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path();
+
+        let record = |execution_id: &str, iteration: u32, outcome| IterationRecord {
+            id: format!("{}-{}", execution_id, iteration),
+            execution_id: execution_id.to_string(),
+            iteration,
+            outcome,
+            duration_secs: 10.0,
+            agent_type: AgentType::Claude,
+            rate_limit_encountered: false,
+            error_message: None,
+            started_at: format!("2026-01-0{}T00:00:00Z", iteration),
+            completed_at: None,
+        };
+        iteration_storage::insert_iteration(
+            project,
+            &record("exec-old", 1, IterationOutcome::Success),
+        )
+        .unwrap();
+        iteration_storage::insert_iteration(
+            project,
+            &record("exec-new", 2, IterationOutcome::Failed),
+        )
+        .unwrap();
+        iteration_storage::save_execution_state(
+            project,
+            &ExecutionStateSnapshot {
+                execution_id: "exec-new".to_string(),
+                state: serde_json::to_string(&RalphLoopState::Running { iteration: 2 }).unwrap(),
+                last_heartbeat: chrono::Utc::now().to_rfc3339(),
+                checkpoint: None,
+            },
+        )
+        .unwrap();
+
+        let executions = list_executions(project).unwrap();
+        let ids: Vec<&str> = executions
+            .0
+            .iter()
+            .map(|e| e.execution_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["exec-new", "exec-old"]);
+        assert!(executions.0[0].active);
+        assert!(!executions.0[1].active);
+        assert_eq!(executions.0[0].stats.failed, 1);
+
+        let report = status(project).unwrap();
+        assert_eq!(report.active_executions.len(), 1);
+
+        // Abbreviated IDs resolve when unambiguous
+        let logs = execution_logs(project, "exec-o", None).unwrap();
+        assert_eq!(logs.execution_id, "exec-old");
+        assert_eq!(logs.iterations.len(), 1);
+        assert_eq!(logs.iterations[0].outcome, Some(IterationOutcome::Success));
+        assert!(execution_logs(project, "exec", None).is_err());
+    }
+}
@@ -0,0 +1,357 @@
+//! Headless loop runner for `ralph-ui run`
+//!
+//! Starts the loop through the server's own start path (same config
+//! resolution, preflight, orchestrator and file storage), then follows the
+//! execution snapshot and reports progress until the loop ends.
+
+use crate::commands::ralph_loop::StartRalphLoopRequest;
+use crate::events::EVENT_RALPH_LOOP_ERROR;
+use crate::ralph_loop::{RalphLoopMetrics, RalphLoopState};
+use crate::server::routes::{start_ralph_loop_server, stop_ralph_loop_server};
+use crate::server::ServerAppState;
+use serde::Serialize;
+use std::fmt;
+use std::io::Write;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+/// How often the execution snapshot is checked for progress
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to wait for a cancelled loop to stop before giving up
+const CANCEL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Progress of a headless run, printed as a line of text or JSON
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum RunEvent {
+    Started {
+        execution_id: String,
+        prd_name: String,
+    },
+    Iteration {
+        iteration: u32,
+        stories_completed: u32,
+        stories_remaining: u32,
+        total_cost: f64,
+    },
+    Retrying {
+        iteration: u32,
+        attempt: u32,
+        reason: String,
+    },
+    Paused {
+        iteration: u32,
+        reason: String,
+    },
+    Cancelling,
+    Finished(RunOutcome),
+}
+
+/// Final result of a headless run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunOutcome {
+    pub execution_id: String,
+    /// "completed", "failed" or "cancelled"
+    pub status: String,
+    pub reason: Option<String>,
+    pub iterations: u32,
+    pub stories_completed: u32,
+    pub stories_remaining: u32,
+    pub total_cost: f64,
+    pub duration_secs: f64,
+}
+
+impl RunOutcome {
+    pub fn succeeded(&self) -> bool {
+        self.status == "completed"
+    }
+
+    /// Process exit code: 0 on completion, 1 otherwise
+    pub fn exit_code(&self) -> i32 {
+        if self.succeeded() {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+impl fmt::Display for RunEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunEvent::Started {
+                execution_id,
+                prd_name,
+            } => write!(f, "Started execution {} for PRD {}", execution_id, prd_name),
+            RunEvent::Iteration {
+                iteration,
+                stories_completed,
+                stories_remaining,
+                total_cost,
+            } => write!(
+                f,
+                "Iteration {}: {}/{} stories passing, ${:.2} spent",
+                iteration,
+                stories_completed,
+                stories_completed + stories_remaining,
+                total_cost
+            ),
+            RunEvent::Retrying {
+                iteration,
+                attempt,
+                reason,
+            } => write!(
+                f,
+                "Iteration {}: retrying (attempt {}): {}",
+                iteration, attempt, reason
+            ),
+            RunEvent::Paused { iteration, reason } => {
+                write!(f, "Iteration {}: paused: {}", iteration, reason)
+            }
+            RunEvent::Cancelling => write!(f, "Cancelling..."),
+            RunEvent::Finished(outcome) => {
+                write!(
+                    f,
+                    "Loop {} after {} iterations: {}/{} stories passing, ${:.2} spent, {:.0}s",
+                    outcome.status,
+                    outcome.iterations,
+                    outcome.stories_completed,
+                    outcome.stories_completed + outcome.stories_remaining,
+                    outcome.total_cost,
+                    outcome.duration_secs
+                )?;
+                if let Some(reason) = &outcome.reason {
+                    write!(f, " ({})", reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn emit(event: &RunEvent, json: bool) {
+    let line = if json {
+        match serde_json::to_string(event) {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("Failed to serialize progress: {}", e);
+                return;
+            }
+        }
+    } else {
+        event.to_string()
+    };
+    // Keep running if stdout is closed (e.g. piped into `head`)
+    let _ = writeln!(std::io::stdout().lock(), "{}", line);
+}
+
+/// Progress event for a running state, if the state reports progress
+fn progress_event(state: &RalphLoopState, metrics: &RalphLoopMetrics) -> Option<RunEvent> {
+    match state {
+        RalphLoopState::Running { iteration } => Some(RunEvent::Iteration {
+            iteration: *iteration,
+            stories_completed: metrics.stories_completed,
+            stories_remaining: metrics.stories_remaining,
+            total_cost: metrics.total_cost,
+        }),
+        RalphLoopState::Retrying {
+            iteration,
+            attempt,
+            reason,
+            ..
+        } => Some(RunEvent::Retrying {
+            iteration: *iteration,
+            attempt: *attempt,
+            reason: reason.clone(),
+        }),
+        RalphLoopState::Paused { iteration, reason } => Some(RunEvent::Paused {
+            iteration: *iteration,
+            reason: reason.clone(),
+        }),
+        _ => None,
+    }
+}
+
+fn outcome(
+    execution_id: &str,
+    status: &str,
+    reason: Option<String>,
+    metrics: &RalphLoopMetrics,
+) -> RunOutcome {
+    RunOutcome {
+        execution_id: execution_id.to_string(),
+        status: status.to_string(),
+        reason,
+        iterations: metrics.total_iterations,
+        stories_completed: metrics.stories_completed,
+        stories_remaining: metrics.stories_remaining,
+        total_cost: metrics.total_cost,
+        duration_secs: metrics.total_duration_secs,
+    }
+}
+
+/// Final outcome for a terminal state
+fn terminal_outcome(
+    execution_id: &str,
+    state: &RalphLoopState,
+    metrics: &RalphLoopMetrics,
+) -> Option<RunOutcome> {
+    match state {
+        RalphLoopState::Completed { .. } => Some(outcome(execution_id, "completed", None, metrics)),
+        RalphLoopState::Failed { reason, .. } => Some(outcome(
+            execution_id,
+            "failed",
+            Some(reason.clone()),
+            metrics,
+        )),
+        RalphLoopState::Cancelled { .. } => Some(outcome(execution_id, "cancelled", None, metrics)),
+        _ => None,
+    }
+}
+
+/// Run a loop to completion, printing progress to stdout (JSON lines with
+/// `json`). Ctrl+C cancels the loop. Returns the final outcome.
+pub async fn run_loop(
+    state: &ServerAppState,
+    request: StartRalphLoopRequest,
+    json: bool,
+) -> Result<RunOutcome, String> {
+    // Subscribe first so an immediate failure isn't missed
+    let mut events = state.broadcaster.subscribe();
+    let prd_name = request.prd_name.clone();
+    let execution_id = start_ralph_loop_server(request, state).await?;
+    emit(
+        &RunEvent::Started {
+            execution_id: execution_id.clone(),
+            prd_name,
+        },
+        json,
+    );
+
+    let mut last_progress: Option<RunEvent> = None;
+    let mut cancel_requested_at: Option<std::time::Instant> = None;
+    // Loops that error out don't always leave a terminal state behind
+    let mut loop_error: Option<String> = None;
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            received = events.recv() => match received {
+                Ok(event) if event.event == EVENT_RALPH_LOOP_ERROR
+                    && event.payload.get("executionId").and_then(|v| v.as_str())
+                        == Some(execution_id.as_str()) =>
+                {
+                    loop_error = Some(
+                        event
+                            .payload
+                            .get("message")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Loop failed")
+                            .to_string(),
+                    );
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) | Err(RecvError::Closed) => {}
+            },
+        }
+
+        if state.shutdown_state.is_shutdown_requested() {
+            match cancel_requested_at {
+                None => {
+                    emit(&RunEvent::Cancelling, json);
+                    cancel_requested_at = Some(std::time::Instant::now());
+                    let state = state.clone();
+                    let id = execution_id.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = stop_ralph_loop_server(id, &state).await {
+                            log::warn!("[CLI] Failed to cancel loop: {}", e);
+                        }
+                    });
+                }
+                Some(at) if at.elapsed() > CANCEL_TIMEOUT => {
+                    return Err("Loop did not stop after cancellation".to_string());
+                }
+                Some(_) => {}
+            }
+        }
+
+        let snapshot = state
+            .ralph_loop_state
+            .get_snapshot(&execution_id)
+            .unwrap_or_default();
+        let metrics = snapshot.metrics.unwrap_or_default();
+        let loop_state = snapshot.state.unwrap_or(RalphLoopState::Idle);
+
+        let result = terminal_outcome(&execution_id, &loop_state, &metrics).or_else(|| {
+            loop_error
+                .take()
+                .map(|reason| outcome(&execution_id, "failed", Some(reason), &metrics))
+        });
+        if let Some(result) = result {
+            emit(&RunEvent::Finished(result.clone()), json);
+            return Ok(result);
+        }
+        if let Some(progress) = progress_event(&loop_state, &metrics) {
+            if last_progress.as_ref() != Some(&progress) {
+                emit(&progress, json);
+                last_progress = Some(progress);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_and_outcome() {
+        let metrics = RalphLoopMetrics {
+            total_iterations: 3,
+            stories_completed: 2,
+            stories_remaining: 1,
+            total_cost: 1.5,
+            ..Default::default()
+        };
+
+        let progress = progress_event(&RalphLoopState::Running { iteration: 3 }, &metrics).unwrap();
+        assert_eq!(
+            progress.to_string(),
+            "Iteration 3: 2/3 stories passing, $1.50 spent"
+        );
+        assert_eq!(
+            serde_json::to_value(&progress).unwrap()["storiesCompleted"],
+            2
+        );
+        assert!(progress_event(&RalphLoopState::Idle, &metrics).is_none());
+
+        let failed = terminal_outcome(
+            "exec-1",
+            &RalphLoopState::Failed {
+                iteration: 3,
+                reason: "Max iterations reached".to_string(),
+            },
+            &metrics,
+        )
+        .unwrap();
+        assert_eq!(failed.exit_code(), 1);
+        assert_eq!(failed.reason.as_deref(), Some("Max iterations reached"));
+
+        let completed = terminal_outcome(
+            "exec-1",
+            &RalphLoopState::Completed {
+                total_iterations: 3,
+            },
+            &metrics,
+        )
+        .unwrap();
+        assert_eq!(completed.exit_code(), 0);
+        assert!(terminal_outcome("exec-1", &RalphLoopState::Idle, &metrics).is_none());
+    }
+}
@@ -1,6 +1,7 @@
 // Agent management commands
 // Uses file-based storage in .ralph-ui/agents/
 
+use crate::agents::rate_limit_ledger::{self, RateLimitEntry};
 use crate::agents::{providers, AgentManager, AgentSpawnConfig, AgentSpawnMode};
 use crate::file_storage::agents as agent_storage;
 use crate::models::{Agent, AgentStatus, AgentType, LogEntry};
@@ -89,6 +90,18 @@ pub fn get_all_agents_status() -> Vec<AgentStatusInfo> {
         .collect()
 }
 
+/// Get all active provider rate limits from the shared ledger
+pub fn get_rate_limits() -> Vec<RateLimitEntry> {
+    rate_limit_ledger::global().active_entries()
+}
+
+/// Clear a provider's rate limit (optionally for one model only)
+///
+/// Returns the number of ledger entries removed.
+pub fn clear_rate_limit(agent_type: AgentType, model: Option<String>) -> usize {
+    rate_limit_ledger::global().clear(agent_type, model.as_deref())
+}
+
 /// Create a new agent
 pub fn create_agent(agent: Agent, project_path: String) -> Result<(), String> {
     let path = as_path(&project_path);
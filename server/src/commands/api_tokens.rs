@@ -0,0 +1,26 @@
+// Named API token Backend commands
+
+use crate::file_storage::api_tokens::{self, ApiTokenInfo, CreatedApiToken, NewApiToken};
+
+/// Create a named API token; the plaintext token is only returned here
+pub fn create_api_token(request: NewApiToken) -> Result<CreatedApiToken, String> {
+    let created = api_tokens::create_token(request)?;
+    log::info!(
+        "[ApiTokens] Created token '{}' ({})",
+        created.info.name,
+        created.info.role
+    );
+    Ok(created)
+}
+
+/// Revoke an API token by ID
+pub fn revoke_api_token(token_id: String) -> Result<ApiTokenInfo, String> {
+    let info = api_tokens::revoke_token(&token_id)?;
+    log::info!("[ApiTokens] Revoked token '{}'", info.name);
+    Ok(info)
+}
+
+/// List API tokens (without their hashes)
+pub fn list_api_tokens() -> Result<Vec<ApiTokenInfo>, String> {
+    api_tokens::list_tokens()
+}
@@ -0,0 +1,8 @@
+// Audit log Backend commands
+
+use crate::file_storage::audit::{self, AuditEntry, AuditFilter};
+
+/// Query the audit log, newest entries first
+pub fn query_audit_log(filter: Option<AuditFilter>) -> Result<Vec<AuditEntry>, String> {
+    audit::query_audit_log(&filter.unwrap_or_default())
+}
@@ -0,0 +1,33 @@
+// Federation peer Backend commands
+
+use crate::federation::{self, NewPeer, PeerInfo, PeerStatus};
+
+/// List federation peers (without their tokens)
+pub fn list_federation_peers() -> Result<Vec<PeerInfo>, String> {
+    Ok(federation::get_peers()?
+        .iter()
+        .map(PeerInfo::from)
+        .collect())
+}
+
+/// Add a peer server
+pub fn add_federation_peer(request: NewPeer) -> Result<PeerInfo, String> {
+    let info = federation::add_peer(request)?;
+    log::info!("[Federation] Added peer '{}' ({})", info.name, info.url);
+    Ok(info)
+}
+
+/// Remove a peer server
+pub fn remove_federation_peer(peer_id: String) -> Result<bool, String> {
+    let removed = federation::remove_peer(&peer_id)?;
+    if removed {
+        log::info!("[Federation] Removed peer {}", peer_id);
+    }
+    Ok(removed)
+}
+
+/// Check reachability, token and version of every enabled peer
+pub async fn get_federation_status() -> Result<Vec<PeerStatus>, String> {
+    let peers = federation::get_enabled_peers()?;
+    Ok(federation::check_peers(&peers).await)
+}
@@ -1,9 +1,10 @@
+use crate::file_storage::activity;
 use crate::git::{
     ai_resolver::{ConflictResolver, ConflictResolverConfig, MergeResolutionResult},
     BranchInfo, CommitInfo, ConflictInfo, DiffInfo, FileStatus, GitManager, MergeResult,
     WorktreeInfo,
 };
-use crate::models::AgentType;
+use crate::models::{ActivityEventType, AgentType};
 use crate::utils::ResultExt;
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -220,9 +221,17 @@ pub fn git_merge_branch(
     target_branch: String,
     state: &GitState,
 ) -> Result<MergeResult, String> {
-    state.with_manager(&repo_path, |manager| {
+    let result = state.with_manager(&repo_path, |manager| {
         manager.merge_branch(&source_branch, &target_branch)
-    })
+    })?;
+    if result.success {
+        record_merge(
+            &repo_path,
+            &target_branch,
+            format!("Merged {} into {}", source_branch, target_branch),
+        );
+    }
+    Ok(result)
 }
 
 /// Abort an ongoing merge
@@ -273,9 +282,24 @@ pub fn git_complete_merge(
     author_email: String,
     state: &GitState,
 ) -> Result<CommitInfo, String> {
-    state.with_manager(&repo_path, |manager| {
+    let commit = state.with_manager(&repo_path, |manager| {
         manager.complete_merge(&message, &author_name, &author_email)
-    })
+    })?;
+    record_merge(
+        &repo_path,
+        "",
+        format!("Merged after resolving conflicts: {}", message),
+    );
+    Ok(commit)
+}
+
+/// Record a merge in the activity journal of a Ralph UI project
+/// (other repositories, such as agent worktrees, are left untouched)
+fn record_merge(repo_path: &str, branch: &str, description: String) {
+    let path = std::path::Path::new(repo_path);
+    if crate::file_storage::get_ralph_ui_dir(path).is_dir() {
+        activity::record_activity(path, ActivityEventType::BranchMerged, branch, description);
+    }
 }
 
 /// Push a branch to the remote repository
@@ -1,109 +1,51 @@
 // Backend commands for Mission Control dashboard
-// Uses file-based storage for aggregating stats across projects
+// Uses file-based storage for aggregating stats across projects, and the
+// federation peers for aggregating across servers
 
+use crate::commands::ralph_loop::ExecutionInfo;
+use crate::federation::{self, LOCAL_SERVER_ID, LOCAL_SERVER_NAME};
+use crate::file_storage::activity::{self as activity_storage, ActivityPage, ActivityQuery};
 use crate::file_storage::agents as agent_storage;
 use crate::file_storage::projects as project_storage;
 use crate::file_storage::sessions as session_storage;
-use crate::models::{ActivityEvent, ActivityEventType, SessionStatus, TaskStatus};
+use crate::models::{ActivityEvent, TaskStatus};
 use crate::utils::as_path;
 
 /// Get activity feed for Mission Control dashboard
-/// Aggregates events from tasks and sessions across all projects
-///
-/// Performance notes:
-/// - Reads full session data because activity events need task-level details
-///   (task.started_at, task.completed_at, task.title, task.status)
-/// - Session index doesn't contain this task-level information
-/// - Future optimization: Store recent activity events in a separate activity log
+/// Reads the activity index, which merges the per-project activity journals
+/// (pages past the indexed events are read from the journals)
 pub fn get_activity_feed(
     limit: Option<i32>,
-    _offset: Option<i32>,
+    offset: Option<i32>,
 ) -> Result<Vec<ActivityEvent>, String> {
-    let limit = limit.unwrap_or(50) as usize;
-
-    let mut events: Vec<ActivityEvent> = Vec::new();
-
-    // Get all registered projects
-    let projects = project_storage::get_all_projects().unwrap_or_default();
-
-    for project in projects {
-        let project_path = as_path(&project.path);
-        if !project_path.exists() {
-            continue;
+    let limit = limit.unwrap_or(50).max(0) as usize;
+    let offset = offset.unwrap_or(0).max(0) as usize;
+
+    // Skip `offset` events by taking the cursor after them
+    let cursor = if offset > 0 {
+        let skipped = activity_storage::query_activity(&ActivityQuery {
+            limit: Some(offset),
+            ..Default::default()
+        })?;
+        match skipped.next_cursor {
+            Some(cursor) => Some(cursor),
+            None => return Ok(Vec::new()),
         }
+    } else {
+        None
+    };
 
-        // Get sessions for this project
-        let sessions = session_storage::list_sessions(project_path).unwrap_or_default();
-
-        for session in sessions {
-            let project_name = project.name.clone();
-
-            // Add session start event
-            let event_type = match session.status {
-                SessionStatus::Completed => ActivityEventType::SessionCompleted,
-                _ => ActivityEventType::SessionStarted,
-            };
-
-            let description = match event_type {
-                ActivityEventType::SessionStarted => format!("Session started: {}", session.name),
-                ActivityEventType::SessionCompleted => {
-                    format!("Session completed: {}", session.name)
-                }
-                _ => session.name.clone(),
-            };
-
-            events.push(ActivityEvent {
-                id: format!("session-{}", session.id),
-                timestamp: session.created_at,
-                event_type,
-                project_path: session.project_path.clone(),
-                project_name: project_name.clone(),
-                session_name: session.name.clone(),
-                description,
-            });
-
-            // Add task events
-            for task in &session.tasks {
-                // Determine event type and timestamp
-                let (event_type, timestamp) = if let Some(completed_at) = task.completed_at {
-                    let event_type = match task.status {
-                        TaskStatus::Failed => ActivityEventType::TaskFailed,
-                        _ => ActivityEventType::TaskCompleted,
-                    };
-                    (event_type, completed_at)
-                } else if let Some(started_at) = task.started_at {
-                    (ActivityEventType::TaskStarted, started_at)
-                } else {
-                    continue;
-                };
-
-                let description = match event_type {
-                    ActivityEventType::TaskCompleted => format!("Completed: {}", task.title),
-                    ActivityEventType::TaskStarted => format!("Started: {}", task.title),
-                    ActivityEventType::TaskFailed => format!("Failed: {}", task.title),
-                    _ => task.title.clone(),
-                };
-
-                events.push(ActivityEvent {
-                    id: format!("task-{}", task.id),
-                    timestamp,
-                    event_type,
-                    project_path: session.project_path.clone(),
-                    project_name: project_name.clone(),
-                    session_name: session.name.clone(),
-                    description,
-                });
-            }
-        }
-    }
-
-    // Sort by timestamp descending
-    events.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-    // Apply limit
-    events.truncate(limit);
+    let page = activity_storage::query_activity(&ActivityQuery {
+        cursor,
+        limit: Some(limit),
+        ..Default::default()
+    })?;
+    Ok(page.events)
+}
 
-    Ok(events)
+/// Get one page of the activity feed with filters, continuing from `query.cursor`
+pub fn get_activity_page(query: Option<ActivityQuery>) -> Result<ActivityPage, String> {
+    activity_storage::query_activity(&query.unwrap_or_default())
 }
 
 /// Get global statistics for Mission Control dashboard
@@ -180,6 +122,7 @@ pub fn get_global_stats() -> Result<GlobalStats, String> {
         total_cost_today,
         active_projects_count,
         total_projects,
+        servers: Vec::new(),
     })
 }
 
@@ -192,6 +135,159 @@ pub struct GlobalStats {
     pub total_cost_today: f64,
     pub active_projects_count: i32,
     pub total_projects: i32,
+    /// Per-server breakdown when federation peers are configured
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<ServerStats>,
+}
+
+impl GlobalStats {
+    fn add(&mut self, other: &GlobalStats) {
+        self.active_agents_count += other.active_agents_count;
+        self.tasks_in_progress += other.tasks_in_progress;
+        self.tasks_completed_today += other.tasks_completed_today;
+        self.total_cost_today += other.total_cost_today;
+        self.active_projects_count += other.active_projects_count;
+        self.total_projects += other.total_projects;
+    }
+}
+
+/// Stats of one server in a federation
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStats {
+    pub server_id: String,
+    pub server_name: String,
+    /// Whether the server answered (always true for this server)
+    pub healthy: bool,
+    pub latency_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<GlobalStats>,
+}
+
+/// A running execution on a server of the federation
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetExecution {
+    #[serde(flatten)]
+    pub execution: ExecutionInfo,
+    pub server_id: String,
+    pub server_name: String,
+}
+
+/// Activity feed of this server and its federation peers
+///
+/// Peer events are tagged with their server; unreachable peers are skipped
+/// (their health shows up in `get_global_stats`).
+pub async fn get_fleet_activity_feed(
+    limit: Option<i32>,
+    offset: Option<i32>,
+) -> Result<Vec<ActivityEvent>, String> {
+    let peers = federation::get_enabled_peers()?;
+    if peers.is_empty() {
+        return get_activity_feed(limit, offset);
+    }
+
+    // Each server's first `offset + limit` events cover the merged page
+    let limit = limit.unwrap_or(50).max(0) as usize;
+    let offset = offset.unwrap_or(0).max(0) as usize;
+    let window = i32::try_from(offset.saturating_add(limit)).unwrap_or(i32::MAX);
+    let mut events = get_activity_feed(Some(window), None)?;
+    let args = serde_json::json!({ "limit": window });
+    for peer_result in
+        federation::invoke_peers::<Vec<ActivityEvent>>(&peers, "get_activity_feed", args).await
+    {
+        let Ok(peer_events) = peer_result.result else {
+            continue;
+        };
+        events.extend(peer_events.into_iter().map(|mut event| {
+            event.id = format!("{}:{}", peer_result.peer.id, event.id);
+            event.server_id = Some(peer_result.peer.id.clone());
+            event.server_name = Some(peer_result.peer.name.clone());
+            event
+        }));
+    }
+
+    events.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+    Ok(events.into_iter().skip(offset).take(limit).collect())
+}
+
+/// Global statistics summed over this server and the peers that answered,
+/// with a per-server breakdown
+pub async fn get_fleet_global_stats() -> Result<GlobalStats, String> {
+    let local = get_global_stats()?;
+    let peers = federation::get_enabled_peers()?;
+    if peers.is_empty() {
+        return Ok(local);
+    }
+
+    let mut total = local.clone();
+    let mut servers = vec![ServerStats {
+        server_id: LOCAL_SERVER_ID.to_string(),
+        server_name: LOCAL_SERVER_NAME.to_string(),
+        healthy: true,
+        latency_ms: 0,
+        error: None,
+        stats: Some(local),
+    }];
+    for peer_result in
+        federation::invoke_peers::<GlobalStats>(&peers, "get_global_stats", serde_json::json!({}))
+            .await
+    {
+        let status = peer_result.status();
+        if let Ok(stats) = &peer_result.result {
+            total.add(stats);
+        }
+        servers.push(ServerStats {
+            server_id: status.peer_id,
+            server_name: status.name,
+            healthy: status.healthy,
+            latency_ms: status.latency_ms,
+            error: status.error,
+            stats: peer_result.result.ok(),
+        });
+    }
+
+    total.servers = servers;
+    Ok(total)
+}
+
+/// Running executions of this server and its federation peers, tagged by server
+pub async fn list_fleet_executions(
+    local: Vec<ExecutionInfo>,
+    federated: bool,
+) -> Result<Vec<FleetExecution>, String> {
+    let mut executions: Vec<FleetExecution> = local
+        .into_iter()
+        .map(|execution| FleetExecution {
+            execution,
+            server_id: LOCAL_SERVER_ID.to_string(),
+            server_name: LOCAL_SERVER_NAME.to_string(),
+        })
+        .collect();
+    if !federated {
+        return Ok(executions);
+    }
+
+    let peers = federation::get_enabled_peers()?;
+    for peer_result in federation::invoke_peers::<Vec<FleetExecution>>(
+        &peers,
+        "list_fleet_executions",
+        serde_json::json!({}),
+    )
+    .await
+    {
+        let Ok(peer_executions) = peer_result.result else {
+            continue;
+        };
+        executions.extend(peer_executions.into_iter().map(|e| FleetExecution {
+            execution: e.execution,
+            server_id: peer_result.peer.id.clone(),
+            server_name: peer_result.peer.name.clone(),
+        }));
+    }
+    Ok(executions)
 }
 
 #[cfg(test)]
@@ -200,7 +296,9 @@ mod tests {
     use crate::file_storage::{
         agents as agent_storage, projects as project_storage, sessions as session_storage,
     };
-    use crate::models::{Agent, AgentStatus, AgentType, Session, SessionConfig, Task};
+    use crate::models::{
+        Agent, AgentStatus, AgentType, Session, SessionConfig, SessionStatus, Task,
+    };
     use chrono::Utc;
     use tempfile::TempDir;
     use uuid::Uuid;
@@ -534,4 +632,29 @@ mod tests {
         let active_agents = agent_storage::get_all_active_agents(path).unwrap();
         assert_eq!(active_agents.len(), 0);
     }
+
+    #[test]
+    fn test_fleet_stats_sum_and_serialization() {
+        let stats = GlobalStats {
+            active_agents_count: 2,
+            tasks_in_progress: 3,
+            tasks_completed_today: 1,
+            total_cost_today: 0.5,
+            active_projects_count: 1,
+            total_projects: 4,
+            servers: Vec::new(),
+        };
+        let json = serde_json::to_value(&stats).unwrap();
+        assert!(json.get("servers").is_none());
+
+        let mut total = stats.clone();
+        total.add(&stats);
+        assert_eq!(total.active_agents_count, 4);
+        assert_eq!(total.total_projects, 8);
+        assert!((total.total_cost_today - 1.0).abs() < f64::EPSILON);
+
+        // Peers answer with local stats only, which deserialize without `servers`
+        let parsed: GlobalStats = serde_json::from_value(json).unwrap();
+        assert!(parsed.servers.is_empty());
+    }
 }
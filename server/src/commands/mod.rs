@@ -1,10 +1,13 @@
 // Backend command handlers for IPC communication
 
 pub mod agents;
+pub mod api_tokens;
+pub mod audit;
 pub mod chat_commands;
 pub mod config;
 pub mod context;
 pub mod context_chat;
+pub mod federation;
 pub mod git;
 pub mod github;
 pub mod mission_control;
@@ -16,13 +19,19 @@ pub mod projects;
 pub mod providers;
 pub mod push;
 pub mod ralph_loop;
+pub mod recordings;
 pub mod recovery;
+pub mod search;
 pub mod sessions;
+pub mod share_links;
 pub mod tasks;
 pub mod templates;
 pub mod terminal;
 pub mod traces;
+pub mod transcripts;
+pub mod triggers;
 pub mod ultra_research;
+pub mod webhooks;
 
 // Re-export all commands for easy registration
 pub use agents::*;
@@ -3,6 +3,8 @@
 //! This module provides the implementation for executing chat agents
 //! with streaming output via WebSocket broadcast.
 
+use crate::agents::rate_limit_ledger::{self, DEFAULT_RATE_LIMIT_BACKOFF_MS};
+use crate::agents::rate_limiter::RateLimitDetector;
 use crate::events::{
     MdFileDetectedPayload, ToolCallCompletedPayload, ToolCallStartedPayload,
     EVENT_MD_FILE_DETECTED, EVENT_TOOL_CALL_COMPLETED, EVENT_TOOL_CALL_STARTED,
@@ -12,7 +14,7 @@ use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
 use std::sync::LazyLock;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
@@ -290,6 +292,9 @@ pub async fn execute_chat_agent_with_env<E: ChatEventEmitter>(
     external_session_id: Option<&str>,
     env_vars: Option<&HashMap<String, String>>,
 ) -> Result<ChatAgentResult, String> {
+    // Don't bother spawning a provider that another run just saw rate-limited
+    rate_limit_ledger::ensure_available(agent_type, None)?;
+
     let (program, args) = build_agent_command(agent_type, prompt, external_session_id);
 
     // Log the command being executed
@@ -381,6 +386,8 @@ pub async fn execute_chat_agent_with_env<E: ChatEventEmitter>(
         .take()
         .ok_or_else(|| "Failed to capture stdout".to_string())?;
 
+    let mut stderr = child.stderr.take();
+
     let mut reader = BufReader::new(stdout).lines();
     let mut accumulated = String::new();
 
@@ -455,6 +462,22 @@ pub async fn execute_chat_agent_with_env<E: ChatEventEmitter>(
         .map_err(|e| format!("Failed to wait for process: {}", e))?;
 
     if !status.success() {
+        // Record rate limits in the shared ledger so other runs back off too
+        let mut stderr_output = String::new();
+        if let Some(stderr) = stderr.as_mut() {
+            let _ = stderr.read_to_string(&mut stderr_output).await;
+        }
+        let combined = format!("{}\n{}", stderr_output, accumulated);
+        if let Some(info) = RateLimitDetector::new().detect_in_stderr(&combined) {
+            rate_limit_ledger::global().record(
+                agent_type,
+                None,
+                Some(&info),
+                "prd_chat",
+                DEFAULT_RATE_LIMIT_BACKOFF_MS,
+            );
+        }
+
         // Check for common interrupt signals
         if let Some(code) = status.code() {
             if code == 130 || code == 137 || code == 143 {
@@ -1,7 +1,7 @@
 // PRD Chat session operations - CRUD for chat sessions
 
-use crate::file_storage::chat_ops;
-use crate::models::{AgentType, ChatMessage, ChatSession, MessageRole, PRDType};
+use crate::file_storage::{activity, chat_ops};
+use crate::models::{ActivityEventType, AgentType, ChatMessage, ChatSession, MessageRole, PRDType};
 use crate::utils::as_path;
 use uuid::Uuid;
 
@@ -104,6 +104,14 @@ pub async fn start_prd_chat_session(
     chat_ops::create_chat_session(project_path_obj, &session)
         .map_err(|e| format!("Failed to create chat session: {}", e))?;
 
+    let chat_title = default_title.unwrap_or_else(|| "Untitled chat".to_string());
+    activity::record_activity(
+        project_path_obj,
+        ActivityEventType::ChatStarted,
+        chat_title.as_str(),
+        format!("Chat started: {}", chat_title),
+    );
+
     // If guided mode is enabled, add an initial welcome message with the first question
     if guided_mode {
         let welcome_message = generate_welcome_message(prd_type_enum.as_ref());
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 // Request Types
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StartChatSessionRequest {
     pub agent_type: String,
@@ -71,7 +71,7 @@ impl SendMessageRequest {
 // Response Types
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SendMessageResponse {
     pub user_message: ChatMessage,
@@ -2,7 +2,10 @@
 //
 // Uses file-based storage in ~/.ralph-ui/projects.json (global registry)
 
+use crate::file_storage::migrations;
+use crate::file_storage::project_archive::{self, ArchiveManifest, ExportOptions, ImportMode};
 use crate::file_storage::projects::{self as file_projects, ApiFolder, ApiProject};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -90,6 +93,9 @@ pub fn register_project(path: String, name: Option<String>) -> Result<ApiProject
             log::warn!("Failed to initialize .ralph-ui directory: {}", e);
         }
 
+        // Upgrade documents committed by older versions of Ralph UI
+        migrations::migrate_project(project_path, false).log();
+
         // Note: Session import will be handled by session_files module
         // which now reads from files directly
     }
@@ -116,6 +122,7 @@ pub async fn register_project_with_folder(
         if let Err(e) = crate::file_storage::init_ralph_ui_dir(project_path) {
             log::warn!("Failed to initialize .ralph-ui directory: {}", e);
         }
+        migrations::migrate_project(project_path, false).log();
     }
 
     // Get the updated project (now with folder_id if assigned)
@@ -125,6 +132,82 @@ pub async fn register_project_with_folder(
     Ok(updated.to_api_project())
 }
 
+/// Exported project state, with the archive base64-encoded for transport
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedProjectState {
+    pub file_name: String,
+    pub manifest: ArchiveManifest,
+    pub data: String,
+}
+
+/// Export a project's `.ralph-ui/` state as a `.tar.gz` archive
+pub fn export_project_state(
+    project_path: String,
+    options: Option<ExportOptions>,
+) -> Result<ExportedProjectState, String> {
+    let archive = project_archive::export_project_state(
+        Path::new(&project_path),
+        &options.unwrap_or_default(),
+    )?;
+    Ok(ExportedProjectState {
+        file_name: archive.file_name(),
+        data: BASE64.encode(&archive.data),
+        manifest: archive.manifest,
+    })
+}
+
+/// Import an exported archive into a project and register the project.
+/// A dry run only reports what would be added, replaced and removed.
+pub fn import_project_state(
+    project_path: String,
+    data: String,
+    mode: Option<ImportMode>,
+    dry_run: bool,
+) -> Result<project_archive::ImportReport, String> {
+    let data = BASE64
+        .decode(data.trim())
+        .map_err(|e| format!("Invalid archive encoding: {}", e))?;
+    import_project_archive(
+        Path::new(&project_path),
+        &data,
+        mode.unwrap_or_default(),
+        dry_run,
+    )
+}
+
+/// Import archive bytes into a project, rebuild its indexes and register it
+pub fn import_project_archive(
+    path: &Path,
+    data: &[u8],
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<project_archive::ImportReport, String> {
+    if !path.is_dir() {
+        return Err(format!("Project directory not found: {}", path.display()));
+    }
+
+    let report = project_archive::import_project_state(path, data, mode, dry_run)?;
+    if dry_run {
+        return Ok(report);
+    }
+
+    // Indexes aren't archived; rebuild them from the imported files
+    if let Err(e) = crate::file_storage::sessions::rebuild_session_index(path) {
+        log::warn!("Failed to rebuild session index: {}", e);
+    }
+    if let Err(e) = crate::file_storage::chat::rebuild_chat_index(path) {
+        log::warn!("Failed to rebuild chat index: {}", e);
+    }
+    if let Err(e) = crate::file_storage::transcripts::rebuild_transcript_index(path) {
+        log::warn!("Failed to rebuild transcript index: {}", e);
+    }
+
+    // Registers the project, initializes .ralph-ui and upgrades older documents
+    register_project(path.to_string_lossy().to_string(), None)?;
+    Ok(report)
+}
+
 /// Get a project by ID
 pub fn get_project(project_id: String) -> Result<ApiProject, String> {
     file_projects::get_project(&project_id)?
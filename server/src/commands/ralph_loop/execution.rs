@@ -4,7 +4,7 @@ use crate::commands::ConfigState;
 use crate::file_storage::iterations as iteration_storage;
 use crate::models::AgentType;
 use crate::ralph_loop::{
-    ErrorStrategy, ExecutionSnapshot, ExecutionStateSnapshot, FallbackChainConfig, IterationRecord,
+    ErrorStrategy, ExecutionSnapshot, ExecutionStateSnapshot, IterationRecord,
     ParallelOrchestrator, PrdExecutor, PrdMetadata, RalphLoopConfig, RalphLoopMetrics,
     RalphLoopOrchestrator, RalphLoopState as RalphLoopExecutionState, RetryConfig,
 };
@@ -12,7 +12,9 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use super::helpers::{resolve_config, resolve_config_opt, RalphLoopManagerState};
+use super::helpers::{
+    fallback_chain_config, resolve_config, resolve_config_opt, RalphLoopManagerState,
+};
 use super::notifications::{send_error_notification, send_loop_completion_notification};
 
 // ============================================================================
@@ -20,7 +22,7 @@ use super::notifications::{send_error_notification, send_loop_completion_notific
 // ============================================================================
 
 /// Execution mode for Ralph Loop
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RalphExecutionMode {
     /// Sequential execution (default) - one story at a time
@@ -31,7 +33,7 @@ pub enum RalphExecutionMode {
 }
 
 /// Request to start a Ralph loop execution
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StartRalphLoopRequest {
     /// Path to the project directory
@@ -70,6 +72,13 @@ pub struct StartRalphLoopRequest {
     pub execution_mode: Option<RalphExecutionMode>,
     /// Maximum parallel agents when using parallel execution mode (default: 3)
     pub max_parallel: Option<u32>,
+    /// Start even if preflight diagnostics report hard failures (default: false)
+    #[serde(default)]
+    pub force: Option<bool>,
+    /// Take over the PRD's lease from a loop that still holds it, e.g. in
+    /// another Ralph UI instance (default: false)
+    #[serde(default)]
+    pub takeover: Option<bool>,
 }
 
 /// Response from starting a Ralph loop
@@ -84,7 +93,7 @@ pub struct StartRalphLoopResponse {
 }
 
 /// Info about an active execution
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExecutionInfo {
     pub execution_id: String,
@@ -94,7 +103,7 @@ pub struct ExecutionInfo {
 
 /// Consolidated snapshot response for efficient polling
 /// Combines state, metrics, agent ID, worktree path, and iteration history in a single IPC call
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RalphLoopSnapshot {
     pub state: Option<RalphLoopExecutionState>,
@@ -256,39 +265,18 @@ pub async fn start_ralph_loop(
 
     // Get fallback config from user settings
 
-    let fallback_config = user_config.as_ref().and_then(|config| {
-        if config.fallback.enabled {
-            let fallback_chain = config
-                .fallback
-                .fallback_chain
-                .clone()
-                .map(|chain| {
-                    // Convert string agent names to AgentType
-                    chain
-                        .into_iter()
-                        .filter_map(|s| match s.to_lowercase().as_str() {
-                            "claude" => Some(AgentType::Claude),
-                            "opencode" => Some(AgentType::Opencode),
-                            "cursor" => Some(AgentType::Cursor),
-                            "codex" => Some(AgentType::Codex),
-                            _ => None,
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .unwrap_or_else(|| vec![agent_type]);
-
-            Some(FallbackChainConfig {
-                fallback_chain,
-                test_primary_recovery: config.fallback.test_primary_recovery.unwrap_or(true),
-                recovery_test_interval: config.fallback.recovery_test_interval.unwrap_or(5),
-                base_backoff_ms: config.fallback.base_backoff_ms,
-                max_backoff_ms: config.fallback.max_backoff_ms,
-                enabled: true,
-            })
-        } else {
-            None
-        }
-    });
+    let fallback_config = user_config
+        .as_ref()
+        .and_then(|config| fallback_chain_config(&config.fallback, agent_type));
+
+    let transcript_settings = user_config
+        .as_ref()
+        .map(|c| c.transcripts.clone())
+        .unwrap_or_default();
+    let recording_settings = user_config
+        .as_ref()
+        .map(|c| c.recordings.clone())
+        .unwrap_or_default();
 
     // Convert ErrorStrategyConfig from user settings to ErrorStrategy
     let error_strategy = user_config
@@ -341,6 +329,8 @@ pub async fn start_ralph_loop(
         env_vars,
         execution_mode: request.execution_mode.unwrap_or_default(),
         max_parallel: request.max_parallel.unwrap_or(3),
+        transcripts: transcript_settings,
+        recordings: recording_settings,
     };
 
     // Check if parallel mode is requested
@@ -381,6 +371,7 @@ pub async fn start_ralph_loop(
     // === SEQUENTIAL EXECUTION PATH ===
     // Create orchestrator
     let mut orchestrator = RalphLoopOrchestrator::new(config.clone());
+    orchestrator.set_rate_limit_ledger(crate::agents::rate_limit_ledger::global());
     let execution_id = orchestrator.execution_id().to_string();
 
     // Get shared snapshots Arc and pass to orchestrator for direct updates
@@ -429,6 +420,7 @@ pub async fn start_ralph_loop(
             execution_id: execution_id.clone(),
             state: serde_json::to_string(&RalphLoopExecutionState::Idle).unwrap_or_default(),
             last_heartbeat: chrono::Utc::now().to_rfc3339(),
+            checkpoint: None,
         };
         iteration_storage::save_execution_state(&project_path_buf, &initial_state)
             .map_err(|e| format!("Failed to save initial execution state: {}", e))?;
@@ -474,6 +466,7 @@ pub async fn start_ralph_loop(
                     orchestrator.state(),
                     RalphLoopExecutionState::Running { .. }
                         | RalphLoopExecutionState::Retrying { .. }
+                        | RalphLoopExecutionState::Paused { .. }
                 )
             };
 
@@ -770,6 +763,7 @@ async fn start_parallel_ralph_loop(
             execution_id: execution_id.clone(),
             state: serde_json::to_string(&RalphLoopExecutionState::Idle).unwrap_or_default(),
             last_heartbeat: chrono::Utc::now().to_rfc3339(),
+            checkpoint: None,
         };
         iteration_storage::save_execution_state(&project_path_buf, &initial_state)
             .map_err(|e| format!("Failed to save initial execution state: {}", e))?;
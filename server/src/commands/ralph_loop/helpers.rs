@@ -1,10 +1,12 @@
 //! Common helper functions and types for Ralph Loop commands
 
+use crate::config::FallbackSettings;
+use crate::models::AgentType;
 use crate::ralph_loop::{
-    ExecutionSnapshot, PrdExecutor, ProgressTracker, PromptBuilder, RalphLoopOrchestrator,
-    SnapshotStore,
+    ExecutionSnapshot, FallbackChainConfig, PrdExecutor, ProgressTracker, PromptBuilder,
+    RalphLoopOrchestrator, SnapshotStore,
 };
-use crate::utils::to_path_buf;
+use crate::utils::{lock_mutex_recover, to_path_buf};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -55,6 +57,44 @@ pub(crate) fn resolve_config_opt<T>(
     request_val.or(prd_val).or(global_val)
 }
 
+/// Build the loop's fallback chain from user settings.
+/// Returns None when fallback is disabled; an unset chain falls back to the primary agent only.
+pub(crate) fn fallback_chain_config(
+    settings: &FallbackSettings,
+    agent_type: AgentType,
+) -> Option<FallbackChainConfig> {
+    if !settings.enabled {
+        return None;
+    }
+
+    let fallback_chain = settings
+        .fallback_chain
+        .clone()
+        .map(|chain| {
+            // Convert string agent names to AgentType
+            chain
+                .into_iter()
+                .filter_map(|s| match s.to_lowercase().as_str() {
+                    "claude" => Some(AgentType::Claude),
+                    "opencode" => Some(AgentType::Opencode),
+                    "cursor" => Some(AgentType::Cursor),
+                    "codex" => Some(AgentType::Codex),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_else(|| vec![agent_type]);
+
+    Some(FallbackChainConfig {
+        fallback_chain,
+        test_primary_recovery: settings.test_primary_recovery.unwrap_or(true),
+        recovery_test_interval: settings.recovery_test_interval.unwrap_or(5),
+        base_backoff_ms: settings.base_backoff_ms,
+        max_backoff_ms: settings.max_backoff_ms,
+        enabled: true,
+    })
+}
+
 // ============================================================================
 // Application State
 // ============================================================================
@@ -67,6 +107,18 @@ pub struct RalphLoopManagerState {
     /// Execution snapshots that can be read without locking the orchestrator
     /// Uses Arc so it can be shared with the orchestrator for direct updates
     pub(crate) snapshots: SnapshotStore,
+    /// Stop flags of running loops, usable while their task holds the orchestrator lock
+    controls: Mutex<HashMap<String, LoopControl>>,
+}
+
+/// Flags for stopping a running loop without locking its orchestrator
+#[derive(Clone)]
+pub struct LoopControl {
+    /// Abort the loop (kills the running agent)
+    pub cancel: Arc<Mutex<bool>>,
+    /// Stop after the current iteration and keep a resume checkpoint
+    /// (sequential loops only)
+    pub checkpoint: Option<Arc<Mutex<bool>>>,
 }
 
 impl RalphLoopManagerState {
@@ -74,6 +126,7 @@ impl RalphLoopManagerState {
         Self {
             executions: Mutex::new(HashMap::new()),
             snapshots: Arc::new(Mutex::new(HashMap::new())),
+            controls: Mutex::new(HashMap::new()),
         }
     }
 
@@ -94,6 +147,12 @@ impl RalphLoopManagerState {
         snapshots.get(execution_id).cloned()
     }
 
+    /// Project an execution of this process runs in (from its snapshot)
+    pub fn execution_project(&self, execution_id: &str) -> Option<String> {
+        self.get_snapshot(execution_id)
+            .and_then(|snapshot| snapshot.project_path)
+    }
+
     /// Get an execution orchestrator by ID (for server mode)
     pub fn get_execution(
         &self,
@@ -119,6 +178,31 @@ impl RalphLoopManagerState {
         executions.insert(execution_id, orchestrator);
         Ok(())
     }
+
+    /// Register the stop flags of a running loop
+    pub fn insert_control(&self, execution_id: String, control: LoopControl) {
+        lock_mutex_recover(&self.controls).insert(execution_id, control);
+    }
+
+    /// Unregister the stop flags of a loop whose task has ended
+    pub fn remove_control(&self, execution_id: &str) {
+        lock_mutex_recover(&self.controls).remove(execution_id);
+    }
+
+    /// Get the stop flags of a loop
+    pub fn get_control(&self, execution_id: &str) -> Option<LoopControl> {
+        lock_mutex_recover(&self.controls)
+            .get(execution_id)
+            .cloned()
+    }
+
+    /// Stop flags of all loops started by this process
+    pub fn controls(&self) -> Vec<(String, LoopControl)> {
+        lock_mutex_recover(&self.controls)
+            .iter()
+            .map(|(id, control)| (id.clone(), control.clone()))
+            .collect()
+    }
 }
 
 impl Default for RalphLoopManagerState {
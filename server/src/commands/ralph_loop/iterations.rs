@@ -2,8 +2,13 @@
 
 use crate::file_storage::iterations as iteration_storage;
 use crate::file_storage::iterations::IterationStats;
-use crate::ralph_loop::{ExecutionStateSnapshot, IterationOutcome, IterationRecord};
+use crate::ralph_loop::tool_analytics::build_tool_analytics_report;
+use crate::ralph_loop::{
+    ExecutionStateSnapshot, IterationOutcome, IterationRecord, ToolAnalyticsFilter,
+    ToolAnalyticsReport,
+};
 use crate::utils::as_path;
+use serde::{Deserialize, Serialize};
 
 // ============================================================================
 // Iteration History Operations
@@ -43,6 +48,7 @@ pub fn get_all_ralph_iterations(
         "failed" => Some(IterationOutcome::Failed),
         "skipped" => Some(IterationOutcome::Skipped),
         "interrupted" => Some(IterationOutcome::Interrupted),
+        "recovered" => Some(IterationOutcome::Recovered),
         _ => None,
     });
 
@@ -50,6 +56,17 @@ pub fn get_all_ralph_iterations(
         .map_err(|e| format!("Failed to get iteration history: {}", e))
 }
 
+/// Get tool-call analytics across executions, grouped by model, agent and story
+pub fn get_ralph_tool_analytics(
+    project_path: String,
+    filter: Option<ToolAnalyticsFilter>,
+) -> Result<ToolAnalyticsReport, String> {
+    let path = as_path(&project_path);
+    let records = iteration_storage::list_tool_stats(path, &filter.unwrap_or_default())
+        .map_err(|e| format!("Failed to get tool analytics: {}", e))?;
+    Ok(build_tool_analytics_report(records))
+}
+
 /// Save an iteration record (used by orchestrator)
 pub fn save_ralph_iteration(project_path: String, record: IterationRecord) -> Result<(), String> {
     let path = as_path(&project_path);
@@ -74,6 +91,7 @@ pub fn update_ralph_iteration(
         "failed" => IterationOutcome::Failed,
         "skipped" => IterationOutcome::Skipped,
         "interrupted" => IterationOutcome::Interrupted,
+        "recovered" => IterationOutcome::Recovered,
         _ => return Err(format!("Invalid outcome: {}", outcome)),
     };
 
@@ -149,6 +167,82 @@ pub fn recover_stale_ralph_iterations(
     Ok(count as u32)
 }
 
+/// A loop checkpointed at server shutdown that can be resumed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumableExecution {
+    pub project_path: String,
+    pub execution_id: String,
+    pub prd_name: String,
+    /// Iteration the loop resumes at
+    pub iteration: u32,
+    pub worktree_path: Option<String>,
+    pub checkpointed_at: String,
+}
+
+/// Loops of a project checkpointed at server shutdown, oldest first
+pub fn resumable_executions(project_path: &str) -> Result<Vec<ResumableExecution>, String> {
+    let snapshots = iteration_storage::get_checkpointed_executions(as_path(project_path))
+        .map_err(|e| format!("Failed to read checkpointed executions: {}", e))?;
+    Ok(snapshots
+        .into_iter()
+        .filter_map(|snapshot| {
+            let checkpoint = snapshot.checkpoint?;
+            Some(ResumableExecution {
+                project_path: project_path.to_string(),
+                execution_id: snapshot.execution_id,
+                prd_name: checkpoint.request.prd_name,
+                iteration: checkpoint.iteration,
+                worktree_path: checkpoint.worktree_path,
+                checkpointed_at: checkpoint.checkpointed_at,
+            })
+        })
+        .collect())
+}
+
+/// List loops checkpointed at shutdown, in one project or all registered projects
+pub fn list_ralph_loop_checkpoints(
+    project_path: Option<String>,
+) -> Result<Vec<ResumableExecution>, String> {
+    let project_paths = match project_path {
+        Some(path) => vec![path],
+        None => crate::file_storage::projects::get_all_projects()?
+            .into_iter()
+            .map(|p| p.path)
+            .collect(),
+    };
+
+    let mut resumable = Vec::new();
+    for path in project_paths.iter().filter(|p| as_path(p).exists()) {
+        resumable.extend(resumable_executions(path)?);
+    }
+    Ok(resumable)
+}
+
+/// List the PRD execution leases of a project, including expired ones left by
+/// instances that stopped without releasing them
+pub fn list_ralph_loop_leases(
+    project_path: String,
+) -> Result<Vec<crate::file_storage::leases::ExecutionLease>, String> {
+    crate::file_storage::leases::list_leases(as_path(&project_path))
+}
+
+/// Discard a shutdown checkpoint instead of resuming the loop
+pub fn discard_ralph_loop_checkpoint(
+    project_path: String,
+    execution_id: String,
+) -> Result<(), String> {
+    let path = as_path(&project_path);
+    let has_checkpoint = iteration_storage::get_execution_state(path, &execution_id)?
+        .is_some_and(|snapshot| snapshot.checkpoint.is_some());
+    if !has_checkpoint {
+        return Err(format!("No checkpoint for execution {}", execution_id));
+    }
+    iteration_storage::delete_execution_state(path, &execution_id)
+        .map_err(|e| format!("Failed to delete execution state: {}", e))?;
+    Ok(())
+}
+
 /// Delete iteration history for an execution (cleanup)
 pub fn delete_ralph_iteration_history(
     project_path: String,
@@ -192,6 +286,11 @@ pub fn cleanup_ralph_iteration_history(
                     if modified_time < threshold_time {
                         if std::fs::remove_file(&file_path).is_ok() {
                             count += 1;
+                            if let Some(name) = file_path.file_name() {
+                                let _ = std::fs::remove_file(
+                                    iterations_dir.join("tool-stats").join(name),
+                                );
+                            }
                         }
                     }
                 }
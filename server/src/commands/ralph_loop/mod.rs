@@ -13,6 +13,7 @@
 //! - config: Config operations (get, set, init, update)
 //! - worktrees: Worktree management (cleanup, list)
 //! - iterations: Iteration history operations
+//! - preflight: Agent and git diagnostics before starting a loop
 
 mod assignments;
 mod brief;
@@ -23,6 +24,7 @@ mod iterations;
 mod learnings;
 mod notifications;
 mod prd_ops;
+mod preflight;
 mod progress;
 mod story_ops;
 mod worktrees;
@@ -35,11 +37,13 @@ pub use assignments::*;
 pub use brief::*;
 pub use config::*;
 pub use execution::*;
-pub use helpers::{RalphFiles, RalphLoopManagerState};
+pub(crate) use helpers::fallback_chain_config;
+pub use helpers::{LoopControl, RalphFiles, RalphLoopManagerState};
 pub use iterations::*;
 pub use learnings::*;
 pub use notifications::send_test_notification;
 pub use prd_ops::*;
+pub use preflight::*;
 pub use progress::*;
 pub use story_ops::*;
 pub use worktrees::*;
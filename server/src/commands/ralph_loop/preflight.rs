@@ -0,0 +1,107 @@
+//! Preflight diagnostics: check agents and git state before starting a loop
+
+use crate::commands::models::ModelCacheState;
+use crate::commands::ConfigState;
+use crate::models::AgentType;
+use crate::ralph_loop::{run_preflight, PrdExecutor, PreflightReport, RalphLoopConfig};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::helpers::{fallback_chain_config, resolve_config, resolve_config_opt};
+
+/// Request to run preflight diagnostics without starting a loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RalphPreflightRequest {
+    /// Path to the project directory
+    pub project_path: String,
+    /// Primary agent type (claude, opencode, cursor, codex, ...)
+    pub agent_type: String,
+    /// Model to validate (falls back to PRD/global config)
+    pub model: Option<String>,
+    /// Branch the loop will work from
+    pub branch: Option<String>,
+    /// Whether the loop will use a worktree (falls back to PRD config, default: true)
+    pub use_worktree: Option<bool>,
+    /// PRD whose stored execution config should be considered
+    pub prd_name: Option<String>,
+}
+
+/// Run preflight diagnostics for a prospective loop
+///
+/// Resolves model and worktree settings the same way `start_ralph_loop` does
+/// (request > PRD stored config > global config) and includes the fallback chain.
+pub async fn run_ralph_preflight(
+    request: RalphPreflightRequest,
+    config_state: &ConfigState,
+    model_cache: Arc<ModelCacheState>,
+) -> Result<PreflightReport, String> {
+    let agent_type: AgentType = request.agent_type.parse()?;
+    let project_path = PathBuf::from(&request.project_path);
+
+    let prd_config = match &request.prd_name {
+        Some(prd_name) => {
+            PrdExecutor::new(&project_path, prd_name)
+                .read_prd()?
+                .execution_config
+        }
+        None => None,
+    };
+    let user_config = config_state.get_config().ok();
+
+    let config = RalphLoopConfig {
+        project_path,
+        agent_type,
+        model: resolve_config_opt(
+            request.model,
+            prd_config.as_ref().and_then(|c| c.model.clone()),
+            user_config.as_ref().and_then(|c| c.execution.model.clone()),
+        ),
+        branch: request.branch,
+        use_worktree: resolve_config(
+            request.use_worktree,
+            prd_config.as_ref().and_then(|c| c.use_worktree),
+            None,
+            true,
+        ),
+        fallback_config: user_config
+            .as_ref()
+            .and_then(|c| fallback_chain_config(&c.fallback, agent_type)),
+        prd_name: request.prd_name.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    preflight_config(&config, model_cache).await
+}
+
+/// Run preflight for a resolved loop config off the async runtime
+/// (the checks spawn short-lived CLI processes)
+pub(crate) async fn preflight_config(
+    config: &RalphLoopConfig,
+    model_cache: Arc<ModelCacheState>,
+) -> Result<PreflightReport, String> {
+    let config = config.clone();
+    tokio::task::spawn_blocking(move || run_preflight(&config, &model_cache.cache))
+        .await
+        .map_err(|e| format!("Preflight task failed: {}", e))
+}
+
+/// Refuse to start a loop whose preflight has hard failures, unless forced
+pub(crate) async fn ensure_preflight(
+    config: &RalphLoopConfig,
+    model_cache: Arc<ModelCacheState>,
+    force: bool,
+) -> Result<PreflightReport, String> {
+    let report = preflight_config(config, model_cache).await?;
+    if report.has_failures() {
+        if !force {
+            return Err(report.failure_summary());
+        }
+        log::warn!(
+            "[start_ralph_loop] Starting despite preflight failures (forced): {}",
+            report.failure_summary()
+        );
+    }
+    Ok(report)
+}
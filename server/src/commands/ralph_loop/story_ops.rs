@@ -10,7 +10,7 @@ use super::helpers::prd_executor;
 // ============================================================================
 
 /// Input for creating a Ralph story
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RalphStoryInput {
     pub id: String,
@@ -0,0 +1,31 @@
+// Terminal recording Backend commands
+
+use crate::commands::ConfigState;
+use crate::file_storage::recordings::{
+    self as recording_storage, RecordingFilter, RecordingMeta, RecordingRetentionResult,
+};
+
+/// List asciicast recordings, most recent first, optionally filtered by
+/// source, project or execution
+pub fn list_recordings(filter: Option<RecordingFilter>) -> Result<Vec<RecordingMeta>, String> {
+    recording_storage::list_recordings(&filter.unwrap_or_default())
+}
+
+/// Get a recording's metadata (the asciicast file is served by GET /api/recordings/:id)
+pub fn get_recording(recording_id: String) -> Result<RecordingMeta, String> {
+    recording_storage::get_recording(&recording_id)
+}
+
+/// Delete a recording
+pub fn delete_recording(recording_id: String) -> Result<(), String> {
+    recording_storage::delete_recording(&recording_id)
+}
+
+/// Apply the configured retention policy to recordings now
+pub fn prune_recordings(config_state: &ConfigState) -> Result<RecordingRetentionResult, String> {
+    let settings = config_state.get_config()?.recordings;
+    recording_storage::apply_retention(
+        settings.max_age_days,
+        settings.max_total_size_mb * 1024 * 1024,
+    )
+}
@@ -1,6 +1,7 @@
 // Recovery Backend commands
 // Uses file-based storage for session data
 
+use crate::file_storage::migrations::{self, MigrationReport};
 use crate::session::{
     lock::{find_stale_locks, remove_stale_lock, LockInfo, SessionLock},
     recovery::SessionRecovery,
@@ -221,6 +222,31 @@ pub async fn refresh_session_lock(project_path: String, session_id: String) -> R
     Ok(())
 }
 
+/// Report stored documents that are outdated or unreadable, without changing
+/// anything. Covers one project, or the global documents and all registered
+/// projects.
+pub async fn get_migration_report(
+    project_path: Option<String>,
+) -> Result<Vec<MigrationReport>, String> {
+    Ok(migration_reports(project_path, true))
+}
+
+/// Migrate outdated stored documents, backing up the originals
+pub async fn run_migrations(project_path: Option<String>) -> Result<Vec<MigrationReport>, String> {
+    let reports = migration_reports(project_path, false);
+    for report in &reports {
+        report.log();
+    }
+    Ok(reports)
+}
+
+fn migration_reports(project_path: Option<String>, dry_run: bool) -> Vec<MigrationReport> {
+    match project_path {
+        Some(project_path) => vec![migrations::migrate_project(as_path(&project_path), dry_run)],
+        None => migrations::migrate_all(dry_run),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
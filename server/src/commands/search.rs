@@ -0,0 +1,22 @@
+// Full-text search Backend commands
+
+use crate::search::{self, IndexUpdateResult, SearchQuery, SearchResults};
+use std::path::PathBuf;
+
+/// Search transcripts, chats, learnings, progress and stories
+pub async fn search(query: SearchQuery) -> Result<SearchResults, String> {
+    tokio::task::spawn_blocking(move || search::search(&query))
+        .await
+        .map_err(|e| format!("Search task failed: {}", e))?
+}
+
+/// Update a project's search index now (or rebuild it from scratch)
+pub async fn update_search_index(
+    project_path: String,
+    rebuild: bool,
+) -> Result<IndexUpdateResult, String> {
+    let project_path = PathBuf::from(project_path);
+    tokio::task::spawn_blocking(move || search::update_index(&project_path, rebuild))
+        .await
+        .map_err(|e| format!("Index task failed: {}", e))?
+}
@@ -1,9 +1,10 @@
 // Session-related commands
 // Uses file-based storage in .ralph-ui/sessions/
 
+use crate::file_storage::activity;
 use crate::file_storage::index::SessionIndexEntry;
 use crate::file_storage::sessions as session_storage;
-use crate::models::{Session, SessionStatus};
+use crate::models::{ActivityEventType, Session, SessionStatus};
 use crate::utils::as_path;
 use std::path::Path;
 
@@ -59,6 +60,27 @@ pub fn update_session_status_internal(
         }
     }
 
+    if old_status != new_status {
+        let name = &current_session.name;
+        match status {
+            SessionStatus::Completed => activity::record_activity(
+                project_path,
+                ActivityEventType::SessionCompleted,
+                name.as_str(),
+                format!("Session completed: {}", name),
+            ),
+            SessionStatus::Active if current_session.status != SessionStatus::Paused => {
+                activity::record_activity(
+                    project_path,
+                    ActivityEventType::SessionStarted,
+                    name.as_str(),
+                    format!("Session started: {}", name),
+                )
+            }
+            _ => {}
+        }
+    }
+
     Ok((old_status, new_status))
 }
 
@@ -0,0 +1,27 @@
+// Execution share link Backend commands
+
+use crate::file_storage::share_links::{self, CreatedShareLink, NewShareLink, ShareLink};
+
+/// Create a read-only share link for an execution; the token is only returned here
+pub fn create_share_link(request: NewShareLink) -> Result<CreatedShareLink, String> {
+    let created = share_links::create_share_link(request)?;
+    log::info!(
+        "[ShareLinks] Created link '{}' for execution {} (expires {})",
+        created.link.label,
+        created.link.execution_id,
+        created.link.expires_at
+    );
+    Ok(created)
+}
+
+/// Revoke a share link by ID
+pub fn revoke_share_link(link_id: String) -> Result<ShareLink, String> {
+    let link = share_links::revoke_share_link(&link_id)?;
+    log::info!("[ShareLinks] Revoked link '{}'", link.label);
+    Ok(link)
+}
+
+/// List share links, optionally for one project
+pub fn list_share_links(project_path: Option<String>) -> Result<Vec<ShareLink>, String> {
+    share_links::list_share_links(project_path.as_deref())
+}
@@ -1,8 +1,9 @@
 // Task-related commands
 // Uses file-based storage in .ralph-ui/sessions/
 
+use crate::file_storage::activity;
 use crate::file_storage::sessions as session_storage;
-use crate::models::{Task, TaskStatus};
+use crate::models::{ActivityEventType, Task, TaskStatus};
 use crate::session::{ProgressStatus, ProgressTracker};
 use crate::utils::as_path;
 use std::path::Path;
@@ -93,6 +94,24 @@ pub fn update_task_status_internal(
         log::warn!("Failed to write progress file: {}", e);
     }
 
+    let activity = match status {
+        TaskStatus::InProgress => Some((ActivityEventType::TaskStarted, "Started")),
+        TaskStatus::Completed => Some((ActivityEventType::TaskCompleted, "Completed")),
+        TaskStatus::Failed => Some((ActivityEventType::TaskFailed, "Failed")),
+        TaskStatus::Pending => None,
+    };
+    if let Some((event_type, verb)) = activity.filter(|_| old_status != new_status) {
+        let session_name = session_storage::read_session(project_path, session_id)
+            .map(|s| s.name)
+            .unwrap_or_default();
+        activity::record_activity(
+            project_path,
+            event_type,
+            session_name,
+            format!("{}: {}", verb, current_task.title),
+        );
+    }
+
     Ok((old_status, new_status))
 }
 
@@ -0,0 +1,146 @@
+// Agent transcript Backend commands
+
+use crate::agents::trace_export::{build_chrome_trace, ChromeTrace, IterationTrace};
+use crate::agents::transcript_capture::TranscriptToolCall;
+use crate::commands::ConfigState;
+use crate::file_storage::transcripts::{
+    self as transcript_storage, TranscriptFilter, TranscriptMeta, TranscriptPage, TranscriptPart,
+    TranscriptRetentionResult,
+};
+use crate::utils::as_path;
+use serde::{Deserialize, Serialize};
+
+/// Default number of lines per transcript page
+const DEFAULT_PAGE_SIZE: usize = 500;
+/// Upper bound on lines per transcript page
+const MAX_PAGE_SIZE: usize = 5000;
+
+/// Decompressed transcript part for download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptDownload {
+    pub file_name: String,
+    pub content: String,
+}
+
+/// List persisted transcripts, optionally filtered by execution, story or agent
+pub fn list_transcripts(
+    project_path: String,
+    filter: Option<TranscriptFilter>,
+) -> Result<Vec<TranscriptMeta>, String> {
+    transcript_storage::list_transcripts(as_path(&project_path), &filter.unwrap_or_default())
+}
+
+/// Read a page of a transcript's raw output
+pub fn get_transcript_page(
+    project_path: String,
+    execution_id: String,
+    iteration: u32,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<TranscriptPage, String> {
+    transcript_storage::read_transcript_page(
+        as_path(&project_path),
+        &execution_id,
+        iteration,
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+    )
+}
+
+/// Get the parsed tool calls of a transcript
+pub fn get_transcript_tool_calls(
+    project_path: String,
+    execution_id: String,
+    iteration: u32,
+) -> Result<Vec<TranscriptToolCall>, String> {
+    transcript_storage::read_transcript_tool_calls(as_path(&project_path), &execution_id, iteration)
+}
+
+/// Get a full, decompressed transcript part (output, tool_calls, subagents or prompt)
+pub fn download_transcript(
+    project_path: String,
+    execution_id: String,
+    iteration: u32,
+    part: Option<TranscriptPart>,
+) -> Result<TranscriptDownload, String> {
+    let part = part.unwrap_or(TranscriptPart::Output);
+    let data = transcript_storage::read_transcript_part(
+        as_path(&project_path),
+        &execution_id,
+        iteration,
+        part,
+    )?;
+    let (label, extension) = match part {
+        TranscriptPart::Output => ("output", "log"),
+        TranscriptPart::ToolCalls => ("tool-calls", "json"),
+        TranscriptPart::Subagents => ("subagents", "json"),
+        TranscriptPart::Prompt => ("prompt", "md"),
+    };
+
+    Ok(TranscriptDownload {
+        file_name: format!("{}-{}-{}.{}", execution_id, iteration, label, extension),
+        content: String::from_utf8_lossy(&data).into_owned(),
+    })
+}
+
+/// Export persisted iterations of an execution as a Chrome Trace Event file
+///
+/// Exports a single iteration when `iteration` is given, otherwise every
+/// persisted iteration of the execution.
+pub fn export_chrome_trace(
+    project_path: String,
+    execution_id: String,
+    iteration: Option<u32>,
+) -> Result<ChromeTrace, String> {
+    let project_path = as_path(&project_path);
+    let filter = TranscriptFilter {
+        execution_id: Some(execution_id.clone()),
+        ..Default::default()
+    };
+
+    let iterations = transcript_storage::list_transcripts(project_path, &filter)?
+        .into_iter()
+        .filter(|meta| iteration.map_or(true, |i| meta.iteration == i))
+        .map(|meta| {
+            let tool_calls = transcript_storage::read_transcript_tool_calls(
+                project_path,
+                &meta.execution_id,
+                meta.iteration,
+            )
+            .unwrap_or_default();
+            let subagent_events = transcript_storage::read_transcript_subagent_events(
+                project_path,
+                &meta.execution_id,
+                meta.iteration,
+            )
+            .unwrap_or_default();
+            IterationTrace {
+                meta,
+                tool_calls,
+                subagent_events,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if iterations.is_empty() {
+        return Err(format!(
+            "No transcripts found for execution {}",
+            execution_id
+        ));
+    }
+    Ok(build_chrome_trace(&iterations))
+}
+
+/// Apply the configured retention policy to a project's transcripts now
+pub fn prune_transcripts(
+    project_path: String,
+    config_state: &ConfigState,
+) -> Result<TranscriptRetentionResult, String> {
+    let settings = config_state.get_config()?.transcripts;
+    transcript_storage::apply_retention(
+        as_path(&project_path),
+        settings.max_age_days,
+        settings.max_total_size_mb * 1024 * 1024,
+    )
+}
@@ -0,0 +1,38 @@
+// Inbound trigger Backend commands
+
+use crate::triggers::{self, CreatedTrigger, NewTrigger, TriggerInfo, TriggerUpdate};
+
+/// List inbound triggers (without their secrets)
+pub fn list_triggers() -> Result<Vec<TriggerInfo>, String> {
+    Ok(triggers::get_triggers()?
+        .iter()
+        .map(TriggerInfo::from)
+        .collect())
+}
+
+/// Create an inbound trigger; the secret is only returned here
+pub fn create_trigger(request: NewTrigger) -> Result<CreatedTrigger, String> {
+    let created = triggers::create_trigger(request)?;
+    log::info!(
+        "[Triggers] Created trigger '{}' for {}",
+        created.info.name,
+        created.info.project_path
+    );
+    Ok(created)
+}
+
+/// Update a trigger's name, secret, rules or enabled state
+pub fn update_trigger(trigger_id: String, update: TriggerUpdate) -> Result<TriggerInfo, String> {
+    let info = triggers::update_trigger(&trigger_id, update)?;
+    log::info!("[Triggers] Updated trigger '{}'", info.name);
+    Ok(info)
+}
+
+/// Delete a trigger
+pub fn delete_trigger(trigger_id: String) -> Result<bool, String> {
+    let removed = triggers::delete_trigger(&trigger_id)?;
+    if removed {
+        log::info!("[Triggers] Deleted trigger {}", trigger_id);
+    }
+    Ok(removed)
+}
@@ -1,5 +1,7 @@
 // Agent spawner for ultra research - handles parallel agent execution
 
+use crate::agents::rate_limit_ledger::{self, DEFAULT_RATE_LIMIT_BACKOFF_MS};
+use crate::agents::rate_limiter::RateLimitDetector;
 use crate::commands::prd_chat::{build_agent_command, ChatAgentResult, ChatEventEmitter};
 use crate::events::{MdFileDetectedPayload, ToolCallCompletedPayload, ToolCallStartedPayload};
 use crate::file_storage::research_ops;
@@ -71,6 +73,9 @@ pub async fn spawn_researcher(
     prompt: &str,
     broadcaster: Arc<EventBroadcaster>,
 ) -> Result<ResearchFinding, String> {
+    // Skip providers that are known to be rate-limited right now
+    rate_limit_ledger::ensure_available(agent.agent_type, None)?;
+
     // Build the agent command
     let (cmd_name, args) = build_agent_command(
         agent.agent_type,
@@ -89,7 +94,21 @@ pub async fn spawn_researcher(
     let emitter = ResearchEventEmitter::new(broadcaster, session.id.clone(), agent.id.clone());
 
     // Run the agent command
-    let result = run_agent_command(cmd_name, &args, project_path, &emitter).await?;
+    let result = match run_agent_command(cmd_name, &args, project_path, &emitter).await {
+        Ok(result) => result,
+        Err(e) => {
+            if let Some(info) = RateLimitDetector::new().detect_in_stderr(&e) {
+                rate_limit_ledger::global().record(
+                    agent.agent_type,
+                    None,
+                    Some(&info),
+                    "research",
+                    DEFAULT_RATE_LIMIT_BACKOFF_MS,
+                );
+            }
+            return Err(e);
+        }
+    };
 
     // Create the finding
     let finding = ResearchFinding {
@@ -111,7 +130,7 @@ async fn run_agent_command(
     working_dir: &Path,
     emitter: &ResearchEventEmitter,
 ) -> Result<ChatAgentResult, String> {
-    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 
     let mut cmd = Command::new(cmd_name);
     cmd.args(args)
@@ -128,6 +147,8 @@ async fn run_agent_command(
         .take()
         .ok_or_else(|| "Failed to capture stdout".to_string())?;
 
+    let mut stderr = child.stderr.take();
+
     let mut reader = BufReader::new(stdout).lines();
     let mut full_response = String::new();
 
@@ -143,7 +164,18 @@ async fn run_agent_command(
         .map_err(|e| format!("Failed to wait for agent: {}", e))?;
 
     if !status.success() {
-        return Err(format!("Agent exited with status: {}", status));
+        let mut stderr_output = String::new();
+        if let Some(stderr) = stderr.as_mut() {
+            let _ = stderr.read_to_string(&mut stderr_output).await;
+        }
+        let stderr_output = stderr_output.trim();
+        if stderr_output.is_empty() {
+            return Err(format!("Agent exited with status: {}", status));
+        }
+        return Err(format!(
+            "Agent exited with status: {}: {}",
+            status, stderr_output
+        ));
     }
 
     Ok(ChatAgentResult {
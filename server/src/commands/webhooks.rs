@@ -0,0 +1,54 @@
+// Outbound webhook Backend commands
+
+use crate::webhooks::{
+    self, CreatedWebhook, DeadLetter, DeliveryResult, NewWebhook, WebhookInfo, WebhookUpdate,
+};
+
+/// List configured webhooks (without their secrets)
+pub fn list_webhooks() -> Result<Vec<WebhookInfo>, String> {
+    Ok(webhooks::get_webhooks()?
+        .iter()
+        .map(WebhookInfo::from)
+        .collect())
+}
+
+/// Create a webhook; the signing secret is only returned here
+pub fn create_webhook(request: NewWebhook) -> Result<CreatedWebhook, String> {
+    let created = webhooks::create_webhook(request)?;
+    log::info!(
+        "[Webhooks] Created webhook '{}' ({})",
+        created.info.name,
+        created.info.url
+    );
+    Ok(created)
+}
+
+/// Update a webhook's URL, secret, format or filters
+pub fn update_webhook(webhook_id: String, update: WebhookUpdate) -> Result<WebhookInfo, String> {
+    let info = webhooks::update_webhook(&webhook_id, update)?;
+    log::info!("[Webhooks] Updated webhook '{}'", info.name);
+    Ok(info)
+}
+
+/// Delete a webhook
+pub fn delete_webhook(webhook_id: String) -> Result<bool, String> {
+    let removed = webhooks::delete_webhook(&webhook_id)?;
+    if removed {
+        log::info!("[Webhooks] Deleted webhook {}", webhook_id);
+    }
+    Ok(removed)
+}
+
+/// Send a test delivery to a webhook and report the outcome
+pub async fn test_webhook(webhook_id: String) -> Result<DeliveryResult, String> {
+    let webhook = webhooks::get_webhook(&webhook_id)?;
+    Ok(webhooks::send_test_delivery(&webhook).await)
+}
+
+/// List deliveries that failed after all retries, newest first
+pub fn list_webhook_dead_letters(
+    webhook_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<DeadLetter>, String> {
+    webhooks::list_dead_letters(webhook_id.as_deref(), limit)
+}
@@ -2,6 +2,7 @@
 
 #![allow(dead_code)] // Config loader infrastructure
 
+use crate::config::merger::RetentionOverrides;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -25,6 +26,16 @@ pub struct RalphConfig {
     /// Agent fallback settings
     #[serde(default)]
     pub fallback: FallbackSettings,
+    /// Agent transcript retention settings
+    #[serde(default)]
+    pub transcripts: TranscriptSettings,
+    /// Terminal recording policy
+    #[serde(default)]
+    pub recordings: RecordingSettings,
+    /// Transcript and recording keys set by the file this was loaded from
+    /// (None when built in code, where every value counts as set)
+    #[serde(skip)]
+    pub retention: Option<RetentionOverrides>,
 }
 
 /// Execution configuration
@@ -289,6 +300,92 @@ impl Default for FallbackSettings {
     }
 }
 
+/// Retention settings for persisted agent transcripts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSettings {
+    /// Persist agent transcripts to `.ralph-ui/transcripts/`
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Delete transcripts older than this many days (0 = keep forever)
+    #[serde(
+        rename = "maxAgeDays",
+        alias = "max_age_days",
+        default = "default_transcript_max_age_days"
+    )]
+    pub max_age_days: u32,
+    /// Delete the oldest transcripts once the compressed total exceeds this (0 = unlimited)
+    #[serde(
+        rename = "maxTotalSizeMb",
+        alias = "max_total_size_mb",
+        default = "default_transcript_max_total_size_mb"
+    )]
+    pub max_total_size_mb: u64,
+}
+
+fn default_transcript_max_age_days() -> u32 {
+    30
+}
+fn default_transcript_max_total_size_mb() -> u64 {
+    512
+}
+
+impl Default for TranscriptSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            max_age_days: default_transcript_max_age_days(),
+            max_total_size_mb: default_transcript_max_total_size_mb(),
+        }
+    }
+}
+
+/// Which terminals are recorded to asciicast files, and for how long they're kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSettings {
+    /// Record user terminal sessions
+    #[serde(default)]
+    pub terminals: bool,
+    /// Record agent PTY output of Ralph loops
+    #[serde(default)]
+    pub agents: bool,
+    /// Also record terminal input (may capture typed secrets)
+    #[serde(rename = "recordInput", alias = "record_input", default)]
+    pub record_input: bool,
+    /// Delete recordings older than this many days (0 = keep forever)
+    #[serde(
+        rename = "maxAgeDays",
+        alias = "max_age_days",
+        default = "default_recording_max_age_days"
+    )]
+    pub max_age_days: u32,
+    /// Delete the oldest recordings once their total exceeds this (0 = unlimited)
+    #[serde(
+        rename = "maxTotalSizeMb",
+        alias = "max_total_size_mb",
+        default = "default_recording_max_total_size_mb"
+    )]
+    pub max_total_size_mb: u64,
+}
+
+fn default_recording_max_age_days() -> u32 {
+    14
+}
+fn default_recording_max_total_size_mb() -> u64 {
+    256
+}
+
+impl Default for RecordingSettings {
+    fn default() -> Self {
+        Self {
+            terminals: false,
+            agents: false,
+            record_input: false,
+            max_age_days: default_recording_max_age_days(),
+            max_total_size_mb: default_recording_max_total_size_mb(),
+        }
+    }
+}
+
 /// Config loader
 pub struct ConfigLoader {
     /// Global config path
@@ -344,8 +441,12 @@ impl ConfigLoader {
         let contents = fs::read_to_string(path)
             .map_err(|e| anyhow!("Failed to read config file '{}': {}", path.display(), e))?;
 
-        let config: RalphConfig = toml::from_str(&contents)
+        let mut config: RalphConfig = toml::from_str(&contents)
             .map_err(|e| anyhow!("Failed to parse config file '{}': {}", path.display(), e))?;
+        config.retention = Some(
+            toml::from_str(&contents)
+                .map_err(|e| anyhow!("Failed to parse config file '{}': {}", path.display(), e))?,
+        );
 
         self.validate_config(&config)?;
 
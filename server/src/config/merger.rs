@@ -1,8 +1,8 @@
 // Configuration merging with priority
 
 use crate::config::loader::{
-    ErrorStrategyConfig, ExecutionConfig, FallbackSettings, GitConfig, RalphConfig, TemplateConfig,
-    ValidationConfig,
+    ErrorStrategyConfig, ExecutionConfig, FallbackSettings, GitConfig, RalphConfig,
+    RecordingSettings, TemplateConfig, TranscriptSettings, ValidationConfig,
 };
 use serde::{Deserialize, Serialize};
 
@@ -20,6 +20,10 @@ pub struct PartialConfig {
     pub templates: Option<PartialTemplateConfig>,
     #[serde(default)]
     pub fallback: Option<PartialFallbackSettings>,
+    #[serde(default)]
+    pub transcripts: Option<PartialTranscriptSettings>,
+    #[serde(default)]
+    pub recordings: Option<PartialRecordingSettings>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -69,6 +73,59 @@ pub struct PartialFallbackSettings {
     pub recovery_test_interval: Option<u32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PartialTranscriptSettings {
+    pub enabled: Option<bool>,
+    #[serde(rename = "maxAgeDays", alias = "max_age_days")]
+    pub max_age_days: Option<u32>,
+    #[serde(rename = "maxTotalSizeMb", alias = "max_total_size_mb")]
+    pub max_total_size_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PartialRecordingSettings {
+    pub terminals: Option<bool>,
+    pub agents: Option<bool>,
+    #[serde(rename = "recordInput", alias = "record_input")]
+    pub record_input: Option<bool>,
+    #[serde(rename = "maxAgeDays", alias = "max_age_days")]
+    pub max_age_days: Option<u32>,
+    #[serde(rename = "maxTotalSizeMb", alias = "max_total_size_mb")]
+    pub max_total_size_mb: Option<u64>,
+}
+
+/// Transcript and recording keys a config file actually sets
+/// (sections without optional fields can't tell unset from default otherwise)
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct RetentionOverrides {
+    #[serde(default)]
+    pub transcripts: PartialTranscriptSettings,
+    #[serde(default)]
+    pub recordings: PartialRecordingSettings,
+}
+
+impl From<&RalphConfig> for RetentionOverrides {
+    /// A config built in code sets every value
+    fn from(config: &RalphConfig) -> Self {
+        let t = &config.transcripts;
+        let r = &config.recordings;
+        Self {
+            transcripts: PartialTranscriptSettings {
+                enabled: Some(t.enabled),
+                max_age_days: Some(t.max_age_days),
+                max_total_size_mb: Some(t.max_total_size_mb),
+            },
+            recordings: PartialRecordingSettings {
+                terminals: Some(r.terminals),
+                agents: Some(r.agents),
+                record_input: Some(r.record_input),
+                max_age_days: Some(r.max_age_days),
+                max_total_size_mb: Some(r.max_total_size_mb),
+            },
+        }
+    }
+}
+
 /// Configuration merger
 /// Priority order: CLI -> Project -> Global -> Defaults
 pub struct ConfigMerger {
@@ -131,12 +188,19 @@ impl ConfigMerger {
 
     /// Merge two full configs
     fn merge_full(&self, base: &RalphConfig, override_config: &RalphConfig) -> RalphConfig {
+        let retention = override_config
+            .retention
+            .clone()
+            .unwrap_or_else(|| RetentionOverrides::from(override_config));
         RalphConfig {
             execution: self.merge_execution(&base.execution, &override_config.execution),
             git: self.merge_git(&base.git, &override_config.git),
             validation: self.merge_validation(&base.validation, &override_config.validation),
             templates: self.merge_templates(&base.templates, &override_config.templates),
             fallback: self.merge_fallback(&base.fallback, &override_config.fallback),
+            transcripts: self.merge_partial_transcripts(&base.transcripts, &retention.transcripts),
+            recordings: self.merge_partial_recordings(&base.recordings, &retention.recordings),
+            retention: None,
         }
     }
 
@@ -168,6 +232,17 @@ impl ConfigMerger {
                 .as_ref()
                 .map(|p| self.merge_partial_fallback(&base.fallback, p))
                 .unwrap_or_else(|| base.fallback.clone()),
+            transcripts: partial
+                .transcripts
+                .as_ref()
+                .map(|p| self.merge_partial_transcripts(&base.transcripts, p))
+                .unwrap_or_else(|| base.transcripts.clone()),
+            recordings: partial
+                .recordings
+                .as_ref()
+                .map(|p| self.merge_partial_recordings(&base.recordings, p))
+                .unwrap_or_else(|| base.recordings.clone()),
+            retention: None,
         }
     }
 
@@ -334,6 +409,32 @@ impl ConfigMerger {
         }
     }
 
+    fn merge_partial_transcripts(
+        &self,
+        base: &TranscriptSettings,
+        partial: &PartialTranscriptSettings,
+    ) -> TranscriptSettings {
+        TranscriptSettings {
+            enabled: partial.enabled.unwrap_or(base.enabled),
+            max_age_days: partial.max_age_days.unwrap_or(base.max_age_days),
+            max_total_size_mb: partial.max_total_size_mb.unwrap_or(base.max_total_size_mb),
+        }
+    }
+
+    fn merge_partial_recordings(
+        &self,
+        base: &RecordingSettings,
+        partial: &PartialRecordingSettings,
+    ) -> RecordingSettings {
+        RecordingSettings {
+            terminals: partial.terminals.unwrap_or(base.terminals),
+            agents: partial.agents.unwrap_or(base.agents),
+            record_input: partial.record_input.unwrap_or(base.record_input),
+            max_age_days: partial.max_age_days.unwrap_or(base.max_age_days),
+            max_total_size_mb: partial.max_total_size_mb.unwrap_or(base.max_total_size_mb),
+        }
+    }
+
     fn merge_partial_fallback(
         &self,
         base: &FallbackSettings,
@@ -376,6 +477,8 @@ impl Default for ConfigMerger {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::loader::ConfigLoader;
+    use tempfile::TempDir;
 
     fn create_global_config() -> RalphConfig {
         RalphConfig {
@@ -438,6 +541,98 @@ mod tests {
         assert_eq!(result.execution.agent_type, "opencode"); // Project wins
     }
 
+    /// Load a config file the way `load_merged_config` does
+    fn load_config(dir: &TempDir, name: &str, contents: &str) -> RalphConfig {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents).unwrap();
+        ConfigLoader::new().load_from_path(&path).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_project_without_retention_sections_keeps_global_settings() {
+        let dir = TempDir::new().unwrap();
+        let global = load_config(
+            &dir,
+            "global.toml",
+            "[transcripts]\nenabled = false\nmaxAgeDays = 90\n\n\
+             [recordings]\nterminals = true\nmaxTotalSizeMb = 1024\n",
+        );
+        let project = load_config(&dir, "project.toml", "[execution]\nmax_parallel = 2\n");
+
+        let result = ConfigMerger::new()
+            .with_global(Some(global))
+            .with_project(Some(project))
+            .merge();
+        assert_eq!(result.transcripts.max_age_days, 90);
+        assert!(!result.transcripts.enabled);
+        assert!(result.recordings.terminals);
+        assert_eq!(result.recordings.max_total_size_mb, 1024);
+    }
+
+    #[test]
+    fn test_project_transcript_settings_override_global_with_defaults() {
+        let dir = TempDir::new().unwrap();
+        let global = load_config(
+            &dir,
+            "global.toml",
+            "[transcripts]\nenabled = true\nmaxAgeDays = 90\nmaxTotalSizeMb = 2048\n",
+        );
+        // The project sets values that happen to equal the defaults
+        let project = load_config(
+            &dir,
+            "project.toml",
+            "[transcripts]\nenabled = false\nmax_age_days = 30\n",
+        );
+
+        let result = ConfigMerger::new()
+            .with_global(Some(global))
+            .with_project(Some(project))
+            .merge();
+        assert!(!result.transcripts.enabled);
+        assert_eq!(result.transcripts.max_age_days, 30);
+        assert_eq!(result.transcripts.max_total_size_mb, 2048); // Unset in project
+    }
+
+    #[test]
+    fn test_project_recording_settings_override_global_with_defaults() {
+        let dir = TempDir::new().unwrap();
+        let global = load_config(
+            &dir,
+            "global.toml",
+            "[recordings]\nterminals = true\nagents = true\nrecordInput = true\nmaxAgeDays = 30\n",
+        );
+        // Opting a project out of recording must win over the global opt-in
+        let project = load_config(
+            &dir,
+            "project.toml",
+            "[recordings]\nterminals = false\nagents = false\nrecord_input = false\nmaxAgeDays = 14\n",
+        );
+
+        let result = ConfigMerger::new()
+            .with_global(Some(global))
+            .with_project(Some(project))
+            .merge();
+        assert!(!result.recordings.terminals);
+        assert!(!result.recordings.agents);
+        assert!(!result.recordings.record_input);
+        assert_eq!(result.recordings.max_age_days, 14);
+    }
+
+    #[test]
+    fn test_config_built_in_code_sets_every_retention_value() {
+        let mut global = create_global_config();
+        global.transcripts.enabled = false;
+        global.recordings.terminals = true;
+        let project = create_project_config();
+
+        let result = ConfigMerger::new()
+            .with_global(Some(global))
+            .with_project(Some(project))
+            .merge();
+        assert!(result.transcripts.enabled);
+        assert!(!result.recordings.terminals);
+    }
+
     #[test]
     fn test_project_overrides_global_config() {
         let global = create_global_config();
@@ -8,7 +8,7 @@ pub mod secrets;
 // Re-export main types
 pub use loader::{
     ConfigLoader, ErrorStrategyConfig, ExecutionConfig, FallbackSettings, GitConfig, RalphConfig,
-    ValidationConfig,
+    RecordingSettings, TranscriptSettings, ValidationConfig,
 };
 pub use merger::{ConfigMerger, PartialConfig};
 pub use providers::{
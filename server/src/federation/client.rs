@@ -0,0 +1,255 @@
+//! Requests to federation peers
+//!
+//! Peers are called through their `/api/invoke` endpoint like any other
+//! client. Federated requests carry `"federated": false`, so a peer answers
+//! with its own data only and peers listing each other never recurse.
+
+use super::types::{FederationPeer, PeerStatus};
+use futures_util::future::join_all;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// A peer's answer to a federated request
+#[derive(Debug, Clone)]
+pub struct PeerResult<T> {
+    pub peer: FederationPeer,
+    pub latency_ms: u64,
+    pub result: Result<T, String>,
+}
+
+impl<T> PeerResult<T> {
+    /// Health of the peer as seen by this request
+    pub fn status(&self) -> PeerStatus {
+        PeerStatus {
+            peer_id: self.peer.id.clone(),
+            name: self.peer.name.clone(),
+            url: self.peer.url.clone(),
+            healthy: self.result.is_ok(),
+            latency_ms: self.latency_ms,
+            version: None,
+            error: self.result.as_ref().err().cloned(),
+        }
+    }
+}
+
+/// Body of an `/api/invoke` response
+#[derive(Debug, Deserialize)]
+struct InvokeResponse {
+    #[serde(default)]
+    data: Option<Value>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+fn request_error(peer: &FederationPeer, e: reqwest::Error) -> String {
+    if e.is_timeout() {
+        format!("Timed out after {}ms", peer.timeout_ms)
+    } else if e.is_connect() {
+        format!("Unreachable: {}", e)
+    } else {
+        e.to_string()
+    }
+}
+
+/// Turn a peer's HTTP response into its result or error message
+async fn read_response(response: reqwest::Response) -> Result<Value, String> {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    match serde_json::from_str::<InvokeResponse>(&body) {
+        Ok(parsed) if status.is_success() => Ok(parsed.data.unwrap_or(Value::Null)),
+        Ok(InvokeResponse {
+            error: Some(error), ..
+        }) => Err(error),
+        // Auth failures are plain text
+        _ if !status.is_success() => Err(format!("HTTP {}: {}", status.as_u16(), body.trim())),
+        Err(e) => Err(format!("Invalid response: {}", e)),
+        Ok(_) => Err("Invalid response".to_string()),
+    }
+}
+
+/// Invoke a command on one peer
+pub async fn invoke_peer<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    peer: &FederationPeer,
+    cmd: &str,
+    args: &Value,
+) -> Result<T, String> {
+    let response = client
+        .post(format!("{}/api/invoke", peer.url))
+        .bearer_auth(&peer.token)
+        .timeout(Duration::from_millis(peer.timeout_ms))
+        .json(&json!({ "cmd": cmd, "args": args }))
+        .send()
+        .await
+        .map_err(|e| request_error(peer, e))?;
+    let data = read_response(response).await?;
+    serde_json::from_value(data).map_err(|e| format!("Unexpected {} result: {}", cmd, e))
+}
+
+/// Invoke a command on all peers concurrently, asking each for local data only
+pub async fn invoke_peers<T: DeserializeOwned>(
+    peers: &[FederationPeer],
+    cmd: &str,
+    mut args: Value,
+) -> Vec<PeerResult<T>> {
+    if !args.is_object() {
+        args = json!({});
+    }
+    args["federated"] = Value::Bool(false);
+
+    let client = reqwest::Client::new();
+    join_all(peers.iter().map(|peer| {
+        let client = &client;
+        let args = &args;
+        async move {
+            let started = Instant::now();
+            let result = invoke_peer(client, peer, cmd, args).await;
+            if let Err(e) = &result {
+                log::warn!("[Federation] {} on peer '{}' failed: {}", cmd, peer.name, e);
+            }
+            PeerResult {
+                peer: peer.clone(),
+                latency_ms: started.elapsed().as_millis() as u64,
+                result,
+            }
+        }
+    }))
+    .await
+}
+
+/// Check that each peer is reachable and accepts its token
+pub async fn check_peers(peers: &[FederationPeer]) -> Vec<PeerStatus> {
+    let client = reqwest::Client::new();
+    join_all(peers.iter().map(|peer| {
+        let client = &client;
+        async move {
+            let started = Instant::now();
+            let result = async {
+                let response = client
+                    .get(format!("{}/api/version", peer.url))
+                    .bearer_auth(&peer.token)
+                    .timeout(Duration::from_millis(peer.timeout_ms))
+                    .send()
+                    .await
+                    .map_err(|e| request_error(peer, e))?;
+                let status = response.status();
+                if !status.is_success() {
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(format!("HTTP {}: {}", status.as_u16(), body.trim()));
+                }
+                let version: Value = response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid response: {}", e))?;
+                Ok(version["version"].as_str().map(str::to_string))
+            }
+            .await;
+
+            let mut status = PeerResult {
+                peer: peer.clone(),
+                latency_ms: started.elapsed().as_millis() as u64,
+                result: result.clone(),
+            }
+            .status();
+            status.version = result.ok().flatten();
+            status
+        }
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::{get, post},
+        Json, Router,
+    };
+
+    /// Minimal stand-in for a peer server: echoes invoke requests
+    async fn spawn_peer(delay: Duration) -> String {
+        let invoke = move |headers: HeaderMap, Json(body): Json<Value>| async move {
+            if headers.get("authorization").and_then(|h| h.to_str().ok()) != Some("Bearer good") {
+                return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+            }
+            tokio::time::sleep(delay).await;
+            if body["cmd"] == "fail" {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"success": false, "error": "boom"})),
+                )
+                    .into_response();
+            }
+            Json(json!({"success": true, "data": body})).into_response()
+        };
+        let app = Router::new().route("/api/invoke", post(invoke)).route(
+            "/api/version",
+            get(|| async { Json(json!({"version": "9.9.9"})) }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    fn peer(name: &str, url: &str, token: &str) -> FederationPeer {
+        FederationPeer {
+            id: name.to_string(),
+            name: name.to_string(),
+            url: url.to_string(),
+            token: token.to_string(),
+            enabled: true,
+            timeout_ms: 500,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invoke_peers_asks_for_local_data() {
+        let url = spawn_peer(Duration::ZERO).await;
+        let peers = vec![peer("a", &url, "good"), peer("b", &url, "bad")];
+
+        let results = invoke_peers::<Value>(&peers, "get_global_stats", json!({})).await;
+        let echoed = results[0].result.as_ref().unwrap();
+        assert_eq!(echoed["cmd"], "get_global_stats");
+        assert_eq!(echoed["args"]["federated"], false);
+        assert!(results[1].result.as_ref().unwrap_err().contains("401"));
+        assert!(!results[1].status().healthy);
+
+        let results = invoke_peers::<Value>(&peers[..1], "fail", Value::Null).await;
+        assert_eq!(results[0].result.as_ref().unwrap_err(), "boom");
+    }
+
+    #[tokio::test]
+    async fn test_slow_and_unreachable_peers() {
+        let slow = spawn_peer(Duration::from_secs(5)).await;
+        let peers = vec![
+            peer("slow", &slow, "good"),
+            // Nothing listens on port 1
+            peer("down", "http://127.0.0.1:1", "good"),
+        ];
+
+        let results = invoke_peers::<Value>(&peers, "get_global_stats", json!({})).await;
+        assert!(results[0]
+            .result
+            .as_ref()
+            .unwrap_err()
+            .contains("Timed out"));
+        assert!(results[0].latency_ms < 5000);
+        assert!(results[1].result.is_err());
+
+        let url = spawn_peer(Duration::ZERO).await;
+        let statuses = check_peers(&[peer("a", &url, "good"), peers[1].clone()]).await;
+        assert!(statuses[0].healthy);
+        assert_eq!(statuses[0].version.as_deref(), Some("9.9.9"));
+        assert!(!statuses[1].healthy);
+    }
+}
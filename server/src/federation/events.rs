@@ -0,0 +1,218 @@
+//! Proxying of peer event streams
+//!
+//! Each peer's `/ws/events` stream is read with the peer's token and its
+//! events are re-sent tagged with the peer. Dropped connections are retried
+//! with backoff and resume from the last sequence seen, so the peer replays
+//! what was missed from its journal.
+
+use super::types::{FederationPeer, LOCAL_SERVER_ID, LOCAL_SERVER_NAME};
+use crate::server::ServerEvent;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Sent when a peer's event stream connects or drops
+pub const EVENT_PEER_STATUS: &str = "federation:peer_status";
+
+/// Delay before the first reconnect; doubled for each further attempt
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest delay between reconnects
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// An event tagged with the server it happened on
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FederatedEvent {
+    #[serde(flatten)]
+    pub event: ServerEvent,
+    pub server_id: String,
+    pub server_name: String,
+}
+
+impl FederatedEvent {
+    /// Tag an event of this server
+    pub fn local(event: ServerEvent) -> Self {
+        Self {
+            event,
+            server_id: LOCAL_SERVER_ID.to_string(),
+            server_name: LOCAL_SERVER_NAME.to_string(),
+        }
+    }
+
+    fn from_peer(peer: &FederationPeer, event: ServerEvent) -> Self {
+        Self {
+            event,
+            server_id: peer.id.clone(),
+            server_name: peer.name.clone(),
+        }
+    }
+
+    fn peer_status(peer: &FederationPeer, connected: bool, error: Option<String>) -> Self {
+        Self::from_peer(
+            peer,
+            ServerEvent {
+                seq: 0,
+                event: EVENT_PEER_STATUS.to_string(),
+                payload: json!({ "connected": connected, "error": error }),
+                project_path: None,
+            },
+        )
+    }
+}
+
+/// WebSocket URL of a peer's event stream
+pub fn peer_events_url(peer: &FederationPeer) -> Result<String, String> {
+    let mut url = reqwest::Url::parse(&peer.url).map_err(|e| format!("Invalid peer URL: {}", e))?;
+    let scheme = match url.scheme() {
+        "https" => "wss",
+        _ => "ws",
+    };
+    url.set_scheme(scheme)
+        .map_err(|_| format!("Can't connect to {} over WebSocket", peer.url))?;
+    url.set_path("/ws/events");
+    url.query_pairs_mut()
+        .clear()
+        .append_pair("token", &peer.token);
+    Ok(url.to_string())
+}
+
+/// Forward a peer's events to `tx` until `tx` is closed
+pub async fn stream_peer_events(peer: FederationPeer, tx: mpsc::Sender<FederatedEvent>) {
+    let mut last_seq: Option<u64> = None;
+    let mut delay = INITIAL_RECONNECT_DELAY;
+
+    loop {
+        let error = read_peer_events(&peer, &tx, &mut last_seq, &mut delay)
+            .await
+            .err();
+        if tx.is_closed() {
+            return;
+        }
+        log::warn!(
+            "[Federation] Event stream of peer '{}' dropped: {}",
+            peer.name,
+            error.as_deref().unwrap_or("closed by peer")
+        );
+        if tx
+            .send(FederatedEvent::peer_status(&peer, false, error))
+            .await
+            .is_err()
+        {
+            return;
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+/// Read one connection to a peer's event stream until it ends
+async fn read_peer_events(
+    peer: &FederationPeer,
+    tx: &mpsc::Sender<FederatedEvent>,
+    last_seq: &mut Option<u64>,
+    delay: &mut Duration,
+) -> Result<(), String> {
+    let url = peer_events_url(peer)?;
+    let connect = tokio_tungstenite::connect_async(url.as_str());
+    let (socket, _) = tokio::time::timeout(Duration::from_millis(peer.timeout_ms), connect)
+        .await
+        .map_err(|_| format!("Timed out after {}ms", peer.timeout_ms))?
+        .map_err(|e| e.to_string())?;
+    let (mut sender, mut receiver) = socket.split();
+
+    // Resume where the previous connection left off
+    if let Some(since) = *last_seq {
+        let subscribe = json!({ "event": "subscribe", "payload": { "since": since } });
+        sender
+            .send(Message::Text(subscribe.to_string()))
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    *delay = INITIAL_RECONNECT_DELAY;
+    log::info!(
+        "[Federation] Connected to event stream of peer '{}'",
+        peer.name
+    );
+    if tx
+        .send(FederatedEvent::peer_status(peer, true, None))
+        .await
+        .is_err()
+    {
+        return Ok(());
+    }
+
+    while let Some(message) = receiver.next().await {
+        let text = match message.map_err(|e| e.to_string())? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let event: ServerEvent = match serde_json::from_str(&text) {
+            Ok(event) => event,
+            Err(e) => {
+                log::debug!("[Federation] Ignoring message from '{}': {}", peer.name, e);
+                continue;
+            }
+        };
+        // Acks and pongs are per-connection control messages
+        if event.seq == 0 {
+            continue;
+        }
+        *last_seq = Some(event.seq);
+        if tx
+            .send(FederatedEvent::from_peer(peer, event))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_events_url() {
+        let mut peer = FederationPeer {
+            id: "p1".to_string(),
+            name: "build-2".to_string(),
+            url: "https://build-2:3420".to_string(),
+            token: "rui_a b&c".to_string(),
+            enabled: true,
+            timeout_ms: 1000,
+            created_at: chrono::Utc::now(),
+        };
+        assert_eq!(
+            peer_events_url(&peer).unwrap(),
+            "wss://build-2:3420/ws/events?token=rui_a+b%26c"
+        );
+
+        peer.url = "http://127.0.0.1:3421".to_string();
+        assert!(peer_events_url(&peer)
+            .unwrap()
+            .starts_with("ws://127.0.0.1:3421/ws/events"));
+    }
+
+    #[test]
+    fn test_federated_event_serialization() {
+        let event = FederatedEvent::local(ServerEvent {
+            seq: 3,
+            event: "ralph:progress".to_string(),
+            payload: json!({"executionId": "e1"}),
+            project_path: None,
+        });
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["seq"], 3);
+        assert_eq!(json["event"], "ralph:progress");
+        assert_eq!(json["serverId"], LOCAL_SERVER_ID);
+    }
+}
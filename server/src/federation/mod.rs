@@ -0,0 +1,18 @@
+//! Federation of several Ralph UI servers
+//!
+//! Peers are other Ralph UI servers listed in `~/.ralph-ui/federation.json`
+//! together with a token for each. Mission Control asks every enabled peer
+//! for its local stats, activity and running executions and merges them with
+//! this server's, tagging each item with the server it came from. Peer event
+//! streams are proxied over `/ws/federation/events`.
+
+pub mod client;
+pub mod events;
+pub mod storage;
+pub mod types;
+
+pub use client::{check_peers, invoke_peers, PeerResult};
+pub use storage::{add_peer, get_enabled_peers, get_peers, remove_peer};
+pub use types::{
+    FederationPeer, NewPeer, PeerInfo, PeerStatus, LOCAL_SERVER_ID, LOCAL_SERVER_NAME,
+};
@@ -0,0 +1,164 @@
+//! Storage for federation peers
+//!
+//! Peers are stored globally in `~/.ralph-ui/federation.json` and can also be
+//! edited by hand:
+//!
+//! ```json
+//! {"peers": [{"id": "build-2", "name": "build-2", "url": "http://build-2:3420",
+//!             "token": "rui_...", "timeoutMs": 5000, "createdAt": "..."}]}
+//! ```
+
+use super::types::{FederationPeer, NewPeer, PeerInfo, DEFAULT_PEER_TIMEOUT_MS};
+use crate::file_storage::migrations::{read_versioned, write_versioned, FEDERATION};
+use crate::file_storage::{ensure_dir, get_global_ralph_ui_dir};
+use crate::utils::{lock_mutex_recover, validate_http_url};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes read-modify-write of the federation file
+static FEDERATION_LOCK: Mutex<()> = Mutex::new(());
+
+/// Storage format for the federation file
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct FederationFile {
+    #[serde(default)]
+    peers: Vec<FederationPeer>,
+}
+
+/// Get the path to the federation file
+pub fn get_federation_path() -> PathBuf {
+    get_federation_path_in(&get_global_ralph_ui_dir())
+}
+
+fn get_federation_path_in(base_dir: &Path) -> PathBuf {
+    base_dir.join("federation.json")
+}
+
+fn load_federation_in(base_dir: &Path) -> Result<FederationFile, String> {
+    let path = get_federation_path_in(base_dir);
+    if !path.exists() {
+        return Ok(FederationFile::default());
+    }
+    read_versioned(&path, &FEDERATION)
+}
+
+fn save_federation_in(base_dir: &Path, data: &FederationFile) -> Result<(), String> {
+    ensure_dir(base_dir)?;
+    write_versioned(&get_federation_path_in(base_dir), data, &FEDERATION)
+}
+
+/// Get all peers (including tokens)
+pub fn get_peers() -> Result<Vec<FederationPeer>, String> {
+    get_peers_in(&get_global_ralph_ui_dir())
+}
+
+/// Get all peers in a specific directory (for testing)
+pub fn get_peers_in(base_dir: &Path) -> Result<Vec<FederationPeer>, String> {
+    Ok(load_federation_in(base_dir)?.peers)
+}
+
+/// Get the peers Mission Control should contact
+pub fn get_enabled_peers() -> Result<Vec<FederationPeer>, String> {
+    Ok(get_peers()?.into_iter().filter(|p| p.enabled).collect())
+}
+
+/// Add a peer
+pub fn add_peer(new_peer: NewPeer) -> Result<PeerInfo, String> {
+    add_peer_in(&get_global_ralph_ui_dir(), new_peer)
+}
+
+/// Add a peer in a specific directory (for testing)
+pub fn add_peer_in(base_dir: &Path, new_peer: NewPeer) -> Result<PeerInfo, String> {
+    let name = new_peer.name.trim();
+    if name.is_empty() {
+        return Err("Peer name is required".to_string());
+    }
+    if new_peer.token.trim().is_empty() {
+        return Err("Peer token is required".to_string());
+    }
+    let url = new_peer.url.trim().trim_end_matches('/');
+    validate_http_url(url, "peer")?;
+
+    let _guard = lock_mutex_recover(&FEDERATION_LOCK);
+    let mut data = load_federation_in(base_dir)?;
+    if data.peers.iter().any(|p| p.name == name || p.url == url) {
+        return Err(format!("Peer '{}' ({}) already exists", name, url));
+    }
+
+    let peer = FederationPeer {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        url: url.to_string(),
+        token: new_peer.token.trim().to_string(),
+        enabled: true,
+        timeout_ms: new_peer.timeout_ms.unwrap_or(DEFAULT_PEER_TIMEOUT_MS),
+        created_at: Utc::now(),
+    };
+    let info = PeerInfo::from(&peer);
+    data.peers.push(peer);
+    save_federation_in(base_dir, &data)?;
+    Ok(info)
+}
+
+/// Remove a peer; returns whether it existed
+pub fn remove_peer(peer_id: &str) -> Result<bool, String> {
+    remove_peer_in(&get_global_ralph_ui_dir(), peer_id)
+}
+
+/// Remove a peer in a specific directory (for testing)
+pub fn remove_peer_in(base_dir: &Path, peer_id: &str) -> Result<bool, String> {
+    let _guard = lock_mutex_recover(&FEDERATION_LOCK);
+    let mut data = load_federation_in(base_dir)?;
+    let before = data.peers.len();
+    data.peers.retain(|p| p.id != peer_id);
+    if data.peers.len() == before {
+        return Ok(false);
+    }
+    save_federation_in(base_dir, &data)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn new_peer(name: &str, url: &str) -> NewPeer {
+        NewPeer {
+            name: name.to_string(),
+            url: url.to_string(),
+            token: "rui_secret".to_string(),
+            timeout_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove_peer() {
+        let temp_dir = TempDir::new().unwrap();
+        let info =
+            add_peer_in(temp_dir.path(), new_peer("build-2", "http://build-2:3420/")).unwrap();
+        assert_eq!(info.url, "http://build-2:3420");
+        assert_eq!(info.timeout_ms, DEFAULT_PEER_TIMEOUT_MS);
+
+        // Same name or URL twice is rejected
+        assert!(add_peer_in(temp_dir.path(), new_peer("build-2", "http://other:3420")).is_err());
+        assert!(add_peer_in(temp_dir.path(), new_peer("other", "http://build-2:3420")).is_err());
+
+        assert!(remove_peer_in(temp_dir.path(), &info.id).unwrap());
+        assert!(!remove_peer_in(temp_dir.path(), &info.id).unwrap());
+        assert!(get_peers_in(temp_dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_peers_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(add_peer_in(temp_dir.path(), new_peer("", "http://a:3420")).is_err());
+        assert!(add_peer_in(temp_dir.path(), new_peer("a", "ftp://a")).is_err());
+        assert!(add_peer_in(temp_dir.path(), new_peer("a", "not a url")).is_err());
+
+        let mut no_token = new_peer("a", "http://a:3420");
+        no_token.token = " ".to_string();
+        assert!(add_peer_in(temp_dir.path(), no_token).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+//! Types for federated servers
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Server ID that results from this server are tagged with
+pub const LOCAL_SERVER_ID: &str = "local";
+
+/// Server name that results from this server are tagged with
+pub const LOCAL_SERVER_NAME: &str = "local";
+
+/// Per-request timeout when none is configured
+pub const DEFAULT_PEER_TIMEOUT_MS: u64 = 5000;
+
+/// Another Ralph UI server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FederationPeer {
+    pub id: String,
+    pub name: String,
+    /// Base URL of the peer (e.g. `http://build-2:3420`)
+    pub url: String,
+    /// Server token or named API token of the peer (viewer role is enough)
+    pub token: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Requests to this peer are abandoned after this many milliseconds
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_PEER_TIMEOUT_MS
+}
+
+/// Peer details safe to show (no token)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub enabled: bool,
+    pub timeout_ms: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&FederationPeer> for PeerInfo {
+    fn from(peer: &FederationPeer) -> Self {
+        Self {
+            id: peer.id.clone(),
+            name: peer.name.clone(),
+            url: peer.url.clone(),
+            enabled: peer.enabled,
+            timeout_ms: peer.timeout_ms,
+            created_at: peer.created_at,
+        }
+    }
+}
+
+/// Options for adding a peer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPeer {
+    pub name: String,
+    pub url: String,
+    pub token: String,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Outcome of contacting a peer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerStatus {
+    pub peer_id: String,
+    pub name: String,
+    pub url: String,
+    pub healthy: bool,
+    pub latency_ms: u64,
+    /// Version reported by the peer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -7,7 +7,7 @@
 //! These files are gitignored as they are runtime-only state.
 
 use super::{ensure_dir, get_ralph_ui_dir, read_json, write_json, FileResult};
-use crate::models::{Agent, AgentStatus, LogEntry, LogLevel};
+use crate::models::{Agent, AgentStatus, AgentType, LogEntry, LogLevel};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File, OpenOptions};
@@ -47,6 +47,27 @@ pub struct AgentStateFile {
     pub created_at: DateTime<Utc>,
     /// When this agent state was last updated
     pub updated_at: DateTime<Utc>,
+    /// Set for agents spawned by a Ralph loop iteration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ralph_loop: Option<LoopAgentInfo>,
+}
+
+/// Ralph loop iteration an agent process was spawned for
+///
+/// Kept so a restarted server can find agents a crashed server left running.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopAgentInfo {
+    pub execution_id: String,
+    pub prd_name: String,
+    pub iteration: u32,
+    pub agent_type: AgentType,
+    /// Executable that was started
+    pub program: String,
+    /// SHA-256 of the process's command line, checked before the PID is trusted
+    pub command_digest: String,
+    /// When the process was started
+    pub started_at: DateTime<Utc>,
 }
 
 /// Log entry for JSONL format
@@ -94,6 +115,7 @@ impl From<&Agent> for AgentStateFile {
             cost: agent.cost,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            ralph_loop: None,
         }
     }
 }
@@ -148,6 +170,23 @@ pub fn save_agent_state(project_path: &Path, agent: &Agent) -> FileResult<PathBu
     Ok(file_path)
 }
 
+/// Save the state of an agent spawned by a Ralph loop iteration
+pub fn save_loop_agent(
+    project_path: &Path,
+    agent: &Agent,
+    info: LoopAgentInfo,
+) -> FileResult<PathBuf> {
+    let agents_dir = get_agents_dir(project_path);
+    ensure_dir(&agents_dir)?;
+
+    let file_path = get_agent_file_path(project_path, &agent.id);
+    let mut state = AgentStateFile::from(agent);
+    state.ralph_loop = Some(info);
+
+    write_json(&file_path, &state)?;
+    Ok(file_path)
+}
+
 /// Read agent state from file
 pub fn read_agent_state(project_path: &Path, agent_id: &str) -> FileResult<AgentStateFile> {
     let file_path = get_agent_file_path(project_path, agent_id);
@@ -372,6 +411,21 @@ pub fn get_all_active_agents(project_path: &Path) -> FileResult<Vec<Agent>> {
     Ok(agents)
 }
 
+/// Ralph loop agents recorded with a process ID
+pub fn list_loop_agents(project_path: &Path) -> FileResult<Vec<AgentStateFile>> {
+    let mut agents = Vec::new();
+    for agent_id in list_agent_ids(project_path)? {
+        match read_agent_state(project_path, &agent_id) {
+            Ok(state) if state.ralph_loop.is_some() && state.process_id.is_some() => {
+                agents.push(state)
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to read agent {}: {}", agent_id, e),
+        }
+    }
+    Ok(agents)
+}
+
 /// Cleanup stale agent files (agents that haven't been updated recently)
 pub fn cleanup_stale_agents(project_path: &Path, max_age_secs: i64) -> FileResult<Vec<String>> {
     let agent_ids = list_agent_ids(project_path)?;
@@ -593,4 +647,33 @@ mod tests {
         assert_eq!(active.len(), 1);
         assert_eq!(active[0].id, "agent-2");
     }
+
+    #[test]
+    fn test_list_loop_agents() {
+        let temp_dir = TempDir::new().unwrap();
+        super::super::init_ralph_ui_dir(temp_dir.path()).unwrap();
+
+        // Plain agents aren't loop agents
+        let mut agent = create_test_agent("agent-1", "session-1");
+        agent.process_id = Some(100);
+        save_agent_state(temp_dir.path(), &agent).unwrap();
+
+        let mut loop_agent = create_test_agent("exec-1-iter-2-attempt-1", "exec-1");
+        loop_agent.process_id = Some(200);
+        let info = LoopAgentInfo {
+            execution_id: "exec-1".to_string(),
+            prd_name: "dark-mode".to_string(),
+            iteration: 2,
+            agent_type: AgentType::Claude,
+            program: "/usr/local/bin/claude".to_string(),
+            command_digest: "ab12".to_string(),
+            started_at: Utc::now(),
+        };
+        save_loop_agent(temp_dir.path(), &loop_agent, info.clone()).unwrap();
+
+        let agents = list_loop_agents(temp_dir.path()).unwrap();
+        assert_eq!(agents.len(), 1);
+        assert_eq!(agents[0].process_id, Some(200));
+        assert_eq!(agents[0].ralph_loop, Some(info));
+    }
 }
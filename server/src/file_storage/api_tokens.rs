@@ -0,0 +1,299 @@
+//! Named API tokens for multi-user server access
+//!
+//! Stored in `~/.ralph-ui/tokens.json`. Only a SHA-256 hash of each token is
+//! kept; the plaintext is returned once, when the token is created.
+
+use super::migrations::{read_versioned, write_versioned, API_TOKENS};
+use super::{ensure_dir, get_global_ralph_ui_dir, FileResult};
+use crate::server::permissions::ApiTokenRole;
+use crate::utils::lock_mutex_recover;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Version of the tokens file format
+const TOKENS_FILE_VERSION: u32 = 1;
+
+/// Prefix that makes named tokens recognizable (e.g. in secret scanners)
+pub const TOKEN_PREFIX: &str = "rui_";
+
+/// Characters of the plaintext token kept for identification
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Serializes read-modify-write of the tokens file
+static TOKENS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A stored token (hash only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub role: ApiTokenRole,
+    /// Project paths the token is limited to (None = all projects)
+    #[serde(default)]
+    pub projects: Option<Vec<String>>,
+    /// Hex-encoded SHA-256 of the plaintext token
+    pub token_hash: String,
+    /// Leading characters of the plaintext token, for display
+    pub token_prefix: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    /// Whether the token can authenticate at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.map_or(true, |at| at > now)
+    }
+}
+
+/// Token details safe to show (no hash)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub role: ApiTokenRole,
+    pub projects: Option<Vec<String>>,
+    pub token_prefix: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub active: bool,
+}
+
+impl From<&ApiToken> for ApiTokenInfo {
+    fn from(token: &ApiToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            name: token.name.clone(),
+            role: token.role,
+            projects: token.projects.clone(),
+            token_prefix: token.token_prefix.clone(),
+            created_at: token.created_at,
+            expires_at: token.expires_at,
+            revoked_at: token.revoked_at,
+            active: token.is_active(Utc::now()),
+        }
+    }
+}
+
+/// A newly created token; `token` is the only copy of the plaintext
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiToken {
+    pub token: String,
+    pub info: ApiTokenInfo,
+}
+
+/// Options for creating a token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewApiToken {
+    pub name: String,
+    pub role: ApiTokenRole,
+    /// Limit the token to these project paths
+    #[serde(default)]
+    pub projects: Option<Vec<String>>,
+    /// Expire the token after this many days
+    #[serde(default)]
+    pub expires_in_days: Option<u32>,
+}
+
+/// Tokens file structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokensFile {
+    pub version: u32,
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,
+}
+
+impl Default for TokensFile {
+    fn default() -> Self {
+        Self {
+            version: TOKENS_FILE_VERSION,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+/// Hex-encoded SHA-256 of a plaintext token
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Get the path of the global tokens file
+pub fn get_tokens_file_path() -> PathBuf {
+    get_tokens_file_path_in(&get_global_ralph_ui_dir())
+}
+
+fn get_tokens_file_path_in(base_dir: &Path) -> PathBuf {
+    base_dir.join("tokens.json")
+}
+
+/// Read the tokens file from a specific directory (empty if missing)
+pub fn read_tokens_from(base_dir: &Path) -> FileResult<TokensFile> {
+    let file_path = get_tokens_file_path_in(base_dir);
+    if !file_path.exists() {
+        return Ok(TokensFile::default());
+    }
+    read_versioned(&file_path, &API_TOKENS)
+}
+
+fn write_tokens_to(base_dir: &Path, file: &TokensFile) -> FileResult<()> {
+    ensure_dir(base_dir)?;
+    write_versioned(&get_tokens_file_path_in(base_dir), file, &API_TOKENS)
+}
+
+/// Create a named token
+pub fn create_token(new_token: NewApiToken) -> FileResult<CreatedApiToken> {
+    create_token_in(&get_global_ralph_ui_dir(), new_token)
+}
+
+/// Create a named token in a specific directory (for testing)
+pub fn create_token_in(base_dir: &Path, new_token: NewApiToken) -> FileResult<CreatedApiToken> {
+    let name = new_token.name.trim();
+    if name.is_empty() {
+        return Err("Token name is required".to_string());
+    }
+
+    let _guard = lock_mutex_recover(&TOKENS_LOCK);
+    let mut file = read_tokens_from(base_dir)?;
+    if file
+        .tokens
+        .iter()
+        .any(|t| t.name == name && t.revoked_at.is_none())
+    {
+        return Err(format!("Token '{}' already exists", name));
+    }
+
+    let secret = format!("{}{}", TOKEN_PREFIX, crate::server::generate_auth_token());
+    let now = Utc::now();
+    let token = ApiToken {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        role: new_token.role,
+        projects: new_token.projects.filter(|p| !p.is_empty()),
+        token_hash: hash_token(&secret),
+        token_prefix: secret.chars().take(DISPLAY_PREFIX_LEN).collect(),
+        created_at: now,
+        expires_at: new_token
+            .expires_in_days
+            .map(|days| now + Duration::days(days as i64)),
+        revoked_at: None,
+    };
+    let info = ApiTokenInfo::from(&token);
+
+    file.tokens.push(token);
+    write_tokens_to(base_dir, &file)?;
+
+    Ok(CreatedApiToken {
+        token: secret,
+        info,
+    })
+}
+
+/// Revoke a token by ID (revoked tokens are kept for auditing)
+pub fn revoke_token(token_id: &str) -> FileResult<ApiTokenInfo> {
+    revoke_token_in(&get_global_ralph_ui_dir(), token_id)
+}
+
+/// Revoke a token in a specific directory (for testing)
+pub fn revoke_token_in(base_dir: &Path, token_id: &str) -> FileResult<ApiTokenInfo> {
+    let _guard = lock_mutex_recover(&TOKENS_LOCK);
+    let mut file = read_tokens_from(base_dir)?;
+    let token = file
+        .tokens
+        .iter_mut()
+        .find(|t| t.id == token_id)
+        .ok_or_else(|| format!("Token not found: {}", token_id))?;
+
+    if token.revoked_at.is_none() {
+        token.revoked_at = Some(Utc::now());
+    }
+    let info = ApiTokenInfo::from(&*token);
+
+    write_tokens_to(base_dir, &file)?;
+    Ok(info)
+}
+
+/// List all tokens, newest first
+pub fn list_tokens() -> FileResult<Vec<ApiTokenInfo>> {
+    list_tokens_in(&get_global_ralph_ui_dir())
+}
+
+/// List tokens in a specific directory (for testing)
+pub fn list_tokens_in(base_dir: &Path) -> FileResult<Vec<ApiTokenInfo>> {
+    let mut tokens: Vec<ApiTokenInfo> = read_tokens_from(base_dir)?
+        .tokens
+        .iter()
+        .map(ApiTokenInfo::from)
+        .collect();
+    tokens.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn new_token(name: &str, role: ApiTokenRole) -> NewApiToken {
+        NewApiToken {
+            name: name.to_string(),
+            role,
+            projects: None,
+            expires_in_days: None,
+        }
+    }
+
+    #[test]
+    fn test_create_stores_hash_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let created =
+            create_token_in(temp_dir.path(), new_token("ci", ApiTokenRole::Viewer)).unwrap();
+
+        assert!(created.token.starts_with(TOKEN_PREFIX));
+        let raw = std::fs::read_to_string(temp_dir.path().join("tokens.json")).unwrap();
+        assert!(!raw.contains(&created.token));
+        assert!(raw.contains(&hash_token(&created.token)));
+    }
+
+    #[test]
+    fn test_duplicate_active_name_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let created =
+            create_token_in(temp_dir.path(), new_token("ci", ApiTokenRole::Viewer)).unwrap();
+        assert!(create_token_in(temp_dir.path(), new_token("ci", ApiTokenRole::Admin)).is_err());
+
+        // The name can be reused once the old token is revoked
+        revoke_token_in(temp_dir.path(), &created.info.id).unwrap();
+        assert!(create_token_in(temp_dir.path(), new_token("ci", ApiTokenRole::Admin)).is_ok());
+    }
+
+    #[test]
+    fn test_revoke_and_expiry() {
+        let temp_dir = TempDir::new().unwrap();
+        let created =
+            create_token_in(temp_dir.path(), new_token("bob", ApiTokenRole::Operator)).unwrap();
+
+        let info = revoke_token_in(temp_dir.path(), &created.info.id).unwrap();
+        assert!(!info.active);
+        assert!(revoke_token_in(temp_dir.path(), "missing").is_err());
+
+        let mut token = read_tokens_from(temp_dir.path()).unwrap().tokens[0].clone();
+        token.revoked_at = None;
+        token.expires_at = Some(Utc::now() - Duration::minutes(1));
+        assert!(!token.is_active(Utc::now()));
+    }
+}
@@ -0,0 +1,334 @@
+//! Append-only audit log of state-changing API calls
+//!
+//! Stored in `~/.ralph-ui/audit/`:
+//! - `audit.jsonl` - Current log (one JSON entry per line)
+//! - `audit-{timestamp}.jsonl` - Rotated logs, oldest pruned first
+
+use super::{ensure_dir, get_global_ralph_ui_dir, FileResult};
+use crate::server::permissions::{self, ApiTokenRole};
+use crate::utils::lock_mutex_recover;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const CURRENT_FILE: &str = "audit.jsonl";
+
+/// Size at which the current log is rotated
+const MAX_AUDIT_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Rotated logs kept before the oldest is deleted
+const MAX_ROTATED_FILES: usize = 20;
+
+/// Default number of entries returned by a query
+const DEFAULT_QUERY_LIMIT: usize = 500;
+
+/// Longest string argument kept verbatim (prompts, file contents, attachments)
+const MAX_ARG_STRING_LEN: usize = 500;
+
+/// Argument names whose values are never written to the log
+const SECRET_KEY_PARTS: &[&str] = &[
+    "token",
+    "password",
+    "secret",
+    "apikey",
+    "api_key",
+    "authorization",
+    "credential",
+];
+
+/// Serializes appends and rotation within this process
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Outcome of an audited call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    Success,
+    Failure,
+    /// Rejected by role or project scope checks
+    Denied,
+}
+
+/// One audited call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// Token name of the caller ("server" for the server token)
+    pub actor: String,
+    pub token_id: Option<String>,
+    pub role: ApiTokenRole,
+    /// Invoke command, `METHOD /path` for REST calls, or `pty_session_*`
+    pub command: String,
+    pub project_path: Option<String>,
+    /// Arguments with secrets redacted and long strings truncated
+    pub args: Value,
+    pub status: AuditStatus,
+    pub http_status: Option<u16>,
+}
+
+/// Filter for querying the audit log
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFilter {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Matches entries for this project or paths inside it
+    pub project_path: Option<String>,
+    /// Token name or token ID
+    pub actor: Option<String>,
+    pub command: Option<String>,
+    pub status: Option<AuditStatus>,
+    /// Maximum entries returned, newest first (default: 500)
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.since.map_or(true, |t| entry.timestamp >= t)
+            && self.until.map_or(true, |t| entry.timestamp <= t)
+            && self.project_path.as_ref().map_or(true, |p| {
+                entry
+                    .project_path
+                    .as_ref()
+                    .is_some_and(|e| permissions::path_in_scope(e, std::slice::from_ref(p)))
+            })
+            && self.actor.as_ref().map_or(true, |a| {
+                entry.actor == *a || entry.token_id.as_deref() == Some(a.as_str())
+            })
+            && self.command.as_ref().map_or(true, |c| entry.command == *c)
+            && self.status.map_or(true, |s| entry.status == s)
+    }
+}
+
+/// Replace secret values and truncate long strings in call arguments
+pub fn redact_args(args: &Value) -> Value {
+    match args {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let lower = key.to_lowercase();
+                    let value = if SECRET_KEY_PARTS.iter().any(|p| lower.contains(p)) {
+                        Value::String("[REDACTED]".to_string())
+                    } else {
+                        redact_args(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_args).collect()),
+        Value::String(s) if s.chars().count() > MAX_ARG_STRING_LEN => {
+            let kept: String = s.chars().take(MAX_ARG_STRING_LEN).collect();
+            Value::String(format!("{}… ({} chars)", kept, s.chars().count()))
+        }
+        other => other.clone(),
+    }
+}
+
+/// Get the global audit directory
+pub fn get_audit_dir() -> PathBuf {
+    get_global_ralph_ui_dir().join("audit")
+}
+
+/// Append an entry to the audit log, rotating it when full
+pub fn append_audit_entry(entry: &AuditEntry) -> FileResult<()> {
+    append_audit_entry_in(&get_audit_dir(), entry)
+}
+
+/// Append an entry in a specific directory (for testing)
+pub fn append_audit_entry_in(audit_dir: &Path, entry: &AuditEntry) -> FileResult<()> {
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+
+    let _guard = lock_mutex_recover(&AUDIT_LOCK);
+    ensure_dir(audit_dir)?;
+
+    let current = audit_dir.join(CURRENT_FILE);
+    let size = fs::metadata(&current).map(|m| m.len()).unwrap_or(0);
+    if size >= MAX_AUDIT_FILE_BYTES {
+        rotate(audit_dir, &current)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&current)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit entry: {}", e))
+}
+
+fn rotate(audit_dir: &Path, current: &Path) -> FileResult<()> {
+    let rotated = audit_dir.join(format!(
+        "audit-{}.jsonl",
+        Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+    ));
+    fs::rename(current, &rotated).map_err(|e| format!("Failed to rotate audit log: {}", e))?;
+
+    let files = rotated_files(audit_dir);
+    for old in files.iter().skip(MAX_ROTATED_FILES) {
+        if let Err(e) = fs::remove_file(old) {
+            log::warn!("[Audit] Failed to prune {:?}: {}", old, e);
+        }
+    }
+    Ok(())
+}
+
+/// Rotated logs, newest first (timestamped names sort chronologically)
+fn rotated_files(audit_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(audit_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("audit-") && n.ends_with(".jsonl"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files.reverse();
+    files
+}
+
+/// Query the audit log, newest entries first
+pub fn query_audit_log(filter: &AuditFilter) -> FileResult<Vec<AuditEntry>> {
+    query_audit_log_in(&get_audit_dir(), filter)
+}
+
+/// Query the audit log in a specific directory (for testing)
+pub fn query_audit_log_in(audit_dir: &Path, filter: &AuditFilter) -> FileResult<Vec<AuditEntry>> {
+    let limit = filter.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    let mut results = Vec::new();
+
+    let files = std::iter::once(audit_dir.join(CURRENT_FILE)).chain(rotated_files(audit_dir));
+    for path in files {
+        if results.len() >= limit {
+            break;
+        }
+        let Ok(file) = File::open(&path) else {
+            continue;
+        };
+
+        let mut entries: Vec<AuditEntry> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+        entries.reverse();
+
+        // Entries within a file are chronological, so older files can't match either
+        let reached_since = filter
+            .since
+            .is_some_and(|since| entries.last().is_some_and(|e| e.timestamp < since));
+
+        results.extend(
+            entries
+                .into_iter()
+                .filter(|e| filter.matches(e))
+                .take(limit - results.len()),
+        );
+        if reached_since {
+            break;
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn entry(actor: &str, command: &str, project: Option<&str>) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now(),
+            actor: actor.to_string(),
+            token_id: None,
+            role: ApiTokenRole::Operator,
+            command: command.to_string(),
+            project_path: project.map(String::from),
+            args: json!({}),
+            status: AuditStatus::Success,
+            http_status: Some(200),
+        }
+    }
+
+    #[test]
+    fn test_redact_args() {
+        let long = "x".repeat(MAX_ARG_STRING_LEN + 10);
+        let redacted = redact_args(&json!({
+            "projectPath": "/work/app",
+            "request": { "apiKey": "sk-123", "prompt": long },
+            "githubToken": "ghp_abc"
+        }));
+
+        assert_eq!(redacted["projectPath"], "/work/app");
+        assert_eq!(redacted["githubToken"], "[REDACTED]");
+        assert_eq!(redacted["request"]["apiKey"], "[REDACTED]");
+        assert!(redacted["request"]["prompt"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("({} chars)", MAX_ARG_STRING_LEN + 10)));
+    }
+
+    #[test]
+    fn test_append_and_query_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        let mut old = entry("alice", "stop_ralph_loop", Some("/work/app"));
+        old.timestamp = Utc::now() - Duration::hours(2);
+        append_audit_entry_in(dir, &old).unwrap();
+        append_audit_entry_in(dir, &entry("bob", "add_ralph_story", Some("/work/app"))).unwrap();
+        append_audit_entry_in(
+            dir,
+            &entry("alice", "start_ralph_loop", Some("/work/other")),
+        )
+        .unwrap();
+
+        let all = query_audit_log_in(dir, &AuditFilter::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].command, "start_ralph_loop"); // newest first
+
+        let by_actor = AuditFilter {
+            actor: Some("alice".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_audit_log_in(dir, &by_actor).unwrap().len(), 2);
+
+        let recent_app = AuditFilter {
+            since: Some(Utc::now() - Duration::hours(1)),
+            project_path: Some("/work/app".to_string()),
+            ..Default::default()
+        };
+        let results = query_audit_log_in(dir, &recent_app).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].actor, "bob");
+    }
+
+    #[test]
+    fn test_rotation_keeps_entries_queryable() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        append_audit_entry_in(dir, &entry("alice", "delete_project", None)).unwrap();
+        rotate(dir, &dir.join(CURRENT_FILE)).unwrap();
+        append_audit_entry_in(dir, &entry("bob", "add_ralph_story", None)).unwrap();
+
+        assert_eq!(rotated_files(dir).len(), 1);
+        let all = query_audit_log_in(dir, &AuditFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[1].command, "delete_project");
+    }
+}
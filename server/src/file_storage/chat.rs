@@ -3,7 +3,8 @@
 //! Stores chat sessions with embedded messages in `.ralph-ui/chat/{id}.json`
 
 use super::index::{update_index_entry, ChatIndexEntry};
-use super::{atomic_write, ensure_dir, get_ralph_ui_dir, read_json, FileResult};
+use super::migrations::{read_versioned, write_versioned, CHAT};
+use super::{ensure_dir, get_ralph_ui_dir, FileResult};
 use crate::models::{ChatAttachment, ChatMessage, ChatSession, DiscoveryProgress, MessageRole};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -203,10 +204,7 @@ pub fn save_chat_file(project_path: &Path, chat_file: &ChatFile) -> FileResult<P
 
     let file_path = get_chat_file_path(project_path, &chat_file.id);
 
-    let content = serde_json::to_string_pretty(chat_file)
-        .map_err(|e| format!("Failed to serialize chat file: {}", e))?;
-
-    atomic_write(&file_path, &content)?;
+    write_versioned(&file_path, chat_file, &CHAT)?;
 
     // Update the index
     let index_entry = chat_file.to_index_entry();
@@ -221,7 +219,7 @@ pub fn save_chat_file(project_path: &Path, chat_file: &ChatFile) -> FileResult<P
 /// Read a chat session from file
 pub fn read_chat_file(project_path: &Path, chat_id: &str) -> FileResult<ChatFile> {
     let file_path = get_chat_file_path(project_path, chat_id);
-    read_json(&file_path)
+    read_versioned(&file_path, &CHAT)
 }
 
 /// Check if a chat file exists
@@ -280,7 +278,7 @@ pub fn list_chat_files(project_path: &Path) -> FileResult<Vec<ChatFile>> {
             continue;
         }
 
-        match read_json::<ChatFile>(&path) {
+        match read_versioned::<ChatFile>(&path, &CHAT) {
             Ok(chat) => chats.push(chat),
             Err(e) => {
                 log::warn!("Failed to read chat file {:?}: {}", path, e);
@@ -294,6 +292,15 @@ pub fn list_chat_files(project_path: &Path) -> FileResult<Vec<ChatFile>> {
     Ok(chats)
 }
 
+/// Rebuild the chat index from the chat files (e.g. after an import)
+pub fn rebuild_chat_index(project_path: &Path) -> FileResult<Vec<ChatIndexEntry>> {
+    super::index::rebuild_index(project_path, "chat", |path| {
+        read_versioned::<ChatFile>(path, &CHAT)
+            .ok()
+            .map(|chat| chat.to_index_entry())
+    })
+}
+
 /// Add a message to a chat session file
 pub fn add_message_to_chat(
     project_path: &Path,
@@ -5,7 +5,11 @@
 //! - Project: `{project}/.ralph-ui/chat-commands.json`
 //! - Global: `~/.ralph-ui/chat-commands.json`
 
-use super::{get_global_ralph_ui_dir, get_ralph_ui_dir, read_json, write_json};
+use super::migrations::{
+    ensure_writable, read_versioned, write_versioned, DocumentSchema, CHAT_COMMANDS,
+    GLOBAL_CHAT_COMMANDS,
+};
+use super::{get_global_ralph_ui_dir, get_ralph_ui_dir};
 use crate::models::{ChatCommandConfig, ChatCommandPreference, ChatCommandScope, ChatCommandsFile};
 use std::path::Path;
 
@@ -20,16 +24,25 @@ fn get_global_commands_path() -> std::path::PathBuf {
 }
 
 /// Load chat commands file from a specific path
-fn load_commands_file(path: &Path) -> ChatCommandsFile {
+fn load_commands_file(path: &Path, schema: &DocumentSchema) -> ChatCommandsFile {
     if path.exists() {
-        read_json(path).unwrap_or_default()
+        read_versioned(path, schema).unwrap_or_else(|e| {
+            log::warn!("Ignoring chat commands file: {}", e);
+            ChatCommandsFile::default()
+        })
     } else {
         ChatCommandsFile::default()
     }
 }
 
 /// Save chat commands file to a specific path
-fn save_commands_file(path: &Path, file: &ChatCommandsFile) -> Result<(), String> {
+fn save_commands_file(
+    path: &Path,
+    file: &ChatCommandsFile,
+    schema: &DocumentSchema,
+) -> Result<(), String> {
+    // Loading falls back to defaults, which mustn't replace a newer file
+    ensure_writable(path, schema)?;
     // Don't save empty files
     if file.is_empty() {
         // If file exists and we're saving empty, delete it
@@ -39,27 +52,31 @@ fn save_commands_file(path: &Path, file: &ChatCommandsFile) -> Result<(), String
         }
         return Ok(());
     }
-    write_json(path, file)
+    write_versioned(path, file, schema)
 }
 
 /// Load project-specific chat commands
 pub fn load_project_commands(project_path: &Path) -> ChatCommandsFile {
-    load_commands_file(&get_project_commands_path(project_path))
+    load_commands_file(&get_project_commands_path(project_path), &CHAT_COMMANDS)
 }
 
 /// Load global chat commands
 pub fn load_global_commands() -> ChatCommandsFile {
-    load_commands_file(&get_global_commands_path())
+    load_commands_file(&get_global_commands_path(), &GLOBAL_CHAT_COMMANDS)
 }
 
 /// Save project-specific chat commands
 pub fn save_project_commands(project_path: &Path, file: &ChatCommandsFile) -> Result<(), String> {
-    save_commands_file(&get_project_commands_path(project_path), file)
+    save_commands_file(
+        &get_project_commands_path(project_path),
+        file,
+        &CHAT_COMMANDS,
+    )
 }
 
 /// Save global chat commands
 pub fn save_global_commands(file: &ChatCommandsFile) -> Result<(), String> {
-    save_commands_file(&get_global_commands_path(), file)
+    save_commands_file(&get_global_commands_path(), file, &GLOBAL_CHAT_COMMANDS)
 }
 
 /// Get builtin commands (hardcoded defaults)
@@ -4,7 +4,8 @@
 //! Context files provide AI agents with consistent understanding of project
 //! product vision, tech stack, conventions, and workflow preferences.
 
-use super::{atomic_write, ensure_dir, get_ralph_ui_dir, read_json, write_json, FileResult};
+use super::migrations::{read_versioned, write_versioned, CONTEXT_CHAT, CONTEXT_CONFIG};
+use super::{atomic_write, ensure_dir, get_ralph_ui_dir, FileResult};
 use crate::models::context::{
     estimate_tokens, ContextChatMessage, ContextChatSession, ContextConfig, ContextFile,
     ContextMode, ProjectContext, DEFAULT_CONTEXT_TEMPLATE, MAX_CONTEXT_FILE_SIZE,
@@ -50,7 +51,7 @@ pub fn get_context_chat_file_path(project_path: &Path, session_id: &str) -> Path
 pub fn read_context_config(project_path: &Path) -> FileResult<ContextConfig> {
     let config_path = get_context_config_path(project_path);
     if config_path.exists() {
-        read_json(&config_path)
+        read_versioned(&config_path, &CONTEXT_CONFIG)
     } else {
         Ok(ContextConfig::default())
     }
@@ -59,7 +60,7 @@ pub fn read_context_config(project_path: &Path) -> FileResult<ContextConfig> {
 /// Save context configuration
 pub fn save_context_config(project_path: &Path, config: &ContextConfig) -> FileResult<()> {
     let config_path = get_context_config_path(project_path);
-    write_json(&config_path, config)
+    write_versioned(&config_path, config, &CONTEXT_CONFIG)
 }
 
 // ============================================================================
@@ -273,7 +274,7 @@ pub fn create_context_chat_session(
 
     let file = ContextChatFile::new(session.clone());
     let file_path = get_context_chat_file_path(project_path, &session.id);
-    write_json(&file_path, &file)
+    write_versioned(&file_path, &file, &CONTEXT_CHAT)
 }
 
 /// Get a context chat session by ID
@@ -282,7 +283,7 @@ pub fn get_context_chat_session(
     session_id: &str,
 ) -> FileResult<ContextChatSession> {
     let file_path = get_context_chat_file_path(project_path, session_id);
-    let file: ContextChatFile = read_json(&file_path)?;
+    let file: ContextChatFile = read_versioned(&file_path, &CONTEXT_CHAT)?;
     Ok(file.session)
 }
 
@@ -293,7 +294,7 @@ pub fn get_context_chat_session_opt(
 ) -> FileResult<Option<ContextChatSession>> {
     let file_path = get_context_chat_file_path(project_path, session_id);
     if file_path.exists() {
-        let file: ContextChatFile = read_json(&file_path)?;
+        let file: ContextChatFile = read_versioned(&file_path, &CONTEXT_CHAT)?;
         Ok(Some(file.session))
     } else {
         Ok(None)
@@ -314,7 +315,7 @@ pub fn list_context_chat_sessions(project_path: &Path) -> FileResult<Vec<Context
     for entry in entries.flatten() {
         let path = entry.path();
         if path.extension().is_some_and(|ext| ext == "json") {
-            match read_json::<ContextChatFile>(&path) {
+            match read_versioned::<ContextChatFile>(&path, &CONTEXT_CHAT) {
                 Ok(file) => sessions.push(file.session),
                 Err(e) => log::warn!("Failed to read context chat file {:?}: {}", path, e),
             }
@@ -333,13 +334,13 @@ pub fn add_context_chat_message(
     message: &ContextChatMessage,
 ) -> FileResult<()> {
     let file_path = get_context_chat_file_path(project_path, session_id);
-    let mut file: ContextChatFile = read_json(&file_path)?;
+    let mut file: ContextChatFile = read_versioned(&file_path, &CONTEXT_CHAT)?;
 
     file.messages.push(message.clone());
     file.session.message_count = file.messages.len() as i32;
     file.session.updated_at = Utc::now();
 
-    write_json(&file_path, &file)
+    write_versioned(&file_path, &file, &CONTEXT_CHAT)
 }
 
 /// Get all messages for a context chat session
@@ -348,7 +349,7 @@ pub fn get_context_chat_messages(
     session_id: &str,
 ) -> FileResult<Vec<ContextChatMessage>> {
     let file_path = get_context_chat_file_path(project_path, session_id);
-    let file: ContextChatFile = read_json(&file_path)?;
+    let file: ContextChatFile = read_versioned(&file_path, &CONTEXT_CHAT)?;
     Ok(file.messages)
 }
 
@@ -359,23 +360,23 @@ pub fn update_context_chat_extracted(
     extracted_context: Option<String>,
 ) -> FileResult<()> {
     let file_path = get_context_chat_file_path(project_path, session_id);
-    let mut file: ContextChatFile = read_json(&file_path)?;
+    let mut file: ContextChatFile = read_versioned(&file_path, &CONTEXT_CHAT)?;
 
     file.session.extracted_context = extracted_context;
     file.session.updated_at = Utc::now();
 
-    write_json(&file_path, &file)
+    write_versioned(&file_path, &file, &CONTEXT_CHAT)
 }
 
 /// Mark context as saved for a context chat session
 pub fn mark_context_chat_saved(project_path: &Path, session_id: &str) -> FileResult<()> {
     let file_path = get_context_chat_file_path(project_path, session_id);
-    let mut file: ContextChatFile = read_json(&file_path)?;
+    let mut file: ContextChatFile = read_versioned(&file_path, &CONTEXT_CHAT)?;
 
     file.session.context_saved = true;
     file.session.updated_at = Utc::now();
 
-    write_json(&file_path, &file)
+    write_versioned(&file_path, &file, &CONTEXT_CHAT)
 }
 
 /// Update external session ID for CLI agent session resumption
@@ -385,11 +386,11 @@ pub fn update_context_chat_external_id(
     external_id: Option<String>,
 ) -> FileResult<()> {
     let file_path = get_context_chat_file_path(project_path, session_id);
-    let mut file: ContextChatFile = read_json(&file_path)?;
+    let mut file: ContextChatFile = read_versioned(&file_path, &CONTEXT_CHAT)?;
 
     file.session.external_session_id = external_id;
 
-    write_json(&file_path, &file)
+    write_versioned(&file_path, &file, &CONTEXT_CHAT)
 }
 
 /// Delete a context chat session
@@ -2,11 +2,21 @@
 //!
 //! Stores iteration history in `.ralph-ui/iterations/`:
 //! - `{execution_id}.json` - Execution state and iteration history
-
-use super::{ensure_dir, get_ralph_ui_dir, read_json, write_json, FileResult};
-use crate::ralph_loop::{ExecutionStateSnapshot, IterationOutcome, IterationRecord};
+//! - `tool-stats/{execution_id}.json` - Per-iteration tool-call analytics
+
+use super::migrations::{read_versioned, write_versioned, EXECUTION, TOOL_STATS};
+use super::{ensure_dir, get_ralph_ui_dir, FileResult};
+use crate::ralph_loop::{
+    ExecutionCheckpoint, ExecutionStateSnapshot, IterationOutcome, IterationRecord,
+    IterationToolStats, ToolAnalyticsFilter,
+};
+use crate::utils::lock_mutex_recover;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes read-modify-write of tool stats files (parallel agents finish concurrently)
+static TOOL_STATS_LOCK: Mutex<()> = Mutex::new(());
 
 /// Version of the iteration file format
 const ITERATION_FILE_VERSION: u32 = 1;
@@ -25,13 +35,26 @@ pub struct ExecutionFile {
     /// Last heartbeat timestamp
     #[serde(default)]
     pub last_heartbeat: Option<String>,
+    /// Resume checkpoint written at server shutdown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<ExecutionCheckpoint>,
     /// Iteration history
     #[serde(default)]
     pub iterations: Vec<IterationRecord>,
 }
 
+/// Tool-call analytics for an execution's iterations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStatsFile {
+    pub version: u32,
+    pub execution_id: String,
+    #[serde(default)]
+    pub iterations: Vec<IterationToolStats>,
+}
+
 /// Statistics for an execution's iterations
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IterationStats {
     pub total: u32,
@@ -39,6 +62,8 @@ pub struct IterationStats {
     pub failed: u32,
     pub skipped: u32,
     pub interrupted: u32,
+    #[serde(default)]
+    pub recovered: u32,
     pub rate_limited: u32,
     pub total_duration_secs: f64,
 }
@@ -53,6 +78,16 @@ pub fn get_execution_file_path(project_path: &Path, execution_id: &str) -> PathB
     get_iterations_dir(project_path).join(format!("{}.json", execution_id))
 }
 
+/// Get the directory holding tool-call analytics
+pub fn get_tool_stats_dir(project_path: &Path) -> PathBuf {
+    get_iterations_dir(project_path).join("tool-stats")
+}
+
+/// Get the file path for an execution's tool-call analytics
+pub fn get_tool_stats_file_path(project_path: &Path, execution_id: &str) -> PathBuf {
+    get_tool_stats_dir(project_path).join(format!("{}.json", execution_id))
+}
+
 /// Get or create an execution file
 fn get_or_create_execution_file(
     project_path: &Path,
@@ -61,13 +96,14 @@ fn get_or_create_execution_file(
     let file_path = get_execution_file_path(project_path, execution_id);
 
     if file_path.exists() {
-        read_json(&file_path)
+        read_versioned(&file_path, &EXECUTION)
     } else {
         Ok(ExecutionFile {
             version: ITERATION_FILE_VERSION,
             execution_id: execution_id.to_string(),
             state: None,
             last_heartbeat: None,
+            checkpoint: None,
             iterations: Vec::new(),
         })
     }
@@ -79,7 +115,7 @@ fn save_execution_file(project_path: &Path, file: &ExecutionFile) -> FileResult<
     ensure_dir(&iterations_dir)?;
 
     let file_path = get_execution_file_path(project_path, &file.execution_id);
-    write_json(&file_path, file)?;
+    write_versioned(&file_path, file, &EXECUTION)?;
 
     Ok(())
 }
@@ -127,6 +163,29 @@ pub fn update_iteration(
     Ok(updated)
 }
 
+/// Record how an iteration ended: updates the latest record of the same
+/// iteration, or inserts `record` if the iteration was never saved
+pub fn upsert_iteration(project_path: &Path, record: &IterationRecord) -> FileResult<()> {
+    let mut file = get_or_create_execution_file(project_path, &record.execution_id)?;
+
+    match file
+        .iterations
+        .iter_mut()
+        .rev()
+        .find(|i| i.iteration == record.iteration)
+    {
+        Some(existing) => {
+            existing.outcome = record.outcome.clone();
+            existing.duration_secs = record.duration_secs;
+            existing.completed_at = record.completed_at.clone();
+            existing.error_message = record.error_message.clone();
+        }
+        None => file.iterations.push(record.clone()),
+    }
+
+    save_execution_file(project_path, &file)
+}
+
 /// Get all iterations for an execution
 pub fn get_iterations_for_execution(
     project_path: &Path,
@@ -168,7 +227,7 @@ pub fn get_iteration_history(
                 continue;
             }
 
-            if let Ok(file) = read_json::<ExecutionFile>(&path) {
+            if let Ok(file) = read_versioned::<ExecutionFile>(&path, &EXECUTION) {
                 all_iterations.extend(file.iterations);
             }
         }
@@ -205,6 +264,7 @@ pub fn get_execution_stats(project_path: &Path, execution_id: &str) -> FileResul
             IterationOutcome::Failed => stats.failed += 1,
             IterationOutcome::Skipped => stats.skipped += 1,
             IterationOutcome::Interrupted => stats.interrupted += 1,
+            IterationOutcome::Recovered => stats.recovered += 1,
         }
 
         if iter.rate_limit_encountered {
@@ -232,9 +292,67 @@ pub fn delete_iterations_for_execution(
     std::fs::remove_file(&file_path)
         .map_err(|e| format!("Failed to delete execution file: {}", e))?;
 
+    let stats_path = get_tool_stats_file_path(project_path, execution_id);
+    if stats_path.exists() {
+        std::fs::remove_file(&stats_path)
+            .map_err(|e| format!("Failed to delete tool stats file: {}", e))?;
+    }
+
     Ok(count)
 }
 
+/// Record tool-call analytics for an iteration (a retried attempt replaces earlier ones)
+pub fn record_tool_stats(project_path: &Path, record: &IterationToolStats) -> FileResult<()> {
+    let _guard = lock_mutex_recover(&TOOL_STATS_LOCK);
+
+    let file_path = get_tool_stats_file_path(project_path, &record.execution_id);
+    let mut file = if file_path.exists() {
+        read_versioned(&file_path, &TOOL_STATS)?
+    } else {
+        ToolStatsFile {
+            version: ITERATION_FILE_VERSION,
+            execution_id: record.execution_id.clone(),
+            iterations: Vec::new(),
+        }
+    };
+
+    file.iterations.retain(|r| r.iteration != record.iteration);
+    file.iterations.push(record.clone());
+
+    ensure_dir(&get_tool_stats_dir(project_path))?;
+    write_versioned(&file_path, &file, &TOOL_STATS)
+}
+
+/// List recorded tool-call analytics across executions
+pub fn list_tool_stats(
+    project_path: &Path,
+    filter: &ToolAnalyticsFilter,
+) -> FileResult<Vec<IterationToolStats>> {
+    let stats_dir = get_tool_stats_dir(project_path);
+    if !stats_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let paths: Vec<PathBuf> = match &filter.execution_id {
+        Some(execution_id) => vec![get_tool_stats_file_path(project_path, execution_id)],
+        None => std::fs::read_dir(&stats_dir)
+            .map_err(|e| format!("Failed to read tool stats directory: {}", e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect(),
+    };
+
+    let mut records = Vec::new();
+    for path in paths.iter().filter(|p| p.exists()) {
+        match read_versioned::<ToolStatsFile>(path, &TOOL_STATS) {
+            Ok(file) => records.extend(file.iterations.into_iter().filter(|r| filter.matches(r))),
+            Err(e) => log::warn!("[Iterations] Skipping tool stats file {:?}: {}", path, e),
+        }
+    }
+
+    Ok(records)
+}
+
 /// Save or update execution state snapshot
 pub fn save_execution_state(
     project_path: &Path,
@@ -243,6 +361,7 @@ pub fn save_execution_state(
     let mut file = get_or_create_execution_file(project_path, &snapshot.execution_id)?;
     file.state = Some(snapshot.state.clone());
     file.last_heartbeat = Some(snapshot.last_heartbeat.clone());
+    file.checkpoint = snapshot.checkpoint.clone();
     save_execution_file(project_path, &file)
 }
 
@@ -270,34 +389,34 @@ pub fn get_execution_state(
     }
 
     let file = get_or_create_execution_file(project_path, execution_id)?;
+    Ok(state_snapshot(file))
+}
 
-    match (&file.state, &file.last_heartbeat) {
-        (Some(state), Some(heartbeat)) => Ok(Some(ExecutionStateSnapshot {
+/// State snapshot of an execution file, if it has state
+fn state_snapshot(file: ExecutionFile) -> Option<ExecutionStateSnapshot> {
+    match (file.state, file.last_heartbeat) {
+        (Some(state), Some(last_heartbeat)) => Some(ExecutionStateSnapshot {
             execution_id: file.execution_id,
-            state: state.clone(),
-            last_heartbeat: heartbeat.clone(),
-        })),
-        _ => Ok(None),
+            state,
+            last_heartbeat,
+            checkpoint: file.checkpoint,
+        }),
+        _ => None,
     }
 }
 
-/// Get stale executions (heartbeat older than threshold)
-pub fn get_stale_executions(
-    project_path: &Path,
-    threshold_secs: i64,
-) -> FileResult<Vec<ExecutionStateSnapshot>> {
+/// Read the state snapshots of all executions of a project
+fn execution_snapshots(project_path: &Path) -> FileResult<Vec<ExecutionStateSnapshot>> {
     let iterations_dir = get_iterations_dir(project_path);
 
     if !iterations_dir.exists() {
         return Ok(Vec::new());
     }
 
-    let threshold_time = chrono::Utc::now() - chrono::Duration::seconds(threshold_secs);
-    let mut stale = Vec::new();
-
     let entries = std::fs::read_dir(&iterations_dir)
         .map_err(|e| format!("Failed to read iterations directory: {}", e))?;
 
+    let mut snapshots = Vec::new();
     for entry in entries {
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
         let path = entry.path();
@@ -306,34 +425,62 @@ pub fn get_stale_executions(
             continue;
         }
 
-        if let Ok(file) = read_json::<ExecutionFile>(&path) {
-            if let (Some(state), Some(heartbeat)) = (&file.state, &file.last_heartbeat) {
-                if let Ok(hb_time) = chrono::DateTime::parse_from_rfc3339(heartbeat) {
-                    if hb_time.with_timezone(&chrono::Utc) < threshold_time {
-                        stale.push(ExecutionStateSnapshot {
-                            execution_id: file.execution_id,
-                            state: state.clone(),
-                            last_heartbeat: heartbeat.clone(),
-                        });
-                    }
-                }
-            }
+        if let Some(snapshot) = read_versioned::<ExecutionFile>(&path, &EXECUTION)
+            .ok()
+            .and_then(state_snapshot)
+        {
+            snapshots.push(snapshot);
         }
     }
 
-    Ok(stale)
+    Ok(snapshots)
+}
+
+/// Get stale executions (heartbeat older than threshold)
+///
+/// Checkpointed executions aren't stale: they are waiting to be resumed.
+pub fn get_stale_executions(
+    project_path: &Path,
+    threshold_secs: i64,
+) -> FileResult<Vec<ExecutionStateSnapshot>> {
+    let threshold_time = chrono::Utc::now() - chrono::Duration::seconds(threshold_secs);
+
+    Ok(execution_snapshots(project_path)?
+        .into_iter()
+        .filter(|snapshot| snapshot.checkpoint.is_none())
+        .filter(|snapshot| {
+            chrono::DateTime::parse_from_rfc3339(&snapshot.last_heartbeat)
+                .is_ok_and(|hb_time| hb_time.with_timezone(&chrono::Utc) < threshold_time)
+        })
+        .collect())
+}
+
+/// Get executions checkpointed at server shutdown, oldest first
+pub fn get_checkpointed_executions(project_path: &Path) -> FileResult<Vec<ExecutionStateSnapshot>> {
+    let mut checkpointed: Vec<ExecutionStateSnapshot> = execution_snapshots(project_path)?
+        .into_iter()
+        .filter(|snapshot| snapshot.checkpoint.is_some())
+        .collect();
+    checkpointed.sort_by_cached_key(|snapshot| {
+        snapshot
+            .checkpoint
+            .as_ref()
+            .map(|c| c.checkpointed_at.clone())
+    });
+    Ok(checkpointed)
 }
 
 /// Delete execution state (cleanup after completion)
 pub fn delete_execution_state(project_path: &Path, execution_id: &str) -> FileResult<usize> {
     let mut file = get_or_create_execution_file(project_path, execution_id)?;
 
-    if file.state.is_none() && file.last_heartbeat.is_none() {
+    if file.state.is_none() && file.last_heartbeat.is_none() && file.checkpoint.is_none() {
         return Ok(0);
     }
 
     file.state = None;
     file.last_heartbeat = None;
+    file.checkpoint = None;
 
     // If no iterations left and no state, delete the file
     if file.iterations.is_empty() {
@@ -470,6 +617,7 @@ mod tests {
             execution_id: "exec-1".to_string(),
             state: r#"{"iteration":3}"#.to_string(),
             last_heartbeat: "2024-01-01T00:00:00Z".to_string(),
+            checkpoint: None,
         };
 
         save_execution_state(temp_dir.path(), &snapshot).unwrap();
@@ -492,6 +640,57 @@ mod tests {
         assert!(retrieved.is_none());
     }
 
+    #[test]
+    fn test_checkpointed_executions() {
+        let temp_dir = setup_test_project();
+        let old_heartbeat = "2024-01-01T00:00:00Z".to_string();
+
+        let stale = ExecutionStateSnapshot {
+            execution_id: "exec-stale".to_string(),
+            state: "{}".to_string(),
+            last_heartbeat: old_heartbeat.clone(),
+            checkpoint: None,
+        };
+        let request = serde_json::from_value(serde_json::json!({
+            "projectPath": temp_dir.path(),
+            "agentType": "claude",
+            "prdName": "dark-mode",
+        }))
+        .unwrap();
+        let checkpointed = ExecutionStateSnapshot {
+            execution_id: "exec-checkpointed".to_string(),
+            state: "{}".to_string(),
+            last_heartbeat: old_heartbeat,
+            checkpoint: Some(ExecutionCheckpoint {
+                request,
+                iteration: 4,
+                worktree_path: None,
+                checkpointed_at: "2024-01-01T00:00:00Z".to_string(),
+            }),
+        };
+        save_execution_state(temp_dir.path(), &stale).unwrap();
+        save_execution_state(temp_dir.path(), &checkpointed).unwrap();
+
+        // Checkpointed executions wait for a resume instead of being recovered as stale
+        let stale_ids: Vec<String> = get_stale_executions(temp_dir.path(), 60)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.execution_id)
+            .collect();
+        assert_eq!(stale_ids, vec!["exec-stale"]);
+
+        let resumable = get_checkpointed_executions(temp_dir.path()).unwrap();
+        assert_eq!(resumable.len(), 1);
+        let checkpoint = resumable[0].checkpoint.as_ref().unwrap();
+        assert_eq!(checkpoint.iteration, 4);
+        assert_eq!(checkpoint.request.prd_name, "dark-mode");
+
+        delete_execution_state(temp_dir.path(), "exec-checkpointed").unwrap();
+        assert!(get_checkpointed_executions(temp_dir.path())
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_mark_interrupted_iterations() {
         let temp_dir = setup_test_project();
@@ -512,4 +711,72 @@ mod tests {
             Some("2024-01-01T00:05:00Z".to_string())
         );
     }
+
+    #[test]
+    fn test_upsert_iteration() {
+        let temp_dir = setup_test_project();
+
+        // An iteration that was never saved is inserted
+        let mut record = create_test_iteration("iter-1", "exec-1", 1);
+        record.outcome = IterationOutcome::Recovered;
+        upsert_iteration(temp_dir.path(), &record).unwrap();
+
+        // A saved one is updated in place
+        let mut second = create_test_iteration("iter-2", "exec-1", 2);
+        second.completed_at = None;
+        insert_iteration(temp_dir.path(), &second).unwrap();
+        let mut ended = create_test_iteration("other-id", "exec-1", 2);
+        ended.outcome = IterationOutcome::Interrupted;
+        upsert_iteration(temp_dir.path(), &ended).unwrap();
+
+        let iterations = get_iterations_for_execution(temp_dir.path(), "exec-1").unwrap();
+        assert_eq!(iterations.len(), 2);
+        assert_eq!(iterations[0].outcome, IterationOutcome::Recovered);
+        assert_eq!(iterations[1].id, "iter-2");
+        assert_eq!(iterations[1].outcome, IterationOutcome::Interrupted);
+        assert!(iterations[1].completed_at.is_some());
+
+        let stats = get_execution_stats(temp_dir.path(), "exec-1").unwrap();
+        assert_eq!(stats.recovered, 1);
+        assert_eq!(stats.interrupted, 1);
+    }
+
+    #[test]
+    fn test_record_and_list_tool_stats() {
+        use crate::ralph_loop::ToolCallStats;
+
+        let temp_dir = setup_test_project();
+        let record =
+            |execution_id: &str, iteration: u32, attempt: u32, model: &str| IterationToolStats {
+                execution_id: execution_id.to_string(),
+                iteration,
+                attempt,
+                agent_id: format!("agent-{}", attempt),
+                agent_type: AgentType::Claude,
+                model: Some(model.to_string()),
+                prd_name: Some("prd".to_string()),
+                story_id: None,
+                recorded_at: "2024-01-01T00:00:00Z".to_string(),
+                stats: ToolCallStats::default(),
+            };
+
+        record_tool_stats(temp_dir.path(), &record("exec-1", 1, 1, "opus")).unwrap();
+        // A retry replaces the earlier attempt
+        record_tool_stats(temp_dir.path(), &record("exec-1", 1, 2, "opus")).unwrap();
+        record_tool_stats(temp_dir.path(), &record("exec-2", 1, 1, "sonnet")).unwrap();
+
+        let all = list_tool_stats(temp_dir.path(), &ToolAnalyticsFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let filter = ToolAnalyticsFilter {
+            model: Some("opus".to_string()),
+            ..Default::default()
+        };
+        let opus = list_tool_stats(temp_dir.path(), &filter).unwrap();
+        assert_eq!(opus.len(), 1);
+        assert_eq!(opus[0].attempt, 2);
+
+        // Iteration history scanning ignores the tool-stats subdirectory
+        assert!(get_iteration_history(temp_dir.path(), None, None, None).is_ok());
+    }
 }
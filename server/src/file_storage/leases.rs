@@ -0,0 +1,289 @@
+//! Cross-process execution leases
+//!
+//! A PRD is executed by one loop at a time, even across Ralph UI instances
+//! sharing a repository (two machines on a shared checkout, or a server and
+//! `ralph-ui run`). The loop holds a lease in `.ralph-ui/leases/{prd_name}.json`
+//! naming its holder, renews it while it runs and removes it when it ends.
+//! A lease whose holder stopped renewing it is broken once it has expired.
+//!
+//! Lease files are read and written under an `fs2` lock on `{prd_name}.lock`,
+//! so two processes can't both acquire the same lease.
+
+use super::{ensure_dir, get_ralph_ui_dir, read_json, write_json, FileResult};
+use chrono::{DateTime, Duration, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Version of the lease file format
+const LEASE_FILE_VERSION: u32 = 1;
+
+/// How long a lease stays valid without being renewed
+pub const LEASE_TTL_SECS: i64 = 90;
+
+/// Who holds a lease
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaseHolder {
+    pub host: String,
+    pub pid: u32,
+    pub version: String,
+}
+
+impl LeaseHolder {
+    /// This process
+    pub fn current() -> Self {
+        Self {
+            host: sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string()),
+            pid: std::process::id(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Lease on a PRD held by one of its executions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionLease {
+    pub version: u32,
+    pub prd_name: String,
+    pub execution_id: String,
+    pub holder: LeaseHolder,
+    pub acquired_at: DateTime<Utc>,
+    /// Last heartbeat
+    pub renewed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ExecutionLease {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "execution {} (PID {} on {}, renewed {}s ago)",
+            self.execution_id,
+            self.holder.pid,
+            self.holder.host,
+            (Utc::now() - self.renewed_at).num_seconds().max(0)
+        )
+    }
+}
+
+/// Get the leases directory path for a project
+pub fn get_leases_dir(project_path: &Path) -> PathBuf {
+    get_ralph_ui_dir(project_path).join("leases")
+}
+
+/// Get the lease file path for a PRD
+pub fn get_lease_file_path(project_path: &Path, prd_name: &str) -> PathBuf {
+    get_leases_dir(project_path).join(format!("{}.json", prd_name))
+}
+
+/// Run `operation` on a PRD's lease file while holding its lock
+fn with_lease_lock<T>(
+    project_path: &Path,
+    prd_name: &str,
+    operation: impl FnOnce(&Path, Option<ExecutionLease>) -> FileResult<T>,
+) -> FileResult<T> {
+    let leases_dir = get_leases_dir(project_path);
+    ensure_dir(&leases_dir)?;
+
+    let lock_path = leases_dir.join(format!("{}.lock", prd_name));
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to open lease lock: {}", e))?;
+    lock_file
+        .lock_exclusive()
+        .map_err(|e| format!("Failed to lock lease: {}", e))?;
+
+    let lease_path = get_lease_file_path(project_path, prd_name);
+    let current = if lease_path.exists() {
+        Some(read_json(&lease_path)?)
+    } else {
+        None
+    };
+
+    // Lock is released when lock_file is dropped
+    operation(&lease_path, current)
+}
+
+/// Get a PRD's lease, if any (expired leases included)
+pub fn get_lease(project_path: &Path, prd_name: &str) -> FileResult<Option<ExecutionLease>> {
+    let lease_path = get_lease_file_path(project_path, prd_name);
+    if !lease_path.exists() {
+        return Ok(None);
+    }
+    read_json(&lease_path).map(Some)
+}
+
+/// List the leases of a project's PRDs
+pub fn list_leases(project_path: &Path) -> FileResult<Vec<ExecutionLease>> {
+    let leases_dir = get_leases_dir(project_path);
+    if !leases_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        fs::read_dir(&leases_dir).map_err(|e| format!("Failed to read leases directory: {}", e))?;
+    let mut leases = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+        match read_json::<ExecutionLease>(&path) {
+            Ok(lease) => leases.push(lease),
+            Err(e) => log::warn!("[Leases] Skipping unreadable lease {:?}: {}", path, e),
+        }
+    }
+    leases.sort_by(|a, b| a.prd_name.cmp(&b.prd_name));
+    Ok(leases)
+}
+
+/// Acquire the lease on a PRD for an execution. Fails while another execution
+/// holds an unexpired lease, unless `takeover` is set.
+pub fn acquire_lease(
+    project_path: &Path,
+    prd_name: &str,
+    execution_id: &str,
+    takeover: bool,
+) -> FileResult<ExecutionLease> {
+    with_lease_lock(project_path, prd_name, |lease_path, current| {
+        let now = Utc::now();
+        let mut acquired_at = now;
+
+        if let Some(current) = current {
+            if current.execution_id == execution_id {
+                acquired_at = current.acquired_at;
+            } else if current.is_expired() {
+                log::info!(
+                    "[Leases] Breaking expired lease on '{}' held by {}",
+                    prd_name,
+                    current.describe()
+                );
+            } else if takeover {
+                log::warn!(
+                    "[Leases] Taking over lease on '{}' from {}",
+                    prd_name,
+                    current.describe()
+                );
+            } else {
+                return Err(format!(
+                    "PRD '{}' is already being executed by {}. Stop that loop, wait for its \
+                     lease to expire at {}, or start with takeover to take it over",
+                    prd_name,
+                    current.describe(),
+                    current.expires_at.to_rfc3339()
+                ));
+            }
+        }
+
+        let lease = ExecutionLease {
+            version: LEASE_FILE_VERSION,
+            prd_name: prd_name.to_string(),
+            execution_id: execution_id.to_string(),
+            holder: LeaseHolder::current(),
+            acquired_at,
+            renewed_at: now,
+            expires_at: now + Duration::seconds(LEASE_TTL_SECS),
+        };
+        write_json(lease_path, &lease)?;
+        Ok(lease)
+    })
+}
+
+/// Renew an execution's lease. Returns false if it no longer holds the lease
+/// (it was taken over, or broken after expiring).
+pub fn renew_lease(project_path: &Path, prd_name: &str, execution_id: &str) -> FileResult<bool> {
+    with_lease_lock(project_path, prd_name, |lease_path, current| {
+        let mut lease = match current {
+            Some(lease) if lease.execution_id == execution_id => lease,
+            _ => return Ok(false),
+        };
+
+        let now = Utc::now();
+        lease.renewed_at = now;
+        lease.expires_at = now + Duration::seconds(LEASE_TTL_SECS);
+        write_json(lease_path, &lease)?;
+        Ok(true)
+    })
+}
+
+/// Release an execution's lease. Returns false if it doesn't hold the lease.
+pub fn release_lease(project_path: &Path, prd_name: &str, execution_id: &str) -> FileResult<bool> {
+    with_lease_lock(
+        project_path,
+        prd_name,
+        |lease_path, current| match current {
+            Some(lease) if lease.execution_id == execution_id => {
+                fs::remove_file(lease_path)
+                    .map_err(|e| format!("Failed to remove lease: {}", e))?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_acquire_renew_release() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path();
+
+        let lease = acquire_lease(project, "dark-mode", "exec-1", false).unwrap();
+        assert_eq!(lease.holder.pid, std::process::id());
+        assert!(!lease.is_expired());
+
+        // Held by exec-1: exec-2 is refused
+        let err = acquire_lease(project, "dark-mode", "exec-2", false).unwrap_err();
+        assert!(err.contains("exec-1"), "{}", err);
+        // Other PRDs are independent
+        acquire_lease(project, "search", "exec-2", false).unwrap();
+
+        // Re-acquiring (resuming the same execution) keeps the acquisition time
+        let again = acquire_lease(project, "dark-mode", "exec-1", false).unwrap();
+        assert_eq!(again.acquired_at, lease.acquired_at);
+
+        assert!(renew_lease(project, "dark-mode", "exec-1").unwrap());
+        let renewed = get_lease(project, "dark-mode").unwrap().unwrap();
+        assert!(renewed.expires_at >= lease.expires_at);
+        assert!(!renew_lease(project, "dark-mode", "exec-2").unwrap());
+
+        assert_eq!(list_leases(project).unwrap().len(), 2);
+        assert!(!release_lease(project, "dark-mode", "exec-2").unwrap());
+        assert!(release_lease(project, "dark-mode", "exec-1").unwrap());
+        assert!(get_lease(project, "dark-mode").unwrap().is_none());
+        assert!(!renew_lease(project, "dark-mode", "exec-1").unwrap());
+    }
+
+    #[test]
+    fn test_expired_lease_and_takeover() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path();
+
+        acquire_lease(project, "dark-mode", "exec-1", false).unwrap();
+
+        // Takeover breaks a live lease; the old holder can't renew it anymore
+        let taken = acquire_lease(project, "dark-mode", "exec-2", true).unwrap();
+        assert_eq!(taken.execution_id, "exec-2");
+        assert!(!renew_lease(project, "dark-mode", "exec-1").unwrap());
+
+        // An expired lease is broken without takeover
+        let mut expired = taken;
+        expired.expires_at = Utc::now() - Duration::seconds(1);
+        write_json(&get_lease_file_path(project, "dark-mode"), &expired).unwrap();
+        let lease = acquire_lease(project, "dark-mode", "exec-3", false).unwrap();
+        assert_eq!(lease.execution_id, "exec-3");
+    }
+}
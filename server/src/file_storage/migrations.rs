@@ -0,0 +1,625 @@
+//! Versioned schema migrations for stored documents
+//!
+//! Every JSON document Ralph UI keeps in `.ralph-ui/` and `~/.ralph-ui/`
+//! carries a `schemaVersion` field. Documents written before the field
+//! existed are version 0. Each document kind has an ordered list of
+//! migrations that upgrade the raw JSON one version at a time, so files
+//! committed by an older Ralph UI are upgraded instead of silently falling
+//! back to serde defaults, and files written by a newer Ralph UI are refused
+//! instead of being overwritten without the fields this version doesn't know.
+//!
+//! Reads upgrade documents in memory ([`read_versioned`]). The migration pass
+//! ([`migrate_project`], [`migrate_global`]) rewrites outdated files on disk
+//! after copying the originals to `backups/migrations/<timestamp>/`, and runs
+//! on startup and whenever a project is opened. With `dry_run` it only reports
+//! what it would do.
+//!
+//! `schemaVersion` is independent of the `version` fields some files already
+//! have (e.g. `ExecutionFile`), which are left untouched.
+
+use super::{
+    atomic_write, ensure_dir, get_global_ralph_ui_dir, get_ralph_ui_dir, read_json, FileResult,
+};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Field holding a stored document's schema version
+pub const SCHEMA_VERSION_FIELD: &str = "schemaVersion";
+
+/// An upgrade step of a document kind
+pub struct Migration {
+    /// Version of the document after this step
+    pub to: u32,
+    pub description: &'static str,
+    /// Rewrites the document (`schemaVersion` is set by the caller)
+    pub apply: fn(&mut Map<String, Value>) -> FileResult<()>,
+}
+
+/// Whether a document kind lives in projects or in the global directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SchemaScope {
+    Project,
+    Global,
+}
+
+/// A stored document kind with its migrations
+pub struct DocumentSchema {
+    pub kind: &'static str,
+    pub scope: SchemaScope,
+    /// Files of this kind in a `.ralph-ui` directory
+    locate: fn(&Path) -> Vec<PathBuf>,
+    /// Ordered by `to`, starting at 1
+    pub migrations: &'static [Migration],
+}
+
+impl DocumentSchema {
+    /// Version this build reads and writes
+    pub fn current_version(&self) -> u32 {
+        self.migrations.last().map_or(0, |m| m.to)
+    }
+
+    /// Upgrade a document to the current version, returning the applied steps
+    fn upgrade(&self, doc: &mut Value) -> FileResult<Vec<&'static Migration>> {
+        let Value::Object(map) = doc else {
+            return Err(format!("{} document is not a JSON object", self.kind));
+        };
+        let from = stored_version(map);
+        let current = self.current_version();
+        if from > current {
+            return Err(format!(
+                "{} schema version {} is newer than this version of Ralph UI supports ({})",
+                self.kind, from, current
+            ));
+        }
+
+        let mut applied = Vec::new();
+        for migration in self.migrations.iter().filter(|m| m.to > from) {
+            (migration.apply)(map).map_err(|e| {
+                format!(
+                    "{} migration to version {} failed: {}",
+                    self.kind, migration.to, e
+                )
+            })?;
+            map.insert(SCHEMA_VERSION_FIELD.to_string(), Value::from(migration.to));
+            applied.push(migration);
+        }
+        Ok(applied)
+    }
+}
+
+fn stored_version(map: &Map<String, Value>) -> u32 {
+    map.get(SCHEMA_VERSION_FIELD)
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v as u32)
+}
+
+// =============================================================================
+// Document kinds
+// =============================================================================
+
+/// Version 1 introduces `schemaVersion` itself
+const ADD_SCHEMA_VERSION: Migration = Migration {
+    to: 1,
+    description: "Add schemaVersion",
+    apply: add_schema_version,
+};
+
+fn add_schema_version(_doc: &mut Map<String, Value>) -> FileResult<()> {
+    Ok(())
+}
+
+/// JSON files directly in a directory
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// JSON files in a directory except its `index.json`
+fn json_files_except_index(dir: &Path) -> Vec<PathBuf> {
+    json_files(dir)
+        .into_iter()
+        .filter(|p| p.file_name().is_some_and(|name| name != "index.json"))
+        .collect()
+}
+
+/// Files with a name in each subdirectory of a directory
+fn nested_files(dir: &Path, name: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().join(name))
+        .filter(|p| p.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+fn single_file(path: PathBuf) -> Vec<PathBuf> {
+    if path.is_file() {
+        vec![path]
+    } else {
+        Vec::new()
+    }
+}
+
+macro_rules! document_schema {
+    ($name:ident, $kind:literal, $scope:ident, $locate:expr) => {
+        pub static $name: DocumentSchema = DocumentSchema {
+            kind: $kind,
+            scope: SchemaScope::$scope,
+            locate: $locate,
+            migrations: &[ADD_SCHEMA_VERSION],
+        };
+    };
+}
+
+document_schema!(PRD, "prd", Project, |dir| {
+    json_files(&dir.join("prds"))
+        .into_iter()
+        .filter(|p| !p.to_string_lossy().ends_with("-structure.json"))
+        .collect()
+});
+document_schema!(SESSION, "session", Project, |dir| {
+    json_files_except_index(&dir.join("sessions"))
+});
+document_schema!(CHAT, "chat", Project, |dir| {
+    json_files_except_index(&dir.join("chat"))
+});
+document_schema!(ASSIGNMENTS, "assignments", Project, |dir| {
+    nested_files(&dir.join("briefs"), "assignments.json")
+});
+document_schema!(LEARNINGS, "learnings", Project, |dir| {
+    nested_files(&dir.join("briefs"), "learnings.json")
+});
+document_schema!(WORKFLOW_STATE, "workflow-state", Project, |dir| {
+    nested_files(&dir.join("workflows"), "state.json")
+});
+document_schema!(CONTEXT_CONFIG, "context-config", Project, |dir| {
+    single_file(dir.join("context-config.json"))
+});
+document_schema!(CONTEXT_CHAT, "context-chat", Project, |dir| {
+    json_files(&dir.join("context-chat"))
+});
+document_schema!(RESEARCH_SESSION, "research-session", Project, |dir| {
+    json_files(&dir.join("research"))
+});
+document_schema!(EXECUTION, "execution", Project, |dir| {
+    json_files(&dir.join("iterations"))
+});
+document_schema!(TOOL_STATS, "tool-stats", Project, |dir| {
+    json_files(&dir.join("iterations").join("tool-stats"))
+});
+document_schema!(CHAT_COMMANDS, "chat-commands", Project, |dir| {
+    single_file(dir.join("chat-commands.json"))
+});
+document_schema!(GLOBAL_CHAT_COMMANDS, "chat-commands", Global, |dir| {
+    single_file(dir.join("chat-commands.json"))
+});
+document_schema!(PROJECTS, "projects", Global, |dir| {
+    single_file(dir.join("projects.json"))
+});
+document_schema!(API_TOKENS, "api-tokens", Global, |dir| {
+    single_file(dir.join("tokens.json"))
+});
+document_schema!(SHARE_LINKS, "share-links", Global, |dir| {
+    single_file(dir.join("share-links.json"))
+});
+document_schema!(WEBHOOKS, "webhooks", Global, |dir| {
+    single_file(dir.join("webhooks.json"))
+});
+document_schema!(TRIGGERS, "triggers", Global, |dir| {
+    single_file(dir.join("triggers.json"))
+});
+document_schema!(FEDERATION, "federation", Global, |dir| {
+    single_file(dir.join("federation.json"))
+});
+document_schema!(PUSH_SUBSCRIPTIONS, "push-subscriptions", Global, |dir| {
+    single_file(dir.join("push_subscriptions.json"))
+});
+
+/// Every versioned document kind. Derived and runtime files (indexes,
+/// leases, agent state, the activity journal, the audit log) aren't
+/// versioned since they are rebuilt or expire, nor are write-once
+/// transcripts and recordings.
+pub static SCHEMAS: &[&DocumentSchema] = &[
+    &PRD,
+    &SESSION,
+    &CHAT,
+    &ASSIGNMENTS,
+    &LEARNINGS,
+    &WORKFLOW_STATE,
+    &CONTEXT_CONFIG,
+    &CONTEXT_CHAT,
+    &RESEARCH_SESSION,
+    &EXECUTION,
+    &TOOL_STATS,
+    &CHAT_COMMANDS,
+    &GLOBAL_CHAT_COMMANDS,
+    &PROJECTS,
+    &API_TOKENS,
+    &SHARE_LINKS,
+    &WEBHOOKS,
+    &TRIGGERS,
+    &FEDERATION,
+    &PUSH_SUBSCRIPTIONS,
+];
+
+// =============================================================================
+// Reading and writing
+// =============================================================================
+
+/// Read a versioned document, upgrading it in memory if it's outdated
+pub fn read_versioned<T: DeserializeOwned>(path: &Path, schema: &DocumentSchema) -> FileResult<T> {
+    let mut doc: Value = read_json(path)?;
+    schema
+        .upgrade(&mut doc)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    serde_json::from_value(doc).map_err(|e| format!("Failed to parse JSON from {:?}: {}", path, e))
+}
+
+/// Parse a versioned document read by the caller, upgrading it in memory
+pub fn parse_versioned<T: DeserializeOwned>(
+    content: &str,
+    schema: &DocumentSchema,
+) -> FileResult<T> {
+    let mut doc: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    schema.upgrade(&mut doc)?;
+    serde_json::from_value(doc).map_err(|e| e.to_string())
+}
+
+/// Serialize a document as pretty-printed JSON stamped with its current version
+pub fn to_versioned_string<T: Serialize>(data: &T, schema: &DocumentSchema) -> FileResult<String> {
+    let mut doc =
+        serde_json::to_value(data).map_err(|e| format!("Failed to serialize to JSON: {}", e))?;
+    if let Value::Object(map) = &mut doc {
+        map.insert(
+            SCHEMA_VERSION_FIELD.to_string(),
+            Value::from(schema.current_version()),
+        );
+    }
+    serde_json::to_string_pretty(&doc).map_err(|e| format!("Failed to serialize to JSON: {}", e))
+}
+
+/// Write a document atomically, stamped with its current version
+pub fn write_versioned<T: Serialize>(
+    path: &Path,
+    data: &T,
+    schema: &DocumentSchema,
+) -> FileResult<()> {
+    atomic_write(path, &to_versioned_string(data, schema)?)
+}
+
+/// Refuse to overwrite a document written by a newer Ralph UI. For callers
+/// that fall back to defaults when a document can't be read.
+pub fn ensure_writable(path: &Path, schema: &DocumentSchema) -> FileResult<()> {
+    let Ok(Value::Object(map)) = read_json::<Value>(path) else {
+        return Ok(());
+    };
+    let stored = stored_version(&map);
+    if stored > schema.current_version() {
+        return Err(format!(
+            "Refusing to overwrite {:?}: {} schema version {} is newer than this version of Ralph UI supports ({})",
+            path,
+            schema.kind,
+            stored,
+            schema.current_version()
+        ));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Migration pass
+// =============================================================================
+
+/// A document that is (or would be) migrated
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentMigration {
+    /// Path relative to the `.ralph-ui` directory
+    pub path: String,
+    pub kind: String,
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Descriptions of the applied steps, in order
+    pub steps: Vec<String>,
+}
+
+/// A document that can't be migrated
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationError {
+    /// Path relative to the `.ralph-ui` directory
+    pub path: String,
+    pub kind: String,
+    pub error: String,
+}
+
+/// Outcome of a migration pass over one `.ralph-ui` directory
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub base_dir: String,
+    pub scope: SchemaScope,
+    pub dry_run: bool,
+    /// Number of versioned documents found
+    pub checked: usize,
+    pub migrated: Vec<DocumentMigration>,
+    pub errors: Vec<MigrationError>,
+    /// Where the originals of migrated documents were copied
+    pub backup_dir: Option<String>,
+}
+
+impl MigrationReport {
+    /// Log what a (non-dry) pass changed
+    pub fn log(&self) {
+        if !self.migrated.is_empty() {
+            log::info!(
+                "Migrated {} document(s) in {} (originals in {})",
+                self.migrated.len(),
+                self.base_dir,
+                self.backup_dir.as_deref().unwrap_or("-")
+            );
+        }
+        for error in &self.errors {
+            log::warn!(
+                "Failed to migrate {} in {}: {}",
+                error.path,
+                self.base_dir,
+                error.error
+            );
+        }
+    }
+}
+
+/// Migrate a project's documents (or report what would be migrated)
+pub fn migrate_project(project_path: &Path, dry_run: bool) -> MigrationReport {
+    migrate_dir(
+        &get_ralph_ui_dir(project_path),
+        SchemaScope::Project,
+        dry_run,
+        Utc::now(),
+    )
+}
+
+/// Migrate the global documents (or report what would be migrated)
+pub fn migrate_global(dry_run: bool) -> MigrationReport {
+    migrate_dir(
+        &get_global_ralph_ui_dir(),
+        SchemaScope::Global,
+        dry_run,
+        Utc::now(),
+    )
+}
+
+/// Migrate the global documents and those of every registered project
+pub fn migrate_all(dry_run: bool) -> Vec<MigrationReport> {
+    // The registry is migrated before it's read for the project list
+    let mut reports = vec![migrate_global(dry_run)];
+    match super::projects::get_all_projects() {
+        Ok(projects) => reports.extend(
+            projects
+                .iter()
+                .map(|p| Path::new(&p.path))
+                .filter(|p| get_ralph_ui_dir(p).exists())
+                .map(|p| migrate_project(p, dry_run)),
+        ),
+        Err(e) => log::warn!("Failed to list projects for migration: {}", e),
+    }
+    reports
+}
+
+fn migrate_dir(
+    base_dir: &Path,
+    scope: SchemaScope,
+    dry_run: bool,
+    now: DateTime<Utc>,
+) -> MigrationReport {
+    let backup_dir = base_dir
+        .join("backups")
+        .join("migrations")
+        .join(now.format("%Y%m%dT%H%M%S%.3fZ").to_string());
+    let mut report = MigrationReport {
+        base_dir: base_dir.to_string_lossy().to_string(),
+        scope,
+        dry_run,
+        checked: 0,
+        migrated: Vec::new(),
+        errors: Vec::new(),
+        backup_dir: None,
+    };
+
+    for schema in SCHEMAS.iter().filter(|s| s.scope == scope) {
+        for path in (schema.locate)(base_dir) {
+            report.checked += 1;
+            let relative = path.strip_prefix(base_dir).unwrap_or(&path).to_path_buf();
+            match migrate_file(&path, &relative, &backup_dir, schema, dry_run) {
+                Ok(Some(migration)) => report.migrated.push(migration),
+                Ok(None) => {}
+                Err(error) => report.errors.push(MigrationError {
+                    path: relative.to_string_lossy().to_string(),
+                    kind: schema.kind.to_string(),
+                    error,
+                }),
+            }
+        }
+    }
+
+    if !dry_run && !report.migrated.is_empty() {
+        report.backup_dir = Some(backup_dir.to_string_lossy().to_string());
+    }
+    report
+}
+
+/// Migrate a file if it's outdated: back it up, then rewrite it
+fn migrate_file(
+    path: &Path,
+    relative: &Path,
+    backup_dir: &Path,
+    schema: &DocumentSchema,
+    dry_run: bool,
+) -> FileResult<Option<DocumentMigration>> {
+    let original = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let mut doc: Value =
+        serde_json::from_str(&original).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let from_version = match &doc {
+        Value::Object(map) => stored_version(map),
+        _ => 0,
+    };
+    let steps = schema.upgrade(&mut doc)?;
+    if steps.is_empty() {
+        return Ok(None);
+    }
+
+    let migration = DocumentMigration {
+        path: relative.to_string_lossy().to_string(),
+        kind: schema.kind.to_string(),
+        from_version,
+        to_version: schema.current_version(),
+        steps: steps.iter().map(|m| m.description.to_string()).collect(),
+    };
+    if dry_run {
+        return Ok(Some(migration));
+    }
+
+    let backup_path = backup_dir.join(relative);
+    if let Some(parent) = backup_path.parent() {
+        ensure_dir(parent)?;
+    }
+    fs::write(&backup_path, &original)
+        .map_err(|e| format!("Failed to back up to {:?}: {}", backup_path, e))?;
+
+    let content = serde_json::to_string_pretty(&doc)
+        .map_err(|e| format!("Failed to serialize to JSON: {}", e))?;
+    // Don't overwrite a write that happened while migrating; reads still
+    // upgrade the document in memory and the next pass retries
+    let unchanged = fs::read_to_string(path).is_ok_and(|current| current == original);
+    if !unchanged {
+        return Err("Modified while migrating; skipped".to_string());
+    }
+    atomic_write(path, &content)?;
+    Ok(Some(migration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Doc {
+        name: String,
+    }
+
+    fn write_raw(path: &Path, value: Value) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, serde_json::to_string_pretty(&value).unwrap()).unwrap();
+    }
+
+    fn read_raw(path: &Path) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_read_versioned_upgrades_and_rejects_newer() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("prds").join("old.json");
+        write_raw(&path, serde_json::json!({ "name": "old" }));
+        let doc: Doc = read_versioned(&path, &PRD).unwrap();
+        assert_eq!(doc.name, "old");
+
+        write_raw(
+            &path,
+            serde_json::json!({ "name": "new", "schemaVersion": 99 }),
+        );
+        let err = read_versioned::<Doc>(&path, &PRD).unwrap_err();
+        assert!(err.contains("newer than this version"));
+        assert!(ensure_writable(&path, &PRD).is_err());
+        assert!(ensure_writable(&temp_dir.path().join("missing.json"), &PRD).is_ok());
+    }
+
+    #[test]
+    fn test_write_versioned_stamps_current_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("doc.json");
+        write_versioned(
+            &path,
+            &Doc {
+                name: "a".to_string(),
+            },
+            &SESSION,
+        )
+        .unwrap();
+        assert_eq!(
+            read_raw(&path)[SCHEMA_VERSION_FIELD],
+            SESSION.current_version()
+        );
+    }
+
+    #[test]
+    fn test_migrate_dir_dry_run_then_apply_with_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        let prd = base.join("prds").join("feature.json");
+        let learnings = base.join("briefs").join("feature").join("learnings.json");
+        let structure = base.join("prds").join("feature-structure.json");
+        write_raw(&prd, serde_json::json!({ "title": "Feature" }));
+        write_raw(
+            &learnings,
+            serde_json::json!({ "entries": [], "schemaVersion": 1 }),
+        );
+        write_raw(&structure, serde_json::json!({}));
+        write_raw(
+            &base.join("chat").join("future.json"),
+            serde_json::json!({ "schemaVersion": 7 }),
+        );
+        let now = Utc::now();
+
+        let plan = migrate_dir(base, SchemaScope::Project, true, now);
+        assert_eq!(plan.checked, 3);
+        assert_eq!(plan.migrated.len(), 1);
+        assert_eq!(plan.migrated[0].path, "prds/feature.json");
+        assert_eq!(plan.migrated[0].from_version, 0);
+        assert_eq!(plan.errors.len(), 1);
+        assert_eq!(plan.errors[0].kind, "chat");
+        assert!(plan.backup_dir.is_none());
+        assert!(read_raw(&prd).get(SCHEMA_VERSION_FIELD).is_none());
+
+        let report = migrate_dir(base, SchemaScope::Project, false, now);
+        assert_eq!(report.migrated.len(), 1);
+        assert_eq!(read_raw(&prd)[SCHEMA_VERSION_FIELD], 1);
+        assert_eq!(read_raw(&prd)["title"], "Feature");
+        let backup = PathBuf::from(report.backup_dir.unwrap()).join("prds/feature.json");
+        assert!(read_raw(&backup).get(SCHEMA_VERSION_FIELD).is_none());
+
+        // Nothing left to do
+        let again = migrate_dir(base, SchemaScope::Project, false, Utc::now());
+        assert!(again.migrated.is_empty());
+        assert!(again.backup_dir.is_none());
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for schema in SCHEMAS {
+            let versions: Vec<u32> = schema.migrations.iter().map(|m| m.to).collect();
+            let expected: Vec<u32> = (1..=versions.len() as u32).collect();
+            assert_eq!(versions, expected, "{} migrations", schema.kind);
+        }
+    }
+}
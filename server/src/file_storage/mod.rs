@@ -13,22 +13,47 @@
 //! - `chat/` - Chat sessions with embedded messages
 //! - `agents/` - Runtime agent state (gitignored)
 //! - `executions/` - Runtime execution state (gitignored)
+//! - `transcripts/` - Compressed per-iteration agent transcripts
+//! - `index/` - Full-text search index (derived, gitignored)
+//! - `leases/` - Execution leases, one per PRD being executed (gitignored)
+//! - `activity.jsonl` - Append-only activity journal for Mission Control (gitignored)
+//! - `backups/` - Originals of documents rewritten by schema migrations or imports (gitignored)
 //!
 //! Global user storage (`~/.ralph-ui/`):
 //! - `projects.json` - Cross-workspace project registry
+//! - `rate-limits.json` - Shared provider rate-limit ledger
+//! - `tokens.json` - Named API tokens (hashed) with roles and project scopes
+//! - `share-links.json` - Signed read-only share links for executions
+//! - `activity-index.json` - Activity of all projects merged from their journals (derived)
+//! - `audit/` - Append-only audit log of state-changing API calls (rotated JSONL)
+//! - `recordings/` - Asciicast recordings of terminal and agent sessions
 //! - `templates/` - User-defined PRD templates
+//! - `backups/` - Originals of global documents rewritten by schema migrations
+//!
+//! Stored JSON documents carry a `schemaVersion` and are upgraded by
+//! [`migrations`] when an older Ralph UI wrote them. [`project_archive`]
+//! moves a project's state between machines as a `.tar.gz`.
 
+pub mod activity;
 pub mod agents;
+pub mod api_tokens;
 pub mod attachments;
+pub mod audit;
 pub mod chat;
 pub mod chat_command_ops;
 pub mod chat_ops;
 pub mod context_ops;
 pub mod index;
 pub mod iterations;
+pub mod leases;
+pub mod migrations;
+pub mod project_archive;
 pub mod projects;
+pub mod recordings;
 pub mod research_ops;
 pub mod sessions;
+pub mod share_links;
+pub mod transcripts;
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -118,6 +143,10 @@ executions/
 attachments/
 context-chat/
 context-dismissed
+index/
+leases/
+activity.jsonl
+backups/
 "#;
         fs::write(&gitignore_path, gitignore_content)
             .map_err(|e| format!("Failed to write .gitignore: {}", e))?;
@@ -0,0 +1,752 @@
+//! Portable archives of a project's Ralph UI state
+//!
+//! An archive is a `.tar.gz` holding `manifest.json` and the selected
+//! sections of `.ralph-ui/` under `ralph-ui/`. The manifest lists every file
+//! with its SHA-256, so an archive is verified in full before anything is
+//! written. Derived and runtime files (indexes, leases, agent state, locks)
+//! are never exported; indexes are rebuilt after an import.
+//!
+//! Importing either merges (files the project already has with different
+//! content are kept and reported as conflicts) or replaces (the archived
+//! sections are replaced, originals are backed up to
+//! `.ralph-ui/backups/imports/<timestamp>/`). Absolute paths of the exported
+//! project inside JSON documents are rewritten to the importing project.
+
+use super::{ensure_dir, get_ralph_ui_dir, FileResult};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Version of the archive layout and manifest
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const CONTENT_DIR: &str = "ralph-ui";
+
+/// Limit on the uncompressed size of an imported archive
+const MAX_ARCHIVE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Parts of `.ralph-ui/` an archive can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveSection {
+    /// PRDs with their stories and progress files
+    Prds,
+    /// Sessions with their tasks
+    Sessions,
+    /// Loop executions with their iterations and tool statistics
+    Executions,
+    /// Per-PRD learnings, assignments and briefs
+    Learnings,
+    /// PRD chats, research sessions and custom chat commands
+    Chats,
+    /// Project context files, configuration and context chats
+    Context,
+    /// PRD workflows with their research
+    Workflows,
+    /// Compressed agent transcripts (can be large)
+    Transcripts,
+    /// Chat attachments (can be large)
+    Attachments,
+}
+
+impl ArchiveSection {
+    pub const ALL: [ArchiveSection; 9] = [
+        ArchiveSection::Prds,
+        ArchiveSection::Sessions,
+        ArchiveSection::Executions,
+        ArchiveSection::Learnings,
+        ArchiveSection::Chats,
+        ArchiveSection::Context,
+        ArchiveSection::Workflows,
+        ArchiveSection::Transcripts,
+        ArchiveSection::Attachments,
+    ];
+
+    /// Directories and files of the section, relative to `.ralph-ui/`
+    fn roots(self) -> &'static [&'static str] {
+        match self {
+            ArchiveSection::Prds => &["prds"],
+            ArchiveSection::Sessions => &["sessions"],
+            ArchiveSection::Executions => &["iterations"],
+            ArchiveSection::Learnings => &["briefs"],
+            ArchiveSection::Chats => &["chat", "research", "chat-commands.json"],
+            ArchiveSection::Context => &["context", "context-config.json", "context-chat"],
+            ArchiveSection::Workflows => &["workflows"],
+            ArchiveSection::Transcripts => &["transcripts"],
+            ArchiveSection::Attachments => &["attachments"],
+        }
+    }
+
+    /// Whether the section is exported unless deselected
+    fn is_default(self) -> bool {
+        !matches!(
+            self,
+            ArchiveSection::Transcripts | ArchiveSection::Attachments
+        )
+    }
+
+    /// Section a relative path belongs to
+    fn of_path(path: &str) -> Option<ArchiveSection> {
+        let first = path.split('/').next()?;
+        Self::ALL
+            .into_iter()
+            .find(|section| section.roots().contains(&first))
+    }
+}
+
+/// What to export
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    /// Sections to export (default: all but transcripts and attachments)
+    #[serde(default)]
+    pub sections: Option<Vec<ArchiveSection>>,
+    /// Add transcripts to the default sections
+    #[serde(default)]
+    pub include_transcripts: bool,
+    /// Add attachments to the default sections
+    #[serde(default)]
+    pub include_attachments: bool,
+}
+
+impl ExportOptions {
+    fn selected_sections(&self) -> BTreeSet<ArchiveSection> {
+        let mut sections: BTreeSet<ArchiveSection> = match &self.sections {
+            Some(sections) => sections.iter().copied().collect(),
+            None => ArchiveSection::ALL
+                .into_iter()
+                .filter(|s| s.is_default())
+                .collect(),
+        };
+        if self.include_transcripts {
+            sections.insert(ArchiveSection::Transcripts);
+        }
+        if self.include_attachments {
+            sections.insert(ArchiveSection::Attachments);
+        }
+        sections
+    }
+
+    /// Whether the export holds sections left out by default (agent
+    /// transcripts, uploaded attachments)
+    pub fn includes_opt_in_sections(&self) -> bool {
+        self.selected_sections().iter().any(|s| !s.is_default())
+    }
+}
+
+/// A file in an archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFile {
+    /// Path relative to `.ralph-ui/`, with `/` separators
+    pub path: String,
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the content
+    pub sha256: String,
+}
+
+/// Description of an archive's content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveManifest {
+    pub format_version: u32,
+    /// Ralph UI version that wrote the archive
+    pub ralph_ui_version: String,
+    pub exported_at: DateTime<Utc>,
+    /// Absolute path of the exported project (rewritten on import)
+    pub project_path: String,
+    pub project_name: String,
+    pub sections: Vec<ArchiveSection>,
+    pub files: Vec<ArchiveFile>,
+}
+
+/// An exported archive
+#[derive(Debug, Clone)]
+pub struct ProjectArchive {
+    pub manifest: ArchiveManifest,
+    /// The `.tar.gz` content
+    pub data: Vec<u8>,
+}
+
+impl ProjectArchive {
+    /// File name for the archive, e.g. `my-app-ralph-state-20260101T120000Z.tar.gz`
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}-ralph-state-{}.tar.gz",
+            self.manifest.project_name,
+            self.manifest.exported_at.format("%Y%m%dT%H%M%SZ")
+        )
+    }
+}
+
+/// How an import treats state the project already has
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Add missing files, keep the project's version of conflicting ones
+    #[default]
+    Merge,
+    /// Replace the archived sections with the archive's content
+    Replace,
+}
+
+/// How an import went (or would go, for a dry run)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub project_path: String,
+    pub mode: ImportMode,
+    pub dry_run: bool,
+    pub manifest: ArchiveManifest,
+    /// Files the project didn't have
+    pub added: Vec<String>,
+    /// Files identical to the project's
+    pub unchanged: usize,
+    /// Files that differ from the project's: kept when merging, replaced otherwise
+    pub conflicts: Vec<String>,
+    /// Files of the archived sections that aren't in the archive (removed when replacing)
+    pub removed: Vec<String>,
+    /// JSON files whose absolute project paths were rewritten
+    pub rewritten: Vec<String>,
+    /// Where originals of replaced and removed files were copied
+    pub backup_dir: Option<String>,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Relative paths of a section's files in a `.ralph-ui` directory.
+/// Top-level indexes, temp files and locks are left out.
+fn section_files(ralph_ui_dir: &Path, section: ArchiveSection) -> Vec<String> {
+    let mut files = Vec::new();
+    for root in section.roots() {
+        let root_path = ralph_ui_dir.join(root);
+        for entry in walkdir::WalkDir::new(&root_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let Ok(relative) = entry.path().strip_prefix(ralph_ui_dir) else {
+                continue;
+            };
+            let relative: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            let name = relative.last().map(String::as_str).unwrap_or_default();
+            if name.ends_with(".tmp")
+                || name.ends_with(".lock")
+                || (relative.len() == 2 && name == "index.json")
+            {
+                continue;
+            }
+            files.push(relative.join("/"));
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Check that an archived path is relative, stays inside `.ralph-ui/` and
+/// belongs to a section
+fn validate_path(path: &str) -> FileResult<ArchiveSection> {
+    let safe = !path.is_empty()
+        && !path.contains('\\')
+        && path
+            .split('/')
+            .all(|c| !c.is_empty() && c != "." && c != ".." && !c.contains(':'));
+    if !safe {
+        return Err(format!("Invalid path in archive: {}", path));
+    }
+    ArchiveSection::of_path(path).ok_or_else(|| format!("Unexpected path in archive: {}", path))
+}
+
+// =============================================================================
+// Export
+// =============================================================================
+
+/// Export the selected sections of a project's `.ralph-ui/` as a `.tar.gz`
+pub fn export_project_state(
+    project_path: &Path,
+    options: &ExportOptions,
+) -> FileResult<ProjectArchive> {
+    let ralph_ui_dir = get_ralph_ui_dir(project_path);
+    if !ralph_ui_dir.exists() {
+        return Err(format!("No Ralph UI state in {}", project_path.display()));
+    }
+
+    let sections: Vec<ArchiveSection> = options.selected_sections().into_iter().collect();
+    let mut contents = Vec::new();
+    let mut files = Vec::new();
+    for section in &sections {
+        for path in section_files(&ralph_ui_dir, *section) {
+            let data = fs::read(ralph_ui_dir.join(&path))
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            files.push(ArchiveFile {
+                path: path.clone(),
+                size: data.len() as u64,
+                sha256: sha256_hex(&data),
+            });
+            contents.push((path, data));
+        }
+    }
+
+    let manifest = ArchiveManifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        ralph_ui_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: Utc::now(),
+        project_path: project_path.to_string_lossy().to_string(),
+        project_name: project_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "project".to_string()),
+        sections,
+        files,
+    };
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    let mtime = manifest.exported_at.timestamp().max(0) as u64;
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let mut append = |name: &str, data: &[u8]| -> FileResult<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, data)
+            .map_err(|e| format!("Failed to add {} to archive: {}", name, e))
+    };
+    append(MANIFEST_FILE, &manifest_json)?;
+    for (path, data) in &contents {
+        append(&format!("{}/{}", CONTENT_DIR, path), data)?;
+    }
+    let data = builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+
+    log::info!(
+        "Exported {} file(s) of {} ({} bytes)",
+        manifest.files.len(),
+        project_path.display(),
+        data.len()
+    );
+    Ok(ProjectArchive { manifest, data })
+}
+
+// =============================================================================
+// Import
+// =============================================================================
+
+/// Read and verify an archive: every manifest entry must be present with its
+/// checksum, and nothing else may be
+fn read_archive(data: &[u8]) -> FileResult<(ArchiveManifest, BTreeMap<String, Vec<u8>>)> {
+    let mut archive = tar::Archive::new(GzDecoder::new(data));
+    let mut manifest: Option<ArchiveManifest> = None;
+    let mut contents = BTreeMap::new();
+    let mut total: u64 = 0;
+
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Invalid archive: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Invalid archive: {}", e))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry
+            .path()
+            .map_err(|e| format!("Invalid archive: {}", e))?
+            .to_string_lossy()
+            .to_string();
+        total += entry.size();
+        if total > MAX_ARCHIVE_BYTES {
+            return Err(format!(
+                "Archive is larger than {} bytes uncompressed",
+                MAX_ARCHIVE_BYTES
+            ));
+        }
+        let mut content = Vec::with_capacity(entry.size() as usize);
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read {} from archive: {}", name, e))?;
+
+        if name == MANIFEST_FILE {
+            manifest = Some(
+                serde_json::from_slice(&content)
+                    .map_err(|e| format!("Invalid archive manifest: {}", e))?,
+            );
+        } else if let Some(path) = name.strip_prefix(&format!("{}/", CONTENT_DIR)) {
+            contents.insert(path.to_string(), content);
+        } else {
+            return Err(format!("Unexpected file in archive: {}", name));
+        }
+    }
+
+    let manifest = manifest.ok_or("Archive has no manifest")?;
+    if manifest.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(format!(
+            "Archive format version {} is newer than this version of Ralph UI supports ({})",
+            manifest.format_version, ARCHIVE_FORMAT_VERSION
+        ));
+    }
+    for file in &manifest.files {
+        let section = validate_path(&file.path)?;
+        if !manifest.sections.contains(&section) {
+            return Err(format!(
+                "{} is not in an archived section ({:?})",
+                file.path, section
+            ));
+        }
+        let content = contents
+            .get(&file.path)
+            .ok_or_else(|| format!("Archive is missing {}", file.path))?;
+        if sha256_hex(content) != file.sha256 {
+            return Err(format!("Checksum mismatch for {}", file.path));
+        }
+    }
+    if contents.len() != manifest.files.len() {
+        let listed: BTreeSet<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        let extra = contents
+            .keys()
+            .find(|path| !listed.contains(path.as_str()))
+            .cloned()
+            .unwrap_or_default();
+        return Err(format!("{} is not listed in the archive manifest", extra));
+    }
+    Ok((manifest, contents))
+}
+
+/// Rewrite strings that are (or are inside) `from` to the same path in `to`
+fn rewrite_paths(value: &mut Value, from: &str, to: &str) -> bool {
+    match value {
+        Value::String(s) => {
+            let rest = if s == from {
+                Some("")
+            } else {
+                s.strip_prefix(from).filter(|rest| rest.starts_with('/'))
+            };
+            match rest {
+                Some(rest) => {
+                    *s = format!("{}{}", to, rest);
+                    true
+                }
+                None => false,
+            }
+        }
+        Value::Array(items) => {
+            let mut changed = false;
+            for item in items {
+                changed |= rewrite_paths(item, from, to);
+            }
+            changed
+        }
+        Value::Object(map) => {
+            let mut changed = false;
+            for item in map.values_mut() {
+                changed |= rewrite_paths(item, from, to);
+            }
+            changed
+        }
+        _ => false,
+    }
+}
+
+/// Write a file atomically (temp file + rename)
+fn write_file(path: &Path, data: &[u8]) -> FileResult<()> {
+    if let Some(parent) = path.parent() {
+        ensure_dir(parent)?;
+    }
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path = path.with_file_name(format!("{}.tmp", file_name));
+    fs::write(&temp_path, data)
+        .map_err(|e| format!("Failed to write temp file {:?}: {}", temp_path, e))?;
+    fs::rename(&temp_path, path)
+        .map_err(|e| format!("Failed to rename {:?} to {:?}: {}", temp_path, path, e))
+}
+
+/// Import an archive into a project (or report what an import would do).
+/// Indexes, registration and schema migrations are left to the caller.
+pub fn import_project_state(
+    project_path: &Path,
+    data: &[u8],
+    mode: ImportMode,
+    dry_run: bool,
+) -> FileResult<ImportReport> {
+    let (manifest, mut contents) = read_archive(data)?;
+    let ralph_ui_dir = get_ralph_ui_dir(project_path);
+
+    // Point absolute paths of the exported project at this one
+    let new_path = project_path.to_string_lossy().to_string();
+    let old_path = manifest.project_path.trim_end_matches('/').to_string();
+    let mut rewritten = Vec::new();
+    if !old_path.is_empty() && old_path != new_path {
+        for (path, content) in contents.iter_mut().filter(|(p, _)| p.ends_with(".json")) {
+            let Ok(mut doc) = serde_json::from_slice::<Value>(content) else {
+                continue;
+            };
+            if rewrite_paths(&mut doc, &old_path, &new_path) {
+                *content = serde_json::to_vec_pretty(&doc)
+                    .map_err(|e| format!("Failed to serialize {}: {}", path, e))?;
+                rewritten.push(path.clone());
+            }
+        }
+    }
+
+    let mut report = ImportReport {
+        project_path: new_path,
+        mode,
+        dry_run,
+        manifest,
+        added: Vec::new(),
+        unchanged: 0,
+        conflicts: Vec::new(),
+        removed: Vec::new(),
+        rewritten,
+        backup_dir: None,
+    };
+    for (path, content) in &contents {
+        match fs::read(ralph_ui_dir.join(path)) {
+            Ok(existing) if &existing == content => report.unchanged += 1,
+            Ok(_) => report.conflicts.push(path.clone()),
+            Err(_) => report.added.push(path.clone()),
+        }
+    }
+    if mode == ImportMode::Replace {
+        for section in &report.manifest.sections {
+            report.removed.extend(
+                section_files(&ralph_ui_dir, *section)
+                    .into_iter()
+                    .filter(|path| !contents.contains_key(path)),
+            );
+        }
+    }
+    if dry_run {
+        return Ok(report);
+    }
+
+    // Back up everything that is about to be overwritten or removed
+    let displaced: Vec<&String> = match mode {
+        ImportMode::Merge => Vec::new(),
+        ImportMode::Replace => report.conflicts.iter().chain(&report.removed).collect(),
+    };
+    if !displaced.is_empty() {
+        let backup_dir = ralph_ui_dir
+            .join("backups")
+            .join("imports")
+            .join(Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string());
+        for path in &displaced {
+            let backup_path = backup_dir.join(path);
+            if let Some(parent) = backup_path.parent() {
+                ensure_dir(parent)?;
+            }
+            fs::copy(ralph_ui_dir.join(path), &backup_path)
+                .map_err(|e| format!("Failed to back up {}: {}", path, e))?;
+        }
+        report.backup_dir = Some(backup_dir.to_string_lossy().to_string());
+    }
+
+    for path in &report.removed {
+        fs::remove_file(ralph_ui_dir.join(path))
+            .map_err(|e| format!("Failed to remove {}: {}", path, e))?;
+    }
+    let written = match mode {
+        ImportMode::Merge => report.added.iter().collect::<Vec<_>>(),
+        ImportMode::Replace => report.added.iter().chain(&report.conflicts).collect(),
+    };
+    for path in written {
+        write_file(&ralph_ui_dir.join(path), &contents[path])?;
+    }
+
+    log::info!(
+        "Imported {} into {} ({} added, {} conflicting, {} removed)",
+        report.manifest.project_name,
+        report.project_path,
+        report.added.len(),
+        report.conflicts.len(),
+        report.removed.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn source_project(root: &Path) -> PathBuf {
+        let project = root.join("source");
+        let dir = get_ralph_ui_dir(&project);
+        let session = format!(r#"{{"id":"s1","projectPath":"{}"}}"#, project.display());
+        write(&dir.join("sessions/s1.json"), &session);
+        write(&dir.join("sessions/index.json"), "{}");
+        write(&dir.join("prds/feature.json"), r#"{"title":"Feature"}"#);
+        write(&dir.join("briefs/feature/learnings.json"), "{}");
+        write(&dir.join("transcripts/exec-1/1/meta.json"), "{}");
+        write(&dir.join("leases/feature.json"), "{}");
+        project
+    }
+
+    #[test]
+    fn test_export_selects_sections_and_skips_runtime_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = source_project(temp_dir.path());
+
+        let archive = export_project_state(&project, &ExportOptions::default()).unwrap();
+        let paths: Vec<&str> = archive
+            .manifest
+            .files
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "prds/feature.json",
+                "sessions/s1.json",
+                "briefs/feature/learnings.json"
+            ]
+        );
+
+        let options = ExportOptions {
+            sections: Some(vec![ArchiveSection::Prds]),
+            include_transcripts: true,
+            ..Default::default()
+        };
+        let archive = export_project_state(&project, &options).unwrap();
+        assert_eq!(
+            archive.manifest.sections,
+            vec![ArchiveSection::Prds, ArchiveSection::Transcripts]
+        );
+        assert_eq!(archive.manifest.files.len(), 2);
+    }
+
+    #[test]
+    fn test_import_merge_rewrites_paths_and_reports_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = source_project(temp_dir.path());
+        let archive = export_project_state(&project, &ExportOptions::default()).unwrap();
+
+        let target = temp_dir.path().join("target");
+        let target_dir = get_ralph_ui_dir(&target);
+        write(&target_dir.join("prds/feature.json"), r#"{"title":"Mine"}"#);
+
+        let plan = import_project_state(&target, &archive.data, ImportMode::Merge, true).unwrap();
+        assert_eq!(plan.conflicts, vec!["prds/feature.json"]);
+        assert_eq!(plan.rewritten, vec!["sessions/s1.json"]);
+        assert!(!target_dir.join("sessions/s1.json").exists());
+
+        let report =
+            import_project_state(&target, &archive.data, ImportMode::Merge, false).unwrap();
+        assert_eq!(report.added.len(), 2);
+        assert!(report.backup_dir.is_none());
+        let prd = fs::read_to_string(target_dir.join("prds/feature.json")).unwrap();
+        assert!(prd.contains("Mine"));
+        let session: Value =
+            serde_json::from_slice(&fs::read(target_dir.join("sessions/s1.json")).unwrap())
+                .unwrap();
+        assert_eq!(session["projectPath"], target.to_string_lossy().as_ref());
+
+        // Importing again changes nothing
+        let again = import_project_state(&target, &archive.data, ImportMode::Merge, false).unwrap();
+        assert!(again.added.is_empty());
+        assert_eq!(again.unchanged, 2);
+    }
+
+    #[test]
+    fn test_import_replace_backs_up_displaced_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = source_project(temp_dir.path());
+        let options = ExportOptions {
+            sections: Some(vec![ArchiveSection::Prds]),
+            ..Default::default()
+        };
+        let archive = export_project_state(&project, &options).unwrap();
+
+        let target = temp_dir.path().join("target");
+        let target_dir = get_ralph_ui_dir(&target);
+        write(&target_dir.join("prds/feature.json"), r#"{"title":"Mine"}"#);
+        write(&target_dir.join("prds/other.json"), "{}");
+        write(&target_dir.join("sessions/keep.json"), "{}");
+
+        let report =
+            import_project_state(&target, &archive.data, ImportMode::Replace, false).unwrap();
+        assert_eq!(report.conflicts, vec!["prds/feature.json"]);
+        assert_eq!(report.removed, vec!["prds/other.json"]);
+        assert!(!target_dir.join("prds/other.json").exists());
+        assert!(target_dir.join("sessions/keep.json").exists());
+        let prd = fs::read_to_string(target_dir.join("prds/feature.json")).unwrap();
+        assert!(prd.contains("Feature"));
+
+        let backup = PathBuf::from(report.backup_dir.unwrap());
+        assert!(backup.join("prds/other.json").exists());
+        let backed_up = fs::read_to_string(backup.join("prds/feature.json")).unwrap();
+        assert!(backed_up.contains("Mine"));
+    }
+
+    #[test]
+    fn test_import_rejects_tampered_and_unsafe_archives() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = source_project(temp_dir.path());
+        let archive = export_project_state(&project, &ExportOptions::default()).unwrap();
+        let target = temp_dir.path().join("target");
+
+        let repack = |manifest: &ArchiveManifest, files: &[(&str, &[u8])]| {
+            let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+            let mut add = |name: &str, data: &[u8]| {
+                // Written raw: the builder refuses names with `..`
+                let mut header = tar::Header::new_gnu();
+                header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append(&header, data).unwrap();
+            };
+            add(MANIFEST_FILE, &serde_json::to_vec(manifest).unwrap());
+            for (name, data) in files {
+                add(name, data);
+            }
+            builder.into_inner().unwrap().finish().unwrap()
+        };
+
+        // Content that doesn't match its checksum
+        let mut manifest = archive.manifest.clone();
+        manifest.files.truncate(1);
+        let tampered = repack(&manifest, &[("ralph-ui/prds/feature.json", b"{}")]);
+        let err = import_project_state(&target, &tampered, ImportMode::Merge, false).unwrap_err();
+        assert!(err.contains("Checksum mismatch"));
+
+        // Paths escaping .ralph-ui
+        manifest.files[0].path = "prds/../../evil.json".to_string();
+        manifest.files[0].sha256 = sha256_hex(b"{}");
+        let unsafe_archive = repack(&manifest, &[("ralph-ui/prds/../../evil.json", b"{}")]);
+        let err =
+            import_project_state(&target, &unsafe_archive, ImportMode::Merge, false).unwrap_err();
+        assert!(err.contains("Invalid path"));
+        assert!(!temp_dir.path().join("evil.json").exists());
+    }
+}
@@ -3,7 +3,8 @@
 //! Stores the cross-workspace project list in `~/.ralph-ui/projects.json`
 //! This file is user-specific and not tracked in git.
 
-use super::{ensure_dir, get_global_ralph_ui_dir, read_json, write_json, FileResult};
+use super::migrations::{read_versioned, write_versioned, PROJECTS};
+use super::{ensure_dir, get_global_ralph_ui_dir, FileResult};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -136,7 +137,7 @@ impl ProjectFolderEntry {
 }
 
 /// API-compatible Project format (matches frontend expectations)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiProject {
     pub id: String,
@@ -180,7 +181,7 @@ fn read_projects_registry_from(base_dir: &Path) -> FileResult<ProjectsRegistry>
         return Ok(ProjectsRegistry::default());
     }
 
-    let mut registry: ProjectsRegistry = read_json(&file_path)?;
+    let mut registry: ProjectsRegistry = read_versioned(&file_path, &PROJECTS)?;
 
     // Run migrations if needed
     migrate_registry(&mut registry, base_dir)?;
@@ -197,7 +198,7 @@ pub fn write_projects_registry(registry: &ProjectsRegistry) -> FileResult<()> {
 fn write_projects_registry_to(base_dir: &Path, registry: &ProjectsRegistry) -> FileResult<()> {
     ensure_dir(base_dir)?;
     let file_path = get_projects_file_path_in(base_dir);
-    write_json(&file_path, registry)
+    write_versioned(&file_path, registry, &PROJECTS)
 }
 
 /// Register a project (upsert - update last_used_at if exists, create if not)
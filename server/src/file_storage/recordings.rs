@@ -0,0 +1,588 @@
+//! Asciicast recordings of terminal sessions
+//!
+//! User terminals and agent PTY streams can be recorded to asciicast v2 files
+//! (https://docs.asciinema.org/manual/asciicast/v2/) in the global
+//! `~/.ralph-ui/recordings/` directory, so they can be replayed after the
+//! session's output buffer is gone:
+//! - `{id}.cast` - Header line followed by `[time, code, data]` event lines
+//! - `{id}.json` - Recording metadata (source, project, execution, iteration)
+//!
+//! Recordings live outside projects because user terminals needn't belong
+//! to one.
+
+use super::{ensure_dir, get_global_ralph_ui_dir, read_json, write_json, FileResult};
+use crate::config::RecordingSettings;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Version of the recording metadata format
+const RECORDING_META_VERSION: u32 = 1;
+
+/// What was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingSource {
+    /// A user terminal session
+    Terminal,
+    /// The PTY output of a Ralph loop agent
+    Agent,
+}
+
+/// Metadata of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingMeta {
+    pub version: u32,
+    pub id: String,
+    pub source: RecordingSource,
+    /// Terminal ID or agent ID
+    pub source_id: String,
+    pub title: String,
+    pub project_path: Option<String>,
+    pub execution_id: Option<String>,
+    pub iteration: Option<u32>,
+    pub cols: u16,
+    pub rows: u16,
+    /// Whether input events were recorded
+    pub includes_input: bool,
+    pub started_at: DateTime<Utc>,
+    /// None while the recording is in progress
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_secs: f64,
+    pub size_bytes: u64,
+    pub event_count: u64,
+    /// Server process writing the recording
+    #[serde(default)]
+    pub recorder_pid: u32,
+}
+
+/// Details of a session to record
+#[derive(Debug, Clone)]
+pub struct NewRecording {
+    pub source: RecordingSource,
+    pub source_id: String,
+    pub title: String,
+    pub project_path: Option<String>,
+    pub execution_id: Option<String>,
+    pub iteration: Option<u32>,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+/// Filter for listing recordings (all fields optional, combined with AND)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingFilter {
+    pub source: Option<RecordingSource>,
+    pub project_path: Option<String>,
+    pub execution_id: Option<String>,
+}
+
+/// Result of applying the retention policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingRetentionResult {
+    pub removed: usize,
+    pub freed_bytes: u64,
+    pub remaining: usize,
+    pub remaining_bytes: u64,
+}
+
+/// Get the global recordings directory
+pub fn get_recordings_dir() -> PathBuf {
+    get_global_ralph_ui_dir().join("recordings")
+}
+
+/// Recording IDs are UUIDs; anything else could escape the directory
+fn validate_id(id: &str) -> FileResult<()> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid recording ID: {}", id));
+    }
+    Ok(())
+}
+
+fn cast_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.cast", id))
+}
+
+fn meta_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+/// Writes one session to an asciicast file as it happens
+pub struct Recorder {
+    dir: PathBuf,
+    meta: RecordingMeta,
+    writer: Option<BufWriter<File>>,
+    started: Instant,
+    /// Trailing bytes of a UTF-8 sequence split across output chunks
+    pending_output: Vec<u8>,
+    retention: (u32, u64),
+    finished: bool,
+}
+
+impl Recorder {
+    /// Start recording in the global recordings directory
+    pub fn start(recording: NewRecording, settings: &RecordingSettings) -> FileResult<Self> {
+        Self::start_in(&get_recordings_dir(), recording, settings)
+    }
+
+    /// Start recording in a specific directory (for testing)
+    pub fn start_in(
+        dir: &Path,
+        recording: NewRecording,
+        settings: &RecordingSettings,
+    ) -> FileResult<Self> {
+        ensure_dir(dir)?;
+        let started_at = Utc::now();
+        let meta = RecordingMeta {
+            version: RECORDING_META_VERSION,
+            id: uuid::Uuid::new_v4().to_string(),
+            source: recording.source,
+            source_id: recording.source_id,
+            title: recording.title,
+            project_path: recording.project_path,
+            execution_id: recording.execution_id,
+            iteration: recording.iteration,
+            cols: recording.cols,
+            rows: recording.rows,
+            includes_input: settings.record_input,
+            started_at,
+            ended_at: None,
+            duration_secs: 0.0,
+            size_bytes: 0,
+            event_count: 0,
+            recorder_pid: std::process::id(),
+        };
+
+        let file = File::create(cast_path(dir, &meta.id))
+            .map_err(|e| format!("Failed to create recording: {}", e))?;
+        let mut writer = BufWriter::new(file);
+        let header = json!({
+            "version": 2,
+            "width": meta.cols,
+            "height": meta.rows,
+            "timestamp": started_at.timestamp(),
+            "title": meta.title,
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(writer, "{}", header)
+            .and_then(|_| writer.flush())
+            .map_err(|e| format!("Failed to write recording header: {}", e))?;
+        write_json(&meta_path(dir, &meta.id), &meta)?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            meta,
+            writer: Some(writer),
+            started: Instant::now(),
+            pending_output: Vec::new(),
+            retention: (
+                settings.max_age_days,
+                settings.max_total_size_mb * 1024 * 1024,
+            ),
+            finished: false,
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.meta.id
+    }
+
+    /// Record terminal output
+    pub fn output(&mut self, data: &[u8]) {
+        self.pending_output.extend_from_slice(data);
+        let text = take_complete_utf8(&mut self.pending_output);
+        if !text.is_empty() {
+            self.event("o", &text);
+        }
+    }
+
+    /// Record terminal input (ignored unless input recording is enabled)
+    pub fn input(&mut self, data: &[u8]) {
+        if self.meta.includes_input {
+            self.event("i", &String::from_utf8_lossy(data));
+        }
+    }
+
+    /// Record a terminal resize
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.event("r", &format!("{}x{}", cols, rows));
+    }
+
+    fn event(&mut self, code: &str, data: &str) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        let elapsed = (self.started.elapsed().as_secs_f64() * 1_000_000.0).round() / 1_000_000.0;
+        // Flushed per event so in-progress recordings can be streamed
+        let written =
+            writeln!(writer, "{}", json!([elapsed, code, data])).and_then(|_| writer.flush());
+        match written {
+            Ok(()) => self.meta.event_count += 1,
+            Err(e) => {
+                log::warn!("[Recordings] Stopped recording {}: {}", self.meta.id, e);
+                self.writer = None;
+            }
+        }
+    }
+
+    /// Finish the recording, write its final metadata and apply the retention
+    /// policy. Called on drop if not called explicitly.
+    pub fn finish(&mut self) -> Option<RecordingMeta> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        if !self.pending_output.is_empty() {
+            let rest =
+                String::from_utf8_lossy(&std::mem::take(&mut self.pending_output)).into_owned();
+            self.event("o", &rest);
+        }
+        if let Some(mut writer) = self.writer.take() {
+            let _ = writer.flush();
+        }
+
+        // Deleted while it was being recorded
+        let cast = cast_path(&self.dir, &self.meta.id);
+        let size_bytes = fs::metadata(&cast).ok()?.len();
+
+        self.meta.ended_at = Some(Utc::now());
+        self.meta.duration_secs = self.started.elapsed().as_secs_f64();
+        self.meta.size_bytes = size_bytes;
+        if let Err(e) = write_json(&meta_path(&self.dir, &self.meta.id), &self.meta) {
+            log::warn!(
+                "[Recordings] Failed to save recording {}: {}",
+                self.meta.id,
+                e
+            );
+        }
+
+        let (max_age_days, max_total_bytes) = self.retention;
+        if let Err(e) = apply_retention_in(&self.dir, max_age_days, max_total_bytes) {
+            log::warn!("[Recordings] Failed to apply retention: {}", e);
+        }
+        Some(self.meta.clone())
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Drain the complete UTF-8 text from `pending`, leaving an incomplete
+/// trailing sequence for the next chunk (invalid bytes are replaced)
+fn take_complete_utf8(pending: &mut Vec<u8>) -> String {
+    let mut text = String::new();
+    let mut rest: &[u8] = pending;
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                text.push_str(valid);
+                rest = &[];
+                break;
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                text.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                match e.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        rest = &after[len..];
+                    }
+                    // Incomplete sequence at the end
+                    None => {
+                        rest = after;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    *pending = rest.to_vec();
+    text
+}
+
+/// List recordings, most recent first
+pub fn list_recordings(filter: &RecordingFilter) -> FileResult<Vec<RecordingMeta>> {
+    list_recordings_in(&get_recordings_dir(), filter)
+}
+
+/// List recordings in a specific directory (for testing)
+pub fn list_recordings_in(dir: &Path, filter: &RecordingFilter) -> FileResult<Vec<RecordingMeta>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read recordings directory: {}", e))?;
+
+    let mut recordings = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+        match read_json::<RecordingMeta>(&path) {
+            Ok(meta) => recordings.push(meta),
+            Err(e) => log::warn!(
+                "[Recordings] Skipping unreadable recording {:?}: {}",
+                path,
+                e
+            ),
+        }
+    }
+
+    recordings.retain(|meta| {
+        filter.source.map_or(true, |s| meta.source == s)
+            && filter
+                .project_path
+                .as_ref()
+                .map_or(true, |p| meta.project_path.as_ref() == Some(p))
+            && filter
+                .execution_id
+                .as_ref()
+                .map_or(true, |e| meta.execution_id.as_ref() == Some(e))
+    });
+    recordings.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+    Ok(recordings)
+}
+
+/// Get a recording's metadata
+pub fn get_recording(id: &str) -> FileResult<RecordingMeta> {
+    get_recording_in(&get_recordings_dir(), id)
+}
+
+/// Get a recording's metadata in a specific directory (for testing)
+pub fn get_recording_in(dir: &Path, id: &str) -> FileResult<RecordingMeta> {
+    validate_id(id)?;
+    let path = meta_path(dir, id);
+    if !path.exists() {
+        return Err(format!("Recording not found: {}", id));
+    }
+    read_json(&path)
+}
+
+/// Get the asciicast file of a recording
+pub fn get_recording_file_path(id: &str) -> FileResult<PathBuf> {
+    let dir = get_recordings_dir();
+    get_recording_in(&dir, id)?;
+    Ok(cast_path(&dir, id))
+}
+
+/// Delete a recording
+pub fn delete_recording(id: &str) -> FileResult<()> {
+    delete_recording_in(&get_recordings_dir(), id)
+}
+
+/// Delete a recording in a specific directory (for testing)
+pub fn delete_recording_in(dir: &Path, id: &str) -> FileResult<()> {
+    get_recording_in(dir, id)?;
+    for path in [cast_path(dir, id), meta_path(dir, id)] {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to delete recording: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Close recordings left in progress by a server that stopped without
+/// finishing them, so they can be pruned like any other. Recordings of live
+/// servers (other instances sharing the directory) are left alone.
+pub fn close_interrupted_recordings() -> FileResult<usize> {
+    close_interrupted_recordings_in(&get_recordings_dir())
+}
+
+/// Close interrupted recordings in a specific directory (for testing)
+pub fn close_interrupted_recordings_in(dir: &Path) -> FileResult<usize> {
+    let mut closed = 0;
+    for mut meta in list_recordings_in(dir, &RecordingFilter::default())? {
+        let recorder_alive = meta.recorder_pid == std::process::id()
+            || crate::agents::orphans::process_identity(meta.recorder_pid).is_some();
+        if meta.ended_at.is_some() || recorder_alive {
+            continue;
+        }
+        let cast = fs::metadata(cast_path(dir, &meta.id)).ok();
+        let ended_at = cast
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or(meta.started_at)
+            .max(meta.started_at);
+        meta.duration_secs = (ended_at - meta.started_at).num_milliseconds() as f64 / 1000.0;
+        meta.size_bytes = cast.map_or(0, |m| m.len());
+        meta.ended_at = Some(ended_at);
+        write_json(&meta_path(dir, &meta.id), &meta)?;
+        closed += 1;
+    }
+    Ok(closed)
+}
+
+/// Delete finished recordings older than `max_age_days`, then the oldest ones
+/// until the total size fits in `max_total_bytes` (0 disables either limit)
+pub fn apply_retention(
+    max_age_days: u32,
+    max_total_bytes: u64,
+) -> FileResult<RecordingRetentionResult> {
+    apply_retention_in(&get_recordings_dir(), max_age_days, max_total_bytes)
+}
+
+/// Apply the retention policy in a specific directory (for testing)
+pub fn apply_retention_in(
+    dir: &Path,
+    max_age_days: u32,
+    max_total_bytes: u64,
+) -> FileResult<RecordingRetentionResult> {
+    let mut recordings = list_recordings_in(dir, &RecordingFilter::default())?;
+    // Oldest first
+    recordings.reverse();
+
+    let size_of = |meta: &RecordingMeta| {
+        fs::metadata(cast_path(dir, &meta.id))
+            .map(|m| m.len())
+            .unwrap_or(0)
+    };
+    let mut total: u64 = recordings.iter().map(size_of).sum();
+    let cutoff = Utc::now() - Duration::days(max_age_days as i64);
+    let mut result = RecordingRetentionResult::default();
+
+    for meta in &recordings {
+        // Recordings in progress are never removed
+        if meta.ended_at.is_none() {
+            continue;
+        }
+        let too_old = max_age_days > 0 && meta.started_at < cutoff;
+        let too_big = max_total_bytes > 0 && total > max_total_bytes;
+        if !too_old && !too_big {
+            continue;
+        }
+        let size = size_of(meta);
+        delete_recording_in(dir, &meta.id)?;
+        total = total.saturating_sub(size);
+        result.removed += 1;
+        result.freed_bytes += size;
+    }
+
+    result.remaining = recordings.len() - result.removed;
+    result.remaining_bytes = total;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn new_recording() -> NewRecording {
+        NewRecording {
+            source: RecordingSource::Agent,
+            source_id: "exec-1-iter-2-attempt-1".to_string(),
+            title: "app: dark-mode iteration 2".to_string(),
+            project_path: Some("/work/app".to_string()),
+            execution_id: Some("exec-1".to_string()),
+            iteration: Some(2),
+            cols: 120,
+            rows: 40,
+        }
+    }
+
+    #[test]
+    fn test_record_asciicast() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let settings = RecordingSettings::default();
+
+        let mut recorder = Recorder::start_in(dir, new_recording(), &settings).unwrap();
+        let id = recorder.id().to_string();
+        assert!(get_recording_in(dir, &id).unwrap().ended_at.is_none());
+
+        // "é" split across two chunks is written whole
+        recorder.output(b"caf\xc3");
+        recorder.output(b"\xa9\r\n");
+        recorder.resize(100, 30);
+        recorder.input(b"secret\n");
+        let meta = recorder.finish().unwrap();
+        assert!(meta.ended_at.is_some());
+        assert_eq!(meta.event_count, 3);
+
+        let cast = fs::read_to_string(cast_path(dir, &id)).unwrap();
+        let lines: Vec<serde_json::Value> = cast
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 120);
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "caf");
+        assert_eq!(lines[2][2], "é\r\n");
+        assert_eq!(lines[3][1], "r");
+        assert_eq!(lines[3][2], "100x30");
+        // Input isn't recorded by default
+        assert_eq!(lines.len(), 4);
+        assert_eq!(meta.size_bytes, cast.len() as u64);
+    }
+
+    #[test]
+    fn test_list_delete_and_retention() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let settings = RecordingSettings {
+            record_input: true,
+            ..Default::default()
+        };
+
+        let mut agent = Recorder::start_in(dir, new_recording(), &settings).unwrap();
+        agent.input(b"ls\n");
+        let agent = agent.finish().unwrap();
+        assert!(agent.includes_input);
+
+        let mut terminal = new_recording();
+        terminal.source = RecordingSource::Terminal;
+        terminal.execution_id = None;
+        let terminal = Recorder::start_in(dir, terminal, &settings).unwrap();
+
+        let all = list_recordings_in(dir, &RecordingFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        let filter = RecordingFilter {
+            execution_id: Some("exec-1".to_string()),
+            ..Default::default()
+        };
+        let by_execution = list_recordings_in(dir, &filter).unwrap();
+        assert_eq!(by_execution.len(), 1);
+        assert_eq!(by_execution[0].id, agent.id);
+
+        // The size limit removes finished recordings only
+        let result = apply_retention_in(dir, 0, 1).unwrap();
+        assert_eq!(result.removed, 1);
+        assert_eq!(result.remaining, 1);
+        assert!(get_recording_in(dir, &agent.id).is_err());
+        assert!(get_recording_in(dir, terminal.id()).is_ok());
+
+        // Recordings of a live server are left alone
+        assert_eq!(close_interrupted_recordings_in(dir).unwrap(), 0);
+
+        // A server stopping mid-recording leaves it in progress until restart
+        std::mem::forget(terminal);
+        let mut terminal = list_recordings_in(dir, &RecordingFilter::default())
+            .unwrap()
+            .remove(0);
+        assert!(terminal.ended_at.is_none());
+        terminal.recorder_pid = u32::MAX;
+        write_json(&meta_path(dir, &terminal.id), &terminal).unwrap();
+        assert_eq!(close_interrupted_recordings_in(dir).unwrap(), 1);
+        let terminal = get_recording_in(dir, &terminal.id).unwrap();
+        assert!(terminal.ended_at.is_some());
+        assert!(terminal.size_bytes > 0);
+
+        delete_recording_in(dir, &terminal.id).unwrap();
+        assert!(list_recordings_in(dir, &RecordingFilter::default())
+            .unwrap()
+            .is_empty());
+        assert!(get_recording_in(dir, "../config").is_err());
+    }
+}
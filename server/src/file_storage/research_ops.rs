@@ -3,7 +3,8 @@
 //! Research sessions are stored in `{project}/.ralph-ui/research/{id}.json`
 //! Research findings are stored in `{project}/.ralph-ui/research/{id}-findings/{agent_id}.md`
 
-use super::{atomic_write, ensure_dir, get_ralph_ui_dir, read_json, write_json, FileResult};
+use super::migrations::{read_versioned, write_versioned, RESEARCH_SESSION};
+use super::{atomic_write, ensure_dir, get_ralph_ui_dir, FileResult};
 use crate::models::{
     DiscussionEntry, ResearchFinding, ResearchSession, ResearchSessionStatus, UltraResearchConfig,
 };
@@ -59,13 +60,13 @@ pub fn create_research_session(project_path: &Path, session: &ResearchSession) -
     ensure_dir(&findings_dir)?;
 
     let path = get_session_path(project_path, &session.id);
-    write_json(&path, session)
+    write_versioned(&path, session, &RESEARCH_SESSION)
 }
 
 /// Read a research session by ID
 pub fn read_research_session(project_path: &Path, session_id: &str) -> FileResult<ResearchSession> {
     let path = get_session_path(project_path, session_id);
-    read_json(&path)
+    read_versioned(&path, &RESEARCH_SESSION)
 }
 
 /// Get a research session if it exists
@@ -83,7 +84,7 @@ pub fn get_research_session_opt(
 /// Save/update a research session
 pub fn save_research_session(project_path: &Path, session: &ResearchSession) -> FileResult<()> {
     let path = get_session_path(project_path, &session.id);
-    write_json(&path, session)
+    write_versioned(&path, session, &RESEARCH_SESSION)
 }
 
 /// List all research sessions for a project
@@ -103,7 +104,7 @@ pub fn list_research_sessions(project_path: &Path) -> FileResult<Vec<ResearchSes
         let path = entry.path();
         if path.extension().map_or(false, |ext| ext == "json") {
             // Skip any non-session files
-            if let Ok(session) = read_json::<ResearchSession>(&path) {
+            if let Ok(session) = read_versioned::<ResearchSession>(&path, &RESEARCH_SESSION) {
                 sessions.push(session);
             }
         }
@@ -4,7 +4,8 @@
 //! This replaces SQLite storage for sessions and tasks.
 
 use super::index::{read_index, update_index_entry, write_index, IndexFile, SessionIndexEntry};
-use super::{atomic_write, ensure_dir, get_ralph_ui_dir, read_json, FileResult};
+use super::migrations::{read_versioned, write_versioned, SESSION};
+use super::{ensure_dir, get_ralph_ui_dir, FileResult};
 use crate::models::{Session, SessionConfig, SessionStatus, Task, TaskStatus};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -183,10 +184,7 @@ pub fn save_session(project_path: &Path, session: &Session) -> FileResult<PathBu
         prd_id: None, // Can be set separately if needed
     };
 
-    let content = serde_json::to_string_pretty(&session_file)
-        .map_err(|e| format!("Failed to serialize session: {}", e))?;
-
-    atomic_write(&file_path, &content)?;
+    write_versioned(&file_path, &session_file, &SESSION)?;
 
     // Update the index
     let index_entry = session_file.to_index_entry();
@@ -201,14 +199,14 @@ pub fn save_session(project_path: &Path, session: &Session) -> FileResult<PathBu
 /// Read a session from file
 pub fn read_session(project_path: &Path, session_id: &str) -> FileResult<Session> {
     let file_path = get_session_file_path(project_path, session_id);
-    let session_file: SessionFile = read_json(&file_path)?;
+    let session_file: SessionFile = read_versioned(&file_path, &SESSION)?;
     Ok(session_file.to_session())
 }
 
 /// Read a session file (raw)
 pub fn read_session_file(project_path: &Path, session_id: &str) -> FileResult<SessionFile> {
     let file_path = get_session_file_path(project_path, session_id);
-    read_json(&file_path)
+    read_versioned(&file_path, &SESSION)
 }
 
 /// Check if a session file exists
@@ -257,7 +255,7 @@ pub fn list_sessions(project_path: &Path) -> FileResult<Vec<Session>> {
             continue;
         }
 
-        match read_json::<SessionFile>(&path) {
+        match read_versioned::<SessionFile>(&path, &SESSION) {
             Ok(file) => sessions.push(file.to_session()),
             Err(e) => {
                 log::warn!("Failed to read session file {:?}: {}", path, e);
@@ -333,7 +331,7 @@ pub fn rebuild_session_index(project_path: &Path) -> FileResult<Vec<SessionIndex
             continue;
         }
 
-        match read_json::<SessionFile>(&path) {
+        match read_versioned::<SessionFile>(&path, &SESSION) {
             Ok(file) => {
                 index_entries.push(file.to_index_entry());
             }
@@ -0,0 +1,452 @@
+//! Read-only share links for a single execution
+//!
+//! Stored in `~/.ralph-ui/share-links.json` together with the key links are
+//! signed with. A link token is `rsl_<id>.<expiry>.<signature>`, where the
+//! signature is an HMAC-SHA256 of the ID and expiry (Unix seconds). Only the
+//! signature is secret; revoking a link marks it in the file.
+
+use super::iterations::get_execution_file_path;
+use super::migrations::{read_versioned, write_versioned, SHARE_LINKS};
+use super::{ensure_dir, get_global_ralph_ui_dir, FileResult};
+use crate::utils::{decode_hex, lock_mutex_recover, prds_dir};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Version of the share links file format
+const SHARE_LINKS_FILE_VERSION: u32 = 1;
+
+/// Prefix that makes share link tokens recognizable
+pub const SHARE_LINK_PREFIX: &str = "rsl_";
+
+/// Lifetime of a link when none is requested
+const DEFAULT_EXPIRES_IN_HOURS: u32 = 24;
+
+/// Longest lifetime a link can be created with (30 days)
+const MAX_EXPIRES_IN_HOURS: u32 = 24 * 30;
+
+/// Serializes read-modify-write of the share links file
+static SHARE_LINKS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A link granting read-only access to one execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLink {
+    pub id: String,
+    /// Who the link was made for, shown in logs
+    pub label: String,
+    pub project_path: String,
+    pub prd_name: String,
+    pub execution_id: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    #[serde(default)]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ShareLink {
+    /// Whether the link can authenticate at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
+
+/// A newly created link; `token` can't be retrieved again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedShareLink {
+    pub token: String,
+    pub link: ShareLink,
+}
+
+/// Options for creating a link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewShareLink {
+    pub project_path: String,
+    pub prd_name: String,
+    pub execution_id: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Expire the link after this many hours (default 24, at most 30 days)
+    #[serde(default)]
+    pub expires_in_hours: Option<u32>,
+}
+
+/// Share links file structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLinksFile {
+    pub version: u32,
+    /// Hex-encoded HMAC key links are signed with
+    #[serde(default)]
+    pub signing_key: String,
+    #[serde(default)]
+    pub links: Vec<ShareLink>,
+}
+
+impl Default for ShareLinksFile {
+    fn default() -> Self {
+        Self {
+            version: SHARE_LINKS_FILE_VERSION,
+            signing_key: String::new(),
+            links: Vec::new(),
+        }
+    }
+}
+
+/// HMAC-SHA256 over a link's ID and expiry
+fn link_mac(key: &str, id: &str, expires: i64) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(format!("{}.{}", id, expires).as_bytes());
+    mac
+}
+
+/// Hex-encoded signature of a link's ID and expiry
+fn sign(key: &str, id: &str, expires: i64) -> String {
+    link_mac(key, id, expires)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Whether `name` can only refer to a file directly inside a directory
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+/// Check the execution and PRD a link would grant belong to its project,
+/// so a link can't carry another project's data under this one's scope
+fn check_link_target(new_link: &NewShareLink) -> FileResult<()> {
+    let project_path = Path::new(&new_link.project_path);
+    if !is_plain_file_name(&new_link.execution_id)
+        || !get_execution_file_path(project_path, &new_link.execution_id).is_file()
+    {
+        return Err(format!(
+            "Execution {} not found in {}",
+            new_link.execution_id, new_link.project_path
+        ));
+    }
+    if !is_plain_file_name(&new_link.prd_name)
+        || !prds_dir(&new_link.project_path)
+            .join(format!("{}.json", new_link.prd_name))
+            .is_file()
+    {
+        return Err(format!(
+            "PRD {} not found in {}",
+            new_link.prd_name, new_link.project_path
+        ));
+    }
+    Ok(())
+}
+
+/// Get the path of the global share links file
+pub fn get_share_links_file_path() -> PathBuf {
+    get_share_links_file_path_in(&get_global_ralph_ui_dir())
+}
+
+fn get_share_links_file_path_in(base_dir: &Path) -> PathBuf {
+    base_dir.join("share-links.json")
+}
+
+/// Read the share links file from a specific directory (empty if missing)
+pub fn read_share_links_from(base_dir: &Path) -> FileResult<ShareLinksFile> {
+    let file_path = get_share_links_file_path_in(base_dir);
+    if !file_path.exists() {
+        return Ok(ShareLinksFile::default());
+    }
+    read_versioned(&file_path, &SHARE_LINKS)
+}
+
+fn write_share_links_to(base_dir: &Path, file: &ShareLinksFile) -> FileResult<()> {
+    ensure_dir(base_dir)?;
+    write_versioned(&get_share_links_file_path_in(base_dir), file, &SHARE_LINKS)
+}
+
+/// Create a share link
+pub fn create_share_link(new_link: NewShareLink) -> FileResult<CreatedShareLink> {
+    create_share_link_in(&get_global_ralph_ui_dir(), new_link)
+}
+
+/// Create a share link in a specific directory (for testing)
+pub fn create_share_link_in(
+    base_dir: &Path,
+    new_link: NewShareLink,
+) -> FileResult<CreatedShareLink> {
+    if new_link.execution_id.trim().is_empty() {
+        return Err("Execution ID is required".to_string());
+    }
+    check_link_target(&new_link)?;
+    let hours = new_link
+        .expires_in_hours
+        .unwrap_or(DEFAULT_EXPIRES_IN_HOURS);
+    if hours == 0 || hours > MAX_EXPIRES_IN_HOURS {
+        return Err(format!(
+            "Share links must expire within 1 to {} hours",
+            MAX_EXPIRES_IN_HOURS
+        ));
+    }
+
+    let _guard = lock_mutex_recover(&SHARE_LINKS_LOCK);
+    let mut file = read_share_links_from(base_dir)?;
+    if file.signing_key.is_empty() {
+        file.signing_key = format!(
+            "{}{}",
+            crate::server::generate_auth_token(),
+            crate::server::generate_auth_token()
+        );
+    }
+
+    let now = Utc::now();
+    // Whole seconds, so the expiry in the token matches the stored one
+    let expires_at = Utc
+        .timestamp_opt((now + Duration::hours(hours as i64)).timestamp(), 0)
+        .single()
+        .ok_or("Invalid expiry")?;
+    let link = ShareLink {
+        id: uuid::Uuid::new_v4().simple().to_string(),
+        label: new_link
+            .label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .unwrap_or_else(|| format!("execution {}", new_link.execution_id)),
+        project_path: new_link.project_path,
+        prd_name: new_link.prd_name,
+        execution_id: new_link.execution_id,
+        created_at: now,
+        expires_at,
+        revoked_at: None,
+    };
+    let expires = expires_at.timestamp();
+    let token = format!(
+        "{}{}.{}.{}",
+        SHARE_LINK_PREFIX,
+        link.id,
+        expires,
+        sign(&file.signing_key, &link.id, expires)
+    );
+
+    file.links.push(link.clone());
+    write_share_links_to(base_dir, &file)?;
+
+    Ok(CreatedShareLink { token, link })
+}
+
+/// Check a link token's signature, expiry and revocation against a loaded
+/// share links file, returning the link it grants
+pub fn verify_token(
+    file: &ShareLinksFile,
+    token: &str,
+    now: DateTime<Utc>,
+) -> FileResult<ShareLink> {
+    let invalid = || "Invalid share link".to_string();
+    let mut parts = token
+        .strip_prefix(SHARE_LINK_PREFIX)
+        .ok_or_else(invalid)?
+        .split('.');
+    let (Some(id), Some(expires), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let expires: i64 = expires.parse().map_err(|_| invalid())?;
+
+    if file.signing_key.is_empty() {
+        return Err(invalid());
+    }
+    let signature = decode_hex(signature).ok_or_else(invalid)?;
+    link_mac(&file.signing_key, id, expires)
+        .verify_slice(&signature)
+        .map_err(|_| invalid())?;
+
+    let link = file
+        .links
+        .iter()
+        .find(|l| l.id == id && l.expires_at.timestamp() == expires)
+        .ok_or_else(invalid)?;
+    if link.revoked_at.is_some() {
+        return Err("Share link has been revoked".to_string());
+    }
+    if link.expires_at <= now {
+        return Err("Share link has expired".to_string());
+    }
+    Ok(link.clone())
+}
+
+/// Revoke a share link by ID (revoked links are kept for auditing)
+pub fn revoke_share_link(link_id: &str) -> FileResult<ShareLink> {
+    revoke_share_link_in(&get_global_ralph_ui_dir(), link_id)
+}
+
+/// Revoke a share link in a specific directory (for testing)
+pub fn revoke_share_link_in(base_dir: &Path, link_id: &str) -> FileResult<ShareLink> {
+    let _guard = lock_mutex_recover(&SHARE_LINKS_LOCK);
+    let mut file = read_share_links_from(base_dir)?;
+    let link = file
+        .links
+        .iter_mut()
+        .find(|l| l.id == link_id)
+        .ok_or_else(|| format!("Share link not found: {}", link_id))?;
+
+    if link.revoked_at.is_none() {
+        link.revoked_at = Some(Utc::now());
+    }
+    let link = link.clone();
+
+    write_share_links_to(base_dir, &file)?;
+    Ok(link)
+}
+
+/// List share links, newest first, optionally for one project
+pub fn list_share_links(project_path: Option<&str>) -> FileResult<Vec<ShareLink>> {
+    list_share_links_in(&get_global_ralph_ui_dir(), project_path)
+}
+
+/// List share links in a specific directory (for testing)
+pub fn list_share_links_in(
+    base_dir: &Path,
+    project_path: Option<&str>,
+) -> FileResult<Vec<ShareLink>> {
+    let mut links: Vec<ShareLink> = read_share_links_from(base_dir)?
+        .links
+        .into_iter()
+        .filter(|l| project_path.map_or(true, |p| l.project_path == p))
+        .collect();
+    links.sort_by_key(|l| std::cmp::Reverse(l.created_at));
+    Ok(links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A project with the `dark-mode` PRD and executions of it
+    fn project_with(execution_ids: &[&str]) -> TempDir {
+        let project = TempDir::new().unwrap();
+        let path = project.path();
+        let prds = prds_dir(&path.to_string_lossy());
+        std::fs::create_dir_all(&prds).unwrap();
+        std::fs::write(prds.join("dark-mode.json"), "{}").unwrap();
+        for execution_id in execution_ids {
+            let file = get_execution_file_path(path, execution_id);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, "{}").unwrap();
+        }
+        project
+    }
+
+    fn new_link(project: &TempDir, execution_id: &str) -> NewShareLink {
+        NewShareLink {
+            project_path: project.path().to_string_lossy().to_string(),
+            prd_name: "dark-mode".to_string(),
+            execution_id: execution_id.to_string(),
+            label: Some("stakeholders".to_string()),
+            expires_in_hours: None,
+        }
+    }
+
+    #[test]
+    fn test_create_and_verify() {
+        let temp_dir = TempDir::new().unwrap();
+        let app = project_with(&["exec-1"]);
+        let created = create_share_link_in(temp_dir.path(), new_link(&app, "exec-1")).unwrap();
+        assert!(created.token.starts_with(SHARE_LINK_PREFIX));
+        assert_eq!(created.link.label, "stakeholders");
+
+        let file = read_share_links_from(temp_dir.path()).unwrap();
+        let link = verify_token(&file, &created.token, Utc::now()).unwrap();
+        assert_eq!(link.execution_id, "exec-1");
+
+        // The signature isn't stored
+        let raw = std::fs::read_to_string(temp_dir.path().join("share-links.json")).unwrap();
+        assert!(!raw.contains(created.token.rsplit('.').next().unwrap()));
+    }
+
+    #[test]
+    fn test_tampered_tokens_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let app = project_with(&["exec-1"]);
+        let created = create_share_link_in(temp_dir.path(), new_link(&app, "exec-1")).unwrap();
+        let file = read_share_links_from(temp_dir.path()).unwrap();
+
+        // Extending the expiry breaks the signature
+        let parts: Vec<&str> = created.token.split('.').collect();
+        let expires: i64 = parts[1].parse().unwrap();
+        let extended = format!("{}.{}.{}", parts[0], expires + 3600, parts[2]);
+        assert!(verify_token(&file, &extended, Utc::now()).is_err());
+
+        let mut forged = created.token.clone();
+        forged.pop();
+        forged.push('x');
+        assert!(verify_token(&file, &forged, Utc::now()).is_err());
+        assert!(verify_token(&file, "rsl_nope", Utc::now()).is_err());
+        assert!(verify_token(&file, "rui_abc", Utc::now()).is_err());
+    }
+
+    #[test]
+    fn test_revoke_and_expiry() {
+        let temp_dir = TempDir::new().unwrap();
+        let app = project_with(&["exec-1"]);
+        let created = create_share_link_in(temp_dir.path(), new_link(&app, "exec-1")).unwrap();
+
+        let file = read_share_links_from(temp_dir.path()).unwrap();
+        let later = Utc::now() + Duration::hours(25);
+        assert!(verify_token(&file, &created.token, later).is_err());
+
+        let revoked = revoke_share_link_in(temp_dir.path(), &created.link.id).unwrap();
+        assert!(!revoked.is_active(Utc::now()));
+        let file = read_share_links_from(temp_dir.path()).unwrap();
+        assert!(verify_token(&file, &created.token, Utc::now()).is_err());
+        assert!(revoke_share_link_in(temp_dir.path(), "missing").is_err());
+    }
+
+    #[test]
+    fn test_expiry_limits_and_listing() {
+        let temp_dir = TempDir::new().unwrap();
+        let app = project_with(&["exec-1"]);
+        let other = project_with(&["exec-2"]);
+        let mut too_long = new_link(&app, "exec-1");
+        too_long.expires_in_hours = Some(MAX_EXPIRES_IN_HOURS + 1);
+        assert!(create_share_link_in(temp_dir.path(), too_long).is_err());
+
+        create_share_link_in(temp_dir.path(), new_link(&app, "exec-1")).unwrap();
+        create_share_link_in(temp_dir.path(), new_link(&other, "exec-2")).unwrap();
+
+        assert_eq!(list_share_links_in(temp_dir.path(), None).unwrap().len(), 2);
+        let other_path = other.path().to_string_lossy();
+        let links = list_share_links_in(temp_dir.path(), Some(&other_path)).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].execution_id, "exec-2");
+    }
+
+    #[test]
+    fn test_links_only_for_own_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let app = project_with(&["exec-1"]);
+        let other = project_with(&["exec-2"]);
+
+        // Another project's execution under this project's path
+        assert!(create_share_link_in(temp_dir.path(), new_link(&app, "exec-2")).is_err());
+        let mut traversal = new_link(&app, "exec-1");
+        traversal.execution_id = format!(
+            "../../{}/.ralph-ui/iterations/exec-2",
+            other.path().display()
+        );
+        assert!(create_share_link_in(temp_dir.path(), traversal).is_err());
+
+        let mut missing_prd = new_link(&app, "exec-1");
+        missing_prd.prd_name = "other-prd".to_string();
+        assert!(create_share_link_in(temp_dir.path(), missing_prd).is_err());
+
+        assert!(list_share_links_in(temp_dir.path(), None)
+            .unwrap()
+            .is_empty());
+    }
+}
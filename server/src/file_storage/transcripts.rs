@@ -0,0 +1,606 @@
+//! File-based storage for persisted agent transcripts
+//!
+//! Stores one directory per agent run in `.ralph-ui/transcripts/`:
+//! - `{execution_id}/{iteration}/output.log.gz` - Raw agent output
+//! - `{execution_id}/{iteration}/tool-calls.json.gz` - Parsed tool calls
+//! - `{execution_id}/{iteration}/subagents.json.gz` - Subagent events (for trace export)
+//! - `{execution_id}/{iteration}/prompt.md.gz` - Final prompt sent to the agent
+//! - `{execution_id}/{iteration}/meta.json` - Transcript metadata
+//! - `index.json` - Metadata for every transcript (filterable by story and agent)
+
+use super::{ensure_dir, get_ralph_ui_dir, read_json, write_json, FileResult};
+use crate::agents::transcript_capture::{TranscriptCapture, TranscriptToolCall};
+use crate::agents::SubagentEvent;
+use crate::models::AgentType;
+use chrono::{DateTime, Duration, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Version of the transcript index format
+const TRANSCRIPT_INDEX_VERSION: u32 = 1;
+
+const OUTPUT_FILE: &str = "output.log.gz";
+const TOOL_CALLS_FILE: &str = "tool-calls.json.gz";
+const SUBAGENTS_FILE: &str = "subagents.json.gz";
+const PROMPT_FILE: &str = "prompt.md.gz";
+const META_FILE: &str = "meta.json";
+
+/// Serializes read-modify-write of index files within this process
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Metadata for one persisted transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMeta {
+    pub execution_id: String,
+    pub iteration: u32,
+    /// Attempt number within the iteration (retries overwrite earlier attempts)
+    pub attempt: u32,
+    pub agent_id: String,
+    pub agent_type: AgentType,
+    pub model: Option<String>,
+    /// PRD the run belonged to
+    #[serde(default)]
+    pub prd_name: Option<String>,
+    pub story_id: Option<String>,
+    pub exit_code: i32,
+    pub started_at: String,
+    pub saved_at: String,
+    /// Uncompressed size of the raw output
+    pub output_bytes: u64,
+    /// Total size of the compressed files on disk
+    pub compressed_bytes: u64,
+    pub line_count: u64,
+    pub tool_call_count: u32,
+    /// Whether raw output hit the capture limit
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Details of an agent run to persist alongside its captured output
+#[derive(Debug, Clone)]
+pub struct NewTranscript {
+    pub execution_id: String,
+    pub iteration: u32,
+    pub attempt: u32,
+    pub agent_id: String,
+    pub agent_type: AgentType,
+    pub model: Option<String>,
+    pub prd_name: Option<String>,
+    pub story_id: Option<String>,
+    pub exit_code: i32,
+    pub started_at: DateTime<Utc>,
+    pub prompt: Option<String>,
+}
+
+/// Filter for listing transcripts (all fields optional, combined with AND)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptFilter {
+    pub execution_id: Option<String>,
+    pub story_id: Option<String>,
+    pub agent_type: Option<AgentType>,
+    pub agent_id: Option<String>,
+}
+
+/// A page of transcript output lines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptPage {
+    pub execution_id: String,
+    pub iteration: u32,
+    /// Index of the first line in this page
+    pub offset: usize,
+    pub lines: Vec<String>,
+    pub total_lines: usize,
+    /// Offset of the next page, or None at the end
+    pub next_offset: Option<usize>,
+}
+
+/// Result of applying the retention policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptRetentionResult {
+    pub removed: usize,
+    pub freed_bytes: u64,
+    pub remaining: usize,
+    pub remaining_bytes: u64,
+}
+
+/// Parts of a transcript that can be downloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptPart {
+    Output,
+    ToolCalls,
+    Subagents,
+    Prompt,
+}
+
+impl TranscriptPart {
+    fn file_name(self) -> &'static str {
+        match self {
+            TranscriptPart::Output => OUTPUT_FILE,
+            TranscriptPart::ToolCalls => TOOL_CALLS_FILE,
+            TranscriptPart::Subagents => SUBAGENTS_FILE,
+            TranscriptPart::Prompt => PROMPT_FILE,
+        }
+    }
+}
+
+impl std::str::FromStr for TranscriptPart {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "output" => Ok(TranscriptPart::Output),
+            "tool_calls" | "toolCalls" => Ok(TranscriptPart::ToolCalls),
+            "subagents" => Ok(TranscriptPart::Subagents),
+            "prompt" => Ok(TranscriptPart::Prompt),
+            _ => Err(format!("Unknown transcript part: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptIndex {
+    version: u32,
+    transcripts: Vec<TranscriptMeta>,
+}
+
+impl Default for TranscriptIndex {
+    fn default() -> Self {
+        Self {
+            version: TRANSCRIPT_INDEX_VERSION,
+            transcripts: Vec::new(),
+        }
+    }
+}
+
+/// Get the transcripts directory path for a project
+pub fn get_transcripts_dir(project_path: &Path) -> PathBuf {
+    get_ralph_ui_dir(project_path).join("transcripts")
+}
+
+/// Get the directory holding one iteration's transcript
+pub fn get_transcript_dir(
+    project_path: &Path,
+    execution_id: &str,
+    iteration: u32,
+) -> FileResult<PathBuf> {
+    validate_execution_id(execution_id)?;
+    Ok(get_transcripts_dir(project_path)
+        .join(execution_id)
+        .join(iteration.to_string()))
+}
+
+/// Get the compressed file for a transcript part (for streaming downloads)
+pub fn get_transcript_file_path(
+    project_path: &Path,
+    execution_id: &str,
+    iteration: u32,
+    part: TranscriptPart,
+) -> FileResult<PathBuf> {
+    let path = get_transcript_dir(project_path, execution_id, iteration)?.join(part.file_name());
+    if !path.exists() {
+        return Err(format!(
+            "Transcript {} for {}/{} not found",
+            part.file_name(),
+            execution_id,
+            iteration
+        ));
+    }
+    Ok(path)
+}
+
+fn index_path(project_path: &Path) -> PathBuf {
+    get_transcripts_dir(project_path).join("index.json")
+}
+
+fn read_index(project_path: &Path) -> FileResult<TranscriptIndex> {
+    let path = index_path(project_path);
+    if path.exists() {
+        read_json(&path)
+    } else {
+        Ok(TranscriptIndex::default())
+    }
+}
+
+/// Execution IDs become directory names, so reject anything path-like
+fn validate_execution_id(execution_id: &str) -> FileResult<()> {
+    if execution_id.is_empty() || execution_id.contains(['/', '\\']) || execution_id.contains("..")
+    {
+        return Err(format!("Invalid execution ID: {:?}", execution_id));
+    }
+    Ok(())
+}
+
+fn write_gz(path: &Path, data: &[u8]) -> FileResult<u64> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .map_err(|e| format!("Failed to compress {:?}: {}", path, e))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress {:?}: {}", path, e))?;
+    fs::write(path, &compressed).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(compressed.len() as u64)
+}
+
+fn read_gz(path: &Path) -> FileResult<Vec<u8>> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    let mut data = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Failed to decompress {:?}: {}", path, e))?;
+    Ok(data)
+}
+
+/// Persist a captured agent run and add it to the index
+///
+/// A later attempt of the same iteration replaces the earlier one.
+pub fn save_transcript(
+    project_path: &Path,
+    transcript: &NewTranscript,
+    capture: &TranscriptCapture,
+) -> FileResult<TranscriptMeta> {
+    let dir = get_transcript_dir(project_path, &transcript.execution_id, transcript.iteration)?;
+    ensure_dir(&dir)?;
+
+    let mut compressed_bytes = write_gz(&dir.join(OUTPUT_FILE), &capture.raw)?;
+    let tool_calls = serde_json::to_vec(&capture.tool_calls)
+        .map_err(|e| format!("Failed to serialize tool calls: {}", e))?;
+    compressed_bytes += write_gz(&dir.join(TOOL_CALLS_FILE), &tool_calls)?;
+    let subagent_events = serde_json::to_vec(&capture.subagent_events)
+        .map_err(|e| format!("Failed to serialize subagent events: {}", e))?;
+    compressed_bytes += write_gz(&dir.join(SUBAGENTS_FILE), &subagent_events)?;
+    if let Some(prompt) = &transcript.prompt {
+        compressed_bytes += write_gz(&dir.join(PROMPT_FILE), prompt.as_bytes())?;
+    }
+
+    let meta = TranscriptMeta {
+        execution_id: transcript.execution_id.clone(),
+        iteration: transcript.iteration,
+        attempt: transcript.attempt,
+        agent_id: transcript.agent_id.clone(),
+        agent_type: transcript.agent_type,
+        model: transcript.model.clone(),
+        prd_name: transcript.prd_name.clone(),
+        story_id: transcript.story_id.clone(),
+        exit_code: transcript.exit_code,
+        started_at: transcript.started_at.to_rfc3339(),
+        saved_at: Utc::now().to_rfc3339(),
+        output_bytes: capture.raw.len() as u64,
+        compressed_bytes,
+        line_count: capture.raw.iter().filter(|&&b| b == b'\n').count() as u64,
+        tool_call_count: capture.tool_calls.len() as u32,
+        truncated: capture.truncated,
+    };
+    write_json(&dir.join(META_FILE), &meta)?;
+
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = read_index(project_path)?;
+    index
+        .transcripts
+        .retain(|t| !(t.execution_id == meta.execution_id && t.iteration == meta.iteration));
+    index.transcripts.push(meta.clone());
+    write_json(&index_path(project_path), &index)?;
+
+    Ok(meta)
+}
+
+/// Rebuild the transcript index from the `meta.json` of every stored
+/// transcript (e.g. after transcripts were copied in from an archive)
+pub fn rebuild_transcript_index(project_path: &Path) -> FileResult<usize> {
+    let dir = get_transcripts_dir(project_path);
+    let mut index = TranscriptIndex::default();
+    for execution in fs::read_dir(&dir).into_iter().flatten().flatten() {
+        for iteration in fs::read_dir(execution.path())
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let meta_path = iteration.path().join(META_FILE);
+            if !meta_path.is_file() {
+                continue;
+            }
+            match read_json::<TranscriptMeta>(&meta_path) {
+                Ok(meta) => index.transcripts.push(meta),
+                Err(e) => log::warn!("[Transcripts] Skipping {:?}: {}", meta_path, e),
+            }
+        }
+    }
+
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let count = index.transcripts.len();
+    ensure_dir(&dir)?;
+    write_json(&index_path(project_path), &index)?;
+    Ok(count)
+}
+
+/// List transcripts matching a filter, newest first
+pub fn list_transcripts(
+    project_path: &Path,
+    filter: &TranscriptFilter,
+) -> FileResult<Vec<TranscriptMeta>> {
+    let mut transcripts: Vec<TranscriptMeta> = read_index(project_path)?
+        .transcripts
+        .into_iter()
+        .filter(|t| {
+            filter
+                .execution_id
+                .as_ref()
+                .map_or(true, |id| &t.execution_id == id)
+                && filter
+                    .story_id
+                    .as_ref()
+                    .map_or(true, |id| t.story_id.as_ref() == Some(id))
+                && filter.agent_type.map_or(true, |a| t.agent_type == a)
+                && filter
+                    .agent_id
+                    .as_ref()
+                    .map_or(true, |id| &t.agent_id == id)
+        })
+        .collect();
+    transcripts.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    Ok(transcripts)
+}
+
+/// Read a page of output lines from a transcript
+pub fn read_transcript_page(
+    project_path: &Path,
+    execution_id: &str,
+    iteration: u32,
+    offset: usize,
+    limit: usize,
+) -> FileResult<TranscriptPage> {
+    let path = get_transcript_file_path(
+        project_path,
+        execution_id,
+        iteration,
+        TranscriptPart::Output,
+    )?;
+    let data = read_gz(&path)?;
+    let text = String::from_utf8_lossy(&data);
+
+    let total_lines = text.lines().count();
+    let lines: Vec<String> = text
+        .lines()
+        .skip(offset)
+        .take(limit)
+        .map(|l| l.to_string())
+        .collect();
+    let end = offset + lines.len();
+
+    Ok(TranscriptPage {
+        execution_id: execution_id.to_string(),
+        iteration,
+        offset,
+        lines,
+        total_lines,
+        next_offset: if end < total_lines { Some(end) } else { None },
+    })
+}
+
+/// Read the parsed tool calls of a transcript
+pub fn read_transcript_tool_calls(
+    project_path: &Path,
+    execution_id: &str,
+    iteration: u32,
+) -> FileResult<Vec<TranscriptToolCall>> {
+    let path = get_transcript_file_path(
+        project_path,
+        execution_id,
+        iteration,
+        TranscriptPart::ToolCalls,
+    )?;
+    serde_json::from_slice(&read_gz(&path)?)
+        .map_err(|e| format!("Failed to parse tool calls {:?}: {}", path, e))
+}
+
+/// Read the subagent events of a transcript (empty for transcripts saved without them)
+pub fn read_transcript_subagent_events(
+    project_path: &Path,
+    execution_id: &str,
+    iteration: u32,
+) -> FileResult<Vec<SubagentEvent>> {
+    let path = get_transcript_dir(project_path, execution_id, iteration)?.join(SUBAGENTS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_slice(&read_gz(&path)?)
+        .map_err(|e| format!("Failed to parse subagent events {:?}: {}", path, e))
+}
+
+/// Read the decompressed contents of a transcript part
+pub fn read_transcript_part(
+    project_path: &Path,
+    execution_id: &str,
+    iteration: u32,
+    part: TranscriptPart,
+) -> FileResult<Vec<u8>> {
+    read_gz(&get_transcript_file_path(
+        project_path,
+        execution_id,
+        iteration,
+        part,
+    )?)
+}
+
+/// Delete transcripts older than `max_age_days`, then the oldest remaining ones
+/// until the compressed total fits in `max_total_bytes` (0 disables either limit)
+pub fn apply_retention(
+    project_path: &Path,
+    max_age_days: u32,
+    max_total_bytes: u64,
+) -> FileResult<TranscriptRetentionResult> {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = read_index(project_path)?;
+    if index.transcripts.is_empty() {
+        return Ok(TranscriptRetentionResult::default());
+    }
+
+    // Oldest first, so the size pass drops the oldest transcripts
+    index
+        .transcripts
+        .sort_by(|a, b| a.saved_at.cmp(&b.saved_at));
+
+    let cutoff = (max_age_days > 0).then(|| Utc::now() - Duration::days(max_age_days as i64));
+    let mut total: u64 = index.transcripts.iter().map(|t| t.compressed_bytes).sum();
+    let mut kept = Vec::with_capacity(index.transcripts.len());
+    let mut result = TranscriptRetentionResult::default();
+
+    for meta in index.transcripts {
+        let expired = cutoff.is_some_and(|cutoff| {
+            DateTime::parse_from_rfc3339(&meta.saved_at)
+                .map(|t| t.with_timezone(&Utc) < cutoff)
+                .unwrap_or(false)
+        });
+        let over_size = max_total_bytes > 0 && total > max_total_bytes;
+
+        if expired || over_size {
+            let dir = get_transcript_dir(project_path, &meta.execution_id, meta.iteration)?;
+            if dir.exists() {
+                fs::remove_dir_all(&dir)
+                    .map_err(|e| format!("Failed to remove {:?}: {}", dir, e))?;
+            }
+            // Drop the execution directory once its last iteration is gone
+            if let Some(parent) = dir.parent() {
+                let _ = fs::remove_dir(parent);
+            }
+            total = total.saturating_sub(meta.compressed_bytes);
+            result.removed += 1;
+            result.freed_bytes += meta.compressed_bytes;
+        } else {
+            kept.push(meta);
+        }
+    }
+
+    result.remaining = kept.len();
+    result.remaining_bytes = total;
+    if result.removed > 0 {
+        log::info!(
+            "[Transcripts] Retention removed {} transcript(s), freed {} bytes",
+            result.removed,
+            result.freed_bytes
+        );
+        index.transcripts = kept;
+        write_json(&index_path(project_path), &index)?;
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn new_transcript(execution_id: &str, iteration: u32, story_id: &str) -> NewTranscript {
+        NewTranscript {
+            execution_id: execution_id.to_string(),
+            iteration,
+            attempt: 1,
+            agent_id: format!("{}-iter-{}", execution_id, iteration),
+            agent_type: AgentType::Claude,
+            model: None,
+            prd_name: Some("feature".to_string()),
+            story_id: Some(story_id.to_string()),
+            exit_code: 0,
+            started_at: Utc::now(),
+            prompt: Some("Implement the story".to_string()),
+        }
+    }
+
+    fn capture_with_lines(count: usize) -> TranscriptCapture {
+        let mut capture = TranscriptCapture::default();
+        for i in 0..count {
+            capture.push_line(&format!("line {}", i), false);
+        }
+        capture.start_tool_call("t1".into(), "Read".into(), None, None);
+        capture
+    }
+
+    #[test]
+    fn test_save_and_page_transcript() {
+        let temp = TempDir::new().unwrap();
+        let meta = save_transcript(
+            temp.path(),
+            &new_transcript("exec-1", 1, "US-1"),
+            &capture_with_lines(25),
+        )
+        .unwrap();
+        assert_eq!(meta.line_count, 25);
+        assert_eq!(meta.tool_call_count, 1);
+
+        let page = read_transcript_page(temp.path(), "exec-1", 1, 20, 10).unwrap();
+        assert_eq!(
+            page.lines,
+            vec!["line 20", "line 21", "line 22", "line 23", "line 24"]
+        );
+        assert_eq!(page.total_lines, 25);
+        assert_eq!(page.next_offset, None);
+
+        let first = read_transcript_page(temp.path(), "exec-1", 1, 0, 10).unwrap();
+        assert_eq!(first.next_offset, Some(10));
+
+        let tool_calls = read_transcript_tool_calls(temp.path(), "exec-1", 1).unwrap();
+        assert_eq!(tool_calls[0].tool_name, "Read");
+
+        let prompt =
+            read_transcript_part(temp.path(), "exec-1", 1, TranscriptPart::Prompt).unwrap();
+        assert_eq!(prompt, b"Implement the story");
+    }
+
+    #[test]
+    fn test_list_transcripts_filters_by_story() {
+        let temp = TempDir::new().unwrap();
+        let capture = capture_with_lines(1);
+        save_transcript(temp.path(), &new_transcript("exec-1", 1, "US-1"), &capture).unwrap();
+        save_transcript(temp.path(), &new_transcript("exec-1", 2, "US-2"), &capture).unwrap();
+        // Retried attempt replaces the earlier entry
+        save_transcript(temp.path(), &new_transcript("exec-1", 2, "US-2"), &capture).unwrap();
+
+        let all = list_transcripts(temp.path(), &TranscriptFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let filter = TranscriptFilter {
+            story_id: Some("US-2".to_string()),
+            ..Default::default()
+        };
+        let filtered = list_transcripts(temp.path(), &filter).unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].iteration, 2);
+    }
+
+    #[test]
+    fn test_retention_by_size_removes_oldest() {
+        let temp = TempDir::new().unwrap();
+        let capture = capture_with_lines(100);
+        let first =
+            save_transcript(temp.path(), &new_transcript("exec-1", 1, "US-1"), &capture).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let second =
+            save_transcript(temp.path(), &new_transcript("exec-2", 1, "US-1"), &capture).unwrap();
+
+        let result = apply_retention(temp.path(), 0, second.compressed_bytes).unwrap();
+        assert_eq!(result.removed, 1);
+        assert_eq!(result.freed_bytes, first.compressed_bytes);
+        assert!(!get_transcripts_dir(temp.path()).join("exec-1").exists());
+        assert!(read_transcript_page(temp.path(), "exec-2", 1, 0, 10).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_path_like_execution_ids() {
+        let temp = TempDir::new().unwrap();
+        assert!(read_transcript_page(temp.path(), "../escape", 1, 0, 10).is_err());
+        assert!(get_transcript_dir(temp.path(), "a/b", 1).is_err());
+    }
+}
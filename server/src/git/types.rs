@@ -5,7 +5,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Represents a git branch
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct BranchInfo {
     pub name: String,
     pub is_head: bool,
@@ -14,7 +14,7 @@ pub struct BranchInfo {
 }
 
 /// Represents a git commit
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct CommitInfo {
     pub id: String,
     pub short_id: String,
@@ -35,14 +35,14 @@ pub struct WorktreeInfo {
 }
 
 /// Represents a file status in git
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct FileStatus {
     pub path: String,
     pub status: String,
 }
 
 /// Represents a diff between commits/branches
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct DiffInfo {
     pub files_changed: usize,
     pub insertions: usize,
@@ -51,7 +51,7 @@ pub struct DiffInfo {
 }
 
 /// Represents a file diff
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct FileDiff {
     pub old_path: Option<String>,
     pub new_path: Option<String>,
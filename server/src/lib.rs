@@ -23,9 +23,11 @@
 
 // Module declarations
 pub mod agents;
+pub mod cli;
 pub mod commands;
 mod config;
 pub mod events;
+pub mod federation;
 pub mod file_storage;
 mod git;
 mod github;
@@ -35,11 +37,14 @@ pub mod plugins;
 pub mod prd_workflow;
 pub mod push;
 pub mod ralph_loop;
+pub mod search;
 mod session;
 pub mod shutdown;
 mod templates;
+pub mod triggers;
 mod utils;
 pub mod watchers;
+pub mod webhooks;
 
 // Server module (HTTP/WebSocket API)
 pub mod server;
@@ -151,24 +156,40 @@ impl Default for PluginRegistryState {
     }
 }
 
+/// What startup recovery leaves for the running server to pick up
+#[derive(Debug, Default)]
+pub struct StartupRecovery {
+    /// Ralph loops checkpointed at the last shutdown, which can be resumed
+    pub resumable_loops: Vec<commands::ralph_loop::ResumableExecution>,
+    /// Agents a crashed server left running, to be monitored until they exit
+    pub adopted_agents: Vec<agents::orphans::OrphanedAgent>,
+}
+
 /// Perform automatic recovery of stale sessions on startup
 /// This checks all registered project paths for sessions that were left in Active state
 /// but have stale lock files (indicating a crash), and transitions them to Paused.
+/// Agents left running by a crashed server are terminated or adopted per `orphan_policy`.
 ///
 /// Note: This uses file-based storage via the project registry.
-pub fn perform_auto_recovery() {
+pub fn perform_auto_recovery(orphan_policy: agents::orphans::OrphanPolicy) -> StartupRecovery {
+    match file_storage::recordings::close_interrupted_recordings() {
+        Ok(0) => {}
+        Ok(closed) => log::info!("Closed {} recording(s) interrupted by shutdown", closed),
+        Err(e) => log::warn!("Failed to close interrupted recordings: {}", e),
+    }
+
     // Get all registered project paths from file-based project storage
     let project_paths: Vec<String> = match file_storage::projects::get_all_projects() {
         Ok(projects) => projects.into_iter().map(|p| p.path).collect(),
         Err(e) => {
             log::warn!("Failed to get project paths for auto-recovery: {}", e);
-            return;
+            return StartupRecovery::default();
         }
     };
 
     if project_paths.is_empty() {
         log::debug!("No projects found, skipping auto-recovery");
-        return;
+        return StartupRecovery::default();
     }
 
     log::info!(
@@ -177,6 +198,7 @@ pub fn perform_auto_recovery() {
     );
 
     let mut total_recovered = 0;
+    let mut recovery = StartupRecovery::default();
 
     for project_path in project_paths {
         let path = as_path(&project_path);
@@ -204,8 +226,26 @@ pub fn perform_auto_recovery() {
             }
         }
 
+        // Terminate or adopt agents a crashed server left running
+        recovery
+            .adopted_agents
+            .extend(agents::orphans::recover_orphaned_agents(
+                path,
+                orphan_policy,
+            ));
+
         // Recover stale Ralph loop executions using file-based storage
         recover_stale_ralph_executions(&project_path);
+
+        // Collect loops checkpointed at the last shutdown
+        match commands::ralph_loop::resumable_executions(&project_path) {
+            Ok(executions) => recovery.resumable_loops.extend(executions),
+            Err(e) => log::warn!(
+                "Failed to check for checkpointed loops in '{}': {}",
+                project_path,
+                e
+            ),
+        }
     }
 
     if total_recovered > 0 {
@@ -216,6 +256,20 @@ pub fn perform_auto_recovery() {
     } else {
         log::debug!("Auto-recovery complete: no stale sessions found");
     }
+    if !recovery.resumable_loops.is_empty() {
+        log::info!(
+            "Found {} Ralph loop(s) checkpointed at shutdown",
+            recovery.resumable_loops.len()
+        );
+    }
+    if !recovery.adopted_agents.is_empty() {
+        log::info!(
+            "Adopted {} agent(s) left running by a previous server",
+            recovery.adopted_agents.len()
+        );
+    }
+
+    recovery
 }
 
 /// Recover Ralph loop executions that were left running after a crash
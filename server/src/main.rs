@@ -1,6 +1,8 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
+use ralph_ui_lib::agents::orphans::OrphanPolicy;
 use ralph_ui_lib::agents::AgentManager;
 use ralph_ui_lib::server::{self, generate_auth_token, ServerAppState};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
@@ -9,6 +11,14 @@ use tokio::sync::mpsc;
 #[command(name = "ralph-ui")]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// Run a command instead of starting the server
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Print JSON instead of text (progress of `run` is printed as JSON lines)
+    #[arg(long, global = true)]
+    json: bool,
+
     /// Port to bind the server to
     #[arg(long, default_value = "3420")]
     port: u16,
@@ -30,14 +40,395 @@ struct Cli {
         value_delimiter = ','
     )]
     cors_origins: Option<Vec<String>>,
+
+    /// PEM certificate (chain) to serve HTTPS with (requires --tls-key)
+    #[arg(long, env = "RALPH_TLS_CERT", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// PEM private key for --tls-cert
+    #[arg(long, env = "RALPH_TLS_KEY", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Serve HTTPS with a self-signed certificate for this machine's hostnames and IPs
+    /// (generated once and kept in ~/.ralph-ui/tls/)
+    #[arg(long, conflicts_with = "tls_cert")]
+    tls_self_signed: bool,
+
+    /// Don't resume loops checkpointed at the last shutdown on startup
+    /// (they can still be resumed with the resume_ralph_loop command)
+    #[arg(long, env = "RALPH_NO_AUTO_RESUME")]
+    no_auto_resume: bool,
+
+    /// What to do with loop agents a crashed server left running: adopt (let them
+    /// finish, then sync the PRD) or terminate
+    #[arg(long, env = "RALPH_ORPHAN_AGENTS", default_value_t = OrphanPolicy::Adopt)]
+    orphan_agents: OrphanPolicy,
+}
+
+/// Headless commands (no server or browser needed)
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a Ralph loop for a PRD, streaming progress to stdout.
+    /// Exits non-zero unless every story passes.
+    Run(RunArgs),
+    /// Show PRD progress and running loops
+    Status(ProjectArgs),
+    /// List the project's PRDs
+    ListPrds(ProjectArgs),
+    /// List the project's loop executions
+    ListExecutions(ProjectArgs),
+    /// Show an execution's iterations and agent output
+    Logs {
+        /// Execution ID (or a unique prefix of it)
+        execution: String,
+        /// Only show this iteration
+        #[arg(long)]
+        iteration: Option<u32>,
+        #[command(flatten)]
+        project: ProjectArgs,
+    },
+    /// Import a Markdown, YAML or JSON PRD document
+    ImportPrd {
+        /// PRD document to import
+        file: PathBuf,
+        /// PRD name (default: derived from the file name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Replace an existing PRD with the same name
+        #[arg(long)]
+        force: bool,
+        #[command(flatten)]
+        project: ProjectArgs,
+    },
+    /// Upgrade stored documents written by older versions (originals are backed up).
+    /// Exits non-zero if any document can't be migrated.
+    Migrate {
+        /// Only report what would be migrated
+        #[arg(long)]
+        dry_run: bool,
+        /// Only migrate this project (default: global documents and all registered projects)
+        #[arg(long)]
+        project: Option<PathBuf>,
+    },
+    /// Export the project's Ralph state (PRDs, executions, learnings, chats, context,
+    /// workflows) as a portable .tar.gz with a checksummed manifest
+    ExportState {
+        /// Archive to write (default: <project>-ralph-state-<timestamp>.tar.gz)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Also export agent transcripts
+        #[arg(long)]
+        transcripts: bool,
+        /// Also export chat attachments
+        #[arg(long)]
+        attachments: bool,
+        #[command(flatten)]
+        project: ProjectArgs,
+    },
+    /// Import an archive written by export-state and register the project.
+    /// Files the project already has are kept unless --replace is given.
+    ImportState {
+        /// Archive to import
+        file: PathBuf,
+        /// Replace the archived sections instead of merging (originals are backed up)
+        #[arg(long)]
+        replace: bool,
+        /// Only report what would be imported
+        #[arg(long)]
+        dry_run: bool,
+        #[command(flatten)]
+        project: ProjectArgs,
+    },
+    /// Serve MCP (Model Context Protocol) over stdin/stdout for IDE agents
+    Mcp {
+        /// Project tool calls default to (otherwise they must pass projectPath)
+        #[arg(long)]
+        project: Option<PathBuf>,
+    },
+}
+
+#[derive(Args, Debug)]
+struct ProjectArgs {
+    /// Project directory
+    #[arg(long, default_value = ".")]
+    project: PathBuf,
+}
+
+impl ProjectArgs {
+    fn path(&self) -> PathBuf {
+        std::fs::canonicalize(&self.project).unwrap_or_else(|e| {
+            exit_with_error(format!("Invalid project {}: {}", self.project.display(), e))
+        })
+    }
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    #[command(flatten)]
+    project: ProjectArgs,
+
+    /// PRD to run (name of `.ralph-ui/prds/<name>.json`)
+    #[arg(long)]
+    prd: String,
+
+    /// Agent to use: claude, opencode, cursor or codex
+    /// (default: the PRD's stored agent, then the configured one)
+    #[arg(long)]
+    agent: Option<String>,
+
+    /// Model to use
+    #[arg(long)]
+    model: Option<String>,
+
+    /// Maximum iterations
+    #[arg(long)]
+    max_iterations: Option<u32>,
+
+    /// Budget: stop once this many dollars have been spent
+    #[arg(long)]
+    max_cost: Option<f64>,
+
+    /// Agent timeout in seconds (0 = no timeout)
+    #[arg(long)]
+    agent_timeout: Option<u64>,
+
+    /// Branch to work on
+    #[arg(long)]
+    branch: Option<String>,
+
+    /// Prompt template
+    #[arg(long)]
+    template: Option<String>,
+
+    /// Test command (e.g. "cargo test")
+    #[arg(long)]
+    test_command: Option<String>,
+
+    /// Lint command (e.g. "cargo clippy")
+    #[arg(long)]
+    lint_command: Option<String>,
+
+    /// Skip running tests
+    #[arg(long)]
+    no_tests: bool,
+
+    /// Skip running lint
+    #[arg(long)]
+    no_lint: bool,
+
+    /// Work in the project directory instead of a worktree
+    #[arg(long)]
+    no_worktree: bool,
+
+    /// Run independent stories in parallel (Beta)
+    #[arg(long)]
+    parallel: bool,
+
+    /// Maximum parallel agents with --parallel
+    #[arg(long, requires = "parallel")]
+    max_parallel: Option<u32>,
+
+    /// Start even if preflight checks fail
+    #[arg(long)]
+    force: bool,
+
+    /// Take the PRD over from a loop that is still running it elsewhere
+    #[arg(long)]
+    takeover: bool,
 }
 
 fn main() {
     let cli = Cli::parse();
-    run_server(cli.port, &cli.bind, cli.token, cli.cors_origins);
+    let json = cli.json;
+    match cli.command {
+        Some(command) => run_command(command, json),
+        None => {
+            let tls = match (cli.tls_cert, cli.tls_key) {
+                (Some(cert_path), Some(key_path)) => Some(server::tls::TlsConfig::Provided {
+                    cert_path,
+                    key_path,
+                }),
+                _ if cli.tls_self_signed => Some(server::tls::TlsConfig::SelfSigned),
+                _ => None,
+            };
+            run_server(
+                cli.port,
+                &cli.bind,
+                cli.token,
+                cli.cors_origins,
+                tls,
+                !cli.no_auto_resume,
+                cli.orphan_agents,
+            );
+        }
+    }
+}
+
+fn exit_with_error(message: String) -> ! {
+    eprintln!("Error: {}", message);
+    std::process::exit(1);
+}
+
+fn run_command(command: Command, json: bool) {
+    use ralph_ui_lib::cli;
+
+    env_logger::init();
+
+    let result = match command {
+        Command::Run(args) => return run_headless(args, json),
+        Command::Mcp { project } => {
+            let project = project.map(|project| ProjectArgs { project }.path());
+            return run_mcp(project);
+        }
+        Command::Status(project) => {
+            cli::status(&project.path()).map(|r| cli::print_report(&r, json))
+        }
+        Command::ListPrds(project) => {
+            cli::list_prds(&project.path()).map(|r| cli::print_report(&r, json))
+        }
+        Command::ListExecutions(project) => {
+            cli::list_executions(&project.path()).map(|r| cli::print_report(&r, json))
+        }
+        Command::Logs {
+            execution,
+            iteration,
+            project,
+        } => cli::execution_logs(&project.path(), &execution, iteration)
+            .map(|r| cli::print_report(&r, json)),
+        Command::ImportPrd {
+            file,
+            name,
+            force,
+            project,
+        } => cli::import_prd(&project.path(), &file, name, force)
+            .map(|r| cli::print_report(&r, json)),
+        Command::Migrate { dry_run, project } => {
+            let project = project.map(|project| ProjectArgs { project }.path());
+            let summary = cli::migrate(project.as_deref(), dry_run);
+            cli::print_report(&summary, json);
+            summary.check()
+        }
+        Command::ExportState {
+            output,
+            transcripts,
+            attachments,
+            project,
+        } => {
+            let options = ralph_ui_lib::file_storage::project_archive::ExportOptions {
+                include_transcripts: transcripts,
+                include_attachments: attachments,
+                ..Default::default()
+            };
+            cli::export_state(&project.path(), output.as_deref(), &options)
+                .map(|r| cli::print_report(&r, json))
+        }
+        Command::ImportState {
+            file,
+            replace,
+            dry_run,
+            project,
+        } => {
+            use ralph_ui_lib::file_storage::project_archive::ImportMode;
+            let mode = if replace {
+                ImportMode::Replace
+            } else {
+                ImportMode::Merge
+            };
+            cli::import_state(&project.path(), &file, mode, dry_run)
+                .map(|r| cli::print_report(&r, json))
+        }
+    };
+    if let Err(e) = result {
+        exit_with_error(e);
+    }
+}
+
+/// `ralph-ui run`: drive a loop with the same state the server uses
+fn run_headless(args: RunArgs, json: bool) {
+    use ralph_ui_lib::commands::ralph_loop::RalphExecutionMode;
+    use ralph_ui_lib::ralph_loop::PrdExecutor;
+
+    let project_path = args.project.path();
+    let prd = PrdExecutor::new(&project_path, &args.prd)
+        .read_prd()
+        .unwrap_or_else(|e| exit_with_error(e));
+
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+    let code = rt.block_on(async {
+        let shutdown_state = ralph_ui_lib::shutdown::ShutdownState::new();
+        if let Err(e) = ralph_ui_lib::shutdown::register_signal_handlers(shutdown_state.clone()) {
+            log::warn!("Failed to register signal handlers: {}", e);
+        }
+        let state = build_state(generate_auth_token(), shutdown_state);
+
+        let agent_type = args
+            .agent
+            .or_else(|| prd.execution_config.and_then(|c| c.agent_type))
+            .or_else(|| {
+                state
+                    .config_state
+                    .get_config()
+                    .ok()
+                    .map(|c| c.execution.agent_type)
+            })
+            .unwrap_or_else(|| "claude".to_string());
+
+        let request = ralph_ui_lib::commands::ralph_loop::StartRalphLoopRequest {
+            project_path: project_path.to_string_lossy().to_string(),
+            agent_type,
+            model: args.model,
+            max_iterations: args.max_iterations,
+            run_tests: args.no_tests.then_some(false),
+            run_lint: args.no_lint.then_some(false),
+            branch: args.branch,
+            completion_promise: None,
+            max_cost: args.max_cost,
+            use_worktree: args.no_worktree.then_some(false),
+            agent_timeout_secs: args.agent_timeout,
+            prd_name: args.prd,
+            template_name: args.template,
+            test_command: args.test_command,
+            lint_command: args.lint_command,
+            execution_mode: args.parallel.then_some(RalphExecutionMode::Parallel),
+            max_parallel: args.max_parallel,
+            force: Some(args.force),
+            takeover: Some(args.takeover),
+        };
+
+        match ralph_ui_lib::cli::run_loop(&state, request, json).await {
+            Ok(outcome) => outcome.exit_code(),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                1
+            }
+        }
+    });
+    std::process::exit(code);
 }
 
-fn run_server(port: u16, bind: &str, token: Option<String>, cors_origins: Option<Vec<String>>) {
+/// `ralph-ui mcp`: stdout carries protocol messages, so logs go to stderr
+fn run_mcp(project: Option<PathBuf>) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+    let result = rt.block_on(async {
+        let shutdown_state = ralph_ui_lib::shutdown::ShutdownState::new();
+        let state = build_state(generate_auth_token(), shutdown_state);
+        let project = project.map(|p| p.to_string_lossy().to_string());
+        server::mcp::run_stdio(state, project).await
+    });
+    if let Err(e) = result {
+        exit_with_error(e);
+    }
+}
+
+fn run_server(
+    port: u16,
+    bind: &str,
+    token: Option<String>,
+    cors_origins: Option<Vec<String>>,
+    tls: Option<server::tls::TlsConfig>,
+    auto_resume: bool,
+    orphan_policy: OrphanPolicy,
+) {
     // Initialize logger
     env_logger::init();
 
@@ -51,97 +442,141 @@ fn run_server(port: u16, bind: &str, token: Option<String>, cors_origins: Option
             log::warn!("Failed to register signal handlers: {}", e);
         }
 
-        // Perform auto-recovery on startup
-        ralph_ui_lib::perform_auto_recovery();
-
-        // Initialize git state
-        let git_state = ralph_ui_lib::commands::git::GitState::new();
-
-        // Initialize config state
-        let config_state = ralph_ui_lib::commands::config::ConfigState::new();
-
-        // Initialize model cache state
-        let model_cache_state = ralph_ui_lib::commands::models::ModelCacheState::new();
-
-        // Create event channels (for potential future forwarding to WebSocket)
-        let (rate_limit_tx, _rate_limit_rx) =
-            mpsc::unbounded_channel::<ralph_ui_lib::agents::RateLimitEvent>();
-        let (completion_tx, _completion_rx) =
-            mpsc::unbounded_channel::<ralph_ui_lib::agents::AgentCompletionEvent>();
-
-        // Create PTY and subagent channels
-        let (pty_data_tx, pty_data_rx) =
-            mpsc::unbounded_channel::<ralph_ui_lib::agents::AgentPtyDataEvent>();
-        let (pty_exit_tx, pty_exit_rx) =
-            mpsc::unbounded_channel::<ralph_ui_lib::agents::AgentPtyExitEvent>();
-        let (subagent_tx, subagent_rx) =
-            mpsc::unbounded_channel::<ralph_ui_lib::agents::SubagentEvent>();
-        let (tool_call_tx, tool_call_rx) =
-            mpsc::unbounded_channel::<ralph_ui_lib::agents::ToolCallStartEvent>();
-        let (tool_call_complete_tx, tool_call_complete_rx) =
-            mpsc::unbounded_channel::<ralph_ui_lib::agents::ToolCallCompleteEvent>();
-
-        // Initialize AgentManager
-        let mut agent_manager = AgentManager::new();
-        agent_manager.set_pty_data_sender(pty_data_tx);
-        agent_manager.set_pty_exit_sender(pty_exit_tx);
-        agent_manager.set_subagent_sender(subagent_tx);
-        agent_manager.set_tool_call_sender(tool_call_tx);
-        agent_manager.set_tool_call_complete_sender(tool_call_complete_tx);
-        let agent_manager = Arc::new(std::sync::Mutex::new(agent_manager));
-
-        // Initialize Plugin Registry
-        let plugin_registry = Arc::new(std::sync::Mutex::new(
-            ralph_ui_lib::plugins::PluginRegistry::new(),
-        ));
+        // Upgrade documents written by older versions before anything reads them
+        for report in ralph_ui_lib::file_storage::migrations::migrate_all(false) {
+            report.log();
+        }
 
-        // Initialize Ralph loop state
-        let ralph_loop_state = ralph_ui_lib::commands::ralph_loop::RalphLoopManagerState::new();
+        // Perform auto-recovery on startup
+        let recovery = ralph_ui_lib::perform_auto_recovery(orphan_policy);
+        let resumable = recovery.resumable_loops;
 
         // Use provided token or generate a random one
         let auth_token = token.unwrap_or_else(generate_auth_token);
+        let state = build_state(auth_token, shutdown_state);
+
+        // Follow agents that outlived a crashed server until they finish
+        if !recovery.adopted_agents.is_empty() {
+            tokio::spawn(ralph_ui_lib::agents::orphans::monitor_adopted_agents(
+                recovery.adopted_agents,
+                state.shutdown_state.clone(),
+            ));
+        }
 
-        // Create server state
-        let state = ServerAppState::new(
-            auth_token,
-            git_state,
-            config_state,
-            shutdown_state,
-            model_cache_state,
-            agent_manager,
-            plugin_registry,
-            ralph_loop_state,
-            rate_limit_tx,
-            completion_tx,
-        );
-
-        // Spawn event forwarders to broadcast agent events to WebSocket clients
-        let broadcaster = state.broadcaster.clone();
-        tokio::spawn(forward_pty_data_events(broadcaster, pty_data_rx));
-
-        let broadcaster = state.broadcaster.clone();
-        tokio::spawn(forward_pty_exit_events(broadcaster, pty_exit_rx));
-
-        let broadcaster = state.broadcaster.clone();
-        tokio::spawn(forward_subagent_events(broadcaster, subagent_rx));
-
-        let broadcaster = state.broadcaster.clone();
-        tokio::spawn(forward_tool_call_events(broadcaster, tool_call_rx));
+        // Continue loops interrupted by the last shutdown
+        if !resumable.is_empty() {
+            if auto_resume {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    server::resume_checkpointed_loops(&state, resumable).await;
+                });
+            } else {
+                log::info!(
+                    "{} loop(s) can be resumed with resume_ralph_loop (list_ralph_loop_checkpoints)",
+                    resumable.len()
+                );
+            }
+        }
 
-        let broadcaster = state.broadcaster.clone();
-        tokio::spawn(forward_tool_call_complete_events(
-            broadcaster,
-            tool_call_complete_rx,
+        // Keep full-text search indexes of registered projects up to date
+        tokio::spawn(ralph_ui_lib::search::run_background_indexer(
+            state.shutdown_state.clone(),
         ));
 
         // Run the server
-        if let Err(e) = server::run_server(port, bind, state, cors_origins).await {
+        if let Err(e) = server::run_server(port, bind, state, cors_origins, tls).await {
             eprintln!("Server error: {}", e);
             std::process::exit(1);
         }
     });
 }
 
+/// Create the shared app state and spawn the agent event forwarders.
+/// Must be called inside the tokio runtime.
+fn build_state(
+    auth_token: String,
+    shutdown_state: ralph_ui_lib::shutdown::ShutdownState,
+) -> ServerAppState {
+    // Initialize git state
+    let git_state = ralph_ui_lib::commands::git::GitState::new();
+
+    // Initialize config state
+    let config_state = ralph_ui_lib::commands::config::ConfigState::new();
+
+    // Initialize model cache state
+    let model_cache_state = ralph_ui_lib::commands::models::ModelCacheState::new();
+
+    // Create event channels (for potential future forwarding to WebSocket)
+    let (rate_limit_tx, _rate_limit_rx) =
+        mpsc::unbounded_channel::<ralph_ui_lib::agents::RateLimitEvent>();
+    let (completion_tx, _completion_rx) =
+        mpsc::unbounded_channel::<ralph_ui_lib::agents::AgentCompletionEvent>();
+
+    // Create PTY and subagent channels
+    let (pty_data_tx, pty_data_rx) =
+        mpsc::unbounded_channel::<ralph_ui_lib::agents::AgentPtyDataEvent>();
+    let (pty_exit_tx, pty_exit_rx) =
+        mpsc::unbounded_channel::<ralph_ui_lib::agents::AgentPtyExitEvent>();
+    let (subagent_tx, subagent_rx) =
+        mpsc::unbounded_channel::<ralph_ui_lib::agents::SubagentEvent>();
+    let (tool_call_tx, tool_call_rx) =
+        mpsc::unbounded_channel::<ralph_ui_lib::agents::ToolCallStartEvent>();
+    let (tool_call_complete_tx, tool_call_complete_rx) =
+        mpsc::unbounded_channel::<ralph_ui_lib::agents::ToolCallCompleteEvent>();
+
+    // Initialize AgentManager
+    let mut agent_manager = AgentManager::new();
+    agent_manager.set_pty_data_sender(pty_data_tx);
+    agent_manager.set_pty_exit_sender(pty_exit_tx);
+    agent_manager.set_subagent_sender(subagent_tx);
+    agent_manager.set_tool_call_sender(tool_call_tx);
+    agent_manager.set_tool_call_complete_sender(tool_call_complete_tx);
+    let agent_manager = Arc::new(std::sync::Mutex::new(agent_manager));
+
+    // Initialize Plugin Registry
+    let plugin_registry = Arc::new(std::sync::Mutex::new(
+        ralph_ui_lib::plugins::PluginRegistry::new(),
+    ));
+
+    // Initialize Ralph loop state
+    let ralph_loop_state = ralph_ui_lib::commands::ralph_loop::RalphLoopManagerState::new();
+
+    // Create server state
+    let state = ServerAppState::new(
+        auth_token,
+        git_state,
+        config_state,
+        shutdown_state,
+        model_cache_state,
+        agent_manager,
+        plugin_registry,
+        ralph_loop_state,
+        rate_limit_tx,
+        completion_tx,
+    );
+
+    // Spawn event forwarders to broadcast agent events to WebSocket clients
+    let broadcaster = state.broadcaster.clone();
+    tokio::spawn(forward_pty_data_events(broadcaster, pty_data_rx));
+
+    let broadcaster = state.broadcaster.clone();
+    tokio::spawn(forward_pty_exit_events(broadcaster, pty_exit_rx));
+
+    let broadcaster = state.broadcaster.clone();
+    tokio::spawn(forward_subagent_events(broadcaster, subagent_rx));
+
+    let broadcaster = state.broadcaster.clone();
+    tokio::spawn(forward_tool_call_events(broadcaster, tool_call_rx));
+
+    let broadcaster = state.broadcaster.clone();
+    tokio::spawn(forward_tool_call_complete_events(
+        broadcaster,
+        tool_call_complete_rx,
+    ));
+
+    state
+}
+
 /// Forward PTY data events from agents to WebSocket clients
 async fn forward_pty_data_events(
     broadcaster: std::sync::Arc<ralph_ui_lib::server::EventBroadcaster>,
@@ -72,7 +72,7 @@ pub enum SessionStatus {
     Failed,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AgentType {
     Claude,
@@ -297,6 +297,13 @@ pub enum ActivityEventType {
     SessionStarted,
     SessionCompleted,
     AgentSpawned,
+    LoopStarted,
+    LoopCompleted,
+    LoopFailed,
+    LoopCancelled,
+    StoryCompleted,
+    ChatStarted,
+    BranchMerged,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -309,6 +316,11 @@ pub struct ActivityEvent {
     pub project_name: String,
     pub session_name: String,
     pub description: String,
+    /// Federation peer the event happened on (None = this server)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
 }
 
 // Project type
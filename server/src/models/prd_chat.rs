@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 // ============================================================================
 
 /// Supported MIME types for chat attachments
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
 pub enum AttachmentMimeType {
     #[serde(rename = "image/png")]
     ImagePng,
@@ -64,7 +64,7 @@ impl std::str::FromStr for AttachmentMimeType {
 }
 
 /// An attachment (image) in a chat message
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatAttachment {
     /// Unique identifier for the attachment
@@ -100,7 +100,7 @@ pub mod attachment_limits {
 
 /// Enum for chat message roles with compile-time validation.
 /// Serializes/deserializes as lowercase strings to match TypeScript union type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum MessageRole {
     User,
@@ -146,7 +146,7 @@ impl std::str::FromStr for MessageRole {
 // ============================================================================
 
 /// A chat session for AI-assisted PRD creation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatSession {
     pub id: String,
@@ -180,11 +180,12 @@ pub struct ChatSession {
     pub external_session_id: Option<String>,
     /// Discovery phase progress tracking
     #[serde(default)]
+    #[schema(value_type = Option<Object>)]
     pub discovery_progress: Option<DiscoveryProgress>,
 }
 
 /// A message in a chat session
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatMessage {
     pub id: String,
@@ -3,7 +3,8 @@
 //! Manages the `.ralph-ui/workflows/{workflow-id}/` directory structure.
 
 use super::state::PrdWorkflowState;
-use crate::file_storage::{atomic_write, ensure_dir, get_ralph_ui_dir, read_json, FileResult};
+use crate::file_storage::migrations::{read_versioned, write_versioned, WORKFLOW_STATE};
+use crate::file_storage::{atomic_write, ensure_dir, get_ralph_ui_dir, FileResult};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -191,10 +192,7 @@ pub fn save_workflow_state(
         ensure_dir(parent)?;
     }
 
-    let content = serde_json::to_string_pretty(state)
-        .map_err(|e| format!("Failed to serialize workflow state: {}", e))?;
-
-    atomic_write(&file_path, &content)?;
+    write_versioned(&file_path, state, &WORKFLOW_STATE)?;
 
     log::debug!("Saved workflow state: {:?}", file_path);
     Ok(file_path)
@@ -203,7 +201,7 @@ pub fn save_workflow_state(
 /// Load workflow state from file
 pub fn load_workflow_state(project_path: &Path, workflow_id: &str) -> FileResult<PrdWorkflowState> {
     let file_path = get_workflow_file_path(project_path, workflow_id, WorkflowFile::State);
-    read_json(&file_path)
+    read_versioned(&file_path, &WORKFLOW_STATE)
 }
 
 /// Delete a workflow directory
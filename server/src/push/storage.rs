@@ -4,9 +4,8 @@
 //! This allows notifications to be sent regardless of which project is active.
 
 use super::types::{PushNotificationSettings, PushSubscription};
-use crate::file_storage::{
-    get_global_ralph_ui_dir, init_global_ralph_ui_dir, read_json, write_json,
-};
+use crate::file_storage::migrations::{read_versioned, write_versioned, PUSH_SUBSCRIPTIONS};
+use crate::file_storage::{get_global_ralph_ui_dir, init_global_ralph_ui_dir};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -28,14 +27,14 @@ fn load_subscriptions() -> Result<SubscriptionsFile, String> {
     if !path.exists() {
         return Ok(SubscriptionsFile::default());
     }
-    read_json(&path)
+    read_versioned(&path, &PUSH_SUBSCRIPTIONS)
 }
 
 /// Save subscriptions to storage
 fn save_subscriptions(data: &SubscriptionsFile) -> Result<(), String> {
     init_global_ralph_ui_dir()?;
     let path = get_subscriptions_path();
-    write_json(&path, data)
+    write_versioned(&path, data, &PUSH_SUBSCRIPTIONS)
 }
 
 /// Save a push subscription
@@ -0,0 +1,114 @@
+//! Activity journal entries for loop executions
+//!
+//! Both orchestrators record when a loop starts and how it ends, and which
+//! stories started passing while it ran, in the project's activity journal.
+
+use super::{RalphLoopMetrics, RalphLoopState, RalphPrd};
+use crate::file_storage::activity::record_activity;
+use crate::models::ActivityEventType;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Record the start of a loop execution
+pub(crate) fn record_loop_started(project_path: &Path, prd_name: &str, execution_id: &str) {
+    record_activity(
+        project_path,
+        ActivityEventType::LoopStarted,
+        prd_name,
+        format!("Loop started: {} ({})", prd_name, execution_id),
+    );
+}
+
+/// Record how a loop execution ended
+pub(crate) fn record_loop_ended(
+    project_path: &Path,
+    prd_name: &str,
+    state: &RalphLoopState,
+    result: &Result<RalphLoopMetrics, String>,
+) {
+    let (event_type, description) = match (result, state) {
+        (Err(e), _) => (
+            ActivityEventType::LoopFailed,
+            format!("Loop failed: {}: {}", prd_name, e),
+        ),
+        (Ok(metrics), RalphLoopState::Completed { total_iterations }) => (
+            ActivityEventType::LoopCompleted,
+            format!(
+                "Loop completed: {} ({} stories in {} iterations)",
+                prd_name, metrics.stories_completed, total_iterations
+            ),
+        ),
+        (Ok(_), RalphLoopState::Failed { reason, .. }) => (
+            ActivityEventType::LoopFailed,
+            format!("Loop failed: {}: {}", prd_name, reason),
+        ),
+        (Ok(_), RalphLoopState::Cancelled { iteration }) => (
+            ActivityEventType::LoopCancelled,
+            format!("Loop cancelled: {} at iteration {}", prd_name, iteration),
+        ),
+        (Ok(_), _) => return,
+    };
+    record_activity(project_path, event_type, prd_name, description);
+}
+
+/// Tracks passing stories between PRD reads to record newly completed ones
+#[derive(Debug, Default)]
+pub(crate) struct StoryCompletions {
+    /// Stories passing at the last read (None before the first read)
+    passing: Option<HashSet<String>>,
+}
+
+impl StoryCompletions {
+    /// Record stories that pass now but didn't at the previous read
+    pub(crate) fn observe(&mut self, project_path: &Path, prd_name: &str, prd: &RalphPrd) {
+        let passing: HashSet<String> = prd
+            .stories
+            .iter()
+            .filter(|s| s.passes)
+            .map(|s| s.id.clone())
+            .collect();
+        if let Some(previous) = &self.passing {
+            for story in prd
+                .stories
+                .iter()
+                .filter(|s| s.passes && !previous.contains(&s.id))
+            {
+                record_activity(
+                    project_path,
+                    ActivityEventType::StoryCompleted,
+                    prd_name,
+                    format!("Story completed: {} {}", story.id, story.title),
+                );
+            }
+        }
+        self.passing = Some(passing);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_storage::activity::get_activity_journal_path;
+    use crate::ralph_loop::RalphStory;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_story_completions_recorded_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut prd = RalphPrd::new("Test", "main");
+        prd.add_story(RalphStory::new("1", "First", "Done"));
+        prd.add_story(RalphStory::new("2", "Second", "Done"));
+        prd.stories[0].passes = true;
+
+        let mut completions = StoryCompletions::default();
+        // Stories passing before the loop started aren't new
+        completions.observe(temp_dir.path(), "test", &prd);
+        prd.stories[1].passes = true;
+        completions.observe(temp_dir.path(), "test", &prd);
+        completions.observe(temp_dir.path(), "test", &prd);
+
+        let journal = std::fs::read_to_string(get_activity_journal_path(temp_dir.path())).unwrap();
+        assert_eq!(journal.lines().count(), 1);
+        assert!(journal.contains("Story completed: 2 Second"));
+    }
+}
@@ -8,7 +8,8 @@
 //!
 //! File location: `.ralph-ui/briefs/{prd_name}/assignments.json`
 
-use crate::file_storage::{atomic_write, ensure_dir, read_json};
+use crate::file_storage::ensure_dir;
+use crate::file_storage::migrations::{read_versioned, write_versioned, ASSIGNMENTS};
 use crate::models::AgentType;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -289,17 +290,14 @@ impl AssignmentsManager {
         if !path.exists() {
             return Ok(AssignmentsFile::default());
         }
-        read_json(&path)
+        read_versioned(&path, &ASSIGNMENTS)
     }
 
     /// Write assignments to file (atomic)
     pub fn write(&self, assignments: &AssignmentsFile) -> Result<(), String> {
         ensure_dir(&self.briefs_dir())?;
 
-        let content = serde_json::to_string_pretty(assignments)
-            .map_err(|e| format!("Failed to serialize assignments: {}", e))?;
-
-        atomic_write(&self.assignments_path(), &content)
+        write_versioned(&self.assignments_path(), assignments, &ASSIGNMENTS)
     }
 
     /// Initialize assignments file for a new execution
@@ -2,11 +2,18 @@
 //!
 //! Manages agent fallback logic when rate limits or errors are encountered.
 //! Coordinates between multiple agents in a fallback chain.
+//!
+//! When attached to a [`RateLimitLedger`], limits discovered by other loops,
+//! chats or research agents are honoured too, and limits discovered here are
+//! shared with them.
 
+use crate::agents::rate_limit_ledger::RateLimitLedger;
+use crate::agents::rate_limiter::RateLimitInfo;
 use crate::models::AgentType;
 use crate::ralph_loop::types::FallbackChainConfig;
 use chrono::{DateTime, Duration, Utc};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// State of an agent in the fallback chain
 #[derive(Debug, Clone)]
@@ -51,6 +58,12 @@ pub struct FallbackOrchestrator {
     iterations_since_recovery_test: u32,
     /// Total iterations run through this orchestrator
     total_iterations: u32,
+    /// Shared cross-execution ledger (None = private state only)
+    ledger: Option<Arc<RateLimitLedger>>,
+    /// Model used by the loop, for model-scoped ledger entries
+    model: Option<String>,
+    /// Label recorded in the ledger as the source of limits found here
+    source: String,
 }
 
 impl FallbackOrchestrator {
@@ -69,9 +82,79 @@ impl FallbackOrchestrator {
             current_agent_index: 0,
             iterations_since_recovery_test: 0,
             total_iterations: 0,
+            ledger: None,
+            model: None,
+            source: "fallback_orchestrator".to_string(),
         }
     }
 
+    /// Attach a shared rate limit ledger
+    ///
+    /// `source` identifies this execution in ledger entries it records.
+    pub fn with_ledger(
+        mut self,
+        ledger: Arc<RateLimitLedger>,
+        model: Option<String>,
+        source: impl Into<String>,
+    ) -> Self {
+        self.ledger = Some(ledger);
+        self.model = model;
+        self.source = source.into();
+        self
+    }
+
+    /// Pull limits recorded by other executions into local agent state
+    fn sync_from_ledger(&mut self) {
+        let Some(ledger) = &self.ledger else {
+            return;
+        };
+        for agent in &self.config.fallback_chain {
+            if let Some(entry) = ledger.check(*agent, self.model.as_deref()) {
+                if let Some(state) = self.agent_states.get_mut(agent) {
+                    state.is_rate_limited = true;
+                    state.rate_limited_at.get_or_insert(entry.limited_at);
+                    if state.retry_at.map_or(true, |t| t < entry.available_at) {
+                        state.retry_at = Some(entry.available_at);
+                    }
+                }
+            }
+        }
+    }
+
+    /// If every agent in the chain is rate-limited, return when the first one reopens
+    ///
+    /// Returns None when at least one agent can be used right now.
+    pub fn all_agents_limited_until(&mut self) -> Option<DateTime<Utc>> {
+        if self.config.fallback_chain.is_empty() {
+            return None;
+        }
+        self.sync_from_ledger();
+
+        // With fallback disabled only the primary is ever used
+        let usable = if self.config.enabled {
+            self.config.fallback_chain.len()
+        } else {
+            1
+        };
+
+        let now = Utc::now();
+        let mut earliest: Option<DateTime<Utc>> = None;
+        for agent in &self.config.fallback_chain[..usable] {
+            let state = self.agent_states.get(agent)?;
+            if !state.is_rate_limited {
+                return None;
+            }
+            let retry_at = state.retry_at?;
+            if retry_at <= now {
+                return None;
+            }
+            if earliest.map_or(true, |t| retry_at < t) {
+                earliest = Some(retry_at);
+            }
+        }
+        earliest
+    }
+
     /// Get the agent to use for the current iteration
     ///
     /// Returns the best available agent considering rate limits and recovery.
@@ -88,6 +171,7 @@ impl FallbackOrchestrator {
 
         self.total_iterations += 1;
         self.iterations_since_recovery_test += 1;
+        self.sync_from_ledger();
 
         // Check if we should test primary recovery
         if self.should_test_primary_recovery() {
@@ -161,29 +245,64 @@ impl FallbackOrchestrator {
     /// Marks the agent as rate-limited and calculates backoff.
     /// Returns the next agent in the fallback chain.
     pub fn report_error(&mut self, agent: AgentType, is_rate_limit: bool) -> Option<AgentType> {
-        if let Some(state) = self.agent_states.get_mut(&agent) {
-            state.failed_iterations += 1;
+        if is_rate_limit {
+            self.report_rate_limit(agent, None)
+        } else {
+            if let Some(state) = self.agent_states.get_mut(&agent) {
+                state.failed_iterations += 1;
+            }
+            self.get_next_fallback_agent(agent)
+        }
+    }
 
-            if is_rate_limit {
+    /// Report a rate limit for an agent, honouring the provider's reset hint
+    ///
+    /// Without a hint the agent backs off exponentially. The limit is also
+    /// recorded in the shared ledger when one is attached.
+    /// Returns the next agent in the fallback chain.
+    pub fn report_rate_limit(
+        &mut self,
+        agent: AgentType,
+        info: Option<&RateLimitInfo>,
+    ) -> Option<AgentType> {
+        let now = Utc::now();
+        let consecutive = match self.agent_states.get_mut(&agent) {
+            Some(state) => {
+                state.failed_iterations += 1;
                 state.is_rate_limited = true;
-                state.rate_limited_at = Some(Utc::now());
+                state.rate_limited_at = Some(now);
                 state.consecutive_limits += 1;
+                Some(state.consecutive_limits)
+            }
+            None => None,
+        };
 
-                // Calculate exponential backoff (need to read consecutive_limits first)
-                let consecutive = state.consecutive_limits;
-                let backoff_ms = self.calculate_backoff(consecutive);
+        if let Some(consecutive) = consecutive {
+            let backoff_ms = self.calculate_backoff(consecutive);
+            let retry_at = info
+                .and_then(|i| i.reset_at)
+                .filter(|at| *at > now)
+                .unwrap_or_else(|| now + Duration::milliseconds(backoff_ms as i64));
 
-                // Get mutable reference again after calculate_backoff
-                if let Some(state) = self.agent_states.get_mut(&agent) {
-                    state.retry_at = Some(Utc::now() + Duration::milliseconds(backoff_ms as i64));
-                }
+            if let Some(state) = self.agent_states.get_mut(&agent) {
+                state.retry_at = Some(retry_at);
+            }
 
-                log::info!(
-                    "[FallbackOrchestrator] Agent {:?} rate-limited, retry in {}ms",
+            if let Some(ledger) = &self.ledger {
+                ledger.record(
                     agent,
-                    backoff_ms
+                    self.model.as_deref(),
+                    info,
+                    &self.source,
+                    self.config.base_backoff_ms,
                 );
             }
+
+            log::info!(
+                "[FallbackOrchestrator] Agent {:?} rate-limited, retry in {}ms",
+                agent,
+                (retry_at - now).num_milliseconds().max(0)
+            );
         }
 
         // Get next available agent
@@ -199,6 +318,9 @@ impl FallbackOrchestrator {
             state.consecutive_limits = 0;
             state.successful_iterations += 1;
         }
+        if let Some(ledger) = &self.ledger {
+            ledger.clear(agent, self.model.as_deref());
+        }
 
         // If this was a recovery test that succeeded, stay with primary
         if self.config.fallback_chain.first() == Some(&agent) {
@@ -474,6 +596,70 @@ mod tests {
         assert!(orchestrator.should_test_primary_recovery());
     }
 
+    #[test]
+    fn test_report_rate_limit_uses_reset_hint() {
+        use crate::agents::rate_limiter::RateLimitType;
+
+        let mut orchestrator = FallbackOrchestrator::new(default_config());
+        let reset_at = Utc::now() + Duration::hours(3);
+        let info = RateLimitInfo {
+            is_rate_limited: true,
+            limit_type: Some(RateLimitType::QuotaExceeded),
+            retry_after_ms: None,
+            reset_at: Some(reset_at),
+            matched_pattern: None,
+            detected_at: Utc::now(),
+        };
+
+        orchestrator.report_rate_limit(AgentType::Claude, Some(&info));
+
+        let remaining = orchestrator
+            .get_time_until_retry(AgentType::Claude)
+            .unwrap();
+        assert!(
+            remaining > 60 * 60 * 1000 * 2,
+            "should wait for the hinted reset"
+        );
+    }
+
+    #[test]
+    fn test_shared_ledger_is_honoured_across_orchestrators() {
+        let ledger = Arc::new(RateLimitLedger::in_memory());
+        let mut first =
+            FallbackOrchestrator::new(default_config()).with_ledger(ledger.clone(), None, "loop-a");
+        let mut second =
+            FallbackOrchestrator::new(default_config()).with_ledger(ledger.clone(), None, "loop-b");
+
+        // Loop A discovers Claude is limited; loop B should skip it without trying
+        first.report_error(AgentType::Claude, true);
+        assert_eq!(second.get_agent_for_iteration(), AgentType::Opencode);
+        assert_eq!(
+            ledger.check(AgentType::Claude, None).unwrap().source,
+            "loop-a"
+        );
+
+        // A success anywhere clears the shared entry
+        second.report_success(AgentType::Claude);
+        assert!(ledger.is_available(AgentType::Claude, None));
+    }
+
+    #[test]
+    fn test_all_agents_limited_until() {
+        let ledger = Arc::new(RateLimitLedger::in_memory());
+        let mut orchestrator =
+            FallbackOrchestrator::new(default_config()).with_ledger(ledger.clone(), None, "t");
+        assert_eq!(orchestrator.all_agents_limited_until(), None);
+
+        orchestrator.report_error(AgentType::Claude, true);
+        orchestrator.report_error(AgentType::Opencode, true);
+        assert_eq!(orchestrator.all_agents_limited_until(), None);
+
+        // The last agent is limited by someone else
+        ledger.record(AgentType::Cursor, None, None, "other", 60_000);
+        let until = orchestrator.all_agents_limited_until().unwrap();
+        assert!(until > Utc::now());
+    }
+
     #[test]
     fn test_get_stats() {
         let mut orchestrator = FallbackOrchestrator::new(default_config());
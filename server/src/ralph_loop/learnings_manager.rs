@@ -9,12 +9,13 @@
 //!
 //! File location: `.ralph-ui/briefs/{prd_name}/learnings.json`
 
-use crate::file_storage::{atomic_write, ensure_dir, read_json};
+use crate::file_storage::ensure_dir;
+use crate::file_storage::migrations::{read_versioned, write_versioned, LEARNINGS};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 /// Type/category of a learning
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum LearningType {
     /// Architectural discovery about the codebase
@@ -67,7 +68,7 @@ impl std::str::FromStr for LearningType {
 }
 
 /// A single learning entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LearningEntry {
     /// Unique identifier for deduplication
@@ -149,7 +150,7 @@ impl LearningEntry {
 }
 
 /// The complete learnings file structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LearningsFile {
     /// List of all learnings
@@ -403,17 +404,14 @@ impl LearningsManager {
         if !path.exists() {
             return Ok(LearningsFile::default());
         }
-        read_json(&path)
+        read_versioned(&path, &LEARNINGS)
     }
 
     /// Write learnings to file (atomic)
     pub fn write(&self, learnings: &LearningsFile) -> Result<(), String> {
         ensure_dir(&self.briefs_dir())?;
 
-        let content = serde_json::to_string_pretty(learnings)
-            .map_err(|e| format!("Failed to serialize learnings: {}", e))?;
-
-        atomic_write(&self.learnings_path(), &content)
+        write_versioned(&self.learnings_path(), learnings, &LEARNINGS)
     }
 
     /// Initialize learnings file
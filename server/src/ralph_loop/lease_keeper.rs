@@ -0,0 +1,140 @@
+//! Holds an execution's PRD lease while its loop runs
+//!
+//! The keeper renews the lease in the background and releases it when
+//! dropped. If renewal finds the lease taken over by another instance, the
+//! loop is cancelled so two loops never write the same PRD.
+
+use crate::file_storage::leases;
+use crate::utils::lock_mutex_recover;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// How often the lease is renewed (well within `leases::LEASE_TTL_SECS`)
+const LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(20);
+
+pub struct LeaseKeeper {
+    project_path: PathBuf,
+    prd_name: String,
+    execution_id: String,
+    renewal: JoinHandle<()>,
+}
+
+impl LeaseKeeper {
+    /// Acquire the PRD's lease for an execution and keep it renewed. `cancel`
+    /// is set if the lease is lost.
+    pub fn acquire(
+        project_path: PathBuf,
+        prd_name: &str,
+        execution_id: &str,
+        takeover: bool,
+        cancel: Arc<Mutex<bool>>,
+    ) -> Result<Self, String> {
+        leases::acquire_lease(&project_path, prd_name, execution_id, takeover)?;
+
+        let renewal = tokio::spawn({
+            let project_path = project_path.clone();
+            let prd_name = prd_name.to_string();
+            let execution_id = execution_id.to_string();
+            async move {
+                let mut interval = tokio::time::interval(LEASE_RENEW_INTERVAL);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    let renewed = tokio::task::spawn_blocking({
+                        let project_path = project_path.clone();
+                        let prd_name = prd_name.clone();
+                        let execution_id = execution_id.clone();
+                        move || leases::renew_lease(&project_path, &prd_name, &execution_id)
+                    })
+                    .await
+                    .unwrap_or_else(|e| Err(format!("Lease renewal panicked: {}", e)));
+
+                    match renewed {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            let holder = leases::get_lease(&project_path, &prd_name)
+                                .ok()
+                                .flatten()
+                                .map(|l| {
+                                    format!("execution {} on {}", l.execution_id, l.holder.host)
+                                })
+                                .unwrap_or_else(|| "nobody".to_string());
+                            log::error!(
+                                "[RalphLoop] Execution {} lost its lease on '{}' (now held by {}), stopping",
+                                execution_id,
+                                prd_name,
+                                holder
+                            );
+                            *lock_mutex_recover(&cancel) = true;
+                            return;
+                        }
+                        // Transient I/O errors: try again at the next tick
+                        Err(e) => log::warn!(
+                            "[RalphLoop] Failed to renew lease of {}: {}",
+                            execution_id,
+                            e
+                        ),
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            project_path,
+            prd_name: prd_name.to_string(),
+            execution_id: execution_id.to_string(),
+            renewal,
+        })
+    }
+}
+
+impl Drop for LeaseKeeper {
+    fn drop(&mut self) {
+        self.renewal.abort();
+        if let Err(e) =
+            leases::release_lease(&self.project_path, &self.prd_name, &self.execution_id)
+        {
+            log::warn!(
+                "[RalphLoop] Failed to release lease of {}: {}",
+                self.execution_id,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_lease_keeper_releases_on_drop() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().to_path_buf();
+        let cancel = Arc::new(Mutex::new(false));
+
+        let keeper = LeaseKeeper::acquire(
+            project.clone(),
+            "dark-mode",
+            "exec-1",
+            false,
+            cancel.clone(),
+        )
+        .unwrap();
+        assert!(LeaseKeeper::acquire(
+            project.clone(),
+            "dark-mode",
+            "exec-2",
+            false,
+            cancel.clone()
+        )
+        .is_err());
+
+        drop(keeper);
+        assert!(leases::get_lease(&project, "dark-mode").unwrap().is_none());
+        assert!(!*lock_mutex_recover(&cancel));
+    }
+}
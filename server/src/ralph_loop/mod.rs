@@ -8,18 +8,22 @@
 //!
 //! Key insight from Theo: "The Ralph loop controls Claude Code, not Claude Code controlling the Ralph loop"
 
+mod activity;
 mod assignments_manager;
 mod brief_builder;
 mod completion;
 mod config;
 pub mod fallback_orchestrator;
 mod learnings_manager;
+pub mod lease_keeper;
 pub mod merge_coordinator;
 pub mod parallel_orchestrator;
 mod prd_executor;
+pub mod preflight;
 mod progress_tracker;
 mod prompt_builder;
 pub mod retry;
+pub mod tool_analytics;
 mod types;
 pub mod worktree_pool;
 
@@ -29,20 +33,33 @@ pub use completion::*;
 pub use config::*;
 pub use fallback_orchestrator::{FallbackOrchestrator, FallbackStats};
 pub use learnings_manager::*;
+pub use lease_keeper::LeaseKeeper;
 pub use merge_coordinator::{CompletedWork, ConflictInfo, MergeCoordinator, MergeResult};
 pub use parallel_orchestrator::{ParallelAgentState, ParallelAgentStatus, ParallelOrchestrator};
 pub use prd_executor::*;
+pub use preflight::{run_preflight, PreflightCheck, PreflightReport, PreflightStatus};
 pub use progress_tracker::*;
 pub use prompt_builder::*;
 pub use retry::{is_retryable_error, RetryConfig, RetryResult};
+pub use tool_analytics::{
+    IterationToolStats, ToolAnalyticsFilter, ToolAnalyticsReport, ToolCallStats,
+};
 pub use types::*;
 pub use worktree_pool::{WorktreeAllocation, WorktreePool};
 
 use crate::agents::manager::AgentManager;
+use crate::agents::orphans;
+use crate::agents::rate_limit_ledger::{RateLimitLedger, DEFAULT_RATE_LIMIT_BACKOFF_MS};
+use crate::agents::rate_limiter::{RateLimitDetector, RateLimitInfo};
+use crate::agents::transcript_capture::TranscriptCapture;
 use crate::agents::{AgentSpawnConfig, AgentSpawnMode};
+use crate::file_storage::agents as agent_storage;
+use crate::file_storage::recordings::{NewRecording, Recorder, RecordingSource};
+use crate::file_storage::transcripts::{self as transcript_storage, NewTranscript};
 use crate::models::AgentType;
 use crate::utils::lock_mutex_recover;
-use std::path::PathBuf;
+use activity::StoryCompletions;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 
@@ -114,6 +131,10 @@ pub struct RalphLoopConfig {
     pub execution_mode: crate::commands::ralph_loop::RalphExecutionMode,
     /// Maximum parallel agents when using parallel execution mode (default: 3)
     pub max_parallel: u32,
+    /// Transcript persistence and retention settings
+    pub transcripts: crate::config::TranscriptSettings,
+    /// Asciicast recording policy for agent PTY output
+    pub recordings: crate::config::RecordingSettings,
 }
 
 impl Default for RalphLoopConfig {
@@ -140,12 +161,14 @@ impl Default for RalphLoopConfig {
             env_vars: None,        // No extra env vars by default
             execution_mode: crate::commands::ralph_loop::RalphExecutionMode::Sequential,
             max_parallel: 3, // Default to 3 parallel agents
+            transcripts: crate::config::TranscriptSettings::default(),
+            recordings: crate::config::RecordingSettings::default(),
         }
     }
 }
 
 /// State of a running Ralph loop
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum RalphLoopState {
     /// Loop has not started
@@ -194,7 +217,7 @@ pub struct RalphLoopStatusEvent {
 }
 
 /// Metrics for a single iteration
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IterationMetrics {
     /// Iteration number (1-indexed)
@@ -222,7 +245,7 @@ pub struct IterationMetrics {
 }
 
 /// Cumulative metrics for an entire Ralph loop execution
-#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RalphLoopMetrics {
     /// Total iterations run
@@ -287,10 +310,14 @@ pub struct RalphLoopOrchestrator {
     snapshot_store: Option<SnapshotStore>,
     /// Flag to signal cancellation
     cancelled: Arc<Mutex<bool>>,
+    /// Flag to stop after the current iteration so the loop can be resumed later
+    checkpoint_requested: Arc<Mutex<bool>>,
     /// Current agent ID for terminal connection
     current_agent_id: Option<String>,
     /// Worktree path if using worktree isolation
     worktree_path: Option<PathBuf>,
+    /// Worktree of the checkpointed execution this orchestrator resumes
+    resume_worktree: Option<PathBuf>,
     /// Effective working path (worktree if enabled, otherwise project_path)
     working_path: PathBuf,
     /// Current progress message for UI
@@ -299,6 +326,10 @@ pub struct RalphLoopOrchestrator {
     fallback_orchestrator: Option<FallbackOrchestrator>,
     /// Currently active agent type (may differ from config due to fallback)
     active_agent_type: AgentType,
+    /// Shared cross-execution rate limit ledger
+    rate_limit_ledger: Option<Arc<RateLimitLedger>>,
+    /// Stories seen passing, for recording completions in the activity journal
+    story_completions: StoryCompletions,
 }
 
 impl RalphLoopOrchestrator {
@@ -344,15 +375,115 @@ impl RalphLoopOrchestrator {
             status_tx: None,
             snapshot_store: None,
             cancelled: Arc::new(Mutex::new(false)),
+            checkpoint_requested: Arc::new(Mutex::new(false)),
             current_agent_id: None,
             worktree_path: None,
+            resume_worktree: None,
             working_path,
             progress_message: None,
             fallback_orchestrator,
             active_agent_type,
+            rate_limit_ledger: None,
+            story_completions: StoryCompletions::default(),
         }
     }
 
+    /// Create an orchestrator that continues an execution stopped at shutdown
+    ///
+    /// Iteration numbering picks up from the saved assignments state.
+    pub fn with_execution_id(config: RalphLoopConfig, execution_id: String) -> Self {
+        let mut orchestrator = Self::new(config);
+        orchestrator.execution_id = execution_id;
+        orchestrator
+    }
+
+    /// Continue in the worktree a checkpointed execution worked in (instead of
+    /// the one derived from the project and branch)
+    pub fn set_resume_worktree(&mut self, worktree_path: PathBuf) {
+        self.resume_worktree = Some(worktree_path);
+    }
+
+    /// Share rate limit state with other executions through a ledger
+    ///
+    /// Limits recorded by other loops, chats or research agents are honoured
+    /// before spawning, and limits hit by this loop are recorded for them.
+    pub fn set_rate_limit_ledger(&mut self, ledger: Arc<RateLimitLedger>) {
+        let source = self.rate_limit_source();
+        self.fallback_orchestrator = self
+            .fallback_orchestrator
+            .take()
+            .map(|fo| fo.with_ledger(ledger.clone(), self.config.model.clone(), source));
+        self.rate_limit_ledger = Some(ledger);
+    }
+
+    /// Label recorded in the ledger for limits hit by this execution
+    fn rate_limit_source(&self) -> String {
+        format!("ralph_loop:{}", self.execution_id)
+    }
+
+    /// If no agent in the fallback chain can run right now, return when one reopens
+    fn rate_limited_until(&mut self) -> Option<chrono::DateTime<chrono::Utc>> {
+        if let Some(ref mut fo) = self.fallback_orchestrator {
+            return fo.all_agents_limited_until();
+        }
+        self.rate_limit_ledger
+            .as_ref()?
+            .check(self.config.agent_type, self.config.model.as_deref())
+            .map(|entry| entry.available_at)
+    }
+
+    /// Pause until the rate limit window reopens (or the loop is cancelled or
+    /// asked to checkpoint for shutdown)
+    ///
+    /// Re-checks the chain periodically so a limit cleared elsewhere (e.g. by a
+    /// successful run in another loop) resumes this loop early.
+    async fn wait_for_rate_limit_window(
+        &mut self,
+        iteration: u32,
+        resume_at: chrono::DateTime<chrono::Utc>,
+    ) {
+        const POLL_INTERVAL_MS: i64 = 5_000;
+
+        let reason = format!(
+            "All agents rate-limited; auto-resuming at {}",
+            resume_at.to_rfc3339()
+        );
+        log::warn!("[RalphLoop] {}", reason);
+        let _ = self.progress_tracker.add_note(iteration, &reason);
+        self.state = RalphLoopState::Paused {
+            iteration,
+            reason: reason.clone(),
+        };
+        self.set_progress(reason);
+
+        loop {
+            if *lock_mutex_recover(&self.cancelled)
+                || *lock_mutex_recover(&self.checkpoint_requested)
+            {
+                return;
+            }
+            let Some(until) = self.rate_limited_until() else {
+                break;
+            };
+            let remaining_ms = (until - chrono::Utc::now()).num_milliseconds();
+            if remaining_ms <= 0 {
+                break;
+            }
+            let sleep_ms = remaining_ms.min(POLL_INTERVAL_MS) as u64;
+            tokio::time::sleep(std::time::Duration::from_millis(sleep_ms)).await;
+        }
+
+        log::info!(
+            "[RalphLoop] Rate limit window reopened, resuming iteration {}",
+            iteration
+        );
+        self.state = RalphLoopState::Running { iteration };
+        self.set_progress(format!(
+            "Resuming after rate limit (iteration {})",
+            iteration
+        ));
+    }
+
     /// Get the currently active agent type (may differ from config due to fallback)
     pub fn active_agent_type(&self) -> AgentType {
         self.active_agent_type
@@ -381,20 +512,31 @@ impl RalphLoopOrchestrator {
     fn report_iteration_success(&mut self, agent: AgentType) {
         if let Some(ref mut fo) = self.fallback_orchestrator {
             fo.report_success(agent);
+        } else if let Some(ledger) = &self.rate_limit_ledger {
+            ledger.clear(agent, self.config.model.as_deref());
         }
     }
 
-    /// Report an error to the fallback orchestrator
+    /// Report a rate limit to the fallback orchestrator and the shared ledger
     ///
     /// Returns the next agent to try if a fallback is available.
-    fn report_iteration_error(
+    fn report_iteration_rate_limit(
         &mut self,
         agent: AgentType,
-        is_rate_limit: bool,
+        info: Option<&RateLimitInfo>,
     ) -> Option<AgentType> {
         if let Some(ref mut fo) = self.fallback_orchestrator {
-            fo.report_error(agent, is_rate_limit)
+            fo.report_rate_limit(agent, info)
         } else {
+            if let Some(ledger) = &self.rate_limit_ledger {
+                ledger.record(
+                    agent,
+                    self.config.model.as_deref(),
+                    info,
+                    &self.rate_limit_source(),
+                    DEFAULT_RATE_LIMIT_BACKOFF_MS,
+                );
+            }
             None
         }
     }
@@ -424,6 +566,13 @@ impl RalphLoopOrchestrator {
         self.cancelled.clone()
     }
 
+    /// Get a handle to stop the loop after its current iteration for a checkpoint.
+    /// The loop ends in the `Cancelled` state; setting the cancel handle as well
+    /// aborts the current iteration.
+    pub fn get_checkpoint_handle(&self) -> Arc<Mutex<bool>> {
+        self.checkpoint_requested.clone()
+    }
+
     /// Get the current execution ID
     pub fn execution_id(&self) -> &str {
         &self.execution_id
@@ -556,6 +705,22 @@ impl RalphLoopOrchestrator {
     pub async fn run(
         &mut self,
         agent_manager_arc: std::sync::Arc<std::sync::Mutex<AgentManager>>,
+    ) -> Result<RalphLoopMetrics, String> {
+        let project_path = self.config.project_path.clone();
+        let prd_name = self.config.prd_name.clone();
+        activity::record_loop_started(&project_path, &prd_name, &self.execution_id);
+
+        let result = self.run_loop(agent_manager_arc).await;
+        // A loop stopped for shutdown is resumed, not cancelled
+        if !*lock_mutex_recover(&self.checkpoint_requested) {
+            activity::record_loop_ended(&project_path, &prd_name, &self.state, &result);
+        }
+        result
+    }
+
+    async fn run_loop(
+        &mut self,
+        agent_manager_arc: std::sync::Arc<std::sync::Mutex<AgentManager>>,
     ) -> Result<RalphLoopMetrics, String> {
         log::debug!(
             "[RalphLoop] run() starting for execution {}",
@@ -602,10 +767,16 @@ impl RalphLoopOrchestrator {
                 iteration
             );
 
-            // Check for cancellation
-            if *lock_mutex_recover(&self.cancelled) {
+            // Check for cancellation (or a checkpoint request at shutdown)
+            let checkpointing = *lock_mutex_recover(&self.checkpoint_requested);
+            if checkpointing || *lock_mutex_recover(&self.cancelled) {
                 log::warn!(
-                    "[RalphLoop] EXIT REASON: Cancelled at iteration {}",
+                    "[RalphLoop] EXIT REASON: {} at iteration {}",
+                    if checkpointing {
+                        "Checkpointed for shutdown"
+                    } else {
+                        "Cancelled"
+                    },
                     iteration
                 );
                 // Clean up current agent PTY before returning
@@ -695,6 +866,8 @@ impl RalphLoopOrchestrator {
                     return Err(e);
                 }
             };
+            self.story_completions
+                .observe(&self.config.project_path, &self.config.prd_name, &prd);
             let prd_status = self.prd_executor.get_status(&prd);
             log::info!(
                 "[RalphLoop] PRD status: {}/{} passing ({}%), all_pass={}, incomplete: {:?}",
@@ -748,6 +921,13 @@ impl RalphLoopOrchestrator {
             self.state = RalphLoopState::Running { iteration };
             self.emit_status();
 
+            // Auto-pause while every agent in the chain is rate-limited, then
+            // re-run the checks above (cancellation, PRD status) before spawning
+            if let Some(resume_at) = self.rate_limited_until() {
+                self.wait_for_rate_limit_window(iteration, resume_at).await;
+                continue;
+            }
+
             // Determine which agent to use (primary or fallback)
             let agent_to_use = self.get_agent_for_iteration();
             if agent_to_use != self.config.agent_type {
@@ -800,8 +980,11 @@ impl RalphLoopOrchestrator {
                 // Success - report to orchestrator
                 self.report_iteration_success(agent_to_use.clone());
             } else if iteration_result.rate_limit_detected {
-                // Rate limit detected - report error and potentially switch agents
-                if let Some(next_agent) = self.report_iteration_error(agent_to_use.clone(), true) {
+                // Rate limit detected - record it and potentially switch agents
+                if let Some(next_agent) = self.report_iteration_rate_limit(
+                    agent_to_use.clone(),
+                    iteration_result.rate_limit_info.as_ref(),
+                ) {
                     log::debug!(
                         "[RalphLoop] Switching to fallback agent {:?} due to rate limit",
                         next_agent
@@ -816,6 +999,11 @@ impl RalphLoopOrchestrator {
                 );
                 // Double-check PRD status
                 let prd = self.prd_executor.read_prd()?;
+                self.story_completions.observe(
+                    &self.config.project_path,
+                    &self.config.prd_name,
+                    &prd,
+                );
                 let prd_status = self.prd_executor.get_status(&prd);
                 log::info!(
                     "[RalphLoop] Post-completion check: {}/{} passing, all_pass={}",
@@ -892,7 +1080,7 @@ impl RalphLoopOrchestrator {
         );
 
         // Track if rate limit is detected during this iteration
-        let mut rate_limit_detected = false;
+        let mut rate_limit_info: Option<RateLimitInfo> = None;
 
         // Clean up previous agent's PTY if there was one
         // (We keep it around after iteration completes so terminal can still show output)
@@ -970,9 +1158,21 @@ impl RalphLoopOrchestrator {
             // Spawn fresh agent instance (creates PTY)
             // Lock, spawn, unlock
             log::debug!("[RalphLoop] Calling agent_manager.spawn_agent()...");
+            let persisted_config = spawn_config.clone();
             let spawn_result = {
                 let mut manager = lock_mutex_recover(&agent_manager_arc);
-                manager.spawn_agent(&agent_id, spawn_config)
+                start_agent_recording(
+                    &self.config,
+                    &manager,
+                    &agent_id,
+                    &self.execution_id,
+                    iteration,
+                );
+                let result = manager.spawn_agent(&agent_id, spawn_config);
+                if result.is_err() {
+                    manager.finish_recording(&agent_id);
+                }
+                result
             };
             log::debug!(
                 "[RalphLoop] spawn_agent returned: {:?}",
@@ -1020,6 +1220,29 @@ impl RalphLoopOrchestrator {
                 return Err(format!("Failed to spawn agent: {}", error_str));
             }
 
+            let attempt_started_at = chrono::Utc::now();
+
+            // Remember the PID until the agent is waited for, so a server
+            // restarted after a crash can find the agent if it outlives us
+            let _persisted_agent = spawn_result.ok().and_then(|pid| {
+                let identity = orphans::process_identity(pid)?;
+                PersistedLoopAgent::save(
+                    &self.config.project_path,
+                    &agent_id,
+                    pid,
+                    &persisted_config,
+                    agent_storage::LoopAgentInfo {
+                        execution_id: self.execution_id.clone(),
+                        prd_name: self.config.prd_name.clone(),
+                        iteration,
+                        agent_type,
+                        program: identity.program,
+                        command_digest: identity.command_digest,
+                        started_at: attempt_started_at,
+                    },
+                )
+            });
+
             // Set current agent ID and emit status AFTER successful spawn (PTY now exists)
             log::debug!("[RalphLoop] Setting current_agent_id to {}", agent_id);
             self.current_agent_id = Some(agent_id.clone());
@@ -1108,8 +1331,9 @@ impl RalphLoopOrchestrator {
                                         ));
                                     }
                                 }
-                                // Sleep briefly before next poll
-                                std::thread::sleep(poll_interval);
+                                // Sleep briefly before next poll (without blocking the
+                                // runtime, so heartbeats and shutdown keep running)
+                                tokio::time::sleep(poll_interval).await;
                             }
                             Err(e) => {
                                 // Clean up agent PTY before returning error
@@ -1143,6 +1367,7 @@ impl RalphLoopOrchestrator {
                     retry_attempts: attempt,
                     was_retried: attempt > 1,
                     rate_limit_detected: false,
+                    rate_limit_info: None,
                 });
             }
 
@@ -1155,10 +1380,33 @@ impl RalphLoopOrchestrator {
             log::debug!("[RalphLoop] Agent finished with exit_code={}", exit_code);
 
             // Get agent output for completion detection and metrics
-            let output = {
+            let (output, capture) = {
                 let manager = lock_mutex_recover(&agent_manager_arc);
-                manager.get_pty_history(&agent_id)
+                manager.finish_recording(&agent_id);
+                (
+                    manager.get_pty_history(&agent_id),
+                    manager.take_transcript(&agent_id),
+                )
+            };
+
+            // Persist the raw transcript (a retried attempt replaces this one)
+            let transcript = NewTranscript {
+                execution_id: self.execution_id.clone(),
+                iteration,
+                attempt,
+                agent_id: agent_id.clone(),
+                agent_type,
+                model: self.config.model.clone(),
+                prd_name: Some(self.config.prd_name.clone()),
+                story_id: self.get_current_story_id(),
+                exit_code,
+                started_at: attempt_started_at,
+                prompt: Some(prompt.clone()),
             };
+            for warning in record_tool_analytics(&self.config, &transcript, capture.as_ref()) {
+                let _ = self.progress_tracker.add_warning(iteration, &warning);
+            }
+            persist_transcript(&self.config, transcript, capture);
             let output_str = String::from_utf8_lossy(&output);
 
             // Debug: Log agent output when it fails
@@ -1173,21 +1421,27 @@ impl RalphLoopOrchestrator {
                 log::debug!("{}", truncated);
             }
 
-            // Check for rate limit in output
-            let output_lower = output_str.to_lowercase();
-            let is_rate_limit_error = output_lower.contains("rate limit")
-                || output_lower.contains("429")
-                || output_lower.contains("too many requests")
-                || output_lower.contains("overloaded");
-
-            if is_rate_limit_error {
-                rate_limit_detected = true;
-                log::debug!("[RalphLoop] Rate limit detected in agent output");
+            // Check for rate limit in output (including provider reset hints)
+            if let Some(info) = RateLimitDetector::new().detect_in_stderr(&output_str) {
+                log::debug!(
+                    "[RalphLoop] Rate limit detected in agent output (retry after {:?}ms)",
+                    info.retry_after_ms
+                );
+                rate_limit_info = Some(info);
             }
 
+            // Don't burn retries on a limit that won't reset within our backoff window;
+            // the ledger will pause the loop until it does
+            let reset_beyond_retry_window = exit_code != 0
+                && rate_limit_info
+                    .as_ref()
+                    .and_then(|info| info.retry_after_ms)
+                    .map_or(false, |ms| ms > self.config.retry_config.max_delay_ms);
+
             // Check if we should retry based on exit code and output
             if exit_code != 0
                 && attempt < max_attempts
+                && !reset_beyond_retry_window
                 && retry::should_retry_agent(exit_code, &output_str)
             {
                 log::warn!(
@@ -1283,7 +1537,8 @@ impl RalphLoopOrchestrator {
                 completion_detected,
                 retry_attempts: attempt,
                 was_retried: attempt > 1,
-                rate_limit_detected,
+                rate_limit_detected: rate_limit_info.is_some(),
+                rate_limit_info,
             });
         }
     }
@@ -1443,43 +1698,11 @@ impl RalphLoopOrchestrator {
             Some(p) => p,
             None => return Ok(()), // No worktree, nothing to sync
         };
-
-        let prd_name = &self.config.prd_name;
-
-        let src_dir = worktree_path.join(".ralph-ui").join("prds");
-        let dst_dir = self.config.project_path.join(".ralph-ui").join("prds");
-
-        // Ensure destination directory exists
-        if !dst_dir.exists() {
-            std::fs::create_dir_all(&dst_dir)
-                .map_err(|e| format!("Failed to create .ralph-ui/prds directory: {}", e))?;
-        }
-
-        // Files to sync: {prd_name}.json, {prd_name}-progress.txt
-        let files_to_sync = [
-            format!("{}.json", prd_name),
-            format!("{}-progress.txt", prd_name),
-        ];
-
-        for filename in &files_to_sync {
-            let src = src_dir.join(filename);
-            let dst = dst_dir.join(filename);
-
-            if src.exists() {
-                if let Err(e) = std::fs::copy(&src, &dst) {
-                    log::warn!(
-                        "[RalphLoop] Failed to sync {} to main project: {}",
-                        filename,
-                        e
-                    );
-                } else {
-                    log::debug!("[RalphLoop] Synced {} to main project", filename);
-                }
-            }
-        }
-
-        log::info!("[RalphLoop] Synced PRD files from worktree to main project");
-        Ok(())
+        sync_prd_files(
+            worktree_path,
+            &self.config.project_path,
+            &self.config.prd_name,
+        )
     }
 
     /// Setup worktree for isolated execution
@@ -1499,6 +1722,12 @@ impl RalphLoopOrchestrator {
             self.execution_id
         );
 
+        if let Some(worktree_path) = self.resume_worktree.take() {
+            if self.resume_in_worktree(worktree_path) {
+                return Ok(());
+            }
+        }
+
         // Create git manager early - needed for branch detection and cleanup operations
         let git_manager = GitManager::new(&self.config.project_path)
             .map_err(|e| format!("Failed to open git repository: {}", e))?;
@@ -1638,6 +1867,48 @@ impl RalphLoopOrchestrator {
             log::info!("[RalphLoop] Resuming existing worktree - keeping worktree PRD data (not overwriting with main)");
         }
 
+        self.enter_worktree(worktree_path, execution_branch);
+        Ok(())
+    }
+
+    /// Continue in the worktree of a checkpointed execution, on the branch it
+    /// has checked out. Returns false if the worktree is gone or unusable.
+    fn resume_in_worktree(&mut self, worktree_path: PathBuf) -> bool {
+        use crate::git::GitManager;
+
+        if !worktree_path.join(".git").exists() {
+            log::warn!(
+                "[RalphLoop] Checkpointed worktree {:?} no longer exists",
+                worktree_path
+            );
+            return false;
+        }
+        let branch = GitManager::new(&worktree_path)
+            .and_then(|git| git.get_current_branch())
+            .map(|branch| branch.name);
+        match branch {
+            Ok(branch) => {
+                log::info!(
+                    "[RalphLoop] Resuming in checkpointed worktree {:?} on branch {}",
+                    worktree_path,
+                    branch
+                );
+                self.enter_worktree(worktree_path, branch);
+                true
+            }
+            Err(e) => {
+                log::warn!(
+                    "[RalphLoop] Can't resume in worktree {:?}: {}",
+                    worktree_path,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Work in a worktree from now on
+    fn enter_worktree(&mut self, worktree_path: PathBuf, execution_branch: String) {
         // Update working path and reinitialize components
         self.worktree_path = Some(worktree_path.clone());
         self.working_path = worktree_path.clone();
@@ -1663,8 +1934,6 @@ impl RalphLoopOrchestrator {
                 e
             );
         }
-
-        Ok(())
     }
 }
 
@@ -1677,6 +1946,203 @@ struct TokenMetrics {
     estimated_cost: f64,
 }
 
+/// Copy a PRD's files (`{prd_name}.json`, `{prd_name}-progress.txt`) from a
+/// worktree to the main project
+pub(crate) fn sync_prd_files(
+    worktree_path: &Path,
+    project_path: &Path,
+    prd_name: &str,
+) -> Result<(), String> {
+    let src_dir = worktree_path.join(".ralph-ui").join("prds");
+    let dst_dir = project_path.join(".ralph-ui").join("prds");
+
+    // Ensure destination directory exists
+    if !dst_dir.exists() {
+        std::fs::create_dir_all(&dst_dir)
+            .map_err(|e| format!("Failed to create .ralph-ui/prds directory: {}", e))?;
+    }
+
+    // Files to sync: {prd_name}.json, {prd_name}-progress.txt
+    let files_to_sync = [
+        format!("{}.json", prd_name),
+        format!("{}-progress.txt", prd_name),
+    ];
+
+    for filename in &files_to_sync {
+        let src = src_dir.join(filename);
+        let dst = dst_dir.join(filename);
+
+        if src.exists() {
+            if let Err(e) = std::fs::copy(&src, &dst) {
+                log::warn!(
+                    "[RalphLoop] Failed to sync {} to main project: {}",
+                    filename,
+                    e
+                );
+            } else {
+                log::debug!("[RalphLoop] Synced {} to main project", filename);
+            }
+        }
+    }
+
+    log::info!("[RalphLoop] Synced PRD files from worktree to main project");
+    Ok(())
+}
+
+/// Record of a running iteration agent in `.ralph-ui/agents/`, removed when
+/// the agent has been waited for. If the server crashes, the record is left
+/// behind so the next start can find the orphaned process.
+struct PersistedLoopAgent {
+    project_path: PathBuf,
+    agent_id: String,
+}
+
+impl PersistedLoopAgent {
+    fn save(
+        project_path: &Path,
+        agent_id: &str,
+        pid: u32,
+        config: &AgentSpawnConfig,
+        info: agent_storage::LoopAgentInfo,
+    ) -> Option<Self> {
+        let agent = crate::models::Agent {
+            id: agent_id.to_string(),
+            session_id: info.execution_id.clone(),
+            task_id: config.task_id.clone(),
+            status: crate::models::AgentStatus::Implementing,
+            process_id: Some(pid),
+            worktree_path: config.worktree_path.clone(),
+            branch: config.branch.clone(),
+            iteration_count: info.iteration as i32,
+            tokens: 0,
+            cost: 0.0,
+            logs: Vec::new(),
+            subagents: Vec::new(),
+        };
+        match agent_storage::save_loop_agent(project_path, &agent, info) {
+            Ok(_) => Some(Self {
+                project_path: project_path.to_path_buf(),
+                agent_id: agent_id.to_string(),
+            }),
+            Err(e) => {
+                log::warn!("[RalphLoop] Failed to record agent {}: {}", agent_id, e);
+                None
+            }
+        }
+    }
+}
+
+impl Drop for PersistedLoopAgent {
+    fn drop(&mut self) {
+        if let Err(e) = agent_storage::delete_agent_files(&self.project_path, &self.agent_id) {
+            log::warn!(
+                "[RalphLoop] Failed to remove agent record {}: {}",
+                self.agent_id,
+                e
+            );
+        }
+    }
+}
+
+/// Columns and rows recorded for agent output (agents have no real terminal size)
+const AGENT_RECORDING_SIZE: (u16, u16) = (120, 40);
+
+/// Start recording an agent's PTY output if the recording policy asks for it
+/// (best effort)
+pub(crate) fn start_agent_recording(
+    config: &RalphLoopConfig,
+    manager: &AgentManager,
+    agent_id: &str,
+    execution_id: &str,
+    iteration: u32,
+) {
+    if !config.recordings.agents {
+        return;
+    }
+    let project_name = config
+        .project_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (cols, rows) = AGENT_RECORDING_SIZE;
+    let recording = NewRecording {
+        source: RecordingSource::Agent,
+        source_id: agent_id.to_string(),
+        title: format!(
+            "{}: {} iteration {}",
+            project_name, config.prd_name, iteration
+        ),
+        project_path: Some(config.project_path.to_string_lossy().to_string()),
+        execution_id: Some(execution_id.to_string()),
+        iteration: Some(iteration),
+        cols,
+        rows,
+    };
+    match Recorder::start(recording, &config.recordings) {
+        Ok(recorder) => manager.start_recording(agent_id, recorder),
+        Err(e) => log::warn!("[RalphLoop] Failed to record agent {}: {}", agent_id, e),
+    }
+}
+
+/// Persist an agent run's transcript and apply the retention policy (best effort)
+pub(crate) fn persist_transcript(
+    config: &RalphLoopConfig,
+    transcript: NewTranscript,
+    capture: Option<TranscriptCapture>,
+) {
+    if !config.transcripts.enabled {
+        return;
+    }
+    let capture = capture.unwrap_or_default();
+
+    if let Err(e) = transcript_storage::save_transcript(&config.project_path, &transcript, &capture)
+    {
+        log::warn!(
+            "[RalphLoop] Failed to save transcript for iteration {}: {}",
+            transcript.iteration,
+            e
+        );
+        return;
+    }
+
+    if let Err(e) = transcript_storage::apply_retention(
+        &config.project_path,
+        config.transcripts.max_age_days,
+        config.transcripts.max_total_size_mb * 1024 * 1024,
+    ) {
+        log::warn!("[RalphLoop] Failed to apply transcript retention: {}", e);
+    }
+}
+
+/// Record tool-call analytics for an agent run and return any detected warnings
+/// (best effort; analytics are kept even when transcripts are disabled)
+pub(crate) fn record_tool_analytics(
+    config: &RalphLoopConfig,
+    transcript: &NewTranscript,
+    capture: Option<&TranscriptCapture>,
+) -> Vec<String> {
+    let calls = capture.map(|c| c.tool_calls.as_slice()).unwrap_or_default();
+    let record = tool_analytics::new_iteration_tool_stats(transcript, calls);
+
+    if let Err(e) =
+        crate::file_storage::iterations::record_tool_stats(&config.project_path, &record)
+    {
+        log::warn!(
+            "[RalphLoop] Failed to save tool stats for iteration {}: {}",
+            transcript.iteration,
+            e
+        );
+    }
+    for warning in &record.stats.warnings {
+        log::warn!(
+            "[RalphLoop] Iteration {}: {}",
+            transcript.iteration,
+            warning
+        );
+    }
+    record.stats.warnings
+}
+
 /// Result of a single iteration
 struct IterationResult {
     exit_code: i32,
@@ -1688,6 +2154,8 @@ struct IterationResult {
     was_retried: bool,
     /// Whether a rate limit was detected during this iteration
     rate_limit_detected: bool,
+    /// Details of the detected rate limit, including any provider reset hint
+    rate_limit_info: Option<RateLimitInfo>,
 }
 
 #[cfg(test)]
@@ -5,16 +5,22 @@
 //! merged back to the main branch.
 
 use crate::agents::manager::AgentManager;
+use crate::agents::rate_limit_ledger::{self, DEFAULT_RATE_LIMIT_BACKOFF_MS};
+use crate::agents::rate_limiter::RateLimitDetector;
 use crate::agents::{AgentSpawnConfig, AgentSpawnMode};
+use crate::file_storage::activity::record_activity;
+use crate::file_storage::transcripts::NewTranscript;
+use crate::models::ActivityEventType;
 use crate::ralph_loop::{
     BriefBuilder, CompletionDetector, LearningsManager, PrdExecutor, ProgressTracker,
     PromptBuilder, RalphLoopConfig, RalphLoopMetrics, RalphPrd, RalphStory,
 };
 use crate::utils::lock_mutex_recover;
 
+use super::activity::{self, StoryCompletions};
 use super::merge_coordinator::{CompletedWork, ConflictInfo, MergeCoordinator, MergeResult};
 use super::worktree_pool::{WorktreeAllocation, WorktreePool};
-use super::RalphLoopState;
+use super::{persist_transcript, record_tool_analytics, start_agent_recording, RalphLoopState};
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -66,6 +72,12 @@ struct AgentHandle {
     child: Option<std::process::Child>,
     /// Start time
     start_time: std::time::Instant,
+    /// Spawn number within this execution (used as the transcript iteration)
+    iteration: u32,
+    /// Prompt the agent was started with
+    prompt: String,
+    /// Wall-clock start time for the transcript
+    started_at: chrono::DateTime<chrono::Utc>,
 }
 
 /// Parallel orchestrator for running multiple agents simultaneously
@@ -108,6 +120,8 @@ pub struct ParallelOrchestrator {
     max_parallel: usize,
     /// Total iterations (agent spawns)
     iteration_count: u32,
+    /// Stories seen passing, for recording completions in the activity journal
+    story_completions: StoryCompletions,
 }
 
 impl ParallelOrchestrator {
@@ -154,6 +168,7 @@ impl ParallelOrchestrator {
             cancelled: Arc::new(Mutex::new(false)),
             max_parallel,
             iteration_count: 0,
+            story_completions: StoryCompletions::default(),
             config,
             execution_id,
         }
@@ -208,6 +223,19 @@ impl ParallelOrchestrator {
     pub async fn run(
         &mut self,
         agent_manager_arc: Arc<Mutex<AgentManager>>,
+    ) -> Result<RalphLoopMetrics, String> {
+        let project_path = self.config.project_path.clone();
+        let prd_name = self.config.prd_name.clone();
+        activity::record_loop_started(&project_path, &prd_name, &self.execution_id);
+
+        let result = self.run_loop(agent_manager_arc).await;
+        activity::record_loop_ended(&project_path, &prd_name, &self.state, &result);
+        result
+    }
+
+    async fn run_loop(
+        &mut self,
+        agent_manager_arc: Arc<Mutex<AgentManager>>,
     ) -> Result<RalphLoopMetrics, String> {
         log::info!(
             "[ParallelOrchestrator] Starting parallel execution {} with max {} agents",
@@ -272,6 +300,8 @@ impl ParallelOrchestrator {
                 }
             };
 
+            self.story_completions
+                .observe(&self.config.project_path, &self.config.prd_name, &prd);
             let prd_status = self.prd_executor.get_status(&prd);
             log::info!(
                 "[ParallelOrchestrator] PRD status: {}/{} passing, active agents: {}",
@@ -311,8 +341,28 @@ impl ParallelOrchestrator {
                 self.worktree_pool.available_slots()
             );
 
+            // Hold off on new spawns while the provider is rate-limited
+            let rate_limit = rate_limit_ledger::global()
+                .check(self.config.agent_type, self.config.model.as_deref());
+            if let Some(limit) = &rate_limit {
+                if self.active_agents.is_empty() {
+                    let wait_ms = limit.remaining_ms(chrono::Utc::now()).min(5_000);
+                    log::info!(
+                        "[ParallelOrchestrator] {} rate-limited until {}, waiting",
+                        self.config.agent_type,
+                        limit.available_at.to_rfc3339()
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(wait_ms.max(100))).await;
+                    continue;
+                }
+            }
+
             // Spawn agents for independent stories (up to available slots)
-            let available_slots = self.max_parallel.saturating_sub(self.active_agents.len());
+            let available_slots = if rate_limit.is_some() {
+                0
+            } else {
+                self.max_parallel.saturating_sub(self.active_agents.len())
+            };
             for story in runnable.iter().take(available_slots) {
                 if let Err(e) = self.spawn_agent_for_story(story, &agent_manager_arc) {
                     log::error!(
@@ -390,7 +440,7 @@ impl ParallelOrchestrator {
             worktree_path: allocation.path.to_string_lossy().to_string(),
             branch: allocation.branch_name.clone(),
             max_iterations: 0, // Let agent run until completion
-            prompt: Some(prompt),
+            prompt: Some(prompt.clone()),
             model: self.config.model.clone(),
             spawn_mode: AgentSpawnMode::Pty,
             plugin_config: None,
@@ -401,7 +451,18 @@ impl ParallelOrchestrator {
         // Spawn agent
         let spawn_result = {
             let mut manager = lock_mutex_recover(agent_manager_arc);
-            manager.spawn_agent(&agent_id, spawn_config)
+            start_agent_recording(
+                &self.config,
+                &manager,
+                &agent_id,
+                &self.execution_id,
+                self.iteration_count,
+            );
+            let result = manager.spawn_agent(&agent_id, spawn_config);
+            if result.is_err() {
+                manager.finish_recording(&agent_id);
+            }
+            result
         };
 
         if let Err(e) = spawn_result {
@@ -426,6 +487,9 @@ impl ParallelOrchestrator {
             allocation: allocation.clone(),
             child,
             start_time: std::time::Instant::now(),
+            iteration: self.iteration_count,
+            prompt,
+            started_at: chrono::Utc::now(),
         };
 
         self.active_agents.insert(story.id.clone(), handle);
@@ -525,10 +589,34 @@ impl ParallelOrchestrator {
                 let handle = self.active_agents.remove(&story_id).unwrap();
 
                 // Get agent output
-                let output = {
+                let (output, capture) = {
                     let manager = lock_mutex_recover(agent_manager_arc);
-                    manager.get_pty_history(&handle.agent_id)
+                    manager.finish_recording(&handle.agent_id);
+                    (
+                        manager.get_pty_history(&handle.agent_id),
+                        manager.take_transcript(&handle.agent_id),
+                    )
+                };
+
+                let transcript = NewTranscript {
+                    execution_id: self.execution_id.clone(),
+                    iteration: handle.iteration,
+                    attempt: 1,
+                    agent_id: handle.agent_id.clone(),
+                    agent_type: self.config.agent_type,
+                    model: self.config.model.clone(),
+                    prd_name: Some(self.config.prd_name.clone()),
+                    story_id: Some(story_id.clone()),
+                    exit_code,
+                    started_at: handle.started_at,
+                    prompt: Some(handle.prompt.clone()),
                 };
+                for warning in record_tool_analytics(&self.config, &transcript, capture.as_ref()) {
+                    let _ = self
+                        .progress_tracker
+                        .add_warning(handle.iteration, &warning);
+                }
+                persist_transcript(&self.config, transcript, capture);
 
                 // Emit exit event
                 {
@@ -574,6 +662,19 @@ impl ParallelOrchestrator {
             }
         }
 
+        // Share rate limits with other loops so they stop spawning too
+        if result.exit_code != 0 {
+            if let Some(info) = RateLimitDetector::new().detect_in_stderr(&output_str) {
+                rate_limit_ledger::global().record(
+                    self.config.agent_type,
+                    self.config.model.as_deref(),
+                    Some(&info),
+                    "parallel_loop",
+                    DEFAULT_RATE_LIMIT_BACKOFF_MS,
+                );
+            }
+        }
+
         // Check for completion promise
         let completion_detected = self.completion_detector.check(&output_str);
 
@@ -592,11 +693,22 @@ impl ParallelOrchestrator {
             };
 
             match self.merge_coordinator.merge(&completed_work) {
-                Ok(MergeResult::Success { .. }) => {
+                Ok(MergeResult::Success { commit_hash, .. }) => {
                     log::info!(
                         "[ParallelOrchestrator] Successfully merged story {}",
                         result.story_id
                     );
+                    record_activity(
+                        &self.config.project_path,
+                        ActivityEventType::BranchMerged,
+                        self.config.prd_name.as_str(),
+                        format!(
+                            "Merged {} for story {} ({})",
+                            completed_work.branch_name,
+                            result.story_id,
+                            commit_hash.get(..7).unwrap_or(&commit_hash)
+                        ),
+                    );
 
                     // Sync PRD from worktree
                     let _ = self
@@ -6,6 +6,7 @@
 //! File location: `.ralph-ui/prds/{prd_name}.json`
 
 use super::types::{PrdStatus, RalphPrd, RalphStory};
+use crate::file_storage::migrations::{parse_versioned, to_versioned_string, PRD};
 use fs2::FileExt;
 use std::fs::OpenOptions;
 use std::path::{Path, PathBuf};
@@ -106,7 +107,7 @@ impl PrdExecutor {
         let content = std::fs::read_to_string(&path)
             .map_err(|e| format!("Failed to read PRD file: {}", e))?;
 
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse PRD file: {}", e))
+        parse_versioned(&content, &PRD).map_err(|e| format!("Failed to parse PRD file: {}", e))
     }
 
     /// Write the PRD to disk using atomic write pattern (temp file + rename)
@@ -123,7 +124,7 @@ impl PrdExecutor {
         })?;
 
         let path = self.prd_path();
-        let content = serde_json::to_string_pretty(prd)
+        let content = to_versioned_string(prd, &PRD)
             .map_err(|e| format!("Failed to serialize PRD: {}", e))?;
 
         // Atomic write: write to temp file first, then rename
@@ -277,6 +278,21 @@ impl PrdExecutor {
         })
     }
 
+    /// Add a story unless one with the same ID exists; returns whether it was added
+    pub fn add_story_if_missing(&self, story: RalphStory) -> Result<bool, String> {
+        self.with_prd_lock(|prd| {
+            if prd.stories.iter().any(|s| s.id == story.id) {
+                return Ok(false);
+            }
+            prd.add_story(story);
+
+            if let Some(ref mut metadata) = prd.metadata {
+                metadata.updated_at = Some(chrono::Utc::now().to_rfc3339());
+            }
+            Ok(true)
+        })
+    }
+
     /// Remove a story from the PRD
     pub fn remove_story(&self, story_id: &str) -> Result<bool, String> {
         let story_id = story_id.to_string();
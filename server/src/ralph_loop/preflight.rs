@@ -0,0 +1,664 @@
+//! Preflight diagnostics for Ralph Loop
+//!
+//! Runs before the first iteration to catch the problems that otherwise make a
+//! loop fail immediately: a missing or outdated CLI, an unauthenticated CLI, a
+//! model the provider doesn't know, or a git working tree the loop can't use.
+//! Every agent the loop may run is checked, including the fallback chain.
+
+use crate::agents::path_resolver::CliPathResolver;
+use crate::agents::providers::get_provider;
+use crate::agents::rate_limit_ledger;
+use crate::agents::ModelCache;
+use crate::git::GitManager;
+use crate::models::AgentType;
+use crate::ralph_loop::RalphLoopConfig;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::LazyLock;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How long a version/auth probe may run before it is killed
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Matches the first dotted version number in `--version` output
+static VERSION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?").unwrap());
+
+/// Oldest CLI version known to support the flags Ralph relies on
+/// (streaming JSON output, non-interactive mode). None = no known minimum.
+fn min_cli_version(agent_type: AgentType) -> Option<(u64, u64, u64)> {
+    match agent_type {
+        AgentType::Claude => Some((1, 0, 0)),
+        AgentType::Codex => Some((0, 20, 0)),
+        AgentType::Opencode => Some((0, 1, 0)),
+        AgentType::Gemini => Some((0, 1, 0)),
+        AgentType::Cursor | AgentType::Qwen | AgentType::Droid => None,
+    }
+}
+
+/// Outcome of a single preflight check, ordered by severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreflightStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// A single preflight check result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    /// Check identifier (binary, version, auth, model, rate_limit, git)
+    pub name: String,
+    /// Agent the check applies to (None for project-level checks)
+    pub agent_type: Option<AgentType>,
+    pub status: PreflightStatus,
+    /// Human-readable outcome
+    pub message: String,
+}
+
+impl PreflightCheck {
+    fn new(
+        name: &str,
+        agent_type: Option<AgentType>,
+        status: PreflightStatus,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            agent_type,
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Full preflight report for a loop configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    /// Worst status across all checks
+    pub status: PreflightStatus,
+    pub checks: Vec<PreflightCheck>,
+    /// Agents that were checked (primary first, then fallbacks)
+    pub agents: Vec<AgentType>,
+    pub checked_at: String,
+}
+
+impl PreflightReport {
+    fn from_checks(agents: Vec<AgentType>, checks: Vec<PreflightCheck>) -> Self {
+        let status = checks
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(PreflightStatus::Pass);
+        Self {
+            status,
+            checks,
+            agents,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Whether any check failed hard
+    pub fn has_failures(&self) -> bool {
+        self.status == PreflightStatus::Fail
+    }
+
+    /// One-line summary of the failed checks, for error messages
+    pub fn failure_summary(&self) -> String {
+        let failures: Vec<String> = self
+            .checks
+            .iter()
+            .filter(|c| c.status == PreflightStatus::Fail)
+            .map(|c| match c.agent_type {
+                Some(agent) => format!("{} {}: {}", agent, c.name, c.message),
+                None => format!("{}: {}", c.name, c.message),
+            })
+            .collect();
+        format!(
+            "Preflight failed ({} problem(s)): {}. Fix these or start with force to skip.",
+            failures.len(),
+            failures.join("; ")
+        )
+    }
+}
+
+/// Run all preflight checks for a loop configuration
+pub fn run_preflight(config: &RalphLoopConfig, model_cache: &ModelCache) -> PreflightReport {
+    let agents = agents_for_config(config);
+    let mut checks = Vec::new();
+
+    for &agent in &agents {
+        // Only the primary agent is bound to the configured model
+        let model = if agent == config.agent_type {
+            config.model.as_deref()
+        } else {
+            None
+        };
+        checks.extend(check_agent(agent, model, model_cache));
+    }
+
+    checks.extend(check_git_state(
+        &config.project_path,
+        config.use_worktree,
+        config.branch.as_deref(),
+    ));
+
+    let report = PreflightReport::from_checks(agents, checks);
+    log::info!(
+        "[Preflight] {:?} for {} ({} checks)",
+        report.status,
+        config.project_path.display(),
+        report.checks.len()
+    );
+    report
+}
+
+/// Primary agent followed by the enabled fallback chain, without duplicates
+fn agents_for_config(config: &RalphLoopConfig) -> Vec<AgentType> {
+    let mut agents = vec![config.agent_type];
+    if let Some(fallback) = config.fallback_config.as_ref().filter(|f| f.enabled) {
+        for agent in &fallback.fallback_chain {
+            if !agents.contains(agent) {
+                agents.push(*agent);
+            }
+        }
+    }
+    agents
+}
+
+/// Binary, version, auth, model and rate-limit checks for one agent
+fn check_agent(
+    agent: AgentType,
+    model: Option<&str>,
+    model_cache: &ModelCache,
+) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+
+    let Some(binary) = CliPathResolver::resolve(agent) else {
+        checks.push(PreflightCheck::new(
+            "binary",
+            Some(agent),
+            PreflightStatus::Fail,
+            format!(
+                "{} CLI not found in PATH or common install locations",
+                agent
+            ),
+        ));
+        return checks;
+    };
+    checks.push(PreflightCheck::new(
+        "binary",
+        Some(agent),
+        PreflightStatus::Pass,
+        format!("Found at {}", binary.display()),
+    ));
+
+    checks.push(check_version(agent, &binary));
+    checks.push(check_auth(agent, &binary));
+
+    if let Some(model) = model {
+        checks.push(check_model(agent, model, model_cache));
+    }
+
+    if let Some(limit) = rate_limit_ledger::global().check(agent, model) {
+        checks.push(PreflightCheck::new(
+            "rate_limit",
+            Some(agent),
+            PreflightStatus::Warn,
+            format!(
+                "Rate-limited until {} (reported by {})",
+                limit.available_at.to_rfc3339(),
+                limit.source
+            ),
+        ));
+    }
+
+    checks
+}
+
+fn check_version(agent: AgentType, binary: &Path) -> PreflightCheck {
+    let output = match run_probe(binary, &["--version"]) {
+        Ok(output) => output,
+        Err(e) => {
+            return PreflightCheck::new("version", Some(agent), PreflightStatus::Warn, e);
+        }
+    };
+
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let Some(version) = parse_version(&text) else {
+        return PreflightCheck::new(
+            "version",
+            Some(agent),
+            PreflightStatus::Warn,
+            "Could not determine CLI version",
+        );
+    };
+
+    let found = format_version(version);
+    match min_cli_version(agent) {
+        Some(min) if version < min => PreflightCheck::new(
+            "version",
+            Some(agent),
+            PreflightStatus::Fail,
+            format!(
+                "Version {} is older than the minimum supported {}; please upgrade",
+                found,
+                format_version(min)
+            ),
+        ),
+        _ => PreflightCheck::new(
+            "version",
+            Some(agent),
+            PreflightStatus::Pass,
+            format!("Version {}", found),
+        ),
+    }
+}
+
+fn check_auth(agent: AgentType, binary: &Path) -> PreflightCheck {
+    let Some(args) = get_provider(&agent).auth_check_args() else {
+        return PreflightCheck::new(
+            "auth",
+            Some(agent),
+            PreflightStatus::Pass,
+            "Provider has no non-interactive auth check; skipped",
+        );
+    };
+
+    let arg_refs: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    match run_probe(binary, &arg_refs) {
+        Ok(output) if output.status.success() => {
+            PreflightCheck::new("auth", Some(agent), PreflightStatus::Pass, "Authenticated")
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = stderr.lines().next().unwrap_or("").trim();
+            PreflightCheck::new(
+                "auth",
+                Some(agent),
+                PreflightStatus::Fail,
+                if detail.is_empty() {
+                    format!("Not authenticated; run `{} login`", agent)
+                } else {
+                    format!("Not authenticated: {}", detail)
+                },
+            )
+        }
+        Err(e) => PreflightCheck::new("auth", Some(agent), PreflightStatus::Warn, e),
+    }
+}
+
+fn check_model(agent: AgentType, model: &str, model_cache: &ModelCache) -> PreflightCheck {
+    let models = model_cache.get_or_fetch(agent);
+    if models.is_empty() {
+        return PreflightCheck::new(
+            "model",
+            Some(agent),
+            PreflightStatus::Warn,
+            "No model list available to validate against",
+        );
+    }
+
+    let known = models
+        .iter()
+        .any(|m| m.id == model || m.id.rsplit('/').next() == Some(model) || m.name == model);
+    if known {
+        PreflightCheck::new(
+            "model",
+            Some(agent),
+            PreflightStatus::Pass,
+            format!("Model '{}' is available", model),
+        )
+    } else {
+        // Model lists may lag behind the provider, so this is only a warning
+        let examples: Vec<&str> = models.iter().take(5).map(|m| m.id.as_str()).collect();
+        PreflightCheck::new(
+            "model",
+            Some(agent),
+            PreflightStatus::Warn,
+            format!(
+                "Model '{}' is not in the known model list (e.g. {})",
+                model,
+                examples.join(", ")
+            ),
+        )
+    }
+}
+
+/// Check that the project's git working tree can be used by the loop
+fn check_git_state(
+    project_path: &Path,
+    use_worktree: bool,
+    branch: Option<&str>,
+) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+
+    let git = match GitManager::new(project_path) {
+        Ok(git) => git,
+        Err(e) => {
+            // Worktree isolation is impossible without a repository
+            let status = if use_worktree {
+                PreflightStatus::Fail
+            } else {
+                PreflightStatus::Warn
+            };
+            checks.push(PreflightCheck::new(
+                "git",
+                None,
+                status,
+                format!("Not a git repository: {}", e.message()),
+            ));
+            return checks;
+        }
+    };
+    let repo = git.repo();
+
+    if repo
+        .head()
+        .ok()
+        .and_then(|h| h.peel_to_commit().ok())
+        .is_none()
+    {
+        checks.push(PreflightCheck::new(
+            "git",
+            None,
+            if use_worktree {
+                PreflightStatus::Fail
+            } else {
+                PreflightStatus::Warn
+            },
+            "Repository has no commits yet",
+        ));
+    }
+
+    if repo.state() != git2::RepositoryState::Clean {
+        checks.push(PreflightCheck::new(
+            "git",
+            None,
+            PreflightStatus::Fail,
+            format!(
+                "A {:?} is in progress; finish or abort it first",
+                repo.state()
+            ),
+        ));
+    }
+
+    if let Some(branch) = branch {
+        if repo.find_branch(branch, git2::BranchType::Local).is_err() {
+            checks.push(PreflightCheck::new(
+                "git",
+                None,
+                PreflightStatus::Fail,
+                format!("Branch '{}' does not exist", branch),
+            ));
+        }
+    }
+
+    match git.get_status() {
+        Ok(changes) if !changes.is_empty() => checks.push(PreflightCheck::new(
+            "git",
+            None,
+            PreflightStatus::Warn,
+            if use_worktree {
+                format!(
+                    "{} uncommitted change(s) will not be visible in the loop's worktree",
+                    changes.len()
+                )
+            } else {
+                format!(
+                    "{} uncommitted change(s) will be mixed into agent commits",
+                    changes.len()
+                )
+            },
+        )),
+        Ok(_) => {}
+        Err(e) => checks.push(PreflightCheck::new(
+            "git",
+            None,
+            PreflightStatus::Warn,
+            format!("Could not read working tree status: {}", e.message()),
+        )),
+    }
+
+    if checks.is_empty() {
+        checks.push(PreflightCheck::new(
+            "git",
+            None,
+            PreflightStatus::Pass,
+            "Working tree is clean",
+        ));
+    }
+    checks
+}
+
+/// Run a short-lived CLI probe, killing it if it exceeds PROBE_TIMEOUT
+fn run_probe(binary: &Path, args: &[&str]) -> Result<Output, String> {
+    let mut child = Command::new(binary)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", binary.display(), e))?;
+
+    // Drain the pipes while waiting, or a probe printing more than the pipe
+    // buffer blocks on write and only ever ends by timing out
+    let stdout = child.stdout.take().map(read_pipe);
+    let stderr = child.stderr.take().map(read_pipe);
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= PROBE_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "`{} {}` timed out after {}s",
+                    binary.display(),
+                    args.join(" "),
+                    PROBE_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(format!("Failed to wait for probe: {}", e)),
+        }
+    };
+
+    let collect = |reader: Option<JoinHandle<std::io::Result<Vec<u8>>>>| match reader {
+        Some(reader) => reader
+            .join()
+            .map_err(|_| "Probe output reader panicked".to_string())?
+            .map_err(|e| format!("Failed to read probe output: {}", e)),
+        None => Ok(Vec::new()),
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout)?,
+        stderr: collect(stderr)?,
+    })
+}
+
+/// Read a child's pipe to the end on its own thread
+fn read_pipe<R: Read + Send + 'static>(mut pipe: R) -> JoinHandle<std::io::Result<Vec<u8>>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        pipe.read_to_end(&mut buf).map(|_| buf)
+    })
+}
+
+fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    let caps = VERSION_RE.captures(text)?;
+    let part = |i: usize| {
+        caps.get(i)
+            .and_then(|m| m.as_str().parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    Some((part(1), part(2), part(3)))
+}
+
+fn format_version((major, minor, patch): (u64, u64, u64)) -> String {
+    format!("{}.{}.{}", major, minor, patch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ralph_loop::FallbackChainConfig;
+    use std::process::Command as StdCommand;
+    use tempfile::TempDir;
+
+    fn init_repo(dir: &Path) {
+        let run = |args: &[&str]| {
+            StdCommand::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+        };
+        run(&["init", "-q", "-b", "main"]);
+        run(&["config", "user.email", "test@example.com"]);
+        run(&["config", "user.name", "Test"]);
+        std::fs::write(dir.join("README.md"), "hello").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "init"]);
+    }
+
+    fn status_of(checks: &[PreflightCheck]) -> PreflightStatus {
+        checks
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(PreflightStatus::Pass)
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.0.43 (Claude Code)"), Some((1, 0, 43)));
+        assert_eq!(parse_version("codex-cli 0.21"), Some((0, 21, 0)));
+        assert_eq!(parse_version("no version here"), None);
+    }
+
+    #[test]
+    fn test_agents_include_fallback_chain_without_duplicates() {
+        let config = RalphLoopConfig {
+            agent_type: AgentType::Claude,
+            fallback_config: Some(FallbackChainConfig {
+                fallback_chain: vec![AgentType::Claude, AgentType::Codex, AgentType::Opencode],
+                enabled: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            agents_for_config(&config),
+            vec![AgentType::Claude, AgentType::Codex, AgentType::Opencode]
+        );
+    }
+
+    #[test]
+    fn test_disabled_fallback_chain_is_ignored() {
+        let config = RalphLoopConfig {
+            agent_type: AgentType::Claude,
+            fallback_config: Some(FallbackChainConfig {
+                fallback_chain: vec![AgentType::Codex],
+                enabled: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(agents_for_config(&config), vec![AgentType::Claude]);
+    }
+
+    #[test]
+    fn test_git_clean_repo_passes() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        let checks = check_git_state(temp.path(), true, Some("main"));
+        assert_eq!(status_of(&checks), PreflightStatus::Pass);
+    }
+
+    #[test]
+    fn test_git_dirty_tree_warns() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        std::fs::write(temp.path().join("README.md"), "changed").unwrap();
+        let checks = check_git_state(temp.path(), false, None);
+        assert_eq!(status_of(&checks), PreflightStatus::Warn);
+    }
+
+    #[test]
+    fn test_git_missing_branch_fails() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        let checks = check_git_state(temp.path(), true, Some("does-not-exist"));
+        assert_eq!(status_of(&checks), PreflightStatus::Fail);
+    }
+
+    #[test]
+    fn test_non_repo_fails_only_with_worktree() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(
+            status_of(&check_git_state(temp.path(), true, None)),
+            PreflightStatus::Fail
+        );
+        assert_eq!(
+            status_of(&check_git_state(temp.path(), false, None)),
+            PreflightStatus::Warn
+        );
+    }
+
+    #[test]
+    fn test_report_summary_lists_failures() {
+        let report = PreflightReport::from_checks(
+            vec![AgentType::Codex],
+            vec![
+                PreflightCheck::new(
+                    "binary",
+                    Some(AgentType::Codex),
+                    PreflightStatus::Pass,
+                    "ok",
+                ),
+                PreflightCheck::new(
+                    "auth",
+                    Some(AgentType::Codex),
+                    PreflightStatus::Fail,
+                    "Not authenticated",
+                ),
+            ],
+        );
+        assert!(report.has_failures());
+        assert!(report
+            .failure_summary()
+            .contains("codex auth: Not authenticated"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_output_larger_than_pipe_buffer() {
+        let started = Instant::now();
+        let output = run_probe(
+            Path::new("sh"),
+            &[
+                "-c",
+                "head -c 300000 /dev/zero; head -c 100000 /dev/zero >&2",
+            ],
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout.len(), 300_000);
+        assert_eq!(output.stderr.len(), 100_000);
+        assert!(started.elapsed() < PROBE_TIMEOUT);
+    }
+}
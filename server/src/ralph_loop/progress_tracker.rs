@@ -184,6 +184,17 @@ impl ProgressTracker {
         self.append_entry(&entry)
     }
 
+    /// Record a warning about agent behaviour
+    pub fn add_warning(&self, iteration: u32, warning: &str) -> Result<(), String> {
+        let entry = ProgressEntry {
+            iteration,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            entry_type: ProgressEntryType::Warning,
+            content: warning.to_string(),
+        };
+        self.append_entry(&entry)
+    }
+
     /// Read all entries from progress.txt
     pub fn read_entries(&self) -> Result<Vec<ProgressEntry>, String> {
         let path = self.progress_path();
@@ -233,6 +244,7 @@ impl ProgressTracker {
             "ERROR" => ProgressEntryType::Error,
             "COMPLETED" => ProgressEntryType::StoryCompleted,
             "NOTE" => ProgressEntryType::Note,
+            "WARNING" => ProgressEntryType::Warning,
             _ => return None,
         };
 
@@ -0,0 +1,528 @@
+//! Tool-call analytics - per-iteration statistics and anti-pattern detection
+//!
+//! Aggregates the tool calls captured from an agent run into counts, durations,
+//! error rates, file and shell activity, and flags pathological patterns such
+//! as a command failing repeatedly or a file being re-read without changes.
+
+use crate::agents::transcript_capture::TranscriptToolCall;
+use crate::models::AgentType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Consecutive failures of the same shell command that raise a warning
+pub const REPEATED_FAILURE_THRESHOLD: u32 = 5;
+/// Reads of the same file without an intervening write that raise a warning
+pub const REPEATED_READ_THRESHOLD: u32 = 5;
+
+/// Shell commands kept per iteration
+const MAX_RECORDED_COMMANDS: usize = 100;
+/// Characters kept per recorded shell command
+const MAX_COMMAND_CHARS: usize = 200;
+
+/// Statistics for one tool within an iteration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolNameStats {
+    pub tool_name: String,
+    pub count: u32,
+    pub errors: u32,
+    pub total_duration_ms: u64,
+    pub p95_duration_ms: u64,
+}
+
+/// Tool-call statistics for one agent run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallStats {
+    pub total_calls: u32,
+    pub errors: u32,
+    pub error_rate: f64,
+    /// Summed duration of completed calls
+    pub total_duration_ms: u64,
+    pub p95_duration_ms: u64,
+    /// Per-tool breakdown, most used first
+    pub by_tool: Vec<ToolNameStats>,
+    /// Distinct files read
+    pub files_read: Vec<String>,
+    /// Distinct files written or edited
+    pub files_written: Vec<String>,
+    /// Reads of a file that had already been read and not changed since
+    pub redundant_reads: u32,
+    pub shell_command_count: u32,
+    pub failed_shell_commands: u32,
+    /// Shell commands in execution order (capped and truncated)
+    pub shell_commands: Vec<String>,
+    /// Detected pathological patterns
+    pub warnings: Vec<String>,
+}
+
+/// Tool-call statistics recorded for an iteration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IterationToolStats {
+    pub execution_id: String,
+    pub iteration: u32,
+    pub attempt: u32,
+    pub agent_id: String,
+    pub agent_type: AgentType,
+    pub model: Option<String>,
+    pub prd_name: Option<String>,
+    pub story_id: Option<String>,
+    pub recorded_at: String,
+    pub stats: ToolCallStats,
+}
+
+/// Filter for querying recorded tool stats (all fields optional, combined with AND)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnalyticsFilter {
+    pub execution_id: Option<String>,
+    pub prd_name: Option<String>,
+    pub story_id: Option<String>,
+    pub agent_type: Option<AgentType>,
+    pub model: Option<String>,
+}
+
+impl ToolAnalyticsFilter {
+    pub fn matches(&self, record: &IterationToolStats) -> bool {
+        self.execution_id
+            .as_ref()
+            .map_or(true, |id| &record.execution_id == id)
+            && self
+                .prd_name
+                .as_ref()
+                .map_or(true, |prd| record.prd_name.as_ref() == Some(prd))
+            && self
+                .story_id
+                .as_ref()
+                .map_or(true, |story| record.story_id.as_ref() == Some(story))
+            && self
+                .agent_type
+                .map_or(true, |agent| record.agent_type == agent)
+            && self
+                .model
+                .as_ref()
+                .map_or(true, |model| record.model.as_ref() == Some(model))
+    }
+}
+
+/// Usage of one tool across a group of iterations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolUsage {
+    pub tool_name: String,
+    pub count: u32,
+    pub errors: u32,
+    pub total_duration_ms: u64,
+}
+
+/// Tool-call statistics aggregated over iterations sharing a key (model, agent, story)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStatsGroup {
+    pub key: String,
+    pub iterations: u32,
+    pub total_calls: u32,
+    pub errors: u32,
+    pub error_rate: f64,
+    pub total_duration_ms: u64,
+    pub avg_calls_per_iteration: f64,
+    pub redundant_reads: u32,
+    pub shell_command_count: u32,
+    pub failed_shell_commands: u32,
+    pub warnings: u32,
+    pub by_tool: Vec<ToolUsage>,
+}
+
+/// Tool analytics across executions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnalyticsReport {
+    /// Matching iterations, newest first
+    pub iterations: Vec<IterationToolStats>,
+    pub by_model: Vec<ToolStatsGroup>,
+    pub by_agent: Vec<ToolStatsGroup>,
+    pub by_story: Vec<ToolStatsGroup>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolKind {
+    Read,
+    Write,
+    Shell,
+    Other,
+}
+
+fn tool_kind(tool_name: &str) -> ToolKind {
+    match tool_name.to_lowercase().as_str() {
+        "read" | "read_file" | "view" | "notebookread" => ToolKind::Read,
+        "write" | "write_file" | "edit" | "edit_file" | "multiedit" | "notebookedit"
+        | "apply_patch" => ToolKind::Write,
+        "bash" | "shell" | "exec" | "exec_command" | "run_command" => ToolKind::Shell,
+        _ => ToolKind::Other,
+    }
+}
+
+fn input_path(input: Option<&serde_json::Value>) -> Option<String> {
+    let input = input?;
+    ["file_path", "filePath", "path", "notebook_path"]
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+        .map(String::from)
+}
+
+fn input_command(input: Option<&serde_json::Value>) -> Option<String> {
+    match input?.get("command")? {
+        serde_json::Value::String(command) => Some(command.clone()),
+        serde_json::Value::Array(parts) => Some(
+            parts
+                .iter()
+                .filter_map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
+
+fn duration_ms(call: &TranscriptToolCall) -> Option<u64> {
+    let started = DateTime::parse_from_rfc3339(&call.started_at).ok()?;
+    let completed = DateTime::parse_from_rfc3339(call.completed_at.as_deref()?).ok()?;
+    Some((completed - started).num_milliseconds().max(0) as u64)
+}
+
+/// 95th percentile (nearest rank) of unsorted durations
+fn p95(durations: &mut [u64]) -> u64 {
+    if durations.is_empty() {
+        return 0;
+    }
+    durations.sort_unstable();
+    let rank = ((durations.len() as f64) * 0.95).ceil() as usize;
+    durations[rank.clamp(1, durations.len()) - 1]
+}
+
+fn error_rate(errors: u32, total: u32) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        errors as f64 / total as f64
+    }
+}
+
+/// Compute statistics and detect anti-patterns for one agent run's tool calls
+pub fn analyze_tool_calls(calls: &[TranscriptToolCall]) -> ToolCallStats {
+    let mut stats = ToolCallStats::default();
+    let mut all_durations = Vec::new();
+    let mut per_tool: HashMap<&str, (ToolNameStats, Vec<u64>)> = HashMap::new();
+    let mut files_read = BTreeSet::new();
+    let mut files_written = BTreeSet::new();
+    // Reads since the last write, per file
+    let mut reads_since_write: HashMap<String, u32> = HashMap::new();
+    // Consecutive failures, per shell command
+    let mut failure_streaks: HashMap<String, u32> = HashMap::new();
+
+    let mut ordered: Vec<&TranscriptToolCall> = calls.iter().collect();
+    ordered.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+
+    for call in ordered {
+        stats.total_calls += 1;
+        if call.is_error {
+            stats.errors += 1;
+        }
+
+        let (tool, durations) = per_tool.entry(call.tool_name.as_str()).or_insert_with(|| {
+            (
+                ToolNameStats {
+                    tool_name: call.tool_name.clone(),
+                    ..Default::default()
+                },
+                Vec::new(),
+            )
+        });
+        tool.count += 1;
+        if call.is_error {
+            tool.errors += 1;
+        }
+        if let Some(ms) = duration_ms(call) {
+            tool.total_duration_ms += ms;
+            durations.push(ms);
+            stats.total_duration_ms += ms;
+            all_durations.push(ms);
+        }
+
+        match tool_kind(&call.tool_name) {
+            ToolKind::Read => {
+                if let Some(path) = input_path(call.input.as_ref()) {
+                    let reads = reads_since_write.entry(path.clone()).or_default();
+                    *reads += 1;
+                    if *reads > 1 {
+                        stats.redundant_reads += 1;
+                    }
+                    if *reads == REPEATED_READ_THRESHOLD {
+                        stats.warnings.push(format!(
+                            "File `{}` was read {} times without changes",
+                            path, REPEATED_READ_THRESHOLD
+                        ));
+                    }
+                    files_read.insert(path);
+                }
+            }
+            ToolKind::Write => {
+                if let Some(path) = input_path(call.input.as_ref()) {
+                    reads_since_write.remove(&path);
+                    files_written.insert(path);
+                }
+            }
+            ToolKind::Shell => {
+                stats.shell_command_count += 1;
+                if call.is_error {
+                    stats.failed_shell_commands += 1;
+                }
+                if let Some(command) = input_command(call.input.as_ref()) {
+                    let streak = failure_streaks.entry(command.clone()).or_default();
+                    if call.is_error {
+                        *streak += 1;
+                        if *streak == REPEATED_FAILURE_THRESHOLD {
+                            stats.warnings.push(format!(
+                                "Command `{}` failed {} times in a row",
+                                truncate(&command, MAX_COMMAND_CHARS),
+                                REPEATED_FAILURE_THRESHOLD
+                            ));
+                        }
+                    } else {
+                        *streak = 0;
+                    }
+                    if stats.shell_commands.len() < MAX_RECORDED_COMMANDS {
+                        stats
+                            .shell_commands
+                            .push(truncate(&command, MAX_COMMAND_CHARS));
+                    }
+                }
+            }
+            ToolKind::Other => {}
+        }
+    }
+
+    stats.error_rate = error_rate(stats.errors, stats.total_calls);
+    stats.p95_duration_ms = p95(&mut all_durations);
+    stats.by_tool = per_tool
+        .into_values()
+        .map(|(mut tool, mut durations)| {
+            tool.p95_duration_ms = p95(&mut durations);
+            tool
+        })
+        .collect();
+    stats
+        .by_tool
+        .sort_by(|a, b| b.count.cmp(&a.count).then(a.tool_name.cmp(&b.tool_name)));
+    stats.files_read = files_read.into_iter().collect();
+    stats.files_written = files_written.into_iter().collect();
+    stats
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(max_chars).collect::<String>())
+    }
+}
+
+/// Aggregate iteration stats into groups keyed by `key_fn`, busiest first
+pub fn group_tool_stats<F>(records: &[IterationToolStats], key_fn: F) -> Vec<ToolStatsGroup>
+where
+    F: Fn(&IterationToolStats) -> String,
+{
+    let mut groups: HashMap<String, (ToolStatsGroup, HashMap<String, ToolUsage>)> = HashMap::new();
+
+    for record in records {
+        let key = key_fn(record);
+        let (group, tools) = groups.entry(key.clone()).or_insert_with(|| {
+            (
+                ToolStatsGroup {
+                    key,
+                    ..Default::default()
+                },
+                HashMap::new(),
+            )
+        });
+        let stats = &record.stats;
+        group.iterations += 1;
+        group.total_calls += stats.total_calls;
+        group.errors += stats.errors;
+        group.total_duration_ms += stats.total_duration_ms;
+        group.redundant_reads += stats.redundant_reads;
+        group.shell_command_count += stats.shell_command_count;
+        group.failed_shell_commands += stats.failed_shell_commands;
+        group.warnings += stats.warnings.len() as u32;
+
+        for tool in &stats.by_tool {
+            let usage = tools
+                .entry(tool.tool_name.clone())
+                .or_insert_with(|| ToolUsage {
+                    tool_name: tool.tool_name.clone(),
+                    ..Default::default()
+                });
+            usage.count += tool.count;
+            usage.errors += tool.errors;
+            usage.total_duration_ms += tool.total_duration_ms;
+        }
+    }
+
+    let mut groups: Vec<ToolStatsGroup> = groups
+        .into_values()
+        .map(|(mut group, tools)| {
+            group.error_rate = error_rate(group.errors, group.total_calls);
+            group.avg_calls_per_iteration = group.total_calls as f64 / group.iterations as f64;
+            group.by_tool = tools.into_values().collect();
+            group
+                .by_tool
+                .sort_by(|a, b| b.count.cmp(&a.count).then(a.tool_name.cmp(&b.tool_name)));
+            group
+        })
+        .collect();
+    groups.sort_by(|a, b| b.total_calls.cmp(&a.total_calls).then(a.key.cmp(&b.key)));
+    groups
+}
+
+/// Build a report (with per-model, per-agent and per-story groups) from records
+pub fn build_tool_analytics_report(mut records: Vec<IterationToolStats>) -> ToolAnalyticsReport {
+    records.sort_by(|a, b| b.recorded_at.cmp(&a.recorded_at));
+
+    ToolAnalyticsReport {
+        by_model: group_tool_stats(&records, |r| {
+            r.model.clone().unwrap_or_else(|| "default".to_string())
+        }),
+        by_agent: group_tool_stats(&records, |r| r.agent_type.to_string()),
+        by_story: group_tool_stats(&records, |r| {
+            r.story_id
+                .clone()
+                .unwrap_or_else(|| "unassigned".to_string())
+        }),
+        iterations: records,
+    }
+}
+
+/// Build an iteration record stamped with the current time
+pub fn new_iteration_tool_stats(
+    transcript: &crate::file_storage::transcripts::NewTranscript,
+    calls: &[TranscriptToolCall],
+) -> IterationToolStats {
+    IterationToolStats {
+        execution_id: transcript.execution_id.clone(),
+        iteration: transcript.iteration,
+        attempt: transcript.attempt,
+        agent_id: transcript.agent_id.clone(),
+        agent_type: transcript.agent_type,
+        model: transcript.model.clone(),
+        prd_name: transcript.prd_name.clone(),
+        story_id: transcript.story_id.clone(),
+        recorded_at: Utc::now().to_rfc3339(),
+        stats: analyze_tool_calls(calls),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(
+        name: &str,
+        input: serde_json::Value,
+        is_error: bool,
+        second: u32,
+        duration_secs: u32,
+    ) -> TranscriptToolCall {
+        TranscriptToolCall {
+            tool_id: format!("{}-{}", name, second),
+            tool_name: name.to_string(),
+            input: Some(input),
+            parent_tool_id: None,
+            output: None,
+            is_error,
+            started_at: format!("2026-01-01T00:{:02}:{:02}Z", second / 60, second % 60),
+            completed_at: Some(format!(
+                "2026-01-01T00:{:02}:{:02}Z",
+                (second + duration_secs) / 60,
+                (second + duration_secs) % 60
+            )),
+        }
+    }
+
+    #[test]
+    fn test_counts_durations_and_files() {
+        let calls = vec![
+            call("Read", json!({"file_path": "src/a.rs"}), false, 0, 1),
+            call("Read", json!({"file_path": "src/a.rs"}), false, 2, 1),
+            call("Edit", json!({"file_path": "src/a.rs"}), false, 4, 1),
+            call("Bash", json!({"command": "cargo test"}), true, 6, 10),
+        ];
+        let stats = analyze_tool_calls(&calls);
+
+        assert_eq!(stats.total_calls, 4);
+        assert_eq!(stats.errors, 1);
+        assert!((stats.error_rate - 0.25).abs() < f64::EPSILON);
+        assert_eq!(stats.total_duration_ms, 13_000);
+        assert_eq!(stats.p95_duration_ms, 10_000);
+        assert_eq!(stats.by_tool[0].tool_name, "Read");
+        assert_eq!(stats.by_tool[0].count, 2);
+        assert_eq!(stats.files_read, vec!["src/a.rs"]);
+        assert_eq!(stats.files_written, vec!["src/a.rs"]);
+        assert_eq!(stats.redundant_reads, 1);
+        assert_eq!(stats.shell_commands, vec!["cargo test"]);
+        assert_eq!(stats.failed_shell_commands, 1);
+        assert!(stats.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_detects_repeated_command_failures() {
+        let calls: Vec<_> = (0..REPEATED_FAILURE_THRESHOLD)
+            .map(|i| call("Bash", json!({"command": "npm test"}), true, i * 2, 1))
+            .collect();
+        let stats = analyze_tool_calls(&calls);
+        assert_eq!(stats.warnings.len(), 1);
+        assert!(stats.warnings[0].contains("npm test"));
+
+        // A success in between resets the streak
+        let mut calls = calls;
+        calls[2].is_error = false;
+        assert!(analyze_tool_calls(&calls).warnings.is_empty());
+    }
+
+    #[test]
+    fn test_detects_rereads_without_changes() {
+        let calls: Vec<_> = (0..REPEATED_READ_THRESHOLD)
+            .map(|i| call("Read", json!({"file_path": "README.md"}), false, i * 2, 1))
+            .collect();
+        let stats = analyze_tool_calls(&calls);
+        assert_eq!(stats.redundant_reads, REPEATED_READ_THRESHOLD - 1);
+        assert_eq!(stats.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_group_tool_stats_by_model() {
+        let stats = analyze_tool_calls(&[call("Read", json!({"path": "a"}), false, 0, 1)]);
+        let record = |model: &str| IterationToolStats {
+            execution_id: "exec".to_string(),
+            iteration: 1,
+            attempt: 1,
+            agent_id: "agent".to_string(),
+            agent_type: AgentType::Claude,
+            model: Some(model.to_string()),
+            prd_name: None,
+            story_id: None,
+            recorded_at: Utc::now().to_rfc3339(),
+            stats: stats.clone(),
+        };
+        let report =
+            build_tool_analytics_report(vec![record("opus"), record("opus"), record("sonnet")]);
+
+        assert_eq!(report.by_model.len(), 2);
+        assert_eq!(report.by_model[0].key, "opus");
+        assert_eq!(report.by_model[0].iterations, 2);
+        assert_eq!(report.by_model[0].by_tool[0].count, 2);
+        assert_eq!(report.by_agent.len(), 1);
+    }
+}
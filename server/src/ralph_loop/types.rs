@@ -153,7 +153,7 @@ pub fn make_prd_filename(title: &str, prd_id: &str) -> String {
 ///
 /// This represents a single unit of work that the agent should complete.
 /// The `passes` field is updated by the agent when the story is implemented and verified.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct RalphStory {
     /// Unique identifier for the story
     pub id: String,
@@ -202,7 +202,7 @@ pub struct RalphStory {
 ///
 /// Primary agents can break stories into subtasks and assign them to assistants.
 /// This enables parallel work while maintaining hierarchical oversight.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct RalphSubtask {
     /// Unique identifier for the subtask (e.g., "US-1.1-ST-1")
     pub id: String,
@@ -468,7 +468,7 @@ impl PrdExecutionConfig {
 ///
 /// Executions and iteration history are embedded directly in this file,
 /// enabling git-trackable state for team sharing.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct RalphPrd {
     /// Title of the PRD
     pub title: String,
@@ -497,16 +497,18 @@ pub struct RalphPrd {
         skip_serializing_if = "Option::is_none",
         rename = "executionConfig"
     )]
+    #[schema(value_type = Option<Object>)]
     pub execution_config: Option<PrdExecutionConfig>,
 
     /// Execution history (embedded, replaces database tables)
     /// Only the most recent executions are kept to avoid file bloat.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(value_type = Vec<Object>)]
     pub executions: Vec<PrdExecution>,
 }
 
 /// Metadata about the PRD
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PrdMetadata {
     /// When the PRD was created
@@ -839,6 +841,7 @@ impl PrdExecution {
                 IterationOutcome::Failed => stats.failed += 1,
                 IterationOutcome::Skipped => stats.skipped += 1,
                 IterationOutcome::Interrupted => stats.interrupted += 1,
+                IterationOutcome::Recovered => stats.recovered += 1,
             }
             if iter.rate_limit_encountered {
                 stats.rate_limited += 1;
@@ -911,6 +914,8 @@ pub struct IterationStats {
     pub failed: u32,
     pub skipped: u32,
     pub interrupted: u32,
+    #[serde(default)]
+    pub recovered: u32,
     pub rate_limited: u32,
     pub total_duration_secs: f64,
 }
@@ -1171,7 +1176,7 @@ impl RalphPrd {
 }
 
 /// Status summary for a PRD
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PrdStatus {
     /// Total number of stories
@@ -1227,6 +1232,8 @@ pub enum ProgressEntryType {
     StoryCompleted,
     /// Manual note added
     Note,
+    /// Pathological agent behaviour detected (e.g. repeated failing commands)
+    Warning,
 }
 
 impl std::fmt::Display for ProgressEntryType {
@@ -1238,6 +1245,7 @@ impl std::fmt::Display for ProgressEntryType {
             ProgressEntryType::Error => write!(f, "ERROR"),
             ProgressEntryType::StoryCompleted => write!(f, "COMPLETED"),
             ProgressEntryType::Note => write!(f, "NOTE"),
+            ProgressEntryType::Warning => write!(f, "WARNING"),
         }
     }
 }
@@ -1366,7 +1374,7 @@ impl Default for ErrorStrategy {
 /// Outcome of a single Ralph Loop iteration
 ///
 /// Tracks what happened during an iteration for history and crash recovery.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum IterationOutcome {
     /// Iteration completed successfully
@@ -1377,6 +1385,8 @@ pub enum IterationOutcome {
     Skipped,
     /// Iteration was interrupted (crash/manual stop)
     Interrupted,
+    /// Agent outlived a crashed server and was monitored to completion by the next one
+    Recovered,
 }
 
 impl std::fmt::Display for IterationOutcome {
@@ -1386,6 +1396,7 @@ impl std::fmt::Display for IterationOutcome {
             IterationOutcome::Failed => write!(f, "failed"),
             IterationOutcome::Skipped => write!(f, "skipped"),
             IterationOutcome::Interrupted => write!(f, "interrupted"),
+            IterationOutcome::Recovered => write!(f, "recovered"),
         }
     }
 }
@@ -1399,6 +1410,7 @@ impl std::str::FromStr for IterationOutcome {
             "failed" => Ok(IterationOutcome::Failed),
             "skipped" => Ok(IterationOutcome::Skipped),
             "interrupted" => Ok(IterationOutcome::Interrupted),
+            "recovered" => Ok(IterationOutcome::Recovered),
             _ => Err(format!("Unknown iteration outcome: {}", s)),
         }
     }
@@ -1442,7 +1454,7 @@ impl Default for FallbackChainConfig {
 /// Record of a single iteration stored in the database
 ///
 /// Used for persistent history and crash recovery.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IterationRecord {
     /// Unique identifier for this record
@@ -1479,6 +1491,24 @@ pub struct ExecutionStateSnapshot {
     pub state: String,
     /// Last heartbeat timestamp (for crash detection)
     pub last_heartbeat: String,
+    /// Set when the loop was stopped by a server shutdown and can be resumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<ExecutionCheckpoint>,
+}
+
+/// What a loop stopped by a server shutdown needs to resume under the same
+/// execution ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionCheckpoint {
+    /// Request the loop was started with; replayed to resume it
+    pub request: crate::commands::ralph_loop::StartRalphLoopRequest,
+    /// Iteration the loop resumes at
+    pub iteration: u32,
+    /// Worktree the loop worked in (kept for the resumed loop)
+    pub worktree_path: Option<String>,
+    /// When the checkpoint was written
+    pub checkpointed_at: String,
 }
 
 #[cfg(test)]
@@ -1628,6 +1658,7 @@ mod tests {
         assert_eq!(IterationOutcome::Failed.to_string(), "failed");
         assert_eq!(IterationOutcome::Skipped.to_string(), "skipped");
         assert_eq!(IterationOutcome::Interrupted.to_string(), "interrupted");
+        assert_eq!(IterationOutcome::Recovered.to_string(), "recovered");
     }
 
     #[test]
@@ -1648,6 +1679,10 @@ mod tests {
             "interrupted".parse::<IterationOutcome>().unwrap(),
             IterationOutcome::Interrupted
         );
+        assert_eq!(
+            "recovered".parse::<IterationOutcome>().unwrap(),
+            IterationOutcome::Recovered
+        );
         assert_eq!(
             "SUCCESS".parse::<IterationOutcome>().unwrap(),
             IterationOutcome::Success
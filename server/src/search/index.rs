@@ -0,0 +1,267 @@
+//! On-disk inverted index
+//!
+//! Stored in `.ralph-ui/index/` as three files:
+//! - `manifest.json` - indexed sources with their fingerprints and document IDs
+//! - `documents.json` - document metadata plus truncated text for snippets
+//! - `postings.json` - term -> document ID -> term frequency
+
+use super::sources::{self, Source};
+use super::tokenizer::tokenize;
+use super::{get_index_dir, IndexUpdateResult, SearchDocument};
+use crate::file_storage::{atomic_write, ensure_dir, read_json, FileResult};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Bump to force a rebuild when the index format or tokenizer changes
+const INDEX_VERSION: u32 = 1;
+
+/// Characters of document text kept for snippets
+const MAX_STORED_TEXT_CHARS: usize = 4000;
+
+/// BM25 parameters
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexManifest {
+    version: u32,
+    updated_at: Option<String>,
+    sources: HashMap<String, IndexedSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexedSource {
+    fingerprint: String,
+    doc_ids: Vec<String>,
+}
+
+/// A document as stored in the index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct StoredDocument {
+    #[serde(flatten)]
+    pub doc: SearchDocument,
+    /// Number of indexed terms (for length normalization)
+    pub length: u32,
+}
+
+/// Inverted index for one project
+#[derive(Debug, Default)]
+pub(super) struct SearchIndex {
+    manifest: IndexManifest,
+    documents: HashMap<String, StoredDocument>,
+    postings: HashMap<String, HashMap<String, u32>>,
+}
+
+fn manifest_path(index_dir: &Path) -> PathBuf {
+    index_dir.join("manifest.json")
+}
+
+fn documents_path(index_dir: &Path) -> PathBuf {
+    index_dir.join("documents.json")
+}
+
+fn postings_path(index_dir: &Path) -> PathBuf {
+    index_dir.join("postings.json")
+}
+
+impl SearchIndex {
+    /// Load a project's index, or None if it was never built or is outdated
+    pub fn load(project_path: &Path) -> FileResult<Option<Self>> {
+        let index_dir = get_index_dir(project_path);
+        if !manifest_path(&index_dir).exists() {
+            return Ok(None);
+        }
+
+        let manifest: IndexManifest = read_json(&manifest_path(&index_dir))?;
+        if manifest.version != INDEX_VERSION {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            manifest,
+            documents: read_json(&documents_path(&index_dir))?,
+            postings: read_json(&postings_path(&index_dir))?,
+        }))
+    }
+
+    /// Write the index (documents and postings before the manifest, so a partial
+    /// write leaves a manifest that still describes older, complete data)
+    pub fn save(&self, project_path: &Path) -> FileResult<()> {
+        let index_dir = get_index_dir(project_path);
+        ensure_dir(&index_dir)?;
+
+        write_compact(&documents_path(&index_dir), &self.documents)?;
+        write_compact(&postings_path(&index_dir), &self.postings)?;
+        write_compact(&manifest_path(&index_dir), &self.manifest)
+    }
+
+    /// Bring the index up to date with the project's current sources
+    pub fn update(&mut self, project_path: &Path) -> IndexUpdateResult {
+        self.manifest.version = INDEX_VERSION;
+        let mut result = IndexUpdateResult::default();
+
+        let discovered: Vec<(Source, String)> = sources::discover(project_path)
+            .into_iter()
+            .filter_map(|s| sources::fingerprint(&s.path).map(|f| (s, f)))
+            .collect();
+        let current: HashMap<&str, &str> = discovered
+            .iter()
+            .map(|(s, f)| (s.key.as_str(), f.as_str()))
+            .collect();
+
+        // Drop sources that no longer exist or have changed
+        let mut stale_docs = HashSet::new();
+        let stale_keys: Vec<String> = self
+            .manifest
+            .sources
+            .iter()
+            .filter(|(key, indexed)| {
+                current.get(key.as_str()) != Some(&indexed.fingerprint.as_str())
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale_keys {
+            if let Some(indexed) = self.manifest.sources.remove(&key) {
+                if !current.contains_key(key.as_str()) {
+                    result.sources_removed += 1;
+                }
+                stale_docs.extend(indexed.doc_ids);
+            }
+        }
+        self.remove_documents(&stale_docs);
+
+        // Index new and changed sources
+        for (source, fingerprint) in discovered {
+            if self.manifest.sources.contains_key(&source.key) {
+                result.sources_unchanged += 1;
+                continue;
+            }
+
+            let documents = match sources::load(project_path, &source) {
+                Ok(documents) => documents,
+                Err(e) => {
+                    log::debug!("[SearchIndex] Skipping {}: {}", source.key, e);
+                    continue;
+                }
+            };
+
+            let doc_ids = documents.iter().map(|d| d.id.clone()).collect();
+            for doc in documents {
+                self.add_document(doc);
+            }
+            self.manifest.sources.insert(
+                source.key,
+                IndexedSource {
+                    fingerprint,
+                    doc_ids,
+                },
+            );
+            result.sources_indexed += 1;
+        }
+
+        self.manifest.updated_at = Some(Utc::now().to_rfc3339());
+        result.documents = self.documents.len();
+        result
+    }
+
+    fn add_document(&mut self, mut doc: SearchDocument) {
+        let terms = tokenize(&format!("{}\n{}", doc.title, doc.text));
+
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for term in &terms {
+            *frequencies.entry(term.clone()).or_default() += 1;
+        }
+        for (term, count) in frequencies {
+            self.postings
+                .entry(term)
+                .or_default()
+                .insert(doc.id.clone(), count);
+        }
+
+        if doc.text.chars().count() > MAX_STORED_TEXT_CHARS {
+            doc.text = doc.text.chars().take(MAX_STORED_TEXT_CHARS).collect();
+        }
+        self.documents.insert(
+            doc.id.clone(),
+            StoredDocument {
+                doc,
+                length: terms.len() as u32,
+            },
+        );
+    }
+
+    fn remove_documents(&mut self, doc_ids: &HashSet<String>) {
+        if doc_ids.is_empty() {
+            return;
+        }
+        for id in doc_ids {
+            self.documents.remove(id);
+        }
+        self.postings.retain(|_, docs| {
+            docs.retain(|id, _| !doc_ids.contains(id));
+            !docs.is_empty()
+        });
+    }
+
+    /// Score documents that contain every term, highest first
+    pub fn query<'a>(
+        &'a self,
+        terms: &[String],
+        accept: impl Fn(&SearchDocument) -> bool,
+    ) -> Vec<(f64, &'a StoredDocument)> {
+        let mut term_postings = Vec::with_capacity(terms.len());
+        for term in terms {
+            match self.postings.get(term) {
+                Some(docs) => term_postings.push(docs),
+                None => return Vec::new(),
+            }
+        }
+        term_postings.sort_by_key(|docs| docs.len());
+        let Some((rarest, rest)) = term_postings.split_first() else {
+            return Vec::new();
+        };
+
+        let doc_count = self.documents.len() as f64;
+        let avg_length = self
+            .documents
+            .values()
+            .map(|d| d.length as f64)
+            .sum::<f64>()
+            / doc_count.max(1.0);
+
+        let mut hits: Vec<(f64, &StoredDocument)> = rarest
+            .keys()
+            .filter(|id| rest.iter().all(|docs| docs.contains_key(*id)))
+            .filter_map(|id| self.documents.get(id))
+            .filter(|stored| accept(&stored.doc))
+            .map(|stored| {
+                let length_norm =
+                    1.0 - BM25_B + BM25_B * stored.length as f64 / avg_length.max(1.0);
+                let score = term_postings
+                    .iter()
+                    .map(|docs| {
+                        let df = docs.len() as f64;
+                        let idf = (1.0 + (doc_count - df + 0.5) / (df + 0.5)).ln();
+                        let tf = docs.get(&stored.doc.id).copied().unwrap_or(0) as f64;
+                        idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * length_norm)
+                    })
+                    .sum();
+                (score, stored)
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.0.total_cmp(&a.0));
+        hits
+    }
+}
+
+fn write_compact<T: Serialize>(path: &Path, data: &T) -> FileResult<()> {
+    let content = serde_json::to_string(data)
+        .map_err(|e| format!("Failed to serialize search index: {}", e))?;
+    atomic_write(path, &content)
+}
@@ -0,0 +1,384 @@
+//! Full-text search across project artifacts
+//!
+//! Maintains an incremental inverted index per project in `.ralph-ui/index/`
+//! covering agent transcripts, PRD chat messages, context chat messages,
+//! learnings, progress files and PRD stories. Sources are re-indexed only when
+//! their file fingerprint changes; a background task keeps registered projects
+//! up to date.
+
+mod index;
+mod sources;
+mod tokenizer;
+
+use crate::file_storage::projects::get_all_projects;
+use crate::file_storage::{get_ralph_ui_dir, FileResult};
+use crate::shutdown::ShutdownState;
+use chrono::{DateTime, Utc};
+use index::SearchIndex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Default number of hits returned by a search
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+/// Upper bound on hits returned by a search
+const MAX_SEARCH_LIMIT: usize = 500;
+
+/// Delay before the first background indexing pass after startup
+const BACKGROUND_INITIAL_DELAY: Duration = Duration::from_secs(30);
+/// Interval between background indexing passes
+const BACKGROUND_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Serializes index updates within this process
+static UPDATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Kind of artifact a search document comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchDocType {
+    Transcript,
+    PrdChat,
+    ContextChat,
+    Learning,
+    Progress,
+    Story,
+}
+
+/// Identifiers needed to open the matching item in the UI
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchLink {
+    /// PRD chat or context chat session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iteration: Option<u32>,
+    /// First line of the matching chunk (transcripts and progress files)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_offset: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub learning_id: Option<String>,
+}
+
+/// A unit of searchable text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchDocument {
+    pub id: String,
+    pub doc_type: SearchDocType,
+    pub title: String,
+    pub prd_name: Option<String>,
+    pub story_id: Option<String>,
+    /// RFC3339 timestamp used for date filtering
+    pub timestamp: Option<String>,
+    pub link: SearchLink,
+    pub text: String,
+}
+
+/// A search request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub query: String,
+    /// Project to search; all registered projects when omitted
+    pub project_path: Option<String>,
+    pub doc_types: Option<Vec<SearchDocType>>,
+    pub prd_name: Option<String>,
+    pub story_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+/// A matching document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub project_path: String,
+    pub doc_id: String,
+    pub doc_type: SearchDocType,
+    pub title: String,
+    pub snippet: String,
+    pub score: f64,
+    pub prd_name: Option<String>,
+    pub story_id: Option<String>,
+    pub timestamp: Option<String>,
+    pub link: SearchLink,
+}
+
+/// Search results across one or more projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    /// Total matches before the limit was applied
+    pub total: usize,
+    pub projects_searched: usize,
+}
+
+/// Outcome of an index update
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexUpdateResult {
+    pub sources_indexed: usize,
+    pub sources_removed: usize,
+    pub sources_unchanged: usize,
+    /// Documents in the index after the update
+    pub documents: usize,
+}
+
+/// Get the search index directory for a project
+pub fn get_index_dir(project_path: &Path) -> PathBuf {
+    get_ralph_ui_dir(project_path).join("index")
+}
+
+/// Incrementally update a project's index, or rebuild it from scratch
+pub fn update_index(project_path: &Path, rebuild: bool) -> FileResult<IndexUpdateResult> {
+    let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut index = if rebuild {
+        SearchIndex::default()
+    } else {
+        SearchIndex::load(project_path)
+            .unwrap_or_else(|e| {
+                log::warn!("[SearchIndex] Rebuilding unreadable index: {}", e);
+                None
+            })
+            .unwrap_or_default()
+    };
+
+    let result = index.update(project_path);
+    index.save(project_path)?;
+    Ok(result)
+}
+
+/// Run a query against one project or all registered projects
+///
+/// Projects without an index are indexed on first search.
+pub fn search(query: &SearchQuery) -> FileResult<SearchResults> {
+    let terms = tokenizer::tokenize(&query.query);
+    if terms.is_empty() {
+        return Err("Search query has no searchable terms".to_string());
+    }
+
+    let project_paths: Vec<String> = match &query.project_path {
+        Some(path) => vec![path.clone()],
+        None => get_all_projects()?
+            .into_iter()
+            .map(|p| p.path)
+            .filter(|p| Path::new(p).is_dir())
+            .collect(),
+    };
+
+    let accept = |doc: &SearchDocument| {
+        query
+            .doc_types
+            .as_ref()
+            .map_or(true, |types| types.contains(&doc.doc_type))
+            && query
+                .prd_name
+                .as_ref()
+                .map_or(true, |prd| doc.prd_name.as_ref() == Some(prd))
+            && query
+                .story_id
+                .as_ref()
+                .map_or(true, |story| doc.story_id.as_ref() == Some(story))
+            && within_dates(doc.timestamp.as_deref(), query.since, query.until)
+    };
+
+    let mut results = SearchResults::default();
+    for project_path in project_paths {
+        let path = Path::new(&project_path);
+        let index = match SearchIndex::load(path)? {
+            Some(index) => index,
+            None => {
+                update_index(path, true)?;
+                match SearchIndex::load(path)? {
+                    Some(index) => index,
+                    None => continue,
+                }
+            }
+        };
+        results.projects_searched += 1;
+
+        for (score, stored) in index.query(&terms, accept) {
+            results.hits.push(SearchHit {
+                project_path: project_path.clone(),
+                doc_id: stored.doc.id.clone(),
+                doc_type: stored.doc.doc_type,
+                title: stored.doc.title.clone(),
+                snippet: tokenizer::snippet(&stored.doc.text, &terms),
+                score,
+                prd_name: stored.doc.prd_name.clone(),
+                story_id: stored.doc.story_id.clone(),
+                timestamp: stored.doc.timestamp.clone(),
+                link: stored.doc.link.clone(),
+            });
+        }
+    }
+
+    results.total = results.hits.len();
+    results.hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.hits.truncate(
+        query
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT),
+    );
+    Ok(results)
+}
+
+fn within_dates(
+    timestamp: Option<&str>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> bool {
+    if since.is_none() && until.is_none() {
+        return true;
+    }
+    let Some(ts) = timestamp
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+    else {
+        return false;
+    };
+    since.map_or(true, |s| ts >= s) && until.map_or(true, |u| ts <= u)
+}
+
+/// Periodically update the indexes of all registered projects until shutdown
+pub async fn run_background_indexer(shutdown_state: ShutdownState) {
+    tokio::time::sleep(BACKGROUND_INITIAL_DELAY).await;
+
+    while !shutdown_state.is_shutdown_requested() {
+        let pass = tokio::task::spawn_blocking(|| {
+            let projects = match get_all_projects() {
+                Ok(projects) => projects,
+                Err(e) => {
+                    log::warn!("[SearchIndex] Failed to read project registry: {}", e);
+                    return;
+                }
+            };
+            for project in projects {
+                let path = Path::new(&project.path);
+                if !get_ralph_ui_dir(path).is_dir() {
+                    continue;
+                }
+                match update_index(path, false) {
+                    Ok(result) if result.sources_indexed > 0 || result.sources_removed > 0 => {
+                        log::debug!(
+                            "[SearchIndex] Updated {}: {} indexed, {} removed, {} documents",
+                            project.path,
+                            result.sources_indexed,
+                            result.sources_removed,
+                            result.documents
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("[SearchIndex] Failed to index {}: {}", project.path, e),
+                }
+            }
+        });
+        if let Err(e) = pass.await {
+            log::warn!("[SearchIndex] Background indexing pass failed: {}", e);
+        }
+
+        tokio::time::sleep(BACKGROUND_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ralph_loop::LearningsManager;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup_project() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let prds_dir = get_ralph_ui_dir(temp.path()).join("prds");
+        fs::create_dir_all(&prds_dir).unwrap();
+        fs::write(
+            prds_dir.join("feature.json"),
+            r#"{"title":"Feature","branch":"main","stories":[
+                {"id":"US-1","title":"Add auth middleware","acceptance":"Requests without a token are rejected"},
+                {"id":"US-2","title":"List endpoint","acceptance":"Returns items"}
+            ]}"#,
+        )
+        .unwrap();
+        fs::write(
+            prds_dir.join("feature-progress.txt"),
+            "[iteration 1] Started US-1\n",
+        )
+        .unwrap();
+        LearningsManager::new(temp.path(), "feature")
+            .add_simple(1, "Use cursor pagination for the list endpoint")
+            .unwrap();
+        temp
+    }
+
+    fn query(project: &TempDir, text: &str) -> SearchQuery {
+        SearchQuery {
+            query: text.to_string(),
+            project_path: Some(project.path().to_string_lossy().into_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_search_returns_snippets_links_and_applies_filters() {
+        let temp = setup_project();
+
+        let results = search(&query(&temp, "auth middleware")).unwrap();
+        assert_eq!(results.total, 1);
+        let hit = &results.hits[0];
+        assert_eq!(hit.doc_type, SearchDocType::Story);
+        assert_eq!(hit.story_id.as_deref(), Some("US-1"));
+        assert_eq!(hit.prd_name.as_deref(), Some("feature"));
+        assert!(hit.snippet.contains("auth middleware"));
+
+        let mut learning_only = query(&temp, "endpoint");
+        learning_only.doc_types = Some(vec![SearchDocType::Learning]);
+        let results = search(&learning_only).unwrap();
+        assert_eq!(results.total, 1);
+        assert!(results.hits[0].link.learning_id.is_some());
+
+        let mut other_prd = query(&temp, "endpoint");
+        other_prd.prd_name = Some("other".to_string());
+        assert_eq!(search(&other_prd).unwrap().total, 0);
+
+        let mut future = query(&temp, "endpoint");
+        future.since = Some(Utc::now() + chrono::Duration::days(1));
+        assert_eq!(search(&future).unwrap().total, 0);
+
+        assert!(search(&query(&temp, "a the")).is_err());
+    }
+
+    #[test]
+    fn test_update_index_only_reindexes_changed_sources() {
+        let temp = setup_project();
+
+        let first = update_index(temp.path(), false).unwrap();
+        assert_eq!(first.sources_indexed, 3);
+
+        let second = update_index(temp.path(), false).unwrap();
+        assert_eq!(second.sources_indexed, 0);
+        assert_eq!(second.sources_unchanged, 3);
+
+        let progress = get_ralph_ui_dir(temp.path()).join("prds/feature-progress.txt");
+        fs::write(&progress, "[iteration 2] Fixed flaky websocket reconnect\n").unwrap();
+        fs::remove_file(LearningsManager::new(temp.path(), "feature").learnings_path()).unwrap();
+
+        let third = update_index(temp.path(), false).unwrap();
+        assert_eq!(third.sources_indexed, 1);
+        assert_eq!(third.sources_removed, 1);
+
+        assert_eq!(search(&query(&temp, "websocket")).unwrap().total, 1);
+        assert_eq!(search(&query(&temp, "pagination")).unwrap().total, 0);
+        assert_eq!(search(&query(&temp, "started")).unwrap().total, 0);
+    }
+}
@@ -0,0 +1,358 @@
+//! Discovery and loading of searchable project artifacts
+//!
+//! Each source is a single file whose fingerprint (size + mtime) decides whether
+//! its documents need re-indexing.
+
+use super::{SearchDocType, SearchDocument, SearchLink};
+use crate::agents::ansi_stripper::strip_ansi;
+use crate::agents::parse_agent_json_output;
+use crate::file_storage::chat::{get_chat_dir, ChatFile};
+use crate::file_storage::context_ops::{get_context_chat_dir, ContextChatFile};
+use crate::file_storage::migrations::{
+    read_versioned, CHAT, CONTEXT_CHAT, LEARNINGS, PRD as PRD_SCHEMA,
+};
+use crate::file_storage::transcripts::{
+    get_transcript_file_path, list_transcripts, read_transcript_part, TranscriptFilter,
+    TranscriptMeta, TranscriptPart,
+};
+use crate::file_storage::{get_ralph_ui_dir, FileResult};
+use crate::ralph_loop::{LearningsFile, RalphPrd};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Raw transcript lines per indexed chunk
+const TRANSCRIPT_CHUNK_LINES: usize = 200;
+/// Progress file lines per indexed chunk
+const PROGRESS_CHUNK_LINES: usize = 50;
+
+/// What a source file contains
+#[derive(Debug, Clone)]
+pub(super) enum SourceKind {
+    Transcript(Box<TranscriptMeta>),
+    PrdChat,
+    ContextChat,
+    Learnings { prd_name: String },
+    Progress { prd_name: String },
+    Prd { prd_name: String },
+}
+
+/// A file that produces search documents
+#[derive(Debug, Clone)]
+pub(super) struct Source {
+    /// Stable key used in the index manifest
+    pub key: String,
+    pub path: PathBuf,
+    pub kind: SourceKind,
+}
+
+/// Find every indexable file in a project
+pub(super) fn discover(project_path: &Path) -> Vec<Source> {
+    let mut sources = Vec::new();
+    let ralph_dir = get_ralph_ui_dir(project_path);
+
+    match list_transcripts(project_path, &TranscriptFilter::default()) {
+        Ok(transcripts) => {
+            for meta in transcripts {
+                if let Ok(path) = get_transcript_file_path(
+                    project_path,
+                    &meta.execution_id,
+                    meta.iteration,
+                    TranscriptPart::Output,
+                ) {
+                    sources.push(Source {
+                        key: format!("transcript:{}:{}", meta.execution_id, meta.iteration),
+                        path,
+                        kind: SourceKind::Transcript(Box::new(meta)),
+                    });
+                }
+            }
+        }
+        Err(e) => log::debug!("[SearchIndex] Skipping transcripts: {}", e),
+    }
+
+    for path in files_with_extension(&get_chat_dir(project_path), "json") {
+        if path.file_name().is_some_and(|n| n == "index.json") {
+            continue;
+        }
+        sources.push(Source {
+            key: format!("prd_chat:{}", file_stem(&path)),
+            path,
+            kind: SourceKind::PrdChat,
+        });
+    }
+
+    for path in files_with_extension(&get_context_chat_dir(project_path), "json") {
+        sources.push(Source {
+            key: format!("context_chat:{}", file_stem(&path)),
+            path,
+            kind: SourceKind::ContextChat,
+        });
+    }
+
+    if let Ok(entries) = fs::read_dir(ralph_dir.join("briefs")) {
+        for entry in entries.flatten() {
+            let path = entry.path().join("learnings.json");
+            if path.is_file() {
+                let prd_name = entry.file_name().to_string_lossy().into_owned();
+                sources.push(Source {
+                    key: format!("learnings:{}", prd_name),
+                    path,
+                    kind: SourceKind::Learnings { prd_name },
+                });
+            }
+        }
+    }
+
+    let prds_dir = ralph_dir.join("prds");
+    for path in files_with_extension(&prds_dir, "json") {
+        let prd_name = file_stem(&path);
+        sources.push(Source {
+            key: format!("prd:{}", prd_name),
+            path,
+            kind: SourceKind::Prd { prd_name },
+        });
+    }
+    for path in files_with_extension(&prds_dir, "txt") {
+        if let Some(prd_name) = file_stem(&path).strip_suffix("-progress") {
+            let prd_name = prd_name.to_string();
+            sources.push(Source {
+                key: format!("progress:{}", prd_name),
+                path,
+                kind: SourceKind::Progress { prd_name },
+            });
+        }
+    }
+
+    sources
+}
+
+/// Size and modification time of a source file, or None if it is gone
+pub(super) fn fingerprint(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    Some(format!("{}-{}", metadata.len(), modified.as_nanos()))
+}
+
+/// Load the documents a source contributes to the index
+pub(super) fn load(project_path: &Path, source: &Source) -> FileResult<Vec<SearchDocument>> {
+    match &source.kind {
+        SourceKind::Transcript(meta) => load_transcript(project_path, meta),
+        SourceKind::PrdChat => load_prd_chat(&source.path),
+        SourceKind::ContextChat => load_context_chat(&source.path),
+        SourceKind::Learnings { prd_name } => load_learnings(&source.path, prd_name),
+        SourceKind::Progress { prd_name } => load_progress(&source.path, prd_name),
+        SourceKind::Prd { prd_name } => load_prd(&source.path, prd_name),
+    }
+}
+
+fn load_transcript(project_path: &Path, meta: &TranscriptMeta) -> FileResult<Vec<SearchDocument>> {
+    let raw = read_transcript_part(
+        project_path,
+        &meta.execution_id,
+        meta.iteration,
+        TranscriptPart::Output,
+    )?;
+    let raw = String::from_utf8_lossy(&raw);
+    let lines: Vec<&str> = raw.lines().collect();
+
+    let mut documents = Vec::new();
+    for (chunk_index, chunk) in lines.chunks(TRANSCRIPT_CHUNK_LINES).enumerate() {
+        // Index the display text rather than the raw stream-json envelope
+        let text = chunk
+            .iter()
+            .map(|line| strip_ansi(&parse_agent_json_output(line)))
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if text.is_empty() {
+            continue;
+        }
+
+        let offset = chunk_index * TRANSCRIPT_CHUNK_LINES;
+        documents.push(SearchDocument {
+            id: format!(
+                "transcript:{}:{}:{}",
+                meta.execution_id, meta.iteration, offset
+            ),
+            doc_type: SearchDocType::Transcript,
+            title: match &meta.story_id {
+                Some(story_id) => format!("Iteration {} ({})", meta.iteration, story_id),
+                None => format!("Iteration {}", meta.iteration),
+            },
+            prd_name: meta.prd_name.clone(),
+            story_id: meta.story_id.clone(),
+            timestamp: Some(meta.started_at.clone()),
+            link: SearchLink {
+                execution_id: Some(meta.execution_id.clone()),
+                iteration: Some(meta.iteration),
+                line_offset: Some(offset),
+                ..Default::default()
+            },
+            text,
+        });
+    }
+    Ok(documents)
+}
+
+fn load_prd_chat(path: &Path) -> FileResult<Vec<SearchDocument>> {
+    let chat: ChatFile = read_versioned(path, &CHAT)?;
+    let title = chat.title.clone().unwrap_or_else(|| "PRD chat".to_string());
+
+    Ok(chat
+        .messages
+        .iter()
+        .map(|message| SearchDocument {
+            id: format!("prd_chat:{}:{}", chat.id, message.id),
+            doc_type: SearchDocType::PrdChat,
+            title: title.clone(),
+            prd_name: chat.prd_id.clone(),
+            story_id: None,
+            timestamp: Some(message.created_at.to_rfc3339()),
+            link: SearchLink {
+                session_id: Some(chat.id.clone()),
+                message_id: Some(message.id.clone()),
+                ..Default::default()
+            },
+            text: message.content.clone(),
+        })
+        .collect())
+}
+
+fn load_context_chat(path: &Path) -> FileResult<Vec<SearchDocument>> {
+    let chat: ContextChatFile = read_versioned(path, &CONTEXT_CHAT)?;
+
+    Ok(chat
+        .messages
+        .iter()
+        .map(|message| SearchDocument {
+            id: format!("context_chat:{}:{}", chat.session.id, message.id),
+            doc_type: SearchDocType::ContextChat,
+            title: "Context chat".to_string(),
+            prd_name: None,
+            story_id: None,
+            timestamp: Some(message.created_at.to_rfc3339()),
+            link: SearchLink {
+                session_id: Some(chat.session.id.clone()),
+                message_id: Some(message.id.clone()),
+                ..Default::default()
+            },
+            text: message.content.clone(),
+        })
+        .collect())
+}
+
+fn load_learnings(path: &Path, prd_name: &str) -> FileResult<Vec<SearchDocument>> {
+    let learnings: LearningsFile = read_versioned(path, &LEARNINGS)?;
+
+    Ok(learnings
+        .entries
+        .iter()
+        .map(|entry| {
+            let mut text = entry.content.clone();
+            if let Some(code) = &entry.code_example {
+                text.push('\n');
+                text.push_str(code);
+            }
+            SearchDocument {
+                id: format!("learning:{}:{}", prd_name, entry.id),
+                doc_type: SearchDocType::Learning,
+                title: format!("{} learning", entry.learning_type),
+                prd_name: Some(prd_name.to_string()),
+                story_id: entry.story_id.clone(),
+                timestamp: Some(entry.timestamp.clone()),
+                link: SearchLink {
+                    learning_id: Some(entry.id.clone()),
+                    iteration: Some(entry.iteration),
+                    ..Default::default()
+                },
+                text,
+            }
+        })
+        .collect())
+}
+
+fn load_progress(path: &Path, prd_name: &str) -> FileResult<Vec<SearchDocument>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read progress file {:?}: {}", path, e))?;
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(|t| DateTime::<Utc>::from(t).to_rfc3339());
+    let lines: Vec<&str> = content.lines().collect();
+
+    Ok(lines
+        .chunks(PROGRESS_CHUNK_LINES)
+        .enumerate()
+        .map(|(chunk_index, chunk)| {
+            let offset = chunk_index * PROGRESS_CHUNK_LINES;
+            SearchDocument {
+                id: format!("progress:{}:{}", prd_name, offset),
+                doc_type: SearchDocType::Progress,
+                title: format!("{} progress", prd_name),
+                prd_name: Some(prd_name.to_string()),
+                story_id: None,
+                timestamp: modified.clone(),
+                link: SearchLink {
+                    line_offset: Some(offset),
+                    ..Default::default()
+                },
+                text: chunk.join("\n"),
+            }
+        })
+        .filter(|doc| !doc.text.trim().is_empty())
+        .collect())
+}
+
+fn load_prd(path: &Path, prd_name: &str) -> FileResult<Vec<SearchDocument>> {
+    let prd: RalphPrd = read_versioned(path, &PRD_SCHEMA)?;
+    let updated_at = prd.metadata.as_ref().and_then(|m| m.updated_at.clone());
+
+    Ok(prd
+        .stories
+        .iter()
+        .map(|story| {
+            let mut text = story.title.clone();
+            if let Some(description) = &story.description {
+                text.push('\n');
+                text.push_str(description);
+            }
+            text.push('\n');
+            text.push_str(&story.acceptance);
+
+            SearchDocument {
+                id: format!("story:{}:{}", prd_name, story.id),
+                doc_type: SearchDocType::Story,
+                title: format!("{}: {}", story.id, story.title),
+                prd_name: Some(prd_name.to_string()),
+                story_id: Some(story.id.clone()),
+                timestamp: updated_at.clone(),
+                link: SearchLink::default(),
+                text,
+            }
+        })
+        .collect())
+}
+
+fn files_with_extension(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    files.sort();
+    files
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
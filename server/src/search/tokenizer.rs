@@ -0,0 +1,95 @@
+//! Tokenization and snippet extraction for the search index
+
+/// Tokens shorter than this are not indexed
+const MIN_TOKEN_LEN: usize = 2;
+/// Tokens longer than this (hashes, base64 blobs) are not indexed
+const MAX_TOKEN_LEN: usize = 40;
+
+/// Characters of context kept before the first match in a snippet
+const SNIPPET_LEADING_CHARS: usize = 60;
+/// Total snippet length in characters
+const SNIPPET_CHARS: usize = 200;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+/// Split text into lowercase index terms
+///
+/// Terms are runs of alphanumerics and underscores, so `auth_middleware` stays one
+/// term while `auth-middleware` becomes two.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter_map(|raw| {
+            let len = raw.chars().count();
+            if !(MIN_TOKEN_LEN..=MAX_TOKEN_LEN).contains(&len) {
+                return None;
+            }
+            let term = raw.to_lowercase();
+            if STOP_WORDS.contains(&term.as_str()) {
+                return None;
+            }
+            Some(term)
+        })
+        .collect()
+}
+
+/// Build a short single-line snippet around the first occurrence of any term
+pub fn snippet(text: &str, terms: &[String]) -> String {
+    let lower = text.to_lowercase();
+    // Lowercasing can change byte lengths for some scripts; only trust positions
+    // when it did not
+    let match_pos = if lower.len() == text.len() {
+        terms.iter().filter_map(|t| lower.find(t.as_str())).min()
+    } else {
+        None
+    };
+
+    let start_char = match_pos
+        .map(|pos| {
+            text[..pos]
+                .chars()
+                .count()
+                .saturating_sub(SNIPPET_LEADING_CHARS)
+        })
+        .unwrap_or(0);
+
+    let excerpt: String = text.chars().skip(start_char).take(SNIPPET_CHARS).collect();
+    let mut snippet = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start_char > 0 {
+        snippet.insert_str(0, "…");
+    }
+    if text.chars().count() > start_char + SNIPPET_CHARS {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_lowercases_and_drops_noise() {
+        let tokens = tokenize("Fix the Auth_Middleware in src/server.rs (a)");
+        assert_eq!(
+            tokens,
+            vec!["fix", "auth_middleware", "src", "server", "rs"]
+        );
+    }
+
+    #[test]
+    fn test_snippet_centers_on_match() {
+        let text = format!(
+            "{} pagination decided here {}",
+            "x ".repeat(100),
+            "y ".repeat(100)
+        );
+        let snippet = snippet(&text, &["pagination".to_string()]);
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("pagination decided here"));
+    }
+}
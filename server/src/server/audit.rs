@@ -0,0 +1,113 @@
+//! Recording of state-changing calls to the audit log
+//!
+//! The auth middleware records state-changing invoke commands (see
+//! `permissions::is_state_changing`) and mutating REST calls; the
+//! PTY handlers record terminal sessions, and the trigger endpoint records
+//! inbound webhook deliveries. Entries are written to
+//! `~/.ralph-ui/audit/` (see `file_storage::audit`).
+
+use serde_json::Value;
+use std::path::Path;
+
+use super::auth::AuthIdentity;
+use crate::file_storage::audit::{self, AuditEntry, AuditStatus};
+
+/// Append an audit entry for a call made by `identity`
+pub fn record(
+    identity: &AuthIdentity,
+    command: &str,
+    project_path: Option<String>,
+    args: &Value,
+    status: AuditStatus,
+    http_status: Option<u16>,
+) {
+    record_in(
+        &audit::get_audit_dir(),
+        identity,
+        command,
+        project_path,
+        args,
+        status,
+        http_status,
+    );
+}
+
+/// Append an audit entry in a specific directory (for testing)
+pub fn record_in(
+    audit_dir: &Path,
+    identity: &AuthIdentity,
+    command: &str,
+    project_path: Option<String>,
+    args: &Value,
+    status: AuditStatus,
+    http_status: Option<u16>,
+) {
+    let entry = AuditEntry {
+        timestamp: chrono::Utc::now(),
+        actor: identity.name.clone(),
+        token_id: identity.token_id.clone(),
+        role: identity.role,
+        command: command.to_string(),
+        project_path,
+        args: audit::redact_args(args),
+        status,
+        http_status,
+    };
+
+    if let Err(e) = audit::append_audit_entry_in(audit_dir, &entry) {
+        log::warn!("[Audit] Failed to record {}: {}", command, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_storage::audit::{query_audit_log_in, AuditFilter};
+    use crate::server::permissions::{self, ApiTokenRole};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_writes_named_like_reads_are_audited() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let identity = AuthIdentity {
+            token_id: Some("t1".to_string()),
+            name: "ci".to_string(),
+            role: ApiTokenRole::Operator,
+            projects: Some(vec!["/work/app".to_string()]),
+            share_link: None,
+        };
+
+        for command in [
+            "export_workflow_to_prd",
+            "check_stale_ralph_executions",
+            "assess_prd_quality",
+            "get_ralph_prd",
+        ] {
+            // Same decision the auth middleware and MCP server make
+            if permissions::is_state_changing(command) {
+                record_in(
+                    dir,
+                    &identity,
+                    command,
+                    Some("/work/app".to_string()),
+                    &json!({ "projectPath": "/work/app" }),
+                    AuditStatus::Success,
+                    Some(200),
+                );
+            }
+        }
+
+        let entries = query_audit_log_in(dir, &AuditFilter::default()).unwrap();
+        let commands: Vec<&str> = entries.iter().map(|e| e.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "assess_prd_quality",
+                "check_stale_ralph_executions",
+                "export_workflow_to_prd"
+            ]
+        );
+    }
+}
@@ -1,16 +1,273 @@
 //! Authentication middleware for the server
 //!
-//! Validates Bearer tokens on all requests except health checks.
+//! Validates Bearer tokens on all requests except health checks. Requests are
+//! authenticated either with the server token (full access) or with a named
+//! API token from `~/.ralph-ui/tokens.json`, whose role and project scope are
+//! enforced per command, REST route and WebSocket channel. Share links from
+//! `~/.ralph-ui/share-links.json` authenticate read-only access to a single
+//! execution. State-changing calls are recorded in the audit log.
 
 use axum::{
     body::Body,
-    extract::Request,
+    extract::{Query, Request},
     http::{header::AUTHORIZATION, Method, StatusCode},
     response::Response,
 };
-use std::sync::Arc;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use tower::Layer;
 
+use super::audit;
+use super::permissions::{self, ApiTokenRole, Permission};
+use crate::file_storage::api_tokens::{self, ApiToken};
+use crate::file_storage::audit::AuditStatus;
+use crate::file_storage::share_links::{self, ShareLink, ShareLinksFile};
+use crate::utils::lock_mutex_recover;
+
+/// Largest `/api/invoke` or REST body buffered for classification
+/// (matches axum's default JSON body limit)
+const INVOKE_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Who made a request; inserted into request extensions after authentication
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthIdentity {
+    /// Named token ID (None for the server token)
+    pub token_id: Option<String>,
+    pub name: String,
+    pub role: ApiTokenRole,
+    /// Project paths the identity is limited to (None = all projects)
+    pub projects: Option<Vec<String>>,
+    /// Share link the request was made with; limits it to one execution
+    #[serde(skip)]
+    pub share_link: Option<ShareLink>,
+}
+
+impl AuthIdentity {
+    /// Identity of the server token printed at startup
+    pub(crate) fn server() -> Self {
+        Self {
+            token_id: None,
+            name: "server".to_string(),
+            role: ApiTokenRole::Admin,
+            projects: None,
+            share_link: None,
+        }
+    }
+
+    fn from_token(token: &ApiToken) -> Self {
+        Self {
+            token_id: Some(token.id.clone()),
+            name: token.name.clone(),
+            role: token.role,
+            projects: token.projects.clone(),
+            share_link: None,
+        }
+    }
+
+    fn from_share_link(link: ShareLink) -> Self {
+        Self {
+            token_id: Some(link.id.clone()),
+            name: format!("share:{}", link.label),
+            role: ApiTokenRole::Viewer,
+            projects: Some(vec![link.project_path.clone()]),
+            share_link: Some(link),
+        }
+    }
+
+    /// Check a request's required permission and referenced projects.
+    /// Project-scoped identities can only use commands that name none of
+    /// their projects if the command can't reveal other projects' data.
+    pub(crate) fn authorize(
+        &self,
+        command: &str,
+        permission: Permission,
+        project_paths: &[String],
+    ) -> Result<(), String> {
+        if !self.role.grants(permission) {
+            return Err(format!(
+                "Token '{}' ({}) lacks {:?} permission",
+                self.name, self.role, permission
+            ));
+        }
+
+        if let Some(scopes) = &self.projects {
+            if project_paths.is_empty() && !permissions::is_project_free(command) {
+                return Err(format!(
+                    "Token '{}' is limited to specific projects",
+                    self.name
+                ));
+            }
+            if let Some(path) = project_paths
+                .iter()
+                .find(|p| !permissions::path_in_scope(p, scopes))
+            {
+                return Err(format!("Token '{}' has no access to {}", self.name, path));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a call on an execution against the project the execution runs
+    /// in (looked up by the server, not taken from the request). A caller
+    /// naming another project is told the execution doesn't exist.
+    pub(crate) fn authorize_execution(
+        &self,
+        command: &str,
+        permission: Permission,
+        execution_project: Option<&str>,
+        named_project: Option<&str>,
+    ) -> Result<(), ExecutionAccessError> {
+        // Unknown executions are reported as missing by the handlers
+        let Some(project) = execution_project else {
+            return Ok(());
+        };
+        if named_project
+            .is_some_and(|named| named.trim_end_matches('/') != project.trim_end_matches('/'))
+        {
+            return Err(ExecutionAccessError::NotFound);
+        }
+        self.authorize(command, permission, &[project.to_string()])
+            .map_err(ExecutionAccessError::Forbidden)
+    }
+
+    /// Limit share links to the event stream and to reading their own
+    /// execution and PRD (no-op for other identities)
+    pub(crate) fn authorize_share_link(
+        &self,
+        path: &str,
+        command: &str,
+        args: &Value,
+    ) -> Result<(), String> {
+        let Some(link) = &self.share_link else {
+            return Ok(());
+        };
+        if path == "/ws/events" {
+            return Ok(());
+        }
+        if path != "/api/invoke" || !permissions::SHARE_LINK_COMMANDS.contains(&command) {
+            return Err(format!("Share link '{}' can't use {}", link.label, command));
+        }
+
+        let scoped = [
+            ("projectPath", &link.project_path),
+            ("executionId", &link.execution_id),
+            ("prdName", &link.prd_name),
+        ];
+        if args.get("executionId").is_none() && args.get("prdName").is_none() {
+            return Err(format!(
+                "Share link '{}' requires an executionId or prdName",
+                link.label
+            ));
+        }
+        for (key, expected) in scoped {
+            if let Some(value) = args.get(key) {
+                if value.as_str() != Some(expected.as_str()) {
+                    return Err(format!(
+                        "Share link '{}' has no access to {} {}",
+                        link.label, key, value
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a call on an execution was refused
+#[derive(Debug, PartialEq)]
+pub(crate) enum ExecutionAccessError {
+    /// The execution runs in a different project than the one named
+    NotFound,
+    /// The identity may not use the execution's project
+    Forbidden(String),
+}
+
+/// Named tokens, reloaded when the tokens file changes
+static TOKEN_CACHE: Mutex<Option<(Option<SystemTime>, Vec<ApiToken>)>> = Mutex::new(None);
+
+fn find_named_token(secret: &str) -> Option<AuthIdentity> {
+    if !secret.starts_with(api_tokens::TOKEN_PREFIX) {
+        return None;
+    }
+
+    let path = api_tokens::get_tokens_file_path();
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+    let mut cache = lock_mutex_recover(&TOKEN_CACHE);
+    if cache.as_ref().map(|(m, _)| *m) != Some(modified) {
+        let tokens = match path.parent().map(api_tokens::read_tokens_from) {
+            Some(Ok(file)) => file.tokens,
+            Some(Err(e)) => {
+                log::warn!("[Auth] Failed to read API tokens: {}", e);
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+        *cache = Some((modified, tokens));
+    }
+
+    let hash = api_tokens::hash_token(secret);
+    let now = chrono::Utc::now();
+    cache.as_ref().and_then(|(_, tokens)| {
+        tokens
+            .iter()
+            .find(|t| t.token_hash == hash && t.is_active(now))
+            .map(AuthIdentity::from_token)
+    })
+}
+
+/// Share links, reloaded when the share links file changes (e.g. on revocation)
+static SHARE_LINK_CACHE: Mutex<Option<(Option<SystemTime>, ShareLinksFile)>> = Mutex::new(None);
+
+fn find_share_link(secret: &str) -> Option<AuthIdentity> {
+    let path = share_links::get_share_links_file_path();
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+    let mut cache = lock_mutex_recover(&SHARE_LINK_CACHE);
+    if cache.as_ref().map(|(m, _)| *m) != Some(modified) {
+        let file = match path.parent().map(share_links::read_share_links_from) {
+            Some(Ok(file)) => file,
+            Some(Err(e)) => {
+                log::warn!("[Auth] Failed to read share links: {}", e);
+                ShareLinksFile::default()
+            }
+            None => ShareLinksFile::default(),
+        };
+        *cache = Some((modified, file));
+    }
+
+    let (_, file) = cache.as_ref()?;
+    match share_links::verify_token(file, secret, chrono::Utc::now()) {
+        Ok(link) => Some(AuthIdentity::from_share_link(link)),
+        Err(e) => {
+            log::debug!("[Auth] Rejected share link: {}", e);
+            None
+        }
+    }
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    Response::builder()
+        .status(status)
+        .body(Body::from(message.into()))
+        .unwrap()
+}
+
+/// Buffer a JSON request body so it can be classified, then put it back
+/// (bodies that aren't JSON classify as `null`)
+async fn buffer_json_body(req: Request) -> Result<(Request, Value), Response> {
+    let (parts, body) = req.into_parts();
+    let bytes = axum::body::to_bytes(body, INVOKE_BODY_LIMIT)
+        .await
+        .map_err(|_| error_response(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large"))?;
+    let value = serde_json::from_slice(&bytes).unwrap_or_default();
+    Ok((Request::from_parts(parts, Body::from(bytes)), value))
+}
+
 /// Authentication layer that validates Bearer tokens
 #[derive(Clone)]
 pub struct AuthLayer {
@@ -66,7 +323,7 @@ where
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
-            let path = req.uri().path();
+            let path = req.uri().path().to_string();
             let method = req.method().clone();
 
             // Skip auth for CORS preflight OPTIONS requests
@@ -76,38 +333,143 @@ where
 
             // Only require auth for API and WebSocket endpoints
             // All other paths (static files, index.html, etc.) are public
-            let requires_auth = path.starts_with("/api/") || path.starts_with("/ws/");
+            let is_websocket = path.starts_with("/ws/");
+            let requires_auth = path.starts_with("/api/") || is_websocket;
 
             if !requires_auth {
                 return inner.call(req).await;
             }
 
-            // For WebSocket endpoints, check query parameter (browsers can't set headers on WS)
-            if path.starts_with("/ws/") {
-                if let Some(query) = req.uri().query() {
-                    if query.contains(&format!("token={}", *token)) {
-                        return inner.call(req).await;
-                    }
+            let query: HashMap<String, String> = Query::try_from_uri(req.uri())
+                .map(|Query(q)| q)
+                .unwrap_or_default();
+
+            // WebSockets pass the token as a query parameter (browsers can't set
+            // headers on WS); everything else uses the Authorization header
+            let provided = req
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.strip_prefix("Bearer "))
+                .map(str::to_string)
+                .or_else(|| is_websocket.then(|| query.get("token").cloned()).flatten());
+
+            let identity = match provided {
+                Some(secret) if secret == *token => Some(AuthIdentity::server()),
+                Some(secret) if secret.starts_with(share_links::SHARE_LINK_PREFIX) => {
+                    find_share_link(&secret)
                 }
-            }
+                Some(secret) => find_named_token(&secret),
+                None => None,
+            };
+            let Some(identity) = identity else {
+                return Ok(error_response(
+                    StatusCode::UNAUTHORIZED,
+                    "Unauthorized: Invalid or missing Bearer token",
+                ));
+            };
+
+            // Classify the request: invoke commands by name and arguments,
+            // WebSockets by channel, MCP per tool call, other routes by HTTP method.
+            // The command name and arguments are kept for the audit log.
+            let (mut req, permission, project_paths, command, args) = if is_websocket {
+                let permission = permissions::websocket_permission(&path);
+                (req, permission, Vec::new(), path.clone(), Value::Null)
+            } else if path == "/api/invoke" && method == Method::POST {
+                let (req, mut value) = match buffer_json_body(req).await {
+                    Ok(buffered) => buffered,
+                    Err(response) => return Ok(response),
+                };
+                let cmd = value
+                    .get("cmd")
+                    .and_then(|c| c.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let args = value.get_mut("args").map(Value::take).unwrap_or_default();
+                let project_paths = permissions::project_paths_in_args(&args);
+                (
+                    req,
+                    permissions::invoke_permission(&cmd, &args),
+                    project_paths,
+                    cmd,
+                    args,
+                )
+            } else if path.starts_with("/api/mcp") {
+                // MCP authorizes and audits each tool call like its invoke command
+                (req, Permission::Read, Vec::new(), path.clone(), Value::Null)
+            } else {
+                let mut project_paths: Vec<String> = ["projectPath", "repoPath"]
+                    .iter()
+                    .filter_map(|key| query.get(*key).cloned())
+                    .collect();
+                // REST writes may name the project in their JSON body instead
+                let req = if matches!(method, Method::POST | Method::PUT | Method::PATCH) {
+                    let (req, body) = match buffer_json_body(req).await {
+                        Ok(buffered) => buffered,
+                        Err(response) => return Ok(response),
+                    };
+                    project_paths.extend(permissions::project_paths_in_args(&body));
+                    req
+                } else {
+                    req
+                };
+                let command = format!("{} {}", method, path);
+                let args = serde_json::to_value(&query).unwrap_or_default();
+                let permission = permissions::http_permission(&method);
+                (req, permission, project_paths, command, args)
+            };
 
-            // Check Authorization header for API endpoints
-            if let Some(auth_header) = req.headers().get(AUTHORIZATION) {
-                if let Ok(auth_str) = auth_header.to_str() {
-                    if auth_str.starts_with("Bearer ") {
-                        let provided_token = &auth_str[7..];
-                        if provided_token == token.as_str() {
-                            return inner.call(req).await;
-                        }
-                    }
+            // PTY sessions are audited by their handlers (upgrades never "succeed" here);
+            // invoke commands by the read allowlist, REST calls by method
+            let audited = if is_websocket {
+                false
+            } else if path == "/api/invoke" {
+                permissions::is_state_changing(&command)
+            } else {
+                permission > Permission::Read
+            };
+            let project_path = project_paths.first().cloned();
+
+            if let Err(e) = identity
+                .authorize(&command, permission, &project_paths)
+                .and_then(|_| identity.authorize_share_link(&path, &command, &args))
+            {
+                log::warn!("[Auth] Denied {} {}: {}", method, path, e);
+                if audited {
+                    audit::record(
+                        &identity,
+                        &command,
+                        project_path,
+                        &args,
+                        AuditStatus::Denied,
+                        Some(StatusCode::FORBIDDEN.as_u16()),
+                    );
                 }
+                return Ok(error_response(
+                    StatusCode::FORBIDDEN,
+                    format!("Forbidden: {}", e),
+                ));
             }
 
-            // Unauthorized
-            Ok(Response::builder()
-                .status(StatusCode::UNAUTHORIZED)
-                .body(Body::from("Unauthorized: Invalid or missing Bearer token"))
-                .unwrap())
+            req.extensions_mut().insert(identity.clone());
+            let response = inner.call(req).await?;
+
+            if audited {
+                let status = if response.status().is_success() {
+                    AuditStatus::Success
+                } else {
+                    AuditStatus::Failure
+                };
+                audit::record(
+                    &identity,
+                    &command,
+                    project_path,
+                    &args,
+                    status,
+                    Some(response.status().as_u16()),
+                );
+            }
+            Ok(response)
         })
     }
 }
@@ -150,4 +512,197 @@ mod tests {
         assert_eq!(hex::encode(&[0x00, 0xff, 0xab]), "00ffab");
         assert_eq!(hex::encode(&[0x12, 0x34]), "1234");
     }
+
+    #[test]
+    fn test_authorize_role_and_scope() {
+        let viewer = AuthIdentity {
+            token_id: Some("t1".to_string()),
+            name: "ci".to_string(),
+            role: ApiTokenRole::Viewer,
+            projects: Some(vec!["/work/app".to_string()]),
+            share_link: None,
+        };
+        let app = ["/work/app".to_string()];
+        assert!(viewer
+            .authorize("get_ralph_prd", Permission::Read, &app)
+            .is_ok());
+        assert!(viewer
+            .authorize(
+                "get_ralph_prd",
+                Permission::Read,
+                &["/work/other".to_string()]
+            )
+            .is_err());
+        assert!(viewer
+            .authorize("start_ralph_loop", Permission::Operate, &app)
+            .is_err());
+
+        let operator = AuthIdentity {
+            role: ApiTokenRole::Operator,
+            ..viewer
+        };
+        assert!(operator
+            .authorize("start_ralph_loop", Permission::Operate, &app)
+            .is_ok());
+        // Scoped tokens can't operate on project-less commands
+        assert!(operator
+            .authorize("stop_ralph_loop", Permission::Operate, &[])
+            .is_err());
+        assert!(AuthIdentity::server()
+            .authorize("save_config", Permission::Admin, &[])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_scoped_token_denied_other_projects_executions() {
+        let operator = AuthIdentity {
+            token_id: Some("t1".to_string()),
+            name: "ci".to_string(),
+            role: ApiTokenRole::Operator,
+            projects: Some(vec!["/work/app".to_string()]),
+            share_link: None,
+        };
+        let other = Some("/work/api");
+
+        // Naming its own project with the other project's execution ID
+        for (command, permission) in [
+            ("stop_ralph_loop", Permission::Operate),
+            ("get_ralph_loop_snapshot", Permission::Read),
+            ("get_ralph_loop_state", Permission::Read),
+        ] {
+            assert_eq!(
+                operator.authorize_execution(command, permission, other, Some("/work/app")),
+                Err(ExecutionAccessError::NotFound),
+                "{}",
+                command
+            );
+            // Naming no project (or the other one) is checked against the execution's
+            assert!(matches!(
+                operator.authorize_execution(command, permission, other, None),
+                Err(ExecutionAccessError::Forbidden(_))
+            ));
+            assert!(matches!(
+                operator.authorize_execution(command, permission, other, other),
+                Err(ExecutionAccessError::Forbidden(_))
+            ));
+        }
+
+        assert_eq!(
+            operator.authorize_execution(
+                "stop_ralph_loop",
+                Permission::Operate,
+                Some("/work/app"),
+                Some("/work/app/")
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            AuthIdentity::server().authorize_execution(
+                "stop_ralph_loop",
+                Permission::Operate,
+                other,
+                None
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_scoped_viewer_denied_project_less_reads() {
+        let viewer = AuthIdentity {
+            token_id: Some("t1".to_string()),
+            name: "ci".to_string(),
+            role: ApiTokenRole::Viewer,
+            projects: Some(vec!["/work/app".to_string()]),
+            share_link: None,
+        };
+        // Project lists and fleet-wide feeds would reveal other projects
+        for endpoint in [
+            "GET /api/v1/projects",
+            "GET /api/v1/projects/p1",
+            "get_all_projects",
+            "get_activity_feed",
+            "list_fleet_executions",
+        ] {
+            assert!(
+                viewer.authorize(endpoint, Permission::Read, &[]).is_err(),
+                "{} should be denied",
+                endpoint
+            );
+        }
+        // Endpoints that carry no project data, or filter by scope
+        assert!(viewer
+            .authorize("/ws/events", Permission::Read, &[])
+            .is_ok());
+        assert!(viewer
+            .authorize("get_available_models", Permission::Read, &[])
+            .is_ok());
+
+        let unscoped = AuthIdentity {
+            projects: None,
+            ..viewer
+        };
+        assert!(unscoped
+            .authorize("GET /api/v1/projects", Permission::Read, &[])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_share_link_limited_to_its_execution() {
+        let now = chrono::Utc::now();
+        let identity = AuthIdentity::from_share_link(ShareLink {
+            id: "l1".to_string(),
+            label: "stakeholders".to_string(),
+            project_path: "/work/app".to_string(),
+            prd_name: "dark-mode".to_string(),
+            execution_id: "exec-1".to_string(),
+            created_at: now,
+            expires_at: now + chrono::Duration::hours(1),
+            revoked_at: None,
+        });
+        let invoke =
+            |cmd: &str, args: Value| identity.authorize_share_link("/api/invoke", cmd, &args);
+
+        assert!(invoke(
+            "get_ralph_iteration_history",
+            serde_json::json!({"projectPath": "/work/app", "executionId": "exec-1"})
+        )
+        .is_ok());
+        assert!(invoke(
+            "get_ralph_learnings",
+            serde_json::json!({"projectPath": "/work/app", "prdName": "dark-mode"})
+        )
+        .is_ok());
+        assert!(invoke(
+            "get_ralph_iteration_history",
+            serde_json::json!({"projectPath": "/work/app", "executionId": "exec-2"})
+        )
+        .is_err());
+        assert!(invoke(
+            "get_ralph_learnings",
+            serde_json::json!({"projectPath": "/work/app", "prdName": "other"})
+        )
+        .is_err());
+        assert!(invoke(
+            "get_ralph_prd_status",
+            serde_json::json!({"projectPath": "/work/app"})
+        )
+        .is_err());
+        assert!(invoke("get_ralph_prd", serde_json::json!({"prdName": "dark-mode"})).is_err());
+        assert!(invoke(
+            "stop_ralph_loop",
+            serde_json::json!({"executionId": "exec-1"})
+        )
+        .is_err());
+
+        assert!(identity
+            .authorize_share_link("/ws/events", "/ws/events", &Value::Null)
+            .is_ok());
+        assert!(identity
+            .authorize_share_link("/ws/pty/t1", "/ws/pty/t1", &Value::Null)
+            .is_err());
+        assert!(identity
+            .authorize_share_link("/api/v1/projects", "GET /api/v1/projects", &Value::Null)
+            .is_err());
+    }
 }
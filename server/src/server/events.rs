@@ -1,38 +1,182 @@
 //! WebSocket event broadcaster for real-time updates
 //!
 //! Bridges the internal event channels to WebSocket clients.
-//! Also handles push notifications for background updates.
+//! Also handles push notifications and outbound webhooks for background updates.
+//!
+//! Every event gets a sequence number and is kept in a bounded replay journal,
+//! so clients can subscribe with a filter and resume after a reconnect:
+//!
+//! ```json
+//! {"event": "subscribe", "payload": {"filter": {"eventTypes": ["ralph:"]}, "since": 42}}
+//! ```
 
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Extension, State,
     },
     response::IntoResponse,
 };
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{stream::SplitSink, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast;
 
+use super::auth::AuthIdentity;
+use super::permissions;
 use super::ServerAppState;
 use crate::push::{NotificationPayload, PushEventType, PushNotifier};
+use crate::utils::lock_mutex_recover;
+use crate::webhooks::WebhookDispatcher;
+
+/// Events kept for replay (all types)
+const REPLAY_JOURNAL_CAPACITY: usize = 1000;
+
+/// `ralph:*` events kept for replay, separately so chatty events can't evict
+/// loop state transitions
+const RALPH_JOURNAL_CAPACITY: usize = 1000;
+
+/// Sent to a client after it subscribes
+const EVENT_SUBSCRIPTION_ACK: &str = "subscription:ack";
 
 /// A server event that can be broadcast to WebSocket clients
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerEvent {
+    /// Monotonically increasing sequence number (0 for per-connection control messages)
+    #[serde(default)]
+    pub seq: u64,
     /// Event type (e.g., "ralph:progress", "agent:completed")
     pub event: String,
     /// Event payload as JSON value
     pub payload: serde_json::Value,
+    /// Project the event belongs to, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
+}
+
+impl ServerEvent {
+    fn payload_str(&self, key: &str) -> Option<&str> {
+        self.payload.get(key).and_then(|v| v.as_str())
+    }
+}
+
+/// Subscription filter sent by a client; empty lists match everything.
+/// An event must match every non-empty list, and events that don't carry
+/// the filtered field don't match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+    #[serde(default)]
+    pub project_paths: Vec<String>,
+    #[serde(default)]
+    pub execution_ids: Vec<String>,
+    /// Matches `agentId`, or `parentAgentId` for subagent events
+    #[serde(default)]
+    pub agent_ids: Vec<String>,
+    /// Event type prefixes (e.g. "ralph:", "agent:status_changed")
+    #[serde(default)]
+    pub event_types: Vec<String>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &ServerEvent) -> bool {
+        let matches_any = |values: &[String], value: Option<&str>| {
+            values.is_empty() || value.is_some_and(|v| values.iter().any(|x| x == v))
+        };
+
+        (self.event_types.is_empty() || self.event_types.iter().any(|p| event.event.starts_with(p)))
+            && (self.project_paths.is_empty()
+                || event
+                    .project_path
+                    .as_deref()
+                    .is_some_and(|p| permissions::path_in_scope(p, &self.project_paths)))
+            && matches_any(&self.execution_ids, event.payload_str("executionId"))
+            && matches_any(
+                &self.agent_ids,
+                event
+                    .payload_str("agentId")
+                    .or_else(|| event.payload_str("parentAgentId")),
+            )
+    }
+}
+
+/// Events available for replay
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    /// Events after the requested sequence, oldest first
+    pub events: Vec<ServerEvent>,
+    /// Some events after the requested sequence were evicted from the journal
+    pub gap: bool,
+    /// Some `ralph:*` events after the requested sequence were evicted
+    pub ralph_gap: bool,
+    /// Sequence of the newest event broadcast so far
+    pub last_seq: u64,
+}
+
+/// Bounded journal of recent events
+#[derive(Default)]
+struct EventJournal {
+    last_seq: u64,
+    recent: VecDeque<ServerEvent>,
+    ralph: VecDeque<ServerEvent>,
+    /// Highest sequence evicted from each queue
+    recent_evicted: u64,
+    ralph_evicted: u64,
+}
+
+impl EventJournal {
+    fn push(&mut self, event: ServerEvent) {
+        let (queue, evicted, capacity) = if event.event.starts_with("ralph:") {
+            (
+                &mut self.ralph,
+                &mut self.ralph_evicted,
+                RALPH_JOURNAL_CAPACITY,
+            )
+        } else {
+            (
+                &mut self.recent,
+                &mut self.recent_evicted,
+                REPLAY_JOURNAL_CAPACITY,
+            )
+        };
+        if queue.len() >= capacity {
+            if let Some(old) = queue.pop_front() {
+                *evicted = old.seq;
+            }
+        }
+        queue.push_back(event);
+    }
+
+    fn replay_since(&self, since: u64) -> Replay {
+        let mut events: Vec<ServerEvent> = self
+            .recent
+            .iter()
+            .chain(self.ralph.iter())
+            .filter(|e| e.seq > since)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.seq);
+
+        Replay {
+            events,
+            gap: self.recent_evicted > since || self.ralph_evicted > since,
+            ralph_gap: self.ralph_evicted > since,
+            last_seq: self.last_seq,
+        }
+    }
 }
 
 /// Broadcasts events to all connected WebSocket clients
-/// Also triggers push notifications for configured event types
+/// Also triggers push notifications and webhooks for configured event types
 pub struct EventBroadcaster {
     tx: broadcast::Sender<ServerEvent>,
     push_notifier: Option<Arc<PushNotifier>>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+    journal: Mutex<EventJournal>,
+    /// Project of each execution started by this server (ralph payloads only carry the ID)
+    execution_projects: Mutex<HashMap<String, String>>,
 }
 
 impl EventBroadcaster {
@@ -42,6 +186,9 @@ impl EventBroadcaster {
         Self {
             tx,
             push_notifier: None,
+            webhooks: None,
+            journal: Mutex::new(EventJournal::default()),
+            execution_projects: Mutex::new(HashMap::new()),
         }
     }
 
@@ -50,15 +197,67 @@ impl EventBroadcaster {
         self.push_notifier = Some(notifier);
     }
 
+    /// Set the dispatcher delivering events to outbound webhooks
+    pub fn set_webhook_dispatcher(&mut self, dispatcher: Arc<WebhookDispatcher>) {
+        self.webhooks = Some(dispatcher);
+    }
+
+    /// Attribute events of an execution to its project (for subscription filters)
+    pub fn register_execution_project(&self, execution_id: &str, project_path: &str) {
+        lock_mutex_recover(&self.execution_projects)
+            .insert(execution_id.to_string(), project_path.to_string());
+    }
+
+    /// Forget the project of an execution whose loop task has ended (journaled
+    /// events keep the project they were broadcast with)
+    pub fn unregister_execution_project(&self, execution_id: &str) {
+        lock_mutex_recover(&self.execution_projects).remove(execution_id);
+    }
+
     /// Broadcast an event to all connected clients
     pub fn broadcast(&self, event_type: &str, payload: impl Serialize) {
+        let payload = serde_json::to_value(payload).unwrap_or(serde_json::Value::Null);
+        let project_path = payload
+            .get("projectPath")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| {
+                let execution_id = payload.get("executionId")?.as_str()?;
+                lock_mutex_recover(&self.execution_projects)
+                    .get(execution_id)
+                    .cloned()
+            });
+
+        // Sequence, journal and channel are updated together so replayed and
+        // live events interleave in order
+        let mut journal = lock_mutex_recover(&self.journal);
+        journal.last_seq += 1;
         let event = ServerEvent {
+            seq: journal.last_seq,
             event: event_type.to_string(),
-            payload: serde_json::to_value(payload).unwrap_or(serde_json::Value::Null),
+            payload,
+            project_path,
         };
+        journal.push(event.clone());
 
         // Ignore send errors (no receivers)
-        let _ = self.tx.send(event);
+        let _ = self.tx.send(event.clone());
+        drop(journal);
+
+        // Matching webhooks may reload their config from disk; don't hold the journal
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.dispatch(&event);
+        }
+    }
+
+    /// Sequence of the newest event broadcast so far
+    pub fn last_seq(&self) -> u64 {
+        lock_mutex_recover(&self.journal).last_seq
+    }
+
+    /// Events broadcast after `since`, from the replay journal
+    pub fn replay_since(&self, since: u64) -> Replay {
+        lock_mutex_recover(&self.journal).replay_since(since)
     }
 
     /// Broadcast an event and also send a push notification
@@ -114,73 +313,196 @@ impl Default for EventBroadcaster {
     }
 }
 
+/// Messages from WebSocket clients (same `{event, payload}` shape as server events)
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", content = "payload", rename_all = "camelCase")]
+enum ClientMessage {
+    Ping(#[allow(dead_code)] serde_json::Value),
+    Subscribe(SubscribeRequest),
+}
+
+/// Replace the connection's filter, optionally replaying missed events
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscribeRequest {
+    #[serde(default)]
+    filter: EventFilter,
+    /// Last sequence the client saw; later journaled events are replayed
+    since: Option<u64>,
+}
+
+/// Per-connection delivery state
+struct Subscription {
+    filter: EventFilter,
+    /// Projects a scoped token is limited to
+    scopes: Option<Vec<String>>,
+    /// Execution a share link is limited to
+    execution_id: Option<String>,
+    /// Highest sequence already considered for this client
+    last_seq: u64,
+}
+
+impl Subscription {
+    fn wants(&self, event: &ServerEvent) -> bool {
+        let in_scope = self.scopes.as_ref().map_or(true, |scopes| {
+            event
+                .project_path
+                .as_deref()
+                .is_some_and(|p| permissions::path_in_scope(p, scopes))
+        });
+        let in_execution = self
+            .execution_id
+            .as_deref()
+            .map_or(true, |id| event.payload_str("executionId") == Some(id));
+        event.seq > self.last_seq && in_scope && in_execution && self.filter.matches(event)
+    }
+}
+
+type EventSender = SplitSink<WebSocket, Message>;
+
+async fn send_event(sender: &mut EventSender, event: &ServerEvent) -> bool {
+    match serde_json::to_string(event) {
+        Ok(json) => sender.send(Message::Text(json.into())).await.is_ok(),
+        Err(e) => {
+            log::warn!("Failed to serialize event: {}", e);
+            true
+        }
+    }
+}
+
+/// Send journaled events after the subscription's last sequence
+async fn send_replay(
+    sender: &mut EventSender,
+    subscription: &mut Subscription,
+    replay: &Replay,
+) -> Option<usize> {
+    let mut sent = 0;
+    for event in &replay.events {
+        if subscription.wants(event) {
+            if !send_event(sender, event).await {
+                return None;
+            }
+            sent += 1;
+        }
+    }
+    subscription.last_seq = subscription.last_seq.max(replay.last_seq);
+    Some(sent)
+}
+
 /// WebSocket upgrade handler
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_websocket(socket, state))
+    ws.on_upgrade(move |socket| handle_websocket(socket, state, identity))
 }
 
 /// Handle a WebSocket connection
-async fn handle_websocket(socket: WebSocket, state: ServerAppState) {
+async fn handle_websocket(socket: WebSocket, state: ServerAppState, identity: AuthIdentity) {
     let (mut sender, mut receiver) = socket.split();
 
     // Subscribe to broadcast events
     let mut event_rx = state.broadcaster.subscribe();
+    let mut subscription = Subscription {
+        filter: EventFilter::default(),
+        scopes: identity.projects.clone(),
+        execution_id: identity.share_link.map(|link| link.execution_id),
+        last_seq: 0,
+    };
 
     log::info!("WebSocket client connected");
 
-    // Spawn a task to forward broadcast events to this client
-    let send_task = tokio::spawn(async move {
-        while let Ok(event) = event_rx.recv().await {
-            match serde_json::to_string(&event) {
-                Ok(json) => {
-                    if sender.send(Message::Text(json.into())).await.is_err() {
+    loop {
+        tokio::select! {
+            received = event_rx.recv() => match received {
+                Ok(event) => {
+                    if subscription.wants(&event) && !send_event(&mut sender, &event).await {
                         break;
                     }
+                    subscription.last_seq = subscription.last_seq.max(event.seq);
                 }
-                Err(e) => {
-                    log::warn!("Failed to serialize event: {}", e);
-                }
-            }
-        }
-    });
-
-    // Handle incoming messages (ping/pong, subscription requests, etc.)
-    while let Some(result) = receiver.next().await {
-        match result {
-            Ok(msg) => {
-                match msg {
-                    Message::Ping(data) => {
-                        // Pong is handled automatically by axum
-                        log::trace!("Received ping: {:?}", data);
-                    }
-                    Message::Pong(_) => {
-                        log::trace!("Received pong");
-                    }
-                    Message::Text(text) => {
-                        // Handle subscription requests or other commands
-                        log::debug!("Received text message: {}", text);
-                        // For now, we broadcast all events to all clients
-                        // Could add filtering/subscription logic here
-                    }
-                    Message::Close(_) => {
-                        log::info!("WebSocket client disconnected");
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    // Slow client: fill the gap from the journal instead of dropping events
+                    log::warn!("WebSocket client lagged by {} events, replaying", skipped);
+                    let replay = state.broadcaster.replay_since(subscription.last_seq);
+                    if send_replay(&mut sender, &mut subscription, &replay).await.is_none() {
                         break;
                     }
-                    _ => {}
                 }
-            }
-            Err(e) => {
-                log::warn!("WebSocket error: {}", e);
-                break;
-            }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+
+            message = receiver.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Ping(_)) => {
+                            let pong = ServerEvent {
+                                seq: 0,
+                                event: "pong".to_string(),
+                                payload: serde_json::Value::Null,
+                                project_path: None,
+                            };
+                            if !send_event(&mut sender, &pong).await {
+                                break;
+                            }
+                        }
+                        Ok(ClientMessage::Subscribe(request)) => {
+                            subscription.filter = request.filter;
+                            let replay = match request.since {
+                                Some(since) => {
+                                    subscription.last_seq = since;
+                                    state.broadcaster.replay_since(since)
+                                }
+                                // New filter applies to live events only
+                                None => Replay {
+                                    last_seq: state.broadcaster.last_seq(),
+                                    ..Default::default()
+                                },
+                            };
+                            let replayed = if request.since.is_some() {
+                                match send_replay(&mut sender, &mut subscription, &replay).await {
+                                    Some(sent) => sent,
+                                    None => break,
+                                }
+                            } else {
+                                0
+                            };
+
+                            let ack = ServerEvent {
+                                seq: 0,
+                                event: EVENT_SUBSCRIPTION_ACK.to_string(),
+                                payload: serde_json::json!({
+                                    "filter": subscription.filter,
+                                    "lastSeq": replay.last_seq,
+                                    "replayed": replayed,
+                                    "gap": replay.gap,
+                                    "ralphGap": replay.ralph_gap,
+                                }),
+                                project_path: None,
+                            };
+                            if !send_event(&mut sender, &ack).await {
+                                break;
+                            }
+                        }
+                        Err(e) => log::debug!("Ignoring WebSocket message: {}", e),
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    log::info!("WebSocket client disconnected");
+                    break;
+                }
+                Some(Ok(_)) => {
+                    // Ping/pong frames are handled automatically by axum
+                }
+                Some(Err(e)) => {
+                    log::warn!("WebSocket error: {}", e);
+                    break;
+                }
+            },
         }
     }
 
-    // Clean up
-    send_task.abort();
     log::info!("WebSocket connection closed");
 }
 
@@ -205,15 +527,115 @@ mod tests {
     #[test]
     fn test_server_event_serialization() {
         let event = ServerEvent {
+            seq: 7,
             event: "ralph:progress".to_string(),
             payload: serde_json::json!({
                 "executionId": "exec-123",
                 "progress": 0.5
             }),
+            project_path: None,
         };
 
         let json = serde_json::to_string(&event).unwrap();
         assert!(json.contains("ralph:progress"));
         assert!(json.contains("exec-123"));
+        assert!(json.contains("\"seq\":7"));
+        assert!(!json.contains("projectPath"));
+    }
+
+    #[test]
+    fn test_sequence_and_execution_project() {
+        let broadcaster = EventBroadcaster::new();
+        let mut rx = broadcaster.subscribe();
+        broadcaster.register_execution_project("exec-1", "/work/app");
+
+        broadcaster.broadcast("agent:completed", serde_json::json!({"agentId": "a1"}));
+        broadcaster.broadcast(
+            "ralph:progress",
+            serde_json::json!({"executionId": "exec-1"}),
+        );
+
+        let first = rx.try_recv().unwrap();
+        let second = rx.try_recv().unwrap();
+        assert_eq!((first.seq, second.seq), (1, 2));
+        assert_eq!(first.project_path, None);
+        assert_eq!(second.project_path.as_deref(), Some("/work/app"));
+
+        broadcaster.unregister_execution_project("exec-1");
+        assert!(lock_mutex_recover(&broadcaster.execution_projects).is_empty());
+        let replay = broadcaster.replay_since(0);
+        assert_eq!(replay.events[1].project_path.as_deref(), Some("/work/app"));
+    }
+
+    #[test]
+    fn test_replay_keeps_ralph_events_when_chatty_events_overflow() {
+        let broadcaster = EventBroadcaster::new();
+        broadcaster.broadcast(
+            "ralph:loop_completed",
+            serde_json::json!({"executionId": "e"}),
+        );
+        for i in 0..REPLAY_JOURNAL_CAPACITY + 5 {
+            broadcaster.broadcast("tool:started", serde_json::json!({ "n": i }));
+        }
+
+        let replay = broadcaster.replay_since(0);
+        assert!(replay.gap);
+        assert!(!replay.ralph_gap);
+        assert_eq!(replay.events[0].event, "ralph:loop_completed");
+        assert_eq!(replay.events.len(), REPLAY_JOURNAL_CAPACITY + 1);
+        assert_eq!(replay.last_seq, REPLAY_JOURNAL_CAPACITY as u64 + 6);
+
+        // Resuming from a recent sequence has no gap
+        let recent = broadcaster.replay_since(replay.last_seq - 2);
+        assert!(!recent.gap);
+        assert_eq!(recent.events.len(), 2);
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let event = |event: &str, payload: serde_json::Value, project: Option<&str>| ServerEvent {
+            seq: 1,
+            event: event.to_string(),
+            payload,
+            project_path: project.map(String::from),
+        };
+        let filter = EventFilter {
+            project_paths: vec!["/work/app".to_string()],
+            event_types: vec!["ralph:".to_string()],
+            ..Default::default()
+        };
+
+        let ralph = serde_json::json!({"executionId": "e1"});
+        assert!(filter.matches(&event("ralph:progress", ralph.clone(), Some("/work/app"))));
+        assert!(!filter.matches(&event("ralph:progress", ralph.clone(), Some("/work/other"))));
+        assert!(!filter.matches(&event("ralph:progress", ralph.clone(), None)));
+        assert!(!filter.matches(&event("agent:completed", ralph, Some("/work/app"))));
+
+        let by_agent = EventFilter {
+            agent_ids: vec!["a1".to_string()],
+            ..Default::default()
+        };
+        let subagent = serde_json::json!({"subagentId": "s1", "parentAgentId": "a1"});
+        assert!(by_agent.matches(&event("subagent:spawned", subagent, None)));
+        assert!(!by_agent.matches(&event("agent:completed", serde_json::json!({}), None)));
+    }
+
+    #[test]
+    fn test_client_message_parsing() {
+        let ping = r#"{"event":"ping","payload":{"timestamp":1}}"#;
+        assert!(matches!(
+            serde_json::from_str::<ClientMessage>(ping).unwrap(),
+            ClientMessage::Ping(_)
+        ));
+
+        let subscribe =
+            r#"{"event":"subscribe","payload":{"filter":{"executionIds":["e1"]},"since":5}}"#;
+        match serde_json::from_str::<ClientMessage>(subscribe).unwrap() {
+            ClientMessage::Subscribe(request) => {
+                assert_eq!(request.since, Some(5));
+                assert_eq!(request.filter.execution_ids, vec!["e1"]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
     }
 }
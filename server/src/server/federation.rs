@@ -0,0 +1,164 @@
+//! Federated event stream
+//!
+//! `/ws/federation/events` merges this server's events with the event streams
+//! of all enabled federation peers, each tagged with `serverId` and
+//! `serverName`. Clients can narrow it with the same `subscribe` filter as
+//! `/ws/events`; replay isn't available because sequence numbers are per
+//! server.
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Extension, State,
+    },
+    response::IntoResponse,
+};
+use futures_util::{stream::SplitSink, SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::{broadcast, mpsc};
+
+use super::auth::AuthIdentity;
+use super::events::{EventFilter, ServerEvent};
+use super::permissions;
+use super::ServerAppState;
+use crate::federation::{
+    self,
+    events::{stream_peer_events, FederatedEvent, EVENT_PEER_STATUS},
+};
+
+/// Peer events buffered before peer streams are slowed down
+const PEER_EVENT_BUFFER: usize = 256;
+
+/// Messages from WebSocket clients (same shape as on `/ws/events`)
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", content = "payload", rename_all = "camelCase")]
+enum ClientMessage {
+    Ping(#[allow(dead_code)] serde_json::Value),
+    Subscribe(SubscribeRequest),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscribeRequest {
+    #[serde(default)]
+    filter: EventFilter,
+}
+
+/// Whether a client with this identity and filter should get an event
+fn wants(identity: &AuthIdentity, filter: &EventFilter, event: &FederatedEvent) -> bool {
+    // Peer health carries no project data
+    if event.event.event == EVENT_PEER_STATUS {
+        return true;
+    }
+    let in_scope = identity.projects.as_ref().map_or(true, |scopes| {
+        event
+            .event
+            .project_path
+            .as_deref()
+            .is_some_and(|p| permissions::path_in_scope(p, scopes))
+    });
+    in_scope && filter.matches(&event.event)
+}
+
+async fn send_event(sender: &mut SplitSink<WebSocket, Message>, event: &FederatedEvent) -> bool {
+    match serde_json::to_string(event) {
+        Ok(json) => sender.send(Message::Text(json.into())).await.is_ok(),
+        Err(e) => {
+            log::warn!("Failed to serialize federated event: {}", e);
+            true
+        }
+    }
+}
+
+/// WebSocket upgrade handler
+pub async fn federated_ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_federated_websocket(socket, state, identity))
+}
+
+async fn handle_federated_websocket(
+    socket: WebSocket,
+    state: ServerAppState,
+    identity: AuthIdentity,
+) {
+    let (mut sender, mut receiver) = socket.split();
+
+    // One proxy task per peer, stopped when this connection closes
+    let (peer_tx, mut peer_rx) = mpsc::channel(PEER_EVENT_BUFFER);
+    let peers = federation::get_enabled_peers().unwrap_or_else(|e| {
+        log::warn!("[Federation] Failed to read peers: {}", e);
+        Vec::new()
+    });
+    let peer_tasks: Vec<_> = peers
+        .into_iter()
+        .map(|peer| tokio::spawn(stream_peer_events(peer, peer_tx.clone())))
+        .collect();
+    drop(peer_tx);
+
+    let mut local_rx = state.broadcaster.subscribe();
+    let mut filter = EventFilter::default();
+
+    log::info!(
+        "Federated WebSocket client connected ({} peers)",
+        peer_tasks.len()
+    );
+
+    loop {
+        let event = tokio::select! {
+            received = local_rx.recv() => match received {
+                Ok(event) => FederatedEvent::local(event),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Federated WebSocket client lagged by {} events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+
+            Some(event) = peer_rx.recv() => event,
+
+            message = receiver.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Ping(_)) => "pong",
+                        Ok(ClientMessage::Subscribe(request)) => {
+                            filter = request.filter;
+                            "subscription:ack"
+                        }
+                        Err(e) => {
+                            log::debug!("Ignoring WebSocket message: {}", e);
+                            continue;
+                        }
+                    };
+                    let reply = FederatedEvent::local(ServerEvent {
+                        seq: 0,
+                        event: reply.to_string(),
+                        payload: serde_json::json!({ "filter": filter }),
+                        project_path: None,
+                    });
+                    if !send_event(&mut sender, &reply).await {
+                        break;
+                    }
+                    continue;
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => {
+                    log::warn!("Federated WebSocket error: {}", e);
+                    break;
+                }
+            },
+        };
+
+        if wants(&identity, &filter, &event) && !send_event(&mut sender, &event).await {
+            break;
+        }
+    }
+
+    for task in peer_tasks {
+        task.abort();
+    }
+    log::info!("Federated WebSocket connection closed");
+}
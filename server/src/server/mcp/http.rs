@@ -0,0 +1,161 @@
+//! MCP over HTTP
+//!
+//! - `POST /api/mcp`: streamable HTTP; the JSON-RPC response is the body
+//! - `GET /api/mcp/sse` + `POST /api/mcp/messages?sessionId=`: HTTP+SSE for
+//!   older clients; responses arrive as `message` events on the stream
+
+use super::{parse_error, McpServer};
+use crate::server::auth::AuthIdentity;
+use crate::server::ServerAppState;
+use crate::utils::lock_mutex_recover;
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Extension, Json,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How often an idle SSE stream checks for server shutdown
+const SHUTDOWN_POLL: Duration = Duration::from_secs(1);
+
+/// Open SSE session
+struct SseSession {
+    /// Token that opened the session; messages must use the same one
+    token_id: Option<String>,
+    sender: mpsc::UnboundedSender<Value>,
+}
+
+static SSE_SESSIONS: LazyLock<Mutex<HashMap<String, SseSession>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Removes the session when its stream is dropped
+struct SessionGuard(String);
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        lock_mutex_recover(&SSE_SESSIONS).remove(&self.0);
+        log::debug!("[MCP] SSE session {} closed", self.0);
+    }
+}
+
+fn parse_body(body: &Bytes) -> Result<Value, Value> {
+    serde_json::from_slice(body).map_err(parse_error)
+}
+
+/// POST /api/mcp
+pub async fn mcp_handler(
+    State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
+    body: Bytes,
+) -> Response {
+    let message = match parse_body(&body) {
+        Ok(message) => message,
+        Err(error) => return (StatusCode::BAD_REQUEST, Json(error)).into_response(),
+    };
+    let server = McpServer::new(state, Some(identity), None);
+    match server.handle_message(message).await {
+        Some(response) => Json(response).into_response(),
+        // Notifications and responses are accepted without a body
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// GET /api/mcp/sse
+pub async fn sse_handler(
+    State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
+) -> impl IntoResponse {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let (sender, receiver) = mpsc::unbounded_channel();
+    lock_mutex_recover(&SSE_SESSIONS).insert(
+        session_id.clone(),
+        SseSession {
+            token_id: identity.token_id.clone(),
+            sender,
+        },
+    );
+    log::debug!(
+        "[MCP] SSE session {} opened by {}",
+        session_id,
+        identity.name
+    );
+
+    let endpoint = Event::default()
+        .event("endpoint")
+        .data(format!("/api/mcp/messages?sessionId={}", session_id));
+    let guard = SessionGuard(session_id);
+
+    let messages = futures_util::stream::unfold(
+        (receiver, state, guard),
+        |(mut receiver, state, guard)| async move {
+            loop {
+                // End the stream on shutdown so graceful shutdown isn't held open
+                if state.shutdown_state.is_shutdown_requested() {
+                    return None;
+                }
+                match tokio::time::timeout(SHUTDOWN_POLL, receiver.recv()).await {
+                    Ok(Some(message)) => {
+                        let event = Event::default().event("message").data(message.to_string());
+                        return Some((Ok::<_, Infallible>(event), (receiver, state, guard)));
+                    }
+                    Ok(None) => return None,
+                    Err(_) => continue,
+                }
+            }
+        },
+    );
+    let stream = futures_util::StreamExt::chain(
+        futures_util::stream::once(async move { Ok(endpoint) }),
+        messages,
+    );
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageQuery {
+    session_id: String,
+}
+
+/// POST /api/mcp/messages?sessionId=
+pub async fn message_handler(
+    State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
+    Query(query): Query<MessageQuery>,
+    body: Bytes,
+) -> Response {
+    let sender = {
+        let sessions = lock_mutex_recover(&SSE_SESSIONS);
+        match sessions.get(&query.session_id) {
+            Some(session) if session.token_id == identity.token_id => session.sender.clone(),
+            _ => return (StatusCode::NOT_FOUND, "Unknown MCP session").into_response(),
+        }
+    };
+
+    let message = match parse_body(&body) {
+        Ok(message) => message,
+        Err(error) => {
+            let _ = sender.send(error);
+            return StatusCode::ACCEPTED.into_response();
+        }
+    };
+    tokio::spawn(async move {
+        let server = McpServer::new(state, Some(identity), None);
+        if let Some(response) = server.handle_message(message).await {
+            let _ = sender.send(response);
+        }
+    });
+    StatusCode::ACCEPTED.into_response()
+}
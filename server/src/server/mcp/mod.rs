@@ -0,0 +1,318 @@
+//! Model Context Protocol (MCP) server
+//!
+//! Lets coding agents in IDEs work with Ralph UI: create PRDs, add stories,
+//! start and inspect loops, and read learnings. Tools map to invoke commands;
+//! resources are PRD files, BRIEF.md and progress logs. Speaks JSON-RPC 2.0 over:
+//! - stdio (`ralph-ui mcp`), with full access to the local machine
+//! - HTTP on the server: `POST /api/mcp` (streamable HTTP) and
+//!   `GET /api/mcp/sse` + `POST /api/mcp/messages` (HTTP+SSE). Each tool call is
+//!   authorized like the invoke command it maps to, and state-changing calls
+//!   are audited.
+
+pub mod http;
+mod resources;
+mod tools;
+
+use super::audit;
+use super::auth::AuthIdentity;
+use super::permissions;
+use super::routes;
+use super::ServerAppState;
+use crate::file_storage::audit::AuditStatus;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Protocol versions we speak, newest first
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// MCP: resource not found
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+const INSTRUCTIONS: &str = "Ralph UI runs autonomous coding loops over PRDs. \
+A PRD (.ralph-ui/prds/<prdName>.json) holds stories with acceptance criteria; \
+a loop works through failing stories until all pass. Create a PRD with \
+init_ralph_prd or extend one with add_ralph_story, then start_ralph_loop and \
+follow it with get_ralph_loop_snapshot.";
+
+type RpcError = (i64, String);
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() },
+    })
+}
+
+/// Response to a body that isn't JSON
+pub fn parse_error(e: impl std::fmt::Display) -> Value {
+    error_response(Value::Null, PARSE_ERROR, format!("Parse error: {}", e))
+}
+
+fn tool_result(text: String, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+/// Handles MCP messages for one client
+pub struct McpServer {
+    state: ServerAppState,
+    /// Authenticated HTTP caller (None over stdio)
+    identity: Option<AuthIdentity>,
+    /// Project used when tool calls leave `projectPath` out
+    default_project: Option<String>,
+}
+
+impl McpServer {
+    pub fn new(
+        state: ServerAppState,
+        identity: Option<AuthIdentity>,
+        default_project: Option<String>,
+    ) -> Self {
+        Self {
+            state,
+            identity,
+            default_project,
+        }
+    }
+
+    /// Handle a JSON-RPC message or batch. Returns None when nothing needs
+    /// a response (notifications and client responses).
+    pub async fn handle_message(&self, message: Value) -> Option<Value> {
+        match message {
+            Value::Array(batch) => {
+                let mut responses = Vec::new();
+                for message in batch {
+                    responses.extend(self.handle_single(message).await);
+                }
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            message => self.handle_single(message).await,
+        }
+    }
+
+    async fn handle_single(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+            // We never send requests, so responses from the client are ignored
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Invalid request",
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = self.dispatch(method, params).await;
+        // Notifications get no response
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    async fn dispatch(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            m if m.starts_with("notifications/") => Ok(Value::Null),
+            "tools/list" => Ok(json!({
+                "tools": tools::TOOLS
+                    .iter()
+                    .map(|t| t.to_json(self.default_project.is_some()))
+                    .collect::<Vec<_>>(),
+            })),
+            "tools/call" => self.call_tool(params).await,
+            "resources/list" => Ok(json!({
+                "resources": self
+                    .projects()
+                    .iter()
+                    .flat_map(|p| resources::project_resources(p))
+                    .map(|r| r.to_json())
+                    .collect::<Vec<_>>(),
+            })),
+            "resources/read" => {
+                let uri = params
+                    .get("uri")
+                    .and_then(|u| u.as_str())
+                    .ok_or((INVALID_PARAMS, "Missing uri".to_string()))?;
+                resources::read_resource(uri, &self.projects()).map_err(|e| (RESOURCE_NOT_FOUND, e))
+            }
+            "resources/templates/list" => Ok(json!({ "resourceTemplates": [] })),
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        }
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+        let version = requested
+            .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+            .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0]);
+        json!({
+            "protocolVersion": version,
+            "capabilities": {
+                "tools": { "listChanged": false },
+                "resources": { "listChanged": false },
+            },
+            "serverInfo": {
+                "name": "ralph-ui",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "instructions": INSTRUCTIONS,
+        })
+    }
+
+    /// Projects whose resources are listed: the default project, or the
+    /// registered projects the caller may access
+    fn projects(&self) -> Vec<String> {
+        if let Some(project) = &self.default_project {
+            return vec![project.clone()];
+        }
+        let registry = match crate::file_storage::projects::read_projects_registry() {
+            Ok(registry) => registry,
+            Err(e) => {
+                log::warn!("[MCP] Failed to read projects: {}", e);
+                return Vec::new();
+            }
+        };
+        let scopes = self.identity.as_ref().and_then(|i| i.projects.as_ref());
+        registry
+            .projects
+            .into_iter()
+            .map(|p| p.path)
+            .filter(|p| scopes.map_or(true, |s| permissions::path_in_scope(p, s)))
+            .collect()
+    }
+
+    fn default_agent(&self) -> String {
+        self.state
+            .config_state
+            .get_config()
+            .ok()
+            .map(|c| c.execution.agent_type)
+            .unwrap_or_else(|| "claude".to_string())
+    }
+
+    async fn call_tool(&self, params: Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(|n| n.as_str())
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let tool = tools::find(name).ok_or((INVALID_PARAMS, format!("Unknown tool: {}", name)))?;
+        let args = tool.complete_args(
+            params.get("arguments").cloned().unwrap_or(Value::Null),
+            self.default_project.as_deref(),
+            || self.default_agent(),
+        );
+
+        let permission = permissions::command_permission(tool.name);
+        let project_paths = permissions::project_paths_in_args(&args);
+        let project_path = project_paths.first().cloned();
+        let audited = self
+            .identity
+            .as_ref()
+            .filter(|_| permissions::is_state_changing(tool.name));
+
+        if let Some(identity) = &self.identity {
+            if let Err(e) = identity.authorize(tool.name, permission, &project_paths) {
+                log::warn!("[MCP] Denied {}: {}", tool.name, e);
+                if let Some(identity) = audited {
+                    audit::record(
+                        identity,
+                        tool.name,
+                        project_path,
+                        &args,
+                        AuditStatus::Denied,
+                        None,
+                    );
+                }
+                return Ok(tool_result(format!("Forbidden: {}", e), true));
+            }
+        }
+
+        let result =
+            routes::route_command(tool.name, args.clone(), &self.state, self.identity.as_ref())
+                .await;
+        if let Some(identity) = audited {
+            let status = if result.is_ok() {
+                AuditStatus::Success
+            } else {
+                AuditStatus::Failure
+            };
+            audit::record(identity, tool.name, project_path, &args, status, None);
+        }
+
+        Ok(match result {
+            Ok(value) => tool_result(
+                serde_json::to_string_pretty(&value).unwrap_or_default(),
+                false,
+            ),
+            Err(e) => {
+                log::warn!("[MCP] Tool {} failed: {}", tool.name, e);
+                tool_result(e, true)
+            }
+        })
+    }
+}
+
+/// Serve MCP over stdin/stdout (one JSON-RPC message per line) until stdin closes
+pub async fn run_stdio(
+    state: ServerAppState,
+    default_project: Option<String>,
+) -> Result<(), String> {
+    let server = Arc::new(McpServer::new(state, None, default_project));
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+
+    // Responses are written by one task so concurrent calls don't interleave
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(response) = rx.recv().await {
+            let line = format!("{}\n", response);
+            if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    log::info!("[MCP] Serving over stdio");
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| format!("Failed to read stdin: {}", e))?
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                let _ = tx.send(parse_error(e));
+                continue;
+            }
+        };
+        let server = server.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            if let Some(response) = server.handle_message(message).await {
+                let _ = tx.send(response);
+            }
+        });
+    }
+
+    drop(tx);
+    let _ = writer.await;
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! MCP resources: PRD files, BRIEF.md and progress logs
+//!
+//! Resources are `file://` URIs of the files themselves. Only files listed
+//! for the caller's projects can be read.
+
+use crate::ralph_loop::BriefBuilder;
+use crate::utils::prds_dir;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// A readable file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resource {
+    pub path: PathBuf,
+    pub name: String,
+    pub description: String,
+    pub mime_type: &'static str,
+}
+
+impl Resource {
+    pub fn uri(&self) -> String {
+        format!("file://{}", self.path.to_string_lossy())
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "uri": self.uri(),
+            "name": self.name,
+            "description": self.description,
+            "mimeType": self.mime_type,
+        })
+    }
+}
+
+/// PRD names of a project (`.ralph-ui/prds/*.json` that aren't side files)
+fn prd_names(project_path: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(prds_dir(project_path)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| {
+            let file_name = e.file_name().to_str()?.to_string();
+            let name = file_name.strip_suffix(".json")?;
+            (!name.ends_with("-structure")).then(|| name.to_string())
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// Resources of one project
+pub fn project_resources(project_path: &str) -> Vec<Resource> {
+    let prds = prds_dir(project_path);
+    let project_name = Path::new(project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.to_string());
+
+    let mut resources = Vec::new();
+    for name in prd_names(project_path) {
+        let candidates = [
+            (
+                prds.join(format!("{}.json", name)),
+                format!("{}/{} PRD", project_name, name),
+                "Stories and their pass/fail state",
+                "application/json",
+            ),
+            (
+                prds.join(format!("{}.md", name)),
+                format!("{}/{} PRD document", project_name, name),
+                "PRD written during planning",
+                "text/markdown",
+            ),
+            (
+                BriefBuilder::new(Path::new(project_path), &name).brief_path(),
+                format!("{}/{} BRIEF.md", project_name, name),
+                "Hand-off summary given to agents",
+                "text/markdown",
+            ),
+            (
+                prds.join(format!("{}-progress.txt", name)),
+                format!("{}/{} progress log", project_name, name),
+                "Progress notes across iterations",
+                "text/plain",
+            ),
+        ];
+        for (path, name, description, mime_type) in candidates {
+            if path.is_file() {
+                resources.push(Resource {
+                    path,
+                    name,
+                    description: description.to_string(),
+                    mime_type,
+                });
+            }
+        }
+    }
+    resources
+}
+
+/// Read a listed resource of one of `projects`
+pub fn read_resource(uri: &str, projects: &[String]) -> Result<Value, String> {
+    let resource = projects
+        .iter()
+        .flat_map(|p| project_resources(p))
+        .find(|r| r.uri() == uri)
+        .ok_or_else(|| format!("Resource not found: {}", uri))?;
+
+    let text = std::fs::read_to_string(&resource.path)
+        .map_err(|e| format!("Failed to read {}: {}", resource.path.display(), e))?;
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": resource.mime_type,
+            "text": text,
+        }]
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_project_resources() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().to_str().unwrap().to_string();
+        let prds = prds_dir(&project);
+        std::fs::create_dir_all(&prds).unwrap();
+        std::fs::write(prds.join("dark-mode.json"), "{}").unwrap();
+        std::fs::write(prds.join("dark-mode-structure.json"), "{}").unwrap();
+        std::fs::write(prds.join("dark-mode-progress.txt"), "iteration 1").unwrap();
+
+        let resources = project_resources(&project);
+        let names: Vec<&str> = resources.iter().map(|r| r.mime_type).collect();
+        assert_eq!(names, vec!["application/json", "text/plain"]);
+
+        let progress = resources[1].uri();
+        let read = read_resource(&progress, std::slice::from_ref(&project)).unwrap();
+        assert_eq!(read["contents"][0]["text"], "iteration 1");
+
+        // Unlisted files and other projects' files can't be read
+        let secret = format!("file://{}/secret.txt", project);
+        std::fs::write(temp_dir.path().join("secret.txt"), "x").unwrap();
+        assert!(read_resource(&secret, std::slice::from_ref(&project)).is_err());
+        assert!(read_resource(&progress, &["/elsewhere".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! MCP tools: a curated set of invoke commands with JSON schemas
+//!
+//! Tool names are the command names and tool arguments are the command's
+//! invoke arguments, so a tool call runs exactly what `/api/invoke` runs.
+
+use serde_json::{json, Map, Value};
+
+/// A published tool
+pub struct ToolDef {
+    /// Invoke command the tool runs
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Whether the tool only reads state
+    pub read_only: bool,
+    /// JSON schema properties (besides `projectPath`)
+    properties: fn() -> Value,
+    /// Required properties (besides `projectPath`)
+    required: &'static [&'static str],
+    /// Where the project path goes: top level, or inside `request`
+    project_arg: ProjectArg,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ProjectArg {
+    /// `projectPath` argument
+    TopLevel,
+    /// `request.projectPath`
+    InRequest,
+    /// The command doesn't take a project
+    None,
+}
+
+fn prd_name() -> Value {
+    json!({ "type": "string", "description": "PRD name (file name in .ralph-ui/prds/ without .json)" })
+}
+
+fn story_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "string", "description": "Unique story ID, e.g. \"US-1.2\"" },
+            "title": { "type": "string" },
+            "description": { "type": "string" },
+            "acceptance": { "type": "string", "description": "Acceptance criteria the agent must satisfy" },
+            "priority": { "type": "integer", "minimum": 0, "description": "Lower runs first" },
+            "dependencies": { "type": "array", "items": { "type": "string" }, "description": "IDs of stories that must pass first" },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "effort": { "type": "string", "enum": ["S", "M", "L", "XL"] }
+        },
+        "required": ["id", "title", "acceptance"]
+    })
+}
+
+fn prd_only() -> Value {
+    json!({ "prdName": prd_name() })
+}
+
+fn story_ref() -> Value {
+    json!({
+        "prdName": prd_name(),
+        "storyId": { "type": "string" }
+    })
+}
+
+/// Tools published over MCP
+pub const TOOLS: &[ToolDef] = &[
+    ToolDef {
+        name: "get_ralph_files",
+        description: "List the project's PRDs (prdNames) and which Ralph files exist.",
+        read_only: true,
+        properties: || json!({}),
+        required: &[],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "get_ralph_prd",
+        description: "Get a PRD with all its stories and their pass/fail state.",
+        read_only: true,
+        properties: prd_only,
+        required: &["prdName"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "get_ralph_prd_status",
+        description: "Get a PRD's progress: passing/failing counts and the next story to work on.",
+        read_only: true,
+        properties: prd_only,
+        required: &["prdName"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "init_ralph_prd",
+        description: "Create a PRD with stories that Ralph loops can work through.",
+        read_only: false,
+        properties: || {
+            json!({
+                "prdName": prd_name(),
+                "request": {
+                    "type": "object",
+                    "properties": {
+                        "title": { "type": "string" },
+                        "description": { "type": "string" },
+                        "branch": { "type": "string", "description": "Git branch the loop works on" },
+                        "stories": { "type": "array", "items": story_schema() }
+                    },
+                    "required": ["title", "branch", "stories"]
+                }
+            })
+        },
+        required: &["prdName", "request"],
+        project_arg: ProjectArg::InRequest,
+    },
+    ToolDef {
+        name: "add_ralph_story",
+        description: "Add a story to a PRD.",
+        read_only: false,
+        properties: || json!({ "prdName": prd_name(), "story": story_schema() }),
+        required: &["prdName", "story"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "mark_ralph_story_passing",
+        description: "Mark a story as passing.",
+        read_only: false,
+        properties: story_ref,
+        required: &["prdName", "storyId"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "mark_ralph_story_failing",
+        description: "Mark a story as failing so a loop works on it again.",
+        read_only: false,
+        properties: story_ref,
+        required: &["prdName", "storyId"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "start_ralph_loop",
+        description: "Start a Ralph loop that works through a PRD's failing stories. Returns the execution ID.",
+        read_only: false,
+        properties: || {
+            json!({
+                "request": {
+                    "type": "object",
+                    "properties": {
+                        "prdName": prd_name(),
+                        "agentType": { "type": "string", "enum": ["claude", "opencode", "cursor", "codex"], "description": "Defaults to the configured agent" },
+                        "model": { "type": "string" },
+                        "maxIterations": { "type": "integer", "minimum": 1 },
+                        "maxCost": { "type": "number", "description": "Stop after spending this many dollars" },
+                        "runTests": { "type": "boolean" },
+                        "runLint": { "type": "boolean" },
+                        "useWorktree": { "type": "boolean" },
+                        "branch": { "type": "string" },
+                        "executionMode": { "type": "string", "enum": ["sequential", "parallel"] },
+                        "takeover": { "type": "boolean", "description": "Take the PRD over from a loop still running it in another Ralph UI instance" }
+                    },
+                    "required": ["prdName"]
+                }
+            })
+        },
+        required: &["request"],
+        project_arg: ProjectArg::InRequest,
+    },
+    ToolDef {
+        name: "stop_ralph_loop",
+        description: "Stop a running Ralph loop.",
+        read_only: false,
+        properties: || json!({ "executionId": { "type": "string" } }),
+        required: &["executionId"],
+        project_arg: ProjectArg::None,
+    },
+    ToolDef {
+        name: "list_ralph_loop_executions_with_details",
+        description: "List loops running on this server with their state.",
+        read_only: true,
+        properties: || json!({}),
+        required: &[],
+        project_arg: ProjectArg::None,
+    },
+    ToolDef {
+        name: "get_ralph_loop_snapshot",
+        description: "Get a loop's state, metrics, current agent and worktree.",
+        read_only: true,
+        properties: || json!({ "executionId": { "type": "string" } }),
+        required: &["executionId"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "get_ralph_iteration_history",
+        description: "Get the outcome, duration and errors of each iteration of a loop.",
+        read_only: true,
+        properties: || json!({ "executionId": { "type": "string" } }),
+        required: &["executionId"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "get_ralph_learnings",
+        description: "Get the learnings agents recorded while working on a PRD.",
+        read_only: true,
+        properties: prd_only,
+        required: &["prdName"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "add_ralph_learning",
+        description: "Record a learning for agents working on a PRD.",
+        read_only: false,
+        properties: || {
+            json!({
+                "prdName": prd_name(),
+                "input": {
+                    "type": "object",
+                    "properties": {
+                        "learningType": { "type": "string", "enum": ["architecture", "gotcha", "pattern", "testing", "tooling", "general"] },
+                        "content": { "type": "string" },
+                        "codeExample": { "type": "string" },
+                        "storyId": { "type": "string" }
+                    },
+                    "required": ["learningType", "content"]
+                }
+            })
+        },
+        required: &["prdName", "input"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "get_ralph_brief",
+        description: "Get BRIEF.md, the hand-off summary agents receive for a PRD.",
+        read_only: true,
+        properties: prd_only,
+        required: &["prdName"],
+        project_arg: ProjectArg::TopLevel,
+    },
+    ToolDef {
+        name: "get_ralph_progress",
+        description: "Get a PRD's progress log.",
+        read_only: true,
+        properties: prd_only,
+        required: &["prdName"],
+        project_arg: ProjectArg::TopLevel,
+    },
+];
+
+/// Find a published tool
+pub fn find(name: &str) -> Option<&'static ToolDef> {
+    TOOLS.iter().find(|t| t.name == name)
+}
+
+impl ToolDef {
+    /// `tools/list` entry. With a default project, `projectPath` is optional.
+    pub fn to_json(&self, has_default_project: bool) -> Value {
+        let mut properties = match (self.properties)() {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        };
+        let mut required: Vec<&str> = self.required.to_vec();
+
+        let project_schema =
+            json!({ "type": "string", "description": "Absolute path of the project" });
+        match self.project_arg {
+            ProjectArg::TopLevel => {
+                properties.insert("projectPath".to_string(), project_schema);
+                if !has_default_project {
+                    required.push("projectPath");
+                }
+            }
+            ProjectArg::InRequest => {
+                if let Some(request) = properties.get_mut("request") {
+                    request["properties"]["projectPath"] = project_schema;
+                    if !has_default_project {
+                        if let Some(Value::Array(req)) = request.get_mut("required") {
+                            req.push(json!("projectPath"));
+                        }
+                    }
+                }
+            }
+            ProjectArg::None => {}
+        }
+
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "inputSchema": {
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            },
+            "annotations": {
+                "readOnlyHint": self.read_only,
+                "destructiveHint": false,
+            }
+        })
+    }
+
+    /// Fill in the default project (and agent) where the caller left them out
+    pub fn complete_args(
+        &self,
+        mut args: Value,
+        default_project: Option<&str>,
+        default_agent: impl FnOnce() -> String,
+    ) -> Value {
+        if !args.is_object() {
+            args = json!({});
+        }
+        let target = match self.project_arg {
+            ProjectArg::TopLevel => Some(&mut args),
+            ProjectArg::InRequest => args.get_mut("request").filter(|r| r.is_object()),
+            ProjectArg::None => None,
+        };
+        if let (Some(target), Some(project)) = (target, default_project) {
+            if target.get("projectPath").is_none() {
+                target["projectPath"] = json!(project);
+            }
+        }
+
+        if self.name == "start_ralph_loop" {
+            if let Some(request) = args.get_mut("request").filter(|r| r.is_object()) {
+                if request.get("agentType").is_none() {
+                    request["agentType"] = json!(default_agent());
+                }
+            }
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::permissions::{command_permission, Permission};
+
+    #[test]
+    fn test_tool_schemas_and_permissions() {
+        for tool in TOOLS {
+            let schema = tool.to_json(false);
+            assert_eq!(schema["inputSchema"]["type"], "object", "{}", tool.name);
+            // Read-only hints match what the permission model enforces
+            assert_eq!(
+                tool.read_only,
+                command_permission(tool.name) == Permission::Read,
+                "{}",
+                tool.name
+            );
+        }
+
+        let prd = find("get_ralph_prd").unwrap();
+        let required = prd.to_json(false)["inputSchema"]["required"].clone();
+        assert_eq!(required, json!(["prdName", "projectPath"]));
+        let required = prd.to_json(true)["inputSchema"]["required"].clone();
+        assert_eq!(required, json!(["prdName"]));
+
+        let start = find("start_ralph_loop").unwrap().to_json(false);
+        assert_eq!(
+            start["inputSchema"]["properties"]["request"]["required"],
+            json!(["prdName", "projectPath"])
+        );
+    }
+
+    #[test]
+    fn test_complete_args() {
+        let start = find("start_ralph_loop").unwrap();
+        let args = start.complete_args(
+            json!({ "request": { "prdName": "dark-mode" } }),
+            Some("/work/app"),
+            || "codex".to_string(),
+        );
+        assert_eq!(args["request"]["projectPath"], "/work/app");
+        assert_eq!(args["request"]["agentType"], "codex");
+
+        // Explicit values win
+        let prd = find("get_ralph_prd").unwrap();
+        let args = prd.complete_args(
+            json!({ "projectPath": "/other", "prdName": "x" }),
+            Some("/work/app"),
+            || unreachable!(),
+        );
+        assert_eq!(args["projectPath"], "/other");
+
+        let stop = find("stop_ralph_loop").unwrap();
+        let args = stop.complete_args(
+            json!({ "executionId": "e" }),
+            Some("/work/app"),
+            || unreachable!(),
+        );
+        assert!(args.get("projectPath").is_none());
+    }
+}
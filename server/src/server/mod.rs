@@ -3,17 +3,26 @@
 //! This module provides server mode that allows accessing Ralph UI
 //! from a browser instead of the native Tauri application.
 
+mod audit;
 mod auth;
 mod events;
+mod federation;
 mod file_watcher;
+pub mod mcp;
+pub mod permissions;
 mod proxy;
 mod pty;
 pub mod pty_registry;
+mod recordings;
+pub mod rest;
 pub mod routes;
 pub mod state;
 mod static_files;
+pub mod tls;
+mod transcripts;
+mod triggers;
 
-pub use auth::{generate_auth_token, AuthLayer};
+pub use auth::{generate_auth_token, AuthIdentity, AuthLayer};
 pub use events::{EventBroadcaster, ServerEvent};
 pub use file_watcher::{ServerFileWatcher, WatchFileResponse};
 pub use proxy::invoke_handler;
@@ -35,17 +44,26 @@ struct VersionInfo {
     version: String,
     release_url: String,
 }
+use axum_server::tls_rustls::RustlsConfig;
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tower_http::cors::{Any, CorsLayer};
 
+/// How long running loops get to finish their iteration at shutdown
+const LOOP_SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// Run the HTTP/WebSocket server
 pub async fn run_server(
     port: u16,
     bind: &str,
     state: ServerAppState,
     cors_origins: Option<Vec<String>>,
+    tls: Option<tls::TlsConfig>,
 ) -> Result<(), String> {
+    // Load certificates first so a bad --tls-cert/--tls-key fails before binding
+    let tls = tls.as_ref().map(tls::load_tls).transpose()?;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+
     // Build CORS layer
     // Must be the outermost layer to handle preflight OPTIONS requests before auth
     // Note: Using explicit headers instead of Any to avoid browser deprecation warnings
@@ -75,16 +93,39 @@ pub async fn run_server(
     // Build the router
     // Layer order: cors (outer) -> auth -> handler
     // This ensures CORS preflight requests are handled before auth check
-    // Note: /ws/pty handles its own auth via query param (WebSocket limitation)
+    // Note: WebSocket routes authenticate via the token query param (browser limitation)
     let mut app = Router::new()
         .route("/api/invoke", post(proxy::invoke_handler))
         .route("/ws/events", get(events::ws_handler))
+        .route(
+            "/ws/federation/events",
+            get(federation::federated_ws_handler),
+        )
         .route("/ws/pty/:terminal_id", get(pty::pty_ws_handler))
         // PTY reconnection endpoint (US-3)
         .route(
             "/ws/pty/:terminal_id/reconnect/:session_id",
             get(pty::pty_reconnect_handler),
         )
+        .route(
+            "/api/transcripts/:execution_id/trace",
+            get(transcripts::trace_export_handler),
+        )
+        .route(
+            "/api/transcripts/:execution_id/:iteration/:part",
+            get(transcripts::transcript_download_handler),
+        )
+        .route(
+            "/api/recordings/:recording_id",
+            get(recordings::recording_stream_handler),
+        )
+        // Inbound webhooks authenticate with their trigger's secret, not a Bearer token
+        .route("/hooks/:trigger_id", post(triggers::trigger_handler))
+        .route("/api/mcp", post(mcp::http::mcp_handler))
+        .route("/api/mcp/sse", get(mcp::http::sse_handler))
+        .route("/api/mcp/messages", post(mcp::http::message_handler))
+        .route("/api/openapi.json", get(rest::openapi_handler))
+        .merge(rest::router())
         .route("/health", get(health_handler))
         .route("/api/version", get(version_handler));
 
@@ -118,7 +159,10 @@ pub async fn run_server(
     println!("║                    Ralph UI Server Mode                       ║");
     println!("╠══════════════════════════════════════════════════════════════╣");
     println!("║                                                               ║");
-    println!("║  Server URL: http://{}:{:<24}  ║", bind, port);
+    println!(
+        "║  Server URL: {:<48}║",
+        format!("{}://{}:{}", scheme, bind, port)
+    );
     println!("║                                                               ║");
     println!("║  Auth Token: {}  ║", state.auth_token);
     println!("║                                                               ║");
@@ -127,18 +171,24 @@ pub async fn run_server(
     println!("║                                                               ║");
     println!("║  Endpoints:                                                   ║");
     println!("║    POST /api/invoke      - Command proxy                     ║");
+    println!("║    *    /api/v1/...      - REST API                          ║");
+    println!("║    GET  /api/openapi.json - OpenAPI document                 ║");
     println!("║    GET  /api/version     - Server version info               ║");
+    println!("║    POST /api/mcp         - MCP server (GET /api/mcp/sse)     ║");
+    println!("║    GET  /api/transcripts - Transcript downloads (gzip)       ║");
+    println!("║    GET  /api/recordings  - Asciicast terminal recordings     ║");
     println!("║    GET  /ws/events       - WebSocket events                  ║");
     println!("║    GET  /ws/pty/:id      - WebSocket PTY terminal            ║");
+    println!("║    POST /hooks/:id       - Inbound webhook triggers          ║");
     println!("║    GET  /health          - Health check                      ║");
     println!("║                                                               ║");
     println!("╚══════════════════════════════════════════════════════════════╝\n");
 
-    let listener = TcpListener::bind(addr)
-        .await
-        .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
-
-    log::info!("Server listening on http://{}", addr);
+    if let Some(tls) = &tls {
+        println!("TLS certificate: {}", tls.cert_path.display());
+        println!("SHA-256 fingerprint (pin this on clients):");
+        println!("  {}\n", tls.fingerprint);
+    }
 
     // Create shutdown signal that waits for the shutdown state flag
     let shutdown_state = state.shutdown_state.clone();
@@ -152,17 +202,43 @@ pub async fn run_server(
         }
     };
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal)
-        .await
-        .map_err(|e| format!("Server error: {}", e))?;
+    match tls {
+        Some(tls) => {
+            // axum-server drives TLS; WebSocket upgrades work the same as over HTTP
+            let handle = axum_server::Handle::new();
+            let shutdown_handle = handle.clone();
+            tokio::spawn(async move {
+                shutdown_signal.await;
+                shutdown_handle.graceful_shutdown(Some(std::time::Duration::from_secs(10)));
+            });
+
+            log::info!("Server listening on https://{}", addr);
+            axum_server::bind_rustls(addr, RustlsConfig::from_config(tls.server_config))
+                .handle(handle)
+                .serve(app.into_make_service())
+                .await
+                .map_err(|e| format!("Server error: {}", e))?;
+        }
+        None => {
+            let listener = TcpListener::bind(addr)
+                .await
+                .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
+
+            log::info!("Server listening on http://{}", addr);
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown_signal)
+                .await
+                .map_err(|e| format!("Server error: {}", e))?;
+        }
+    }
 
     // Perform cleanup before exit
     let cleanup_result = perform_cleanup(&state).await;
     match cleanup_result {
         Ok(result) => {
             log::info!(
-                "Cleanup successful: {} agents stopped, {} worktrees cleaned",
+                "Cleanup successful: {} loops checkpointed, {} agents stopped, {} worktrees cleaned",
+                result.loops_checkpointed,
                 result.agents_stopped,
                 result.worktrees_cleaned
             );
@@ -175,6 +251,29 @@ pub async fn run_server(
     Ok(())
 }
 
+/// Resume loops checkpointed at the last shutdown under their execution IDs
+pub async fn resume_checkpointed_loops(
+    state: &ServerAppState,
+    loops: Vec<crate::commands::ralph_loop::ResumableExecution>,
+) {
+    for resumable in loops {
+        if let Err(e) = routes::resume_ralph_loop_server(
+            resumable.project_path.clone(),
+            resumable.execution_id.clone(),
+            state,
+        )
+        .await
+        {
+            log::warn!(
+                "Failed to resume loop {} ({}): {}. Resume it with resume_ralph_loop.",
+                resumable.execution_id,
+                resumable.prd_name,
+                e
+            );
+        }
+    }
+}
+
 /// Perform cleanup before the server exits
 async fn perform_cleanup(
     state: &ServerAppState,
@@ -182,7 +281,11 @@ async fn perform_cleanup(
     log::info!("Performing pre-exit cleanup...");
     let mut result = crate::shutdown::ShutdownResult::new();
 
-    // 1. Stop all agents
+    // 1. Let running loops finish (or abort) their iteration and checkpoint them
+    result.loops_checkpointed =
+        routes::checkpoint_ralph_loops_server(state, LOOP_SHUTDOWN_GRACE).await;
+
+    // 2. Stop all remaining agents
     {
         let mut agent_manager = state
             .agent_manager
@@ -196,18 +299,7 @@ async fn perform_cleanup(
         }
     }
 
-    // 2. Clean up worktrees
-    // We iterate over all repositories managed by GitState
-    // and prune any orphaned worktrees or explicitly clean up if needed.
-    // Note: Most worktrees are managed by Ralph Loop and should be cleaned up by it,
-    // but a global prune ensures nothing is left behind.
-    /*
-    {
-        // GitState doesn't expose list of repos easily, but we can prune orphaned ones
-        // if we have a way to iterate. For now, we'll rely on the fact that
-        // ralph_loop_state might have info, or just stop at agents for now.
-    }
-    */
+    // Worktrees are kept: checkpointed loops continue in them when resumed
 
     Ok(result)
 }
@@ -0,0 +1,582 @@
+//! Roles, permissions and the classification of server endpoints
+//!
+//! Every invoke command, REST route and WebSocket channel maps to the
+//! permission it requires. Roles are cumulative: operators can do everything
+//! viewers can, admins everything operators can.
+
+use crate::file_storage::project_archive::ExportOptions;
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Permission required to use an endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// Observe state: read PRDs, iterations, logs, events
+    Read,
+    /// Drive work: start/stop loops, chat, edit stories, use terminals
+    Operate,
+    /// Destructive or server-wide changes: delete data, push, configuration, tokens
+    Admin,
+}
+
+/// Role carried by an API token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiTokenRole {
+    Viewer,
+    Operator,
+    Admin,
+}
+
+impl ApiTokenRole {
+    /// Highest permission this role grants
+    pub fn max_permission(self) -> Permission {
+        match self {
+            ApiTokenRole::Viewer => Permission::Read,
+            ApiTokenRole::Operator => Permission::Operate,
+            ApiTokenRole::Admin => Permission::Admin,
+        }
+    }
+
+    pub fn grants(self, permission: Permission) -> bool {
+        permission <= self.max_permission()
+    }
+}
+
+impl std::fmt::Display for ApiTokenRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiTokenRole::Viewer => write!(f, "viewer"),
+            ApiTokenRole::Operator => write!(f, "operator"),
+            ApiTokenRole::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+impl std::str::FromStr for ApiTokenRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "viewer" => Ok(ApiTokenRole::Viewer),
+            "operator" => Ok(ApiTokenRole::Operator),
+            "admin" => Ok(ApiTokenRole::Admin),
+            _ => Err(format!(
+                "Invalid role: {} (expected viewer, operator or admin)",
+                s
+            )),
+        }
+    }
+}
+
+/// Commands that only read state. Anything not listed here (or in the admin
+/// lists) requires Operate, so a new command never gets Read from its name.
+const READ_COMMANDS: &[&str] = &[
+    // Sessions and tasks
+    "get_session",
+    "get_sessions",
+    "get_sessions_index",
+    "get_task",
+    "get_tasks_for_session",
+    // Agents
+    "agent_has_pty",
+    "get_active_agents",
+    "get_agent",
+    "get_agent_logs",
+    "get_agent_pty_id",
+    "get_agents_for_session",
+    "get_agents_for_task",
+    "get_all_active_agents",
+    "get_all_agents_status",
+    "get_rate_limits",
+    // Git and GitHub
+    "git_check_merge_conflicts",
+    "git_get_commit",
+    "git_get_commit_history",
+    "git_get_conflict_details",
+    "git_get_current_branch",
+    "git_get_diff",
+    "git_get_status",
+    "git_get_working_diff",
+    "git_is_repository",
+    "git_list_branches",
+    "git_list_worktrees",
+    "github_get_issue",
+    "github_get_pull_request",
+    "github_list_issues",
+    "github_list_pull_requests",
+    // Ralph loop
+    "analyze_ralph_prd_stories",
+    "export_ralph_learnings",
+    "get_all_ralph_iterations",
+    "get_ralph_assignments",
+    "get_ralph_brief",
+    "get_ralph_config",
+    "get_ralph_files",
+    "get_ralph_files_in_use",
+    "get_ralph_historical_briefs",
+    "get_ralph_iteration_history",
+    "get_ralph_iteration_stats",
+    "get_ralph_learnings",
+    "get_ralph_loop_current_agent",
+    "get_ralph_loop_metrics",
+    "get_ralph_loop_snapshot",
+    "get_ralph_loop_state",
+    "get_ralph_loop_worktree_path",
+    "get_ralph_prd",
+    "get_ralph_prd_status",
+    "get_ralph_progress",
+    "get_ralph_progress_summary",
+    "get_ralph_prompt",
+    "get_ralph_tool_analytics",
+    "has_ralph_files",
+    "list_ralph_loop_checkpoints",
+    "list_ralph_loop_executions",
+    "list_ralph_loop_executions_with_details",
+    "list_ralph_loop_leases",
+    "list_ralph_worktrees",
+    // PRDs, PRD chat and research
+    "check_agent_availability",
+    "detect_prd_from_history",
+    "get_guided_questions",
+    "get_prd_chat_history",
+    "get_prd_count",
+    "get_prd_file",
+    "get_prd_plan_content",
+    "get_research_progress",
+    "get_research_session",
+    "list_prd_chat_sessions",
+    "list_research_sessions",
+    "preview_prd_extraction",
+    "scan_prd_files",
+    // PRD workflows
+    "get_execution_order",
+    "get_prd_workflow",
+    "get_ready_requirements",
+    "get_research_files",
+    "list_prd_workflows",
+    "read_research_file_content",
+    "validate_dependencies",
+    // Context files and context chat
+    "get_context_chat_messages",
+    "get_context_chat_session",
+    "get_context_config",
+    "get_context_file",
+    "get_context_files",
+    "get_context_for_injection",
+    "get_default_context_template",
+    "get_project_context",
+    "has_context_files",
+    "list_context_chat_sessions",
+    // Chat commands
+    "is_chat_command_modified",
+    "list_chat_commands",
+    // Config, projects, templates and misc
+    "check_stale_sessions",
+    "export_project_state",
+    "get_active_provider",
+    "get_activity_feed",
+    "get_activity_page",
+    "get_all_folders",
+    "get_all_projects",
+    "get_api_providers",
+    "get_available_models",
+    "get_config",
+    "get_config_paths_cmd",
+    "get_favorite_projects",
+    "get_global_stats",
+    "get_home_directory",
+    "get_migration_report",
+    "get_project",
+    "get_project_by_path",
+    "get_push_settings",
+    "get_push_subscription_count",
+    "get_recent_projects",
+    "get_session_lock_info",
+    "get_subagent_events",
+    "get_subagent_summary",
+    "get_subagent_tree",
+    "get_template_content",
+    "get_vapid_public_key",
+    "is_subagent_active",
+    "list_builtin_templates",
+    "list_directory",
+    "list_fleet_executions",
+    "list_push_subscriptions",
+    "list_templates",
+    "parse_agent_output",
+    "preview_template",
+    "render_task_prompt",
+    "render_template",
+    // Transcripts and traces
+    "download_transcript",
+    "export_chrome_trace",
+    "get_transcript_page",
+    "get_transcript_tool_calls",
+    "list_transcripts",
+    // Search, share links and federation
+    "search",
+    "list_share_links",
+    "get_federation_status",
+];
+
+/// Commands that destroy data, reach outside the server or change server-wide settings
+const ADMIN_PREFIXES: &[&str] = &["delete_", "cleanup_", "prune_"];
+
+const ADMIN_COMMANDS: &[&str] = &[
+    "git_push_branch",
+    "git_delete_branch",
+    "git_remove_worktree",
+    "git_merge_branch",
+    "git_complete_merge",
+    "git_init_repository",
+    "github_create_pull_request",
+    "clear_ralph_progress",
+    "clear_trace_data",
+    "save_config",
+    "reload_config",
+    "set_config_project_path",
+    "update_execution_config",
+    "update_fallback_config",
+    "update_git_config",
+    "update_validation_config",
+    "update_research_config",
+    "set_provider_token",
+    "set_active_provider",
+    "update_push_settings",
+    "create_filesystem_directory",
+    "run_migrations",
+    "import_project_state",
+    "create_api_token",
+    "revoke_api_token",
+    "list_api_tokens",
+    "query_audit_log",
+    "list_webhooks",
+    "create_webhook",
+    "update_webhook",
+    "test_webhook",
+    "list_webhook_dead_letters",
+    "list_triggers",
+    "create_trigger",
+    "update_trigger",
+    // Peer URLs and tokens reach other servers
+    "list_federation_peers",
+    "add_federation_peer",
+    "remove_federation_peer",
+    // Recordings and PTY history hold everything shown in (and possibly typed into) any terminal
+    "list_recordings",
+    "get_recording",
+    "get_agent_pty_history",
+];
+
+/// Endpoints project-scoped tokens may use without naming a project: they
+/// return no project data, or filter what they return by the token's scope
+const PROJECT_FREE_ENDPOINTS: &[&str] = &[
+    // Filtered per event / per tool call
+    "/ws/events",
+    "/ws/federation/events",
+    "/api/mcp",
+    "/api/mcp/sse",
+    "/api/mcp/messages",
+    "GET /api/openapi.json",
+    "GET /api/version",
+    "get_available_models",
+    "get_api_providers",
+    "get_active_provider",
+    "check_agent_availability",
+    "list_builtin_templates",
+    "get_default_context_template",
+    "get_vapid_public_key",
+    "parse_agent_output",
+];
+
+/// Commands a share link can call, limited to its execution and PRD
+pub const SHARE_LINK_COMMANDS: &[&str] = &[
+    "get_ralph_loop_snapshot",
+    "get_ralph_loop_state",
+    "get_ralph_loop_metrics",
+    "get_ralph_iteration_history",
+    "get_ralph_iteration_stats",
+    "get_ralph_learnings",
+    "get_ralph_prd_status",
+];
+
+/// Permission required by an invoke command (unclassified commands require Operate)
+pub fn command_permission(cmd: &str) -> Permission {
+    if ADMIN_COMMANDS.contains(&cmd) || ADMIN_PREFIXES.iter().any(|p| cmd.starts_with(p)) {
+        Permission::Admin
+    } else if !is_state_changing(cmd) {
+        Permission::Read
+    } else {
+        Permission::Operate
+    }
+}
+
+/// Permission required by an invoke command called with these arguments:
+/// exports of transcripts or attachments need more than read access
+pub fn invoke_permission(cmd: &str, args: &Value) -> Permission {
+    let permission = command_permission(cmd);
+    if cmd != "export_project_state" {
+        return permission;
+    }
+    let opt_in = match args.get("options") {
+        None | Some(Value::Null) => false,
+        // Options the command would reject can't be checked, so don't trust them
+        Some(options) => serde_json::from_value::<ExportOptions>(options.clone())
+            .map_or(true, |options| options.includes_opt_in_sections()),
+    };
+    if opt_in {
+        permission.max(Permission::Operate)
+    } else {
+        permission
+    }
+}
+
+/// Whether an invoke command changes state (anything not on the read
+/// allowlist); these are the commands recorded in the audit log
+pub fn is_state_changing(cmd: &str) -> bool {
+    !READ_COMMANDS.contains(&cmd)
+}
+
+/// Whether a project-scoped token may use an endpoint that names no project
+/// (an invoke command, `<METHOD> <path>` route or WebSocket path)
+pub fn is_project_free(endpoint: &str) -> bool {
+    PROJECT_FREE_ENDPOINTS.contains(&endpoint)
+}
+
+/// Permission required by a REST or download route, by HTTP method
+pub fn http_permission(method: &Method) -> Permission {
+    match *method {
+        Method::GET | Method::HEAD => Permission::Read,
+        Method::DELETE => Permission::Admin,
+        _ => Permission::Operate,
+    }
+}
+
+/// Permission required by a WebSocket channel
+pub fn websocket_permission(path: &str) -> Permission {
+    if path.starts_with("/ws/pty") {
+        // Terminals accept input
+        Permission::Operate
+    } else {
+        Permission::Read
+    }
+}
+
+/// Argument names that carry the project (or repository) a command acts on
+const PROJECT_ARG_KEYS: &[&str] = &["projectPath", "repoPath", "worktreePath", "path"];
+
+/// Collect project paths referenced by invoke arguments (top level and one
+/// level of nesting, e.g. `request.projectPath`)
+pub fn project_paths_in_args(args: &Value) -> Vec<String> {
+    let mut paths = Vec::new();
+    let Some(object) = args.as_object() else {
+        return paths;
+    };
+
+    for value in std::iter::once(args).chain(object.values().filter(|v| v.is_object())) {
+        for key in PROJECT_ARG_KEYS {
+            if let Some(path) = value.get(*key).and_then(|v| v.as_str()) {
+                paths.push(path.to_string());
+            }
+        }
+    }
+    paths
+}
+
+/// Whether `path` is one of the scoped projects or inside one
+/// (paths with `..` components are never in scope)
+pub fn path_in_scope(path: &str, scopes: &[String]) -> bool {
+    if std::path::Path::new(path)
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return false;
+    }
+    let path = path.trim_end_matches('/');
+    scopes.iter().any(|scope| {
+        let scope = scope.trim_end_matches('/');
+        path == scope
+            || path
+                .strip_prefix(scope)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_command_permission() {
+        assert_eq!(command_permission("get_ralph_prd"), Permission::Read);
+        assert_eq!(command_permission("git_get_status"), Permission::Read);
+        assert_eq!(command_permission("start_ralph_loop"), Permission::Operate);
+        assert_eq!(command_permission("add_ralph_story"), Permission::Operate);
+        assert_eq!(command_permission("delete_project"), Permission::Admin);
+        assert_eq!(command_permission("git_push_branch"), Permission::Admin);
+        assert_eq!(command_permission("create_api_token"), Permission::Admin);
+        assert_eq!(command_permission("create_share_link"), Permission::Operate);
+        assert_eq!(
+            command_permission("get_agent_pty_history"),
+            Permission::Admin
+        );
+        assert_eq!(
+            command_permission("import_project_state"),
+            Permission::Admin
+        );
+    }
+
+    #[test]
+    fn test_export_with_opt_in_sections_needs_operate() {
+        let export = |options: Value| {
+            invoke_permission(
+                "export_project_state",
+                &json!({ "projectPath": "/p", "options": options }),
+            )
+        };
+        assert_eq!(export(Value::Null), Permission::Read);
+        assert_eq!(export(json!({ "sections": ["prds"] })), Permission::Read);
+        assert_eq!(
+            export(json!({ "includeTranscripts": true })),
+            Permission::Operate
+        );
+        assert_eq!(
+            export(json!({ "includeAttachments": true })),
+            Permission::Operate
+        );
+        assert_eq!(
+            export(json!({ "sections": ["prds", "transcripts"] })),
+            Permission::Operate
+        );
+        assert_eq!(export(json!({ "sections": "all" })), Permission::Operate);
+        assert_eq!(
+            invoke_permission("import_project_state", &json!({})),
+            Permission::Admin
+        );
+    }
+
+    /// Every command the invoke router dispatches, found among the string
+    /// literals of the route modules
+    fn routed_commands() -> Vec<String> {
+        use crate::server::routes::*;
+
+        const SOURCES: &[&str] = &[
+            include_str!("routes/agent_routes.rs"),
+            include_str!("routes/api_token_routes.rs"),
+            include_str!("routes/audit_routes.rs"),
+            include_str!("routes/chat_command_routes.rs"),
+            include_str!("routes/config_routes.rs"),
+            include_str!("routes/context_routes.rs"),
+            include_str!("routes/federation_routes.rs"),
+            include_str!("routes/git_routes.rs"),
+            include_str!("routes/prd_routes.rs"),
+            include_str!("routes/prd_workflow_routes.rs"),
+            include_str!("routes/ralph_loop_routes.rs"),
+            include_str!("routes/recording_routes.rs"),
+            include_str!("routes/search_routes.rs"),
+            include_str!("routes/session_routes.rs"),
+            include_str!("routes/share_link_routes.rs"),
+            include_str!("routes/task_routes.rs"),
+            include_str!("routes/transcript_routes.rs"),
+            include_str!("routes/trigger_routes.rs"),
+            include_str!("routes/webhook_routes.rs"),
+        ];
+        let is_routed = |cmd: &str| {
+            session_routes::is_session_command(cmd)
+                || task_routes::is_task_command(cmd)
+                || agent_routes::is_agent_command(cmd)
+                || git_routes::is_git_command(cmd)
+                || ralph_loop_routes::is_ralph_loop_command(cmd)
+                || prd_routes::is_prd_command(cmd)
+                || prd_workflow_routes::is_prd_workflow_command(cmd)
+                || config_routes::is_config_command(cmd)
+                || chat_command_routes::is_chat_command_route(cmd)
+                || context_routes::is_context_command(cmd)
+                || transcript_routes::is_transcript_command(cmd)
+                || recording_routes::is_recording_command(cmd)
+                || search_routes::is_search_command(cmd)
+                || api_token_routes::is_api_token_command(cmd)
+                || share_link_routes::is_share_link_command(cmd)
+                || audit_routes::is_audit_command(cmd)
+                || webhook_routes::is_webhook_command(cmd)
+                || federation_routes::is_federation_command(cmd)
+                || trigger_routes::is_trigger_command(cmd)
+        };
+
+        let literal = regex::Regex::new(r#""([a-z][a-z0-9_]*)""#).unwrap();
+        let mut commands: Vec<String> = SOURCES
+            .iter()
+            .flat_map(|source| literal.captures_iter(source))
+            .map(|c| c[1].to_string())
+            .filter(|cmd| is_routed(cmd))
+            .collect();
+        commands.sort();
+        commands.dedup();
+        commands
+    }
+
+    #[test]
+    fn test_only_allowlisted_commands_are_read() {
+        let commands = routed_commands();
+        assert!(commands.len() > 200, "found {} commands", commands.len());
+
+        for cmd in &commands {
+            assert_eq!(
+                command_permission(cmd) == Permission::Read,
+                READ_COMMANDS.contains(&cmd.as_str()),
+                "{}",
+                cmd
+            );
+        }
+        for cmd in READ_COMMANDS {
+            assert!(commands.iter().any(|c| c == cmd), "{} is not routed", cmd);
+        }
+
+        // Named like reads, but they write
+        assert_eq!(
+            command_permission("export_workflow_to_prd"),
+            Permission::Operate
+        );
+        assert_eq!(
+            command_permission("check_stale_ralph_executions"),
+            Permission::Operate
+        );
+        assert_eq!(
+            command_permission("assess_prd_quality"),
+            Permission::Operate
+        );
+    }
+
+    #[test]
+    fn test_role_grants() {
+        assert!(ApiTokenRole::Viewer.grants(Permission::Read));
+        assert!(!ApiTokenRole::Viewer.grants(Permission::Operate));
+        assert!(ApiTokenRole::Operator.grants(Permission::Operate));
+        assert!(!ApiTokenRole::Operator.grants(Permission::Admin));
+        assert!(ApiTokenRole::Admin.grants(Permission::Admin));
+    }
+
+    #[test]
+    fn test_project_paths_in_args() {
+        let args = json!({
+            "projectPath": "/work/a",
+            "request": { "projectPath": "/work/b", "agentType": "claude" },
+            "limit": 5
+        });
+        assert_eq!(project_paths_in_args(&args), vec!["/work/a", "/work/b"]);
+    }
+
+    #[test]
+    fn test_path_in_scope() {
+        let scopes = vec!["/work/app/".to_string()];
+        assert!(path_in_scope("/work/app", &scopes));
+        assert!(path_in_scope("/work/app/.worktrees/x", &scopes));
+        assert!(!path_in_scope("/work/application", &scopes));
+        assert!(!path_in_scope("/work", &scopes));
+        assert!(!path_in_scope("/work/app/../other", &scopes));
+    }
+}
@@ -16,9 +16,9 @@
 
 use super::events::EventBroadcaster;
 use super::routes;
-use super::ServerAppState;
+use super::{AuthIdentity, ServerAppState};
 use axum::{
-    extract::State,
+    extract::{Extension, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
@@ -75,11 +75,13 @@ impl IntoResponse for InvokeError {
 /// Main invoke handler - routes commands to their implementations
 pub async fn invoke_handler(
     State(state): State<ServerAppState>,
+    identity: Option<Extension<AuthIdentity>>,
     Json(req): Json<InvokeRequest>,
 ) -> Result<Json<InvokeResponse>, InvokeError> {
     log::debug!("Invoke command: {} with args: {:?}", req.cmd, req.args);
 
-    let result = routes::route_command(&req.cmd, req.args, &state).await;
+    let identity = identity.map(|Extension(identity)| identity);
+    let result = routes::route_command(&req.cmd, req.args, &state, identity.as_ref()).await;
 
     match result {
         Ok(data) => Ok(Json(InvokeResponse {
@@ -539,27 +541,91 @@ fn parse_ai_story_response(
 // become frequent.
 // =============================================================================
 
+/// Parse the agent type of a Ralph loop request (loops support a subset of agents)
+pub fn parse_loop_agent_type(agent_type: &str) -> Result<crate::models::AgentType, String> {
+    use crate::models::AgentType;
+
+    match agent_type.to_lowercase().as_str() {
+        "claude" => Ok(AgentType::Claude),
+        "opencode" => Ok(AgentType::Opencode),
+        "cursor" => Ok(AgentType::Cursor),
+        "codex" => Ok(AgentType::Codex),
+        _ => Err(format!("Unknown agent type: {}", agent_type)),
+    }
+}
+
 /// Server-compatible version of start_ralph_loop that uses EventBroadcaster
 /// instead of the old's app_handle for events.
 pub async fn start_ralph_loop_server(
     request: crate::commands::ralph_loop::StartRalphLoopRequest,
     state: &ServerAppState,
 ) -> Result<String, String> {
+    run_ralph_loop_server(request, state, None, None).await
+}
+
+/// Resume a loop checkpointed at server shutdown under its execution ID
+pub async fn resume_ralph_loop_server(
+    project_path: String,
+    execution_id: String,
+    state: &ServerAppState,
+) -> Result<String, String> {
+    use crate::file_storage::iterations as iteration_storage;
+
+    if state
+        .ralph_loop_state
+        .get_execution(&execution_id)?
+        .is_some()
+    {
+        return Err(format!("Execution {} is already running", execution_id));
+    }
+    let checkpoint =
+        iteration_storage::get_execution_state(Path::new(&project_path), &execution_id)?
+            .and_then(|snapshot| snapshot.checkpoint)
+            .ok_or_else(|| format!("No checkpoint for execution {}", execution_id))?;
+
+    log::info!(
+        "[RalphLoop] Resuming execution {} ({}) after iteration {}",
+        execution_id,
+        checkpoint.request.prd_name,
+        checkpoint.iteration
+    );
+    run_ralph_loop_server(
+        checkpoint.request,
+        state,
+        Some(execution_id),
+        checkpoint.worktree_path,
+    )
+    .await
+}
+
+/// Start a loop, or continue a checkpointed one when `resume_execution_id` is set
+/// (in the worktree it was checkpointed in, if it still exists)
+async fn run_ralph_loop_server(
+    request: crate::commands::ralph_loop::StartRalphLoopRequest,
+    state: &ServerAppState,
+    resume_execution_id: Option<String>,
+    resume_worktree: Option<String>,
+) -> Result<String, String> {
+    use crate::commands::ralph_loop::LoopControl;
     use crate::events::{
         RalphLoopCompletedPayload, RalphLoopErrorPayload, RalphLoopErrorType,
         EVENT_RALPH_LOOP_COMPLETED, EVENT_RALPH_LOOP_ERROR,
     };
     use crate::file_storage::iterations as iteration_storage;
-    use crate::models::AgentType;
     use crate::ralph_loop::{
-        ErrorStrategy, ExecutionStateSnapshot, FallbackChainConfig, PrdExecutor, PrdMetadata,
-        RalphLoopConfig, RalphLoopOrchestrator, RalphLoopState as RalphLoopExecutionState,
-        RetryConfig,
+        ErrorStrategy, ExecutionCheckpoint, ExecutionStateSnapshot, LeaseKeeper, PrdExecutor,
+        PrdMetadata, RalphLoopConfig, RalphLoopOrchestrator,
+        RalphLoopState as RalphLoopExecutionState, RetryConfig,
     };
+    use crate::utils::lock_mutex_recover;
     use std::path::PathBuf;
 
     const HEARTBEAT_INTERVAL_SECS: u64 = 5;
 
+    // Kept for the checkpoint written if the server shuts down mid-loop
+    let checkpoint_request = request.clone();
+    let takeover = request.takeover.unwrap_or(false);
+
     // Read PRD to get stored execution config
     let project_path_buf = PathBuf::from(&request.project_path);
     let executor = PrdExecutor::new(&project_path_buf, &request.prd_name);
@@ -584,14 +650,7 @@ pub async fn start_ralph_loop_server(
         }
     );
 
-    // Parse agent type
-    let agent_type = match request.agent_type.to_lowercase().as_str() {
-        "claude" => AgentType::Claude,
-        "opencode" => AgentType::Opencode,
-        "cursor" => AgentType::Cursor,
-        "codex" => AgentType::Codex,
-        _ => return Err(format!("Unknown agent type: {}", request.agent_type)),
-    };
+    let agent_type = parse_loop_agent_type(&request.agent_type)?;
 
     // Resolve config values with precedence: request > prd > global > default
     let resolved_model = request
@@ -685,38 +744,18 @@ pub async fn start_ralph_loop_server(
 
     // Get fallback config from user settings
     let fallback_config = user_config.as_ref().and_then(|config| {
-        if config.fallback.enabled {
-            let fallback_chain = config
-                .fallback
-                .fallback_chain
-                .clone()
-                .map(|chain| {
-                    chain
-                        .into_iter()
-                        .filter_map(|s| match s.to_lowercase().as_str() {
-                            "claude" => Some(AgentType::Claude),
-                            "opencode" => Some(AgentType::Opencode),
-                            "cursor" => Some(AgentType::Cursor),
-                            "codex" => Some(AgentType::Codex),
-                            _ => None,
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .unwrap_or_else(|| vec![agent_type]);
-
-            Some(FallbackChainConfig {
-                fallback_chain,
-                test_primary_recovery: config.fallback.test_primary_recovery.unwrap_or(true),
-                recovery_test_interval: config.fallback.recovery_test_interval.unwrap_or(5),
-                base_backoff_ms: config.fallback.base_backoff_ms,
-                max_backoff_ms: config.fallback.max_backoff_ms,
-                enabled: true,
-            })
-        } else {
-            None
-        }
+        crate::commands::ralph_loop::fallback_chain_config(&config.fallback, agent_type)
     });
 
+    let transcript_settings = user_config
+        .as_ref()
+        .map(|c| c.transcripts.clone())
+        .unwrap_or_default();
+    let recording_settings = user_config
+        .as_ref()
+        .map(|c| c.recordings.clone())
+        .unwrap_or_default();
+
     // Convert ErrorStrategyConfig from user settings to ErrorStrategy
     let error_strategy = user_config
         .and_then(|c| c.fallback.error_strategy)
@@ -768,8 +807,18 @@ pub async fn start_ralph_loop_server(
         env_vars,
         execution_mode: request.execution_mode.unwrap_or_default(),
         max_parallel: request.max_parallel.unwrap_or(3),
+        transcripts: transcript_settings,
+        recordings: recording_settings,
     };
 
+    // Refuse to start on missing/unauthenticated CLIs or an unusable git tree
+    crate::commands::ralph_loop::ensure_preflight(
+        &config,
+        state.model_cache_state.clone(),
+        request.force.unwrap_or(false),
+    )
+    .await?;
+
     // Check if parallel mode is requested
     use crate::commands::ralph_loop::RalphExecutionMode;
     let is_parallel_mode = matches!(config.execution_mode, RalphExecutionMode::Parallel);
@@ -787,14 +836,40 @@ pub async fn start_ralph_loop_server(
         // Use parallel orchestrator - extract needed values since request was partially moved
         let project_path = config.project_path.to_string_lossy().to_string();
         let prd_name = config.prd_name.clone();
-        return start_parallel_ralph_loop_server(config, prd, project_path, prd_name, state).await;
+        return start_parallel_ralph_loop_server(
+            config,
+            prd,
+            project_path,
+            prd_name,
+            takeover,
+            state,
+        )
+        .await;
     }
 
     // === SEQUENTIAL EXECUTION PATH ===
-    // Create orchestrator
-    let mut orchestrator = RalphLoopOrchestrator::new(config.clone());
+    // Create orchestrator (reusing the execution ID when resuming)
+    let mut orchestrator = match resume_execution_id {
+        Some(execution_id) => {
+            RalphLoopOrchestrator::with_execution_id(config.clone(), execution_id)
+        }
+        None => RalphLoopOrchestrator::new(config.clone()),
+    };
+    if let Some(worktree_path) = resume_worktree {
+        orchestrator.set_resume_worktree(PathBuf::from(worktree_path));
+    }
+    orchestrator.set_rate_limit_ledger(crate::agents::rate_limit_ledger::global());
     let execution_id = orchestrator.execution_id().to_string();
 
+    // Refuse to run a PRD another loop (possibly in another process) is running
+    let lease = LeaseKeeper::acquire(
+        project_path_buf.clone(),
+        &request.prd_name,
+        &execution_id,
+        takeover,
+        orchestrator.get_cancel_handle(),
+    )?;
+
     // Get shared snapshots Arc and pass to orchestrator
     let snapshots_arc = state.ralph_loop_state.snapshots_arc();
     orchestrator.set_snapshot_store(snapshots_arc.clone());
@@ -841,16 +916,30 @@ pub async fn start_ralph_loop_server(
             execution_id: execution_id.clone(),
             state: serde_json::to_string(&RalphLoopExecutionState::Idle).unwrap_or_default(),
             last_heartbeat: chrono::Utc::now().to_rfc3339(),
+            checkpoint: None,
         };
         iteration_storage::save_execution_state(&project_path_buf, &initial_state)
             .map_err(|e| format!("Failed to save initial execution state: {}", e))?;
     }
 
+    // Register stop flags (the loop task holds the orchestrator lock while running)
+    let checkpoint_handle = orchestrator.get_checkpoint_handle();
+    state.ralph_loop_state.insert_control(
+        execution_id.clone(),
+        LoopControl {
+            cancel: orchestrator.get_cancel_handle(),
+            checkpoint: Some(checkpoint_handle.clone()),
+        },
+    );
+
     // Store orchestrator in state
     let orchestrator_arc = Arc::new(tokio::sync::Mutex::new(orchestrator));
     state
         .ralph_loop_state
         .insert_execution(execution_id.clone(), orchestrator_arc.clone())?;
+    state
+        .broadcaster
+        .register_execution_project(&execution_id, &request.project_path);
 
     // Clone the agent manager Arc for the spawned task
     let agent_manager_arc = state.agent_manager.clone();
@@ -879,6 +968,7 @@ pub async fn start_ralph_loop_server(
                     orchestrator.state(),
                     RalphLoopExecutionState::Running { .. }
                         | RalphLoopExecutionState::Retrying { .. }
+                        | RalphLoopExecutionState::Paused { .. }
                 )
             };
 
@@ -910,8 +1000,11 @@ pub async fn start_ralph_loop_server(
     let project_path_for_loop = project_path_buf.clone();
     let prd_name_for_loop = request.prd_name.clone();
     let broadcaster = state.broadcaster.clone();
+    let loop_state = state.ralph_loop_state.clone();
 
     tokio::spawn(async move {
+        // Released when the loop task ends
+        let _lease = lease;
         log::info!(
             "[RalphLoop] Background task started for {} (server mode)",
             execution_id_for_loop
@@ -920,7 +1013,55 @@ pub async fn start_ralph_loop_server(
         let mut orchestrator = orchestrator_arc.lock().await;
         let result = orchestrator.run(agent_manager_arc).await;
 
-        // Clean up execution state from file storage
+        // A loop stopped for a shutdown checkpoint keeps its state for resuming;
+        // otherwise clean up execution state from file storage
+        let checkpointed_at = match orchestrator.state() {
+            RalphLoopExecutionState::Cancelled { iteration }
+                if *lock_mutex_recover(&checkpoint_handle) =>
+            {
+                Some(*iteration)
+            }
+            _ => None,
+        };
+        if let Some(iteration) = checkpointed_at {
+            let now = chrono::Utc::now().to_rfc3339();
+            let snapshot = ExecutionStateSnapshot {
+                execution_id: execution_id_for_loop.clone(),
+                state: serde_json::to_string(orchestrator.state()).unwrap_or_default(),
+                last_heartbeat: now.clone(),
+                checkpoint: Some(ExecutionCheckpoint {
+                    request: checkpoint_request,
+                    iteration,
+                    worktree_path: orchestrator
+                        .worktree_path()
+                        .map(|p| p.to_string_lossy().to_string()),
+                    checkpointed_at: now.clone(),
+                }),
+            };
+            let saved = iteration_storage::mark_interrupted_iterations(
+                &project_path_for_loop,
+                &execution_id_for_loop,
+                &now,
+            )
+            .and_then(|_| {
+                iteration_storage::save_execution_state(&project_path_for_loop, &snapshot)
+            });
+            match saved {
+                Ok(()) => log::info!(
+                    "[RalphLoop] Checkpointed execution {} at iteration {} for resume",
+                    execution_id_for_loop,
+                    iteration
+                ),
+                Err(e) => log::warn!(
+                    "[RalphLoop] Failed to checkpoint execution {}: {}",
+                    execution_id_for_loop,
+                    e
+                ),
+            }
+            loop_state.remove_control(&execution_id_for_loop);
+            broadcaster.unregister_execution_project(&execution_id_for_loop);
+            return;
+        }
         if let Err(e) = iteration_storage::delete_execution_state(
             &project_path_for_loop,
             &execution_id_for_loop,
@@ -1067,6 +1208,8 @@ pub async fn start_ralph_loop_server(
                 broadcaster.broadcast(EVENT_RALPH_LOOP_ERROR, payload);
             }
         }
+        loop_state.remove_control(&execution_id_for_loop);
+        broadcaster.unregister_execution_project(&execution_id_for_loop);
     });
 
     Ok(execution_id)
@@ -1078,6 +1221,7 @@ async fn start_parallel_ralph_loop_server(
     mut prd: crate::ralph_loop::RalphPrd,
     project_path: String,
     prd_name: String,
+    takeover: bool,
     state: &ServerAppState,
 ) -> Result<String, String> {
     use crate::events::{
@@ -1086,8 +1230,8 @@ async fn start_parallel_ralph_loop_server(
     };
     use crate::file_storage::iterations as iteration_storage;
     use crate::ralph_loop::{
-        ExecutionSnapshot, ExecutionStateSnapshot, ParallelOrchestrator, PrdExecutor, PrdMetadata,
-        RalphLoopState as RalphLoopExecutionState,
+        ExecutionSnapshot, ExecutionStateSnapshot, LeaseKeeper, ParallelOrchestrator, PrdExecutor,
+        PrdMetadata, RalphLoopState as RalphLoopExecutionState,
     };
     use std::path::PathBuf;
 
@@ -1097,6 +1241,13 @@ async fn start_parallel_ralph_loop_server(
     // Create parallel orchestrator
     let mut orchestrator = ParallelOrchestrator::new(config.clone(), max_parallel);
     let execution_id = orchestrator.execution_id().to_string();
+    let lease = LeaseKeeper::acquire(
+        project_path_buf.clone(),
+        &prd_name,
+        &execution_id,
+        takeover,
+        orchestrator.get_cancel_handle(),
+    )?;
 
     log::info!(
         "[start_parallel_ralph_loop_server] Created ParallelOrchestrator {} with max {} agents",
@@ -1151,13 +1302,24 @@ async fn start_parallel_ralph_loop_server(
             execution_id: execution_id.clone(),
             state: serde_json::to_string(&RalphLoopExecutionState::Idle).unwrap_or_default(),
             last_heartbeat: chrono::Utc::now().to_rfc3339(),
+            checkpoint: None,
         };
         iteration_storage::save_execution_state(&project_path_buf, &initial_state)
             .map_err(|e| format!("Failed to save initial execution state: {}", e))?;
     }
 
-    // Get cancel handle
-    let _cancel_handle = orchestrator.get_cancel_handle();
+    state
+        .broadcaster
+        .register_execution_project(&execution_id, &project_path);
+
+    // Register the cancel flag so the loop can be stopped while it runs
+    state.ralph_loop_state.insert_control(
+        execution_id.clone(),
+        crate::commands::ralph_loop::LoopControl {
+            cancel: orchestrator.get_cancel_handle(),
+            checkpoint: None,
+        },
+    );
 
     // Clone for task
     let agent_manager_arc = state.agent_manager.clone();
@@ -1166,9 +1328,12 @@ async fn start_parallel_ralph_loop_server(
     let prd_name_for_loop = prd_name.clone();
     let broadcaster = state.broadcaster.clone();
     let snapshots_arc_for_loop = snapshots_arc.clone();
+    let loop_state = state.ralph_loop_state.clone();
 
     // Spawn parallel execution task
     tokio::spawn(async move {
+        // Released when the loop task ends
+        let _lease = lease;
         log::info!(
             "[ParallelRalphLoop] Background task started for {} (server mode)",
             execution_id_for_loop
@@ -1289,6 +1454,8 @@ async fn start_parallel_ralph_loop_server(
                 broadcaster.broadcast(EVENT_RALPH_LOOP_ERROR, payload);
             }
         }
+        loop_state.remove_control(&execution_id_for_loop);
+        broadcaster.unregister_execution_project(&execution_id_for_loop);
     });
 
     Ok(execution_id)
@@ -1301,6 +1468,12 @@ pub async fn stop_ralph_loop_server(
 ) -> Result<(), String> {
     use crate::ralph_loop::RalphLoopOrchestrator;
 
+    // The loop task holds the orchestrator lock while running, so use its flag
+    if let Some(control) = state.ralph_loop_state.get_control(&execution_id) {
+        *crate::utils::lock_mutex_recover(&control.cancel) = true;
+        return Ok(());
+    }
+
     let orchestrator_arc = state.ralph_loop_state.get_execution(&execution_id)?;
 
     if let Some(orchestrator_arc) = orchestrator_arc {
@@ -1313,6 +1486,94 @@ pub async fn stop_ralph_loop_server(
     }
 }
 
+/// How long an aborted loop gets to exit after its agent is killed
+const LOOP_ABORT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Stop running loops for a server shutdown, keeping resume checkpoints
+///
+/// Sequential loops stop after their current iteration; any still running
+/// after `grace` have the iteration aborted. Parallel loops are cancelled.
+/// Returns the number of loops checkpointed.
+pub async fn checkpoint_ralph_loops_server(
+    state: &ServerAppState,
+    grace: std::time::Duration,
+) -> usize {
+    use crate::file_storage::iterations as iteration_storage;
+    use crate::ralph_loop::RalphLoopState as RalphLoopExecutionState;
+    use crate::utils::lock_mutex_recover;
+
+    let running: Vec<_> = state
+        .ralph_loop_state
+        .controls()
+        .into_iter()
+        .filter_map(|(execution_id, control)| {
+            let snapshot = state.ralph_loop_state.get_snapshot(&execution_id)?;
+            let finished = matches!(
+                snapshot.state,
+                Some(
+                    RalphLoopExecutionState::Completed { .. }
+                        | RalphLoopExecutionState::Failed { .. }
+                        | RalphLoopExecutionState::Cancelled { .. }
+                )
+            );
+            (!finished).then_some((execution_id, control, snapshot.project_path))
+        })
+        .collect();
+    if running.is_empty() {
+        return 0;
+    }
+
+    log::info!(
+        "[RalphLoop] Stopping {} running loop(s) for shutdown (grace period {}s)",
+        running.len(),
+        grace.as_secs()
+    );
+    for (_, control, _) in &running {
+        match &control.checkpoint {
+            Some(checkpoint) => *lock_mutex_recover(checkpoint) = true,
+            None => *lock_mutex_recover(&control.cancel) = true,
+        }
+    }
+
+    // Loop tasks hold their orchestrator lock until they have finished
+    // (including writing the checkpoint), so acquiring it means the loop is done
+    let deadline = tokio::time::Instant::now() + grace;
+    let mut checkpointed = 0;
+    for (execution_id, control, project_path) in &running {
+        let Ok(Some(orchestrator)) = state.ralph_loop_state.get_execution(execution_id) else {
+            continue;
+        };
+        if tokio::time::timeout_at(deadline, orchestrator.lock())
+            .await
+            .is_err()
+        {
+            log::warn!(
+                "[RalphLoop] Loop {} did not finish its iteration in time, aborting it",
+                execution_id
+            );
+            *lock_mutex_recover(&control.cancel) = true;
+            if tokio::time::timeout(LOOP_ABORT_TIMEOUT, orchestrator.lock())
+                .await
+                .is_err()
+            {
+                log::warn!("[RalphLoop] Loop {} did not stop", execution_id);
+                continue;
+            }
+        }
+
+        let has_checkpoint = project_path.as_ref().is_some_and(|path| {
+            iteration_storage::get_execution_state(Path::new(path), execution_id)
+                .ok()
+                .flatten()
+                .is_some_and(|snapshot| snapshot.checkpoint.is_some())
+        });
+        if has_checkpoint {
+            checkpointed += 1;
+        }
+    }
+    checkpointed
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -2,11 +2,13 @@
 //!
 //! Provides interactive terminal sessions over WebSocket for browser clients.
 //! Supports session persistence and reconnection for mobile resilience (US-3, US-4).
+//! Several clients can share a session; only the one holding the write lock
+//! can type into it.
 
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        Path, Query, State,
+        Extension, Path, State,
     },
     response::IntoResponse,
 };
@@ -15,8 +17,13 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use super::pty_registry::SessionState;
+use super::audit;
+use super::auth::AuthIdentity;
+use super::permissions::Permission;
+use super::pty_registry::{PtySession, SessionState, TerminalViewer};
 use super::ServerAppState;
+use crate::file_storage::audit::AuditStatus;
+use crate::file_storage::recordings::{NewRecording, Recorder, RecordingSource};
 
 /// PTY session setup request (first message from client)
 #[derive(Debug, Deserialize)]
@@ -41,7 +48,20 @@ struct PtyResize {
 enum ClientMessage {
     Setup(PtySetup),
     Resize(PtyResize),
-    Input { data: String },
+    Input {
+        data: String,
+    },
+    /// Ask for the write lock (granted at once if nobody holds it)
+    RequestWrite,
+    /// Give up the write lock
+    ReleaseWrite,
+    /// Hand the write lock to another viewer (writer only)
+    #[serde(rename_all = "camelCase")]
+    GrantWrite {
+        viewer_id: String,
+    },
+    /// Take the write lock from its holder (admins only)
+    TakeWrite,
 }
 
 /// Response with session info
@@ -50,12 +70,9 @@ enum ClientMessage {
 struct SessionInfo {
     session_id: String,
     terminal_id: String,
-}
-
-/// Query parameters for PTY WebSocket
-#[derive(Debug, Deserialize)]
-pub struct PtyQuery {
-    token: Option<String>,
+    /// Set when the session is being recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recording_id: Option<String>,
 }
 
 /// Path parameters for reconnect endpoint
@@ -69,19 +86,11 @@ pub struct ReconnectPath {
 pub async fn pty_ws_handler(
     ws: WebSocketUpgrade,
     Path(terminal_id): Path<String>,
-    Query(query): Query<PtyQuery>,
     State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
 ) -> impl IntoResponse {
-    // Validate token from query parameter
-    if let Some(token) = query.token {
-        if token != state.auth_token {
-            return (axum::http::StatusCode::UNAUTHORIZED, "Invalid token").into_response();
-        }
-    } else {
-        return (axum::http::StatusCode::UNAUTHORIZED, "Missing token").into_response();
-    }
-
-    ws.on_upgrade(move |socket| handle_new_pty_session(socket, terminal_id, state))
+    // The token query parameter is validated (and its role checked) by the auth middleware
+    ws.on_upgrade(move |socket| handle_new_pty_session(socket, terminal_id, state, identity))
         .into_response()
 }
 
@@ -89,18 +98,9 @@ pub async fn pty_ws_handler(
 pub async fn pty_reconnect_handler(
     ws: WebSocketUpgrade,
     Path(path): Path<ReconnectPath>,
-    Query(query): Query<PtyQuery>,
     State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
 ) -> impl IntoResponse {
-    // Validate token
-    if let Some(token) = query.token {
-        if token != state.auth_token {
-            return (axum::http::StatusCode::UNAUTHORIZED, "Invalid token").into_response();
-        }
-    } else {
-        return (axum::http::StatusCode::UNAUTHORIZED, "Missing token").into_response();
-    }
-
     // Check if session exists
     let session = state.pty_registry.get_session(&path.session_id).await;
     if session.is_none() {
@@ -112,13 +112,82 @@ pub async fn pty_reconnect_handler(
     }
 
     ws.on_upgrade(move |socket| {
-        handle_pty_reconnection(socket, path.terminal_id, path.session_id, state)
+        handle_pty_reconnection(socket, path.terminal_id, path.session_id, state, identity)
     })
     .into_response()
 }
 
+/// Record a terminal session event in the audit log
+fn audit_session(identity: &AuthIdentity, command: &str, session: &PtySession) {
+    let args = serde_json::json!({
+        "terminalId": session.terminal_id,
+        "sessionId": session.id,
+        "cwd": session.cwd,
+    });
+    audit::record(
+        identity,
+        command,
+        session.cwd.clone(),
+        &args,
+        AuditStatus::Success,
+        None,
+    );
+}
+
+/// Start recording a new terminal session if the recording policy asks for it
+fn start_terminal_recording(
+    state: &ServerAppState,
+    terminal_id: &str,
+    setup: &PtySetup,
+) -> Option<Recorder> {
+    let settings = state.config_state.get_config().ok()?.recordings;
+    if !settings.terminals {
+        return None;
+    }
+
+    // Attribute the terminal to the registered project it was opened in
+    let project_path = setup.cwd.as_ref().and_then(|cwd| {
+        crate::file_storage::projects::get_all_projects()
+            .ok()?
+            .into_iter()
+            .map(|p| p.path)
+            .filter(|p| std::path::Path::new(cwd).starts_with(p))
+            .max_by_key(|p| p.len())
+    });
+    let title = match &setup.cwd {
+        Some(cwd) => format!("Terminal {} ({})", terminal_id, cwd),
+        None => format!("Terminal {}", terminal_id),
+    };
+    let recording = NewRecording {
+        source: RecordingSource::Terminal,
+        source_id: terminal_id.to_string(),
+        title,
+        project_path,
+        execution_id: None,
+        iteration: None,
+        cols: setup.cols,
+        rows: setup.rows,
+    };
+    match Recorder::start(recording, &settings) {
+        Ok(recorder) => Some(recorder),
+        Err(e) => {
+            log::warn!(
+                "[Recordings] Failed to record terminal {}: {}",
+                terminal_id,
+                e
+            );
+            None
+        }
+    }
+}
+
 /// Handle a new PTY WebSocket session
-async fn handle_new_pty_session(socket: WebSocket, terminal_id: String, state: ServerAppState) {
+async fn handle_new_pty_session(
+    socket: WebSocket,
+    terminal_id: String,
+    state: ServerAppState,
+    identity: AuthIdentity,
+) {
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
     log::info!("PTY WebSocket client connected: {}", terminal_id);
@@ -155,9 +224,16 @@ async fn handle_new_pty_session(socket: WebSocket, terminal_id: String, state: S
     };
 
     // Create session in registry
+    let recorder = start_terminal_recording(&state, &terminal_id, &setup);
     let session = match state
         .pty_registry
-        .create_session(terminal_id.clone(), setup.cols, setup.rows, setup.cwd)
+        .create_session(
+            terminal_id.clone(),
+            setup.cols,
+            setup.rows,
+            setup.cwd,
+            recorder,
+        )
         .await
     {
         Ok(session) => session,
@@ -170,10 +246,13 @@ async fn handle_new_pty_session(socket: WebSocket, terminal_id: String, state: S
         }
     };
 
+    audit_session(&identity, "pty_session_start", &session);
+
     // Send session info to client
     let session_info = SessionInfo {
         session_id: session.id.clone(),
         terminal_id: terminal_id.clone(),
+        recording_id: session.recording_id(),
     };
     if let Ok(info_json) = serde_json::to_string(&session_info) {
         let _ = ws_sender
@@ -184,7 +263,7 @@ async fn handle_new_pty_session(socket: WebSocket, terminal_id: String, state: S
     }
 
     // Handle the session
-    handle_pty_io(ws_sender, ws_receiver, session, state).await;
+    handle_pty_io(ws_sender, ws_receiver, session, state, identity).await;
 }
 
 /// Handle PTY reconnection
@@ -193,6 +272,7 @@ async fn handle_pty_reconnection(
     terminal_id: String,
     session_id: String,
     state: ServerAppState,
+    identity: AuthIdentity,
 ) {
     let (mut ws_sender, ws_receiver) = socket.split();
 
@@ -228,11 +308,13 @@ async fn handle_pty_reconnection(
 
     // Mark as connected
     state.pty_registry.mark_connected(&session_id).await;
+    audit_session(&identity, "pty_session_reconnect", &session);
 
     // Send session info
     let session_info = SessionInfo {
         session_id: session.id.clone(),
         terminal_id: terminal_id.clone(),
+        recording_id: session.recording_id(),
     };
     if let Ok(info_json) = serde_json::to_string(&session_info) {
         let _ = ws_sender
@@ -263,19 +345,75 @@ async fn handle_pty_reconnection(
     }
 
     // Handle the session
-    handle_pty_io(ws_sender, ws_receiver, session, state).await;
+    handle_pty_io(ws_sender, ws_receiver, session, state, identity).await;
+}
+
+/// Apply a write-lock message from a viewer
+fn handle_write_lock(
+    session: &PtySession,
+    viewer: &TerminalViewer,
+    identity: &AuthIdentity,
+    message: ClientMessage,
+) -> Result<(), String> {
+    match message {
+        ClientMessage::RequestWrite => session.request_write(&viewer.id).map(|_| ()),
+        ClientMessage::ReleaseWrite => session.release_write(&viewer.id),
+        ClientMessage::GrantWrite { viewer_id } => session.grant_write(&viewer.id, &viewer_id),
+        ClientMessage::TakeWrite => {
+            if !identity.role.grants(Permission::Admin) {
+                return Err("Only admins can take the write lock".to_string());
+            }
+            session.take_write(&viewer.id)?;
+            audit_session(identity, "pty_take_write", session);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
 }
 
 /// Handle PTY I/O for a session (shared between new and reconnect)
 async fn handle_pty_io(
     ws_sender: futures_util::stream::SplitSink<WebSocket, Message>,
     mut ws_receiver: futures_util::stream::SplitStream<WebSocket>,
-    session: Arc<super::pty_registry::PtySession>,
+    session: Arc<PtySession>,
     state: ServerAppState,
+    identity: AuthIdentity,
 ) {
     let session_id = session.id.clone();
     let ws_sender = Arc::new(Mutex::new(ws_sender));
 
+    // Join the session's viewers; subscribing first delivers our own presence update
+    let mut control_rx = session.subscribe_control();
+    let viewer = session.add_viewer(identity.name.clone(), identity.token_id.clone());
+    if let Ok(viewer_json) = serde_json::to_string(&viewer) {
+        let _ = ws_sender
+            .lock()
+            .await
+            .send(Message::Text(
+                format!("{{\"type\": \"viewer\", \"data\": {}}}", viewer_json).into(),
+            ))
+            .await;
+    }
+
+    // Task: Forward presence and write-lock changes to WebSocket
+    let ws_sender_clone = ws_sender.clone();
+    let control_task = tokio::spawn(async move {
+        loop {
+            let event = match control_rx.recv().await {
+                Ok(event) => event,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            };
+            let Ok(json) = serde_json::to_string(&event) else {
+                continue;
+            };
+            let mut sender = ws_sender_clone.lock().await;
+            if sender.send(Message::Text(json.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
     // Subscribe to output
     let mut output_rx = session.subscribe();
     let ws_sender_clone = ws_sender.clone();
@@ -298,12 +436,18 @@ async fn handle_pty_io(
                 // Try to parse as a command message
                 match serde_json::from_str::<ClientMessage>(&text) {
                     Ok(ClientMessage::Input { data }) => {
+                        if !session.can_write(&viewer.id) {
+                            continue;
+                        }
                         if let Err(e) = session.write(data.as_bytes()).await {
                             log::warn!("Failed to write to PTY: {}", e);
                             break;
                         }
                     }
                     Ok(ClientMessage::Resize(resize)) => {
+                        if !session.can_write(&viewer.id) {
+                            continue;
+                        }
                         if let Err(e) = session.resize(resize.cols, resize.rows).await {
                             log::warn!("Failed to resize PTY: {}", e);
                         }
@@ -311,8 +455,21 @@ async fn handle_pty_io(
                     Ok(ClientMessage::Setup(_)) => {
                         log::warn!("Received duplicate setup message, ignoring");
                     }
+                    Ok(message) => {
+                        if let Err(e) = handle_write_lock(&session, &viewer, &identity, message) {
+                            let error = serde_json::json!({ "error": e }).to_string();
+                            let _ = ws_sender
+                                .lock()
+                                .await
+                                .send(Message::Text(error.into()))
+                                .await;
+                        }
+                    }
                     Err(_) => {
                         // Not a JSON message, treat as raw input
+                        if !session.can_write(&viewer.id) {
+                            continue;
+                        }
                         if let Err(e) = session.write(text.as_bytes()).await {
                             log::warn!("Failed to write to PTY: {}", e);
                             break;
@@ -321,6 +478,9 @@ async fn handle_pty_io(
                 }
             }
             Ok(Message::Binary(data)) => {
+                if !session.can_write(&viewer.id) {
+                    continue;
+                }
                 if let Err(e) = session.write(&data).await {
                     log::warn!("Failed to write binary to PTY: {}", e);
                     break;
@@ -338,9 +498,14 @@ async fn handle_pty_io(
         }
     }
 
-    // Client disconnected - mark session as disconnected but keep it alive
+    // Client disconnected - once the last viewer leaves, mark the session as
+    // disconnected but keep it alive
     output_task.abort();
-    state.pty_registry.mark_disconnected(&session_id).await;
+    control_task.abort();
+    if session.remove_viewer(&viewer.id) == 0 {
+        state.pty_registry.mark_disconnected(&session_id).await;
+    }
+    audit_session(&identity, "pty_session_disconnect", &session);
 
     log::info!("PTY client disconnected, session preserved: {}", session_id);
 }
@@ -362,6 +527,14 @@ mod tests {
         let input = r#"{"type": "input", "data": "ls -la\n"}"#;
         let msg: ClientMessage = serde_json::from_str(input).unwrap();
         assert!(matches!(msg, ClientMessage::Input { .. }));
+
+        let grant = r#"{"type": "grantWrite", "viewerId": "v2"}"#;
+        let msg: ClientMessage = serde_json::from_str(grant).unwrap();
+        assert!(matches!(msg, ClientMessage::GrantWrite { viewer_id } if viewer_id == "v2"));
+
+        let take = r#"{"type": "takeWrite"}"#;
+        let msg: ClientMessage = serde_json::from_str(take).unwrap();
+        assert!(matches!(msg, ClientMessage::TakeWrite));
     }
 
     #[test]
@@ -369,9 +542,11 @@ mod tests {
         let info = SessionInfo {
             session_id: "abc123".to_string(),
             terminal_id: "term-1".to_string(),
+            recording_id: None,
         };
         let json = serde_json::to_string(&info).unwrap();
         assert!(json.contains("sessionId"));
         assert!(json.contains("terminalId"));
+        assert!(!json.contains("recordingId"));
     }
 }
@@ -5,8 +5,14 @@
 //! - Session persistence after client disconnect
 //! - Output buffering for reconnection replay (100KB)
 //! - Automatic cleanup of stale sessions (10 minutes)
+//! - Optional asciicast recording of the whole session
+//! - Multiple viewers per session, one of which holds the write lock
 
+use crate::file_storage::recordings::Recorder;
+use crate::utils::lock_mutex_recover;
+use chrono::{DateTime, Utc};
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::sync::Arc;
@@ -89,6 +95,131 @@ pub enum SessionState {
     Closing,
 }
 
+/// A client connected to a session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalViewer {
+    pub id: String,
+    /// Name of the viewer's API token
+    pub name: String,
+    pub token_id: Option<String>,
+    pub connected_at: DateTime<Utc>,
+}
+
+/// Who is watching a session and who may type into it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalPresence {
+    pub viewers: Vec<TerminalViewer>,
+    /// Viewer holding the write lock (None = nobody may type)
+    pub writer_id: Option<String>,
+}
+
+/// Presence or write-lock change, broadcast to a session's viewers
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum TerminalControlEvent {
+    Presence(TerminalPresence),
+    /// A viewer asked the writer for the write lock
+    #[serde(rename_all = "camelCase")]
+    WriteRequested {
+        viewer_id: String,
+        name: String,
+    },
+}
+
+/// Viewers of a session and its write lock
+///
+/// Only the writer's input and resizes reach the PTY. A viewer connecting to
+/// a session nobody writes to gets the lock, so a single client behaves as if
+/// there were no lock at all.
+#[derive(Debug, Default)]
+pub struct TerminalViewers {
+    viewers: Vec<TerminalViewer>,
+    writer_id: Option<String>,
+}
+
+impl TerminalViewers {
+    pub fn presence(&self) -> TerminalPresence {
+        TerminalPresence {
+            viewers: self.viewers.clone(),
+            writer_id: self.writer_id.clone(),
+        }
+    }
+
+    fn viewer(&self, viewer_id: &str) -> Result<&TerminalViewer, String> {
+        self.viewers
+            .iter()
+            .find(|v| v.id == viewer_id)
+            .ok_or_else(|| format!("Not a viewer of this session: {}", viewer_id))
+    }
+
+    pub fn is_writer(&self, viewer_id: &str) -> bool {
+        self.writer_id.as_deref() == Some(viewer_id)
+    }
+
+    pub fn add(&mut self, name: String, token_id: Option<String>) -> TerminalViewer {
+        let viewer = TerminalViewer {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            token_id,
+            connected_at: Utc::now(),
+        };
+        self.viewers.push(viewer.clone());
+        if self.writer_id.is_none() {
+            self.writer_id = Some(viewer.id.clone());
+        }
+        viewer
+    }
+
+    /// Remove a viewer, releasing the write lock if it held it. Returns the
+    /// number of viewers left.
+    pub fn remove(&mut self, viewer_id: &str) -> usize {
+        self.viewers.retain(|v| v.id != viewer_id);
+        if self.is_writer(viewer_id) {
+            self.writer_id = None;
+        }
+        self.viewers.len()
+    }
+
+    /// Ask for the write lock: granted if nobody holds it, otherwise the
+    /// writer has to grant it. Returns whether the viewer now holds it.
+    pub fn request(&mut self, viewer_id: &str) -> Result<bool, String> {
+        self.viewer(viewer_id)?;
+        if self.writer_id.is_none() {
+            self.writer_id = Some(viewer_id.to_string());
+        }
+        Ok(self.is_writer(viewer_id))
+    }
+
+    /// Give up the write lock
+    pub fn release(&mut self, viewer_id: &str) -> Result<(), String> {
+        if !self.is_writer(viewer_id) {
+            return Err("You don't hold the write lock".to_string());
+        }
+        self.writer_id = None;
+        Ok(())
+    }
+
+    /// Hand the write lock from its writer to another viewer
+    pub fn grant(&mut self, writer_id: &str, viewer_id: &str) -> Result<(), String> {
+        if !self.is_writer(writer_id) {
+            return Err("Only the viewer holding the write lock can grant it".to_string());
+        }
+        self.viewer(viewer_id)?;
+        self.writer_id = Some(viewer_id.to_string());
+        Ok(())
+    }
+
+    /// Take the write lock regardless of who holds it (admins only; checked by
+    /// the caller)
+    pub fn take(&mut self, viewer_id: &str) -> Result<(), String> {
+        self.viewer(viewer_id)?;
+        self.writer_id = Some(viewer_id.to_string());
+        Ok(())
+    }
+}
+
 /// A registered PTY session
 pub struct PtySession {
     /// Unique session ID
@@ -108,6 +239,12 @@ pub struct PtySession {
     output_buffer: Arc<Mutex<OutputBuffer>>,
     /// Broadcast channel for real-time output
     output_tx: broadcast::Sender<Vec<u8>>,
+    /// Asciicast recorder, finished when the shell exits or the session is removed
+    recorder: Arc<std::sync::Mutex<Option<Recorder>>>,
+    /// Connected viewers and the write lock
+    viewers: std::sync::Mutex<TerminalViewers>,
+    /// Broadcast channel for presence and write-lock changes
+    control_tx: broadcast::Sender<TerminalControlEvent>,
     /// Session state
     state: Arc<RwLock<SessionState>>,
     /// Last activity time
@@ -124,6 +261,7 @@ impl PtySession {
         cols: u16,
         rows: u16,
         cwd: Option<String>,
+        recorder: Option<Recorder>,
     ) -> Result<Self, String> {
         // Create PTY
         let pty_system = native_pty_system();
@@ -177,6 +315,7 @@ impl PtySession {
         let (output_tx, _) = broadcast::channel(1024);
         let state = Arc::new(RwLock::new(SessionState::Connected));
         let last_activity = Arc::new(RwLock::new(Instant::now()));
+        let recorder = Arc::new(std::sync::Mutex::new(recorder));
 
         let session = Self {
             id: id.clone(),
@@ -188,6 +327,9 @@ impl PtySession {
             writer,
             output_buffer: output_buffer.clone(),
             output_tx: output_tx.clone(),
+            recorder: recorder.clone(),
+            viewers: std::sync::Mutex::new(TerminalViewers::default()),
+            control_tx: broadcast::channel(64).0,
             state,
             last_activity: last_activity.clone(),
             created_at: Instant::now(),
@@ -211,6 +353,9 @@ impl PtySession {
                     }
                     Ok(n) => {
                         let data = buf[..n].to_vec();
+                        if let Some(recorder) = lock_mutex_recover(&recorder).as_mut() {
+                            recorder.output(&data);
+                        }
 
                         // Update activity time
                         let rt = tokio::runtime::Handle::current();
@@ -230,6 +375,8 @@ impl PtySession {
                     }
                 }
             }
+            // The shell exited
+            lock_mutex_recover(&recorder).take();
         });
 
         Ok(session)
@@ -237,6 +384,9 @@ impl PtySession {
 
     /// Write data to the PTY
     pub async fn write(&self, data: &[u8]) -> Result<(), String> {
+        if let Some(recorder) = lock_mutex_recover(&self.recorder).as_mut() {
+            recorder.input(data);
+        }
         let mut writer = self.writer.lock().await;
         writer
             .write_all(data)
@@ -259,10 +409,104 @@ impl PtySession {
                 pixel_height: 0,
             })
             .map_err(|e| format!("Failed to resize PTY: {}", e))?;
+        if let Some(recorder) = lock_mutex_recover(&self.recorder).as_mut() {
+            recorder.resize(cols, rows);
+        }
         *self.last_activity.write().await = Instant::now();
         Ok(())
     }
 
+    /// ID of the session's recording, if it's being recorded
+    pub fn recording_id(&self) -> Option<String> {
+        lock_mutex_recover(&self.recorder)
+            .as_ref()
+            .map(|r| r.id().to_string())
+    }
+
+    /// Stop recording the session
+    pub fn finish_recording(&self) {
+        lock_mutex_recover(&self.recorder).take();
+    }
+
+    /// Subscribe to presence and write-lock changes
+    pub fn subscribe_control(&self) -> broadcast::Receiver<TerminalControlEvent> {
+        self.control_tx.subscribe()
+    }
+
+    /// Current viewers and writer
+    pub fn presence(&self) -> TerminalPresence {
+        lock_mutex_recover(&self.viewers).presence()
+    }
+
+    /// Whether a viewer's input and resizes reach the PTY
+    pub fn can_write(&self, viewer_id: &str) -> bool {
+        lock_mutex_recover(&self.viewers).is_writer(viewer_id)
+    }
+
+    /// Change the viewers or write lock and broadcast the new presence
+    fn update_viewers<T>(
+        &self,
+        update: impl FnOnce(&mut TerminalViewers) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut viewers = lock_mutex_recover(&self.viewers);
+        let before = viewers.presence();
+        let result = update(&mut viewers)?;
+        let presence = viewers.presence();
+        drop(viewers);
+        if presence != before {
+            let _ = self
+                .control_tx
+                .send(TerminalControlEvent::Presence(presence));
+        }
+        Ok(result)
+    }
+
+    /// Register a connected client
+    pub fn add_viewer(&self, name: String, token_id: Option<String>) -> TerminalViewer {
+        self.update_viewers(|v| Ok(v.add(name, token_id)))
+            .expect("adding a viewer can't fail")
+    }
+
+    /// Unregister a disconnected client; returns the number of viewers left
+    pub fn remove_viewer(&self, viewer_id: &str) -> usize {
+        self.update_viewers(|v| Ok(v.remove(viewer_id)))
+            .unwrap_or_default()
+    }
+
+    /// Ask for the write lock; the writer is notified if it isn't free
+    pub fn request_write(&self, viewer_id: &str) -> Result<bool, String> {
+        let granted = self.update_viewers(|v| v.request(viewer_id))?;
+        if !granted {
+            let name = self
+                .presence()
+                .viewers
+                .into_iter()
+                .find(|v| v.id == viewer_id)
+                .map(|v| v.name)
+                .unwrap_or_default();
+            let _ = self.control_tx.send(TerminalControlEvent::WriteRequested {
+                viewer_id: viewer_id.to_string(),
+                name,
+            });
+        }
+        Ok(granted)
+    }
+
+    /// Give up the write lock
+    pub fn release_write(&self, viewer_id: &str) -> Result<(), String> {
+        self.update_viewers(|v| v.release(viewer_id))
+    }
+
+    /// Hand the write lock to another viewer
+    pub fn grant_write(&self, writer_id: &str, viewer_id: &str) -> Result<(), String> {
+        self.update_viewers(|v| v.grant(writer_id, viewer_id))
+    }
+
+    /// Take the write lock from whoever holds it
+    pub fn take_write(&self, viewer_id: &str) -> Result<(), String> {
+        self.update_viewers(|v| v.take(viewer_id))
+    }
+
     /// Get buffered output for replay on reconnection
     pub async fn get_buffered_output(&self) -> Vec<u8> {
         self.output_buffer.lock().await.get_all().to_vec()
@@ -320,10 +564,18 @@ impl PtyRegistry {
         cols: u16,
         rows: u16,
         cwd: Option<String>,
+        recorder: Option<Recorder>,
     ) -> Result<Arc<PtySession>, String> {
         let session_id = uuid::Uuid::new_v4().to_string();
-        let session =
-            PtySession::new(session_id.clone(), terminal_id.clone(), cols, rows, cwd).await?;
+        let session = PtySession::new(
+            session_id.clone(),
+            terminal_id.clone(),
+            cols,
+            rows,
+            cwd,
+            recorder,
+        )
+        .await?;
 
         let session = Arc::new(session);
         self.sessions
@@ -359,6 +611,7 @@ impl PtyRegistry {
         let session = self.sessions.write().await.remove(session_id);
         if let Some(ref s) = session {
             s.set_state(SessionState::Closing).await;
+            s.finish_recording();
             log::info!("Removed PTY session {}", session_id);
         }
         session
@@ -469,6 +722,52 @@ mod tests {
         assert_eq!(buffer.get_all(), b"abcdefghij");
     }
 
+    #[test]
+    fn test_terminal_write_lock() {
+        let mut viewers = TerminalViewers::default();
+
+        // The first viewer gets the lock, later ones are read-only
+        let laptop = viewers.add("laptop".to_string(), None);
+        let phone = viewers.add("phone".to_string(), Some("tok-1".to_string()));
+        assert!(viewers.is_writer(&laptop.id));
+        assert!(!viewers.is_writer(&phone.id));
+
+        // Requests wait for the writer to grant the lock
+        assert!(!viewers.request(&phone.id).unwrap());
+        assert!(viewers.grant(&phone.id, &laptop.id).is_err());
+        viewers.grant(&laptop.id, &phone.id).unwrap();
+        assert!(viewers.is_writer(&phone.id));
+
+        // Released or disconnected locks are free for the next request
+        assert!(viewers.release(&laptop.id).is_err());
+        viewers.release(&phone.id).unwrap();
+        assert_eq!(viewers.presence().writer_id, None);
+        assert!(viewers.request(&laptop.id).unwrap());
+        viewers.take(&phone.id).unwrap();
+        assert!(viewers.is_writer(&phone.id));
+        assert_eq!(viewers.remove(&phone.id), 1);
+        assert_eq!(viewers.presence().writer_id, None);
+
+        assert!(viewers.request("stranger").is_err());
+        assert!(viewers.take("stranger").is_err());
+    }
+
+    #[test]
+    fn test_terminal_control_event_serialization() {
+        let event = TerminalControlEvent::WriteRequested {
+            viewer_id: "v1".to_string(),
+            name: "phone".to_string(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "writeRequested");
+        assert_eq!(json["data"]["viewerId"], "v1");
+
+        let event = TerminalControlEvent::Presence(TerminalViewers::default().presence());
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "presence");
+        assert!(json["data"]["writerId"].is_null());
+    }
+
     #[tokio::test]
     #[ignore] // Requires PTY support not available in CI containers
     async fn test_registry_basic() {
@@ -476,7 +775,7 @@ mod tests {
 
         // Create session
         let result = registry
-            .create_session("term-1".to_string(), 80, 24, None)
+            .create_session("term-1".to_string(), 80, 24, None, None)
             .await;
 
         // May fail on CI without PTY support, so we just check the registry works
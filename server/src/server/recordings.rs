@@ -0,0 +1,88 @@
+//! HTTP streaming endpoint for asciicast terminal recordings
+//!
+//! Streams the `.cast` file so it can be fed straight to an asciicast player.
+//! With `follow=true`, a recording still in progress is tailed until it ends.
+
+use axum::{
+    body::Body,
+    extract::{Path, Query},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use futures_util::stream;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+
+use super::auth::AuthIdentity;
+use super::permissions::Permission;
+use crate::file_storage::recordings;
+
+/// How often a followed recording is polled for new events
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Query parameters for the recording stream endpoint
+#[derive(Debug, Deserialize)]
+pub struct RecordingStreamQuery {
+    #[serde(default)]
+    follow: bool,
+}
+
+/// GET /api/recordings/:recording_id?follow=true
+pub async fn recording_stream_handler(
+    Path(recording_id): Path<String>,
+    Query(query): Query<RecordingStreamQuery>,
+    Extension(identity): Extension<AuthIdentity>,
+) -> Response {
+    // Same permission as list_recordings/get_recording
+    if let Err(e) = identity.authorize("get_recording", Permission::Admin, &[]) {
+        return (StatusCode::FORBIDDEN, format!("Forbidden: {}", e)).into_response();
+    }
+
+    let path = match recordings::get_recording_file_path(&recording_id) {
+        Ok(path) => path,
+        Err(e) => return (StatusCode::NOT_FOUND, e).into_response(),
+    };
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) => {
+            return (
+                StatusCode::NOT_FOUND,
+                format!("Failed to open recording: {}", e),
+            )
+                .into_response()
+        }
+    };
+
+    let chunks = stream::unfold(Some(file), move |file| {
+        let recording_id = recording_id.clone();
+        async move {
+            let mut file = file?;
+            let mut buf = vec![0u8; 64 * 1024];
+            loop {
+                match file.read(&mut buf).await {
+                    Ok(0) => {
+                        let in_progress = recordings::get_recording(&recording_id)
+                            .is_ok_and(|meta| meta.ended_at.is_none());
+                        if !query.follow || !in_progress {
+                            return None;
+                        }
+                        tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+                    }
+                    Ok(n) => {
+                        buf.truncate(n);
+                        return Some((Ok::<_, std::io::Error>(buf), Some(file)));
+                    }
+                    Err(e) => return Some((Err(e), None)),
+                }
+            }
+        }
+    });
+
+    (
+        [(CONTENT_TYPE, "application/x-asciicast")],
+        Body::from_stream(chunks),
+    )
+        .into_response()
+}
@@ -0,0 +1,157 @@
+//! REST endpoints for PRD chat sessions and messages
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use super::{ApiError, ApiErrorBody, ApiJson, ApiQuery, ApiResult, ProjectQuery};
+use crate::commands::prd_chat::{
+    self as chat_commands, SendMessageRequest, SendMessageResponse, StartChatSessionRequest,
+};
+use crate::file_storage::chat_ops;
+use crate::models::{ChatAttachment, ChatMessage, ChatSession, PRDType};
+use crate::server::{proxy, ServerAppState};
+
+/// Request to send a message to a chat session
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SendChatMessageRequest {
+    pub content: String,
+    /// Optional image attachments
+    #[serde(default)]
+    pub attachments: Option<Vec<ChatAttachment>>,
+}
+
+fn require_session(project_path: &str, session_id: &str) -> ApiResult<()> {
+    chat_ops::get_chat_session_opt(std::path::Path::new(project_path), session_id)?
+        .map(|_| ())
+        .ok_or_else(|| ApiError::not_found(format!("Chat session not found: {}", session_id)))
+}
+
+fn validate_start_request(request: &StartChatSessionRequest) -> ApiResult<()> {
+    chat_commands::parse_agent_type(&request.agent_type).map_err(ApiError::bad_request)?;
+    if request.project_path.is_none() {
+        return Err(ApiError::bad_request(
+            "projectPath is required for chat sessions",
+        ));
+    }
+    if let Some(prd_type) = &request.prd_type {
+        prd_type
+            .parse::<PRDType>()
+            .map_err(|e| ApiError::bad_request(format!("Invalid PRD type: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// List the project's chat sessions
+#[utoipa::path(
+    get,
+    path = "/api/v1/chats",
+    tag = "chats",
+    params(ProjectQuery),
+    responses((status = 200, body = Vec<ChatSession>))
+)]
+pub async fn list_sessions(
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<Vec<ChatSession>>> {
+    Ok(Json(
+        chat_commands::list_prd_chat_sessions(query.project_path).await?,
+    ))
+}
+
+/// Start a chat session
+#[utoipa::path(
+    post,
+    path = "/api/v1/chats",
+    tag = "chats",
+    request_body = StartChatSessionRequest,
+    responses(
+        (status = 201, body = ChatSession),
+        (status = 400, body = ApiErrorBody)
+    )
+)]
+pub async fn start_session(
+    ApiJson(request): ApiJson<StartChatSessionRequest>,
+) -> ApiResult<(StatusCode, Json<ChatSession>)> {
+    validate_start_request(&request)?;
+    let session = chat_commands::start_prd_chat_session(request).await?;
+    Ok((StatusCode::CREATED, Json(session)))
+}
+
+/// Delete a chat session and its messages
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chats/{session_id}",
+    tag = "chats",
+    params(("session_id" = String, Path, description = "Chat session ID"), ProjectQuery),
+    responses(
+        (status = 204),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn delete_session(
+    Path(session_id): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<StatusCode> {
+    require_session(&query.project_path, &session_id)?;
+    chat_commands::delete_prd_chat_session(session_id, query.project_path).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get a chat session's messages
+#[utoipa::path(
+    get,
+    path = "/api/v1/chats/{session_id}/messages",
+    tag = "chats",
+    params(("session_id" = String, Path, description = "Chat session ID"), ProjectQuery),
+    responses(
+        (status = 200, body = Vec<ChatMessage>),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn list_messages(
+    Path(session_id): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<Vec<ChatMessage>>> {
+    require_session(&query.project_path, &session_id)?;
+    Ok(Json(
+        chat_commands::get_prd_chat_history(session_id, query.project_path).await?,
+    ))
+}
+
+/// Send a message and wait for the agent's reply
+///
+/// Streaming output is broadcast on `/ws/events` as with the invoke API.
+#[utoipa::path(
+    post,
+    path = "/api/v1/chats/{session_id}/messages",
+    tag = "chats",
+    params(("session_id" = String, Path, description = "Chat session ID"), ProjectQuery),
+    request_body = SendChatMessageRequest,
+    responses(
+        (status = 200, body = SendMessageResponse),
+        (status = 400, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn send_message(
+    State(state): State<ServerAppState>,
+    Path(session_id): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+    ApiJson(request): ApiJson<SendChatMessageRequest>,
+) -> ApiResult<Json<SendMessageResponse>> {
+    require_session(&query.project_path, &session_id)?;
+    let request = SendMessageRequest {
+        session_id,
+        content: request.content,
+        project_path: query.project_path,
+        attachments: request.attachments,
+    };
+    request.validate().map_err(ApiError::bad_request)?;
+    Ok(Json(
+        proxy::send_prd_chat_message_server(request, &state.broadcaster).await?,
+    ))
+}
@@ -0,0 +1,212 @@
+//! REST endpoints for Ralph loop executions and their iterations
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::prds::require_prd;
+use super::{ApiError, ApiErrorBody, ApiJson, ApiQuery, ApiResult, ProjectQuery};
+use crate::commands::ralph_loop::{
+    self as ralph_commands, ExecutionInfo, RalphLoopSnapshot, StartRalphLoopRequest,
+};
+use crate::file_storage::iterations::IterationStats;
+use crate::ralph_loop::IterationRecord;
+use crate::server::auth::ExecutionAccessError;
+use crate::server::permissions::Permission;
+use crate::server::{proxy, AuthIdentity, ServerAppState};
+
+/// Response to starting an execution
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StartExecutionResponse {
+    pub execution_id: String,
+}
+
+/// Whether the execution has a running loop task or orchestrator
+fn is_active(state: &ServerAppState, execution_id: &str) -> ApiResult<bool> {
+    let loop_state = &state.ralph_loop_state;
+    Ok(loop_state.get_control(execution_id).is_some()
+        || loop_state.get_execution(execution_id)?.is_some())
+}
+
+fn execution_not_found(execution_id: &str) -> ApiError {
+    ApiError::not_found(format!("Execution not found: {}", execution_id))
+}
+
+/// Check the caller may use the execution in the project it runs in, which
+/// must be the project named in the query
+fn authorize_execution(
+    state: &ServerAppState,
+    identity: &AuthIdentity,
+    command: &str,
+    permission: Permission,
+    execution_id: &str,
+    project_path: &str,
+) -> ApiResult<()> {
+    let execution_project = state.ralph_loop_state.execution_project(execution_id);
+    identity
+        .authorize_execution(
+            command,
+            permission,
+            execution_project.as_deref(),
+            Some(project_path),
+        )
+        .map_err(|e| match e {
+            ExecutionAccessError::NotFound => execution_not_found(execution_id),
+            ExecutionAccessError::Forbidden(e) => ApiError::forbidden(e),
+        })
+}
+
+/// List executions known to this server
+#[utoipa::path(
+    get,
+    path = "/api/v1/executions",
+    tag = "executions",
+    responses((status = 200, body = Vec<ExecutionInfo>))
+)]
+pub async fn list_executions(
+    State(state): State<ServerAppState>,
+) -> ApiResult<Json<Vec<ExecutionInfo>>> {
+    Ok(Json(
+        ralph_commands::list_ralph_loop_executions_with_details(&state.ralph_loop_state)?,
+    ))
+}
+
+/// Start a Ralph loop execution for a PRD
+#[utoipa::path(
+    post,
+    path = "/api/v1/executions",
+    tag = "executions",
+    request_body = StartRalphLoopRequest,
+    responses(
+        (status = 201, body = StartExecutionResponse),
+        (status = 400, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+        (status = 500, body = ApiErrorBody)
+    )
+)]
+pub async fn start_execution(
+    State(state): State<ServerAppState>,
+    ApiJson(request): ApiJson<StartRalphLoopRequest>,
+) -> ApiResult<(StatusCode, Json<StartExecutionResponse>)> {
+    proxy::parse_loop_agent_type(&request.agent_type).map_err(ApiError::bad_request)?;
+    require_prd(&request.project_path, &request.prd_name)?;
+    let execution_id = proxy::start_ralph_loop_server(request, &state).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(StartExecutionResponse { execution_id }),
+    ))
+}
+
+/// Get an execution's state, metrics and iteration history
+#[utoipa::path(
+    get,
+    path = "/api/v1/executions/{execution_id}",
+    tag = "executions",
+    params(("execution_id" = String, Path, description = "Execution ID"), ProjectQuery),
+    responses(
+        (status = 200, body = RalphLoopSnapshot),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn get_execution(
+    State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
+    Path(execution_id): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<RalphLoopSnapshot>> {
+    authorize_execution(
+        &state,
+        &identity,
+        "GET /api/v1/executions/:execution_id",
+        Permission::Read,
+        &execution_id,
+        &query.project_path,
+    )?;
+    if state.ralph_loop_state.get_snapshot(&execution_id).is_none()
+        && !is_active(&state, &execution_id)?
+    {
+        return Err(execution_not_found(&execution_id));
+    }
+    Ok(Json(
+        ralph_commands::get_ralph_loop_snapshot(
+            query.project_path,
+            execution_id,
+            &state.ralph_loop_state,
+        )
+        .await?,
+    ))
+}
+
+/// Request cancellation of a running execution
+#[utoipa::path(
+    post,
+    path = "/api/v1/executions/{execution_id}/stop",
+    tag = "executions",
+    params(("execution_id" = String, Path, description = "Execution ID"), ProjectQuery),
+    responses(
+        (status = 202),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn stop_execution(
+    State(state): State<ServerAppState>,
+    Extension(identity): Extension<AuthIdentity>,
+    Path(execution_id): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<StatusCode> {
+    authorize_execution(
+        &state,
+        &identity,
+        "POST /api/v1/executions/:execution_id/stop",
+        Permission::Operate,
+        &execution_id,
+        &query.project_path,
+    )?;
+    if !is_active(&state, &execution_id)? {
+        return Err(execution_not_found(&execution_id));
+    }
+    proxy::stop_ralph_loop_server(execution_id, &state).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// List an execution's recorded iterations
+#[utoipa::path(
+    get,
+    path = "/api/v1/executions/{execution_id}/iterations",
+    tag = "iterations",
+    params(("execution_id" = String, Path, description = "Execution ID"), ProjectQuery),
+    responses((status = 200, body = Vec<IterationRecord>))
+)]
+pub async fn list_iterations(
+    Path(execution_id): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<Vec<IterationRecord>>> {
+    Ok(Json(ralph_commands::get_ralph_iteration_history(
+        query.project_path,
+        execution_id,
+    )?))
+}
+
+/// Get outcome and duration totals for an execution's iterations
+#[utoipa::path(
+    get,
+    path = "/api/v1/executions/{execution_id}/iterations/stats",
+    tag = "iterations",
+    params(("execution_id" = String, Path, description = "Execution ID"), ProjectQuery),
+    responses((status = 200, body = IterationStats))
+)]
+pub async fn get_iteration_stats(
+    Path(execution_id): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<IterationStats>> {
+    Ok(Json(ralph_commands::get_ralph_iteration_stats(
+        query.project_path,
+        execution_id,
+    )?))
+}
@@ -0,0 +1,132 @@
+//! REST endpoints for repository branches, status, commits and diffs
+
+use axum::{extract::State, Json};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::{ApiError, ApiErrorBody, ApiQuery, ApiResult};
+use crate::commands::git as git_commands;
+use crate::git::{BranchInfo, CommitInfo, DiffInfo, FileStatus};
+use crate::server::ServerAppState;
+
+/// Default number of commits returned by the history endpoint
+const DEFAULT_COMMIT_LIMIT: usize = 50;
+
+/// Query parameters identifying a repository
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query, rename_all = "camelCase")]
+pub struct RepoQuery {
+    /// Absolute path of the repository (or worktree)
+    pub repo_path: String,
+}
+
+/// Query parameters for the commit history endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query, rename_all = "camelCase")]
+pub struct CommitsQuery {
+    /// Absolute path of the repository (or worktree)
+    pub repo_path: String,
+    /// Maximum number of commits (default: 50)
+    pub limit: Option<usize>,
+}
+
+fn require_repo(repo_path: &str) -> ApiResult<()> {
+    if !std::path::Path::new(repo_path).is_dir() {
+        return Err(ApiError::not_found(format!(
+            "Repository not found: {}",
+            repo_path
+        )));
+    }
+    Ok(())
+}
+
+/// List local branches
+#[utoipa::path(
+    get,
+    path = "/api/v1/git/branches",
+    tag = "git",
+    params(RepoQuery),
+    responses(
+        (status = 200, body = Vec<BranchInfo>),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn list_branches(
+    State(state): State<ServerAppState>,
+    ApiQuery(query): ApiQuery<RepoQuery>,
+) -> ApiResult<Json<Vec<BranchInfo>>> {
+    require_repo(&query.repo_path)?;
+    Ok(Json(git_commands::git_list_branches(
+        query.repo_path,
+        &state.git_state,
+    )?))
+}
+
+/// Get working tree status
+#[utoipa::path(
+    get,
+    path = "/api/v1/git/status",
+    tag = "git",
+    params(RepoQuery),
+    responses(
+        (status = 200, body = Vec<FileStatus>),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn get_status(
+    State(state): State<ServerAppState>,
+    ApiQuery(query): ApiQuery<RepoQuery>,
+) -> ApiResult<Json<Vec<FileStatus>>> {
+    require_repo(&query.repo_path)?;
+    Ok(Json(git_commands::git_get_status(
+        query.repo_path,
+        &state.git_state,
+    )?))
+}
+
+/// Get commit history from HEAD
+#[utoipa::path(
+    get,
+    path = "/api/v1/git/commits",
+    tag = "git",
+    params(CommitsQuery),
+    responses(
+        (status = 200, body = Vec<CommitInfo>),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn list_commits(
+    State(state): State<ServerAppState>,
+    ApiQuery(query): ApiQuery<CommitsQuery>,
+) -> ApiResult<Json<Vec<CommitInfo>>> {
+    require_repo(&query.repo_path)?;
+    Ok(Json(git_commands::git_get_commit_history(
+        query.repo_path,
+        query.limit.unwrap_or(DEFAULT_COMMIT_LIMIT),
+        &state.git_state,
+    )?))
+}
+
+/// Get the diff of uncommitted changes
+#[utoipa::path(
+    get,
+    path = "/api/v1/git/diff",
+    tag = "git",
+    params(RepoQuery),
+    responses(
+        (status = 200, body = DiffInfo),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn get_working_diff(
+    State(state): State<ServerAppState>,
+    ApiQuery(query): ApiQuery<RepoQuery>,
+) -> ApiResult<Json<DiffInfo>> {
+    require_repo(&query.repo_path)?;
+    Ok(Json(git_commands::git_get_working_diff(
+        query.repo_path,
+        &state.git_state,
+    )?))
+}
@@ -0,0 +1,264 @@
+//! Resource-oriented REST API
+//!
+//! Typed endpoints under `/api/v1` for external tooling, alongside the
+//! `/api/invoke` command proxy used by the bundled frontend. Handlers reuse the
+//! same command functions as the proxy and describe themselves with utoipa so
+//! the OpenAPI document at `/api/openapi.json` stays in sync with the code.
+//!
+//! Resources are organized into sub-modules:
+//! - projects: Registered projects
+//! - prds: PRDs, their stories and learnings
+//! - executions: Ralph loop executions and their iterations
+//! - chats: PRD chat sessions and messages
+//! - git: Repository branches, status, commits and diffs
+
+mod chats;
+mod executions;
+mod git;
+mod openapi;
+mod prds;
+mod projects;
+
+pub use openapi::{openapi_handler, ApiDoc};
+
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, FromRequestParts, Query, Request},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, patch, post, put},
+    Json, Router,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::ServerAppState;
+
+/// Build the `/api/v1` router
+pub fn router() -> Router<ServerAppState> {
+    Router::new()
+        .route(
+            "/api/v1/projects",
+            get(projects::list_projects).post(projects::register_project),
+        )
+        .route(
+            "/api/v1/projects/:project_id",
+            get(projects::get_project)
+                .patch(projects::update_project)
+                .delete(projects::delete_project),
+        )
+        .route("/api/v1/prds", get(prds::list_prds))
+        .route("/api/v1/prds/:prd_name", get(prds::get_prd))
+        .route("/api/v1/prds/:prd_name/status", get(prds::get_prd_status))
+        .route(
+            "/api/v1/prds/:prd_name/stories",
+            get(prds::list_stories).post(prds::add_story),
+        )
+        .route(
+            "/api/v1/prds/:prd_name/stories/:story_id",
+            get(prds::get_story).delete(prds::remove_story),
+        )
+        .route(
+            "/api/v1/prds/:prd_name/stories/:story_id/passes",
+            put(prds::set_story_passes),
+        )
+        .route(
+            "/api/v1/prds/:prd_name/learnings",
+            get(prds::list_learnings).post(prds::add_learning),
+        )
+        .route(
+            "/api/v1/prds/:prd_name/learnings/:learning_id",
+            patch(prds::update_learning).delete(prds::delete_learning),
+        )
+        .route(
+            "/api/v1/executions",
+            get(executions::list_executions).post(executions::start_execution),
+        )
+        .route(
+            "/api/v1/executions/:execution_id",
+            get(executions::get_execution),
+        )
+        .route(
+            "/api/v1/executions/:execution_id/stop",
+            post(executions::stop_execution),
+        )
+        .route(
+            "/api/v1/executions/:execution_id/iterations",
+            get(executions::list_iterations),
+        )
+        .route(
+            "/api/v1/executions/:execution_id/iterations/stats",
+            get(executions::get_iteration_stats),
+        )
+        .route(
+            "/api/v1/chats",
+            get(chats::list_sessions).post(chats::start_session),
+        )
+        .route("/api/v1/chats/:session_id", delete(chats::delete_session))
+        .route(
+            "/api/v1/chats/:session_id/messages",
+            get(chats::list_messages).post(chats::send_message),
+        )
+        .route("/api/v1/git/branches", get(git::list_branches))
+        .route("/api/v1/git/status", get(git::get_status))
+        .route("/api/v1/git/commits", get(git::list_commits))
+        .route("/api/v1/git/diff", get(git::get_working_diff))
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// Error details returned by every REST endpoint on failure
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiErrorDetail {
+    /// HTTP status code
+    pub status: u16,
+    /// Machine-readable error code (e.g. `not_found`, `bad_request`)
+    pub code: String,
+    /// Human-readable message
+    pub message: String,
+}
+
+/// Error response body
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiErrorBody {
+    pub error: ApiErrorDetail,
+}
+
+/// A REST API error with its HTTP status
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+
+    fn code(&self) -> &'static str {
+        match self.status {
+            StatusCode::BAD_REQUEST => "bad_request",
+            StatusCode::FORBIDDEN => "forbidden",
+            StatusCode::NOT_FOUND => "not_found",
+            StatusCode::CONFLICT => "conflict",
+            StatusCode::UNPROCESSABLE_ENTITY => "unprocessable_entity",
+            _ => "internal_error",
+        }
+    }
+}
+
+/// Command functions report errors as strings. Handlers check for missing
+/// resources and invalid input explicitly, so anything left is a server error.
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ApiErrorBody {
+            error: ApiErrorDetail {
+                status: self.status.as_u16(),
+                code: self.code().to_string(),
+                message: self.message,
+            },
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// Result type for REST handlers
+pub type ApiResult<T> = Result<T, ApiError>;
+
+// =============================================================================
+// Extractors
+// =============================================================================
+
+/// JSON body extractor that reports rejections as `ApiError`
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ApiJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        Json::<T>::from_request(req, state)
+            .await
+            .map(|Json(value)| ApiJson(value))
+            .map_err(|e| ApiError::new(e.status(), e.body_text()))
+    }
+}
+
+/// Query string extractor that reports rejections as `ApiError`
+pub struct ApiQuery<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|Query(value)| ApiQuery(value))
+            .map_err(|e| ApiError::bad_request(e.body_text()))
+    }
+}
+
+// =============================================================================
+// Shared parameters
+// =============================================================================
+
+/// Query parameters for project-scoped resources
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query, rename_all = "camelCase")]
+pub struct ProjectQuery {
+    /// Absolute path of the project directory
+    pub project_path: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_errors_are_internal_errors() {
+        // Error text never decides the status, even when it reads like a 404
+        let err = ApiError::from("Failed to read PRD: file not found".to_string());
+        assert_eq!(err.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.code(), "internal_error");
+        assert_eq!(ApiError::conflict("Story exists").code(), "conflict");
+    }
+}
@@ -0,0 +1,111 @@
+//! OpenAPI 3 document for the REST API, served at `/api/openapi.json`
+
+use axum::Json;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use super::{chats, executions, git, prds, projects, ApiErrorBody};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Ralph UI API",
+        description = "Resource-oriented API for projects, PRDs, stories, executions, \
+                       iterations, learnings, chats and git. Errors use the `ApiErrorBody` shape."
+    ),
+    paths(
+        projects::list_projects,
+        projects::register_project,
+        projects::get_project,
+        projects::update_project,
+        projects::delete_project,
+        prds::list_prds,
+        prds::get_prd,
+        prds::get_prd_status,
+        prds::list_stories,
+        prds::add_story,
+        prds::get_story,
+        prds::remove_story,
+        prds::set_story_passes,
+        prds::list_learnings,
+        prds::add_learning,
+        prds::update_learning,
+        prds::delete_learning,
+        executions::list_executions,
+        executions::start_execution,
+        executions::get_execution,
+        executions::stop_execution,
+        executions::list_iterations,
+        executions::get_iteration_stats,
+        chats::list_sessions,
+        chats::start_session,
+        chats::delete_session,
+        chats::list_messages,
+        chats::send_message,
+        git::list_branches,
+        git::get_status,
+        git::list_commits,
+        git::get_working_diff,
+    ),
+    components(schemas(ApiErrorBody)),
+    modifiers(&BearerAuth),
+    security(("bearer" = [])),
+    tags(
+        (name = "projects", description = "Registered projects"),
+        (name = "prds", description = "PRDs and their status"),
+        (name = "stories", description = "Stories within a PRD"),
+        (name = "learnings", description = "Learnings accumulated for a PRD"),
+        (name = "executions", description = "Ralph loop executions"),
+        (name = "iterations", description = "Iteration history of an execution"),
+        (name = "chats", description = "PRD chat sessions"),
+        (name = "git", description = "Repository inspection")
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the server's bearer token scheme
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// GET /api/openapi.json
+pub async fn openapi_handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_document_covers_resources() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+        let paths = doc["paths"].as_object().unwrap();
+        for path in [
+            "/api/v1/projects",
+            "/api/v1/prds/{prd_name}/stories",
+            "/api/v1/executions/{execution_id}/iterations",
+            "/api/v1/prds/{prd_name}/learnings",
+            "/api/v1/chats/{session_id}/messages",
+            "/api/v1/git/commits",
+        ] {
+            assert!(paths.contains_key(path), "missing {}", path);
+        }
+
+        // Referenced schemas are collected into components
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+        for schema in ["ApiErrorBody", "RalphPrd", "RalphStory", "IterationRecord"] {
+            assert!(schemas.contains_key(schema), "missing schema {}", schema);
+        }
+    }
+}
@@ -0,0 +1,394 @@
+//! REST endpoints for PRDs, their stories and learnings
+
+use axum::{extract::Path, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{ApiError, ApiErrorBody, ApiJson, ApiQuery, ApiResult, ProjectQuery};
+use crate::commands::ralph_loop::{
+    self as ralph_commands, AddLearningInput, RalphStoryInput, UpdateLearningInput,
+};
+use crate::ralph_loop::{LearningEntry, LearningsFile, PrdStatus, RalphPrd, RalphStory};
+use crate::utils::prds_dir;
+
+/// A PRD with its progress
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PrdSummary {
+    /// PRD file name (without extension)
+    pub name: String,
+    pub title: String,
+    pub branch: String,
+    pub status: PrdStatus,
+}
+
+/// Request to mark a story as passing or failing
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetStoryPassesRequest {
+    pub passes: bool,
+}
+
+/// Partial update of a learning (empty strings clear optional fields)
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLearningRequest {
+    pub learning_type: Option<String>,
+    pub content: Option<String>,
+    pub code_example: Option<String>,
+    pub story_id: Option<String>,
+}
+
+/// Fail with 404 unless the project has a PRD file with this name
+pub(super) fn require_prd(project_path: &str, prd_name: &str) -> ApiResult<()> {
+    if !prds_dir(project_path)
+        .join(format!("{}.json", prd_name))
+        .is_file()
+    {
+        return Err(ApiError::not_found(format!("PRD not found: {}", prd_name)));
+    }
+    Ok(())
+}
+
+fn find_story(prd: RalphPrd, story_id: &str) -> ApiResult<RalphStory> {
+    prd.stories
+        .into_iter()
+        .find(|s| s.id == story_id)
+        .ok_or_else(|| ApiError::not_found(format!("Story not found: {}", story_id)))
+}
+
+/// List the project's PRDs with their status
+#[utoipa::path(
+    get,
+    path = "/api/v1/prds",
+    tag = "prds",
+    params(ProjectQuery),
+    responses((status = 200, body = Vec<PrdSummary>))
+)]
+pub async fn list_prds(
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<Vec<PrdSummary>>> {
+    let files = ralph_commands::get_ralph_files(query.project_path.clone())?;
+
+    let mut prds = Vec::new();
+    for name in files.prd_names {
+        let prd = match ralph_commands::get_ralph_prd(query.project_path.clone(), name.clone()) {
+            Ok(prd) => prd,
+            Err(e) => {
+                log::warn!("[REST] Skipping unreadable PRD {}: {}", name, e);
+                continue;
+            }
+        };
+        let status =
+            ralph_commands::get_ralph_prd_status(query.project_path.clone(), name.clone())?;
+        prds.push(PrdSummary {
+            name,
+            title: prd.title,
+            branch: prd.branch,
+            status,
+        });
+    }
+    prds.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Json(prds))
+}
+
+/// Get a PRD
+#[utoipa::path(
+    get,
+    path = "/api/v1/prds/{prd_name}",
+    tag = "prds",
+    params(("prd_name" = String, Path, description = "PRD name"), ProjectQuery),
+    responses(
+        (status = 200, body = RalphPrd),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn get_prd(
+    Path(prd_name): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<RalphPrd>> {
+    require_prd(&query.project_path, &prd_name)?;
+    Ok(Json(ralph_commands::get_ralph_prd(
+        query.project_path,
+        prd_name,
+    )?))
+}
+
+/// Get a PRD's completion status
+#[utoipa::path(
+    get,
+    path = "/api/v1/prds/{prd_name}/status",
+    tag = "prds",
+    params(("prd_name" = String, Path, description = "PRD name"), ProjectQuery),
+    responses(
+        (status = 200, body = PrdStatus),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn get_prd_status(
+    Path(prd_name): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<PrdStatus>> {
+    require_prd(&query.project_path, &prd_name)?;
+    Ok(Json(ralph_commands::get_ralph_prd_status(
+        query.project_path,
+        prd_name,
+    )?))
+}
+
+/// List a PRD's stories
+#[utoipa::path(
+    get,
+    path = "/api/v1/prds/{prd_name}/stories",
+    tag = "stories",
+    params(("prd_name" = String, Path, description = "PRD name"), ProjectQuery),
+    responses(
+        (status = 200, body = Vec<RalphStory>),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn list_stories(
+    Path(prd_name): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<Vec<RalphStory>>> {
+    require_prd(&query.project_path, &prd_name)?;
+    let prd = ralph_commands::get_ralph_prd(query.project_path, prd_name)?;
+    Ok(Json(prd.stories))
+}
+
+/// Add a story to a PRD
+#[utoipa::path(
+    post,
+    path = "/api/v1/prds/{prd_name}/stories",
+    tag = "stories",
+    params(("prd_name" = String, Path, description = "PRD name"), ProjectQuery),
+    request_body = RalphStoryInput,
+    responses(
+        (status = 201, body = RalphStory),
+        (status = 404, body = ApiErrorBody),
+        (status = 409, body = ApiErrorBody)
+    )
+)]
+pub async fn add_story(
+    Path(prd_name): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+    ApiJson(story): ApiJson<RalphStoryInput>,
+) -> ApiResult<(StatusCode, Json<RalphStory>)> {
+    require_prd(&query.project_path, &prd_name)?;
+    let story_id = story.id.clone();
+    let prd = ralph_commands::get_ralph_prd(query.project_path.clone(), prd_name.clone())?;
+    if prd.stories.iter().any(|s| s.id == story_id) {
+        return Err(ApiError::conflict(format!(
+            "Story already exists: {}",
+            story_id
+        )));
+    }
+    ralph_commands::add_ralph_story(query.project_path.clone(), prd_name.clone(), story)?;
+    let prd = ralph_commands::get_ralph_prd(query.project_path, prd_name)?;
+    Ok((StatusCode::CREATED, Json(find_story(prd, &story_id)?)))
+}
+
+/// Get a story
+#[utoipa::path(
+    get,
+    path = "/api/v1/prds/{prd_name}/stories/{story_id}",
+    tag = "stories",
+    params(
+        ("prd_name" = String, Path, description = "PRD name"),
+        ("story_id" = String, Path, description = "Story ID"),
+        ProjectQuery
+    ),
+    responses(
+        (status = 200, body = RalphStory),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn get_story(
+    Path((prd_name, story_id)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<RalphStory>> {
+    require_prd(&query.project_path, &prd_name)?;
+    let prd = ralph_commands::get_ralph_prd(query.project_path, prd_name)?;
+    Ok(Json(find_story(prd, &story_id)?))
+}
+
+/// Remove a story from a PRD
+#[utoipa::path(
+    delete,
+    path = "/api/v1/prds/{prd_name}/stories/{story_id}",
+    tag = "stories",
+    params(
+        ("prd_name" = String, Path, description = "PRD name"),
+        ("story_id" = String, Path, description = "Story ID"),
+        ProjectQuery
+    ),
+    responses(
+        (status = 204),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn remove_story(
+    Path((prd_name, story_id)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<StatusCode> {
+    require_prd(&query.project_path, &prd_name)?;
+    if !ralph_commands::remove_ralph_story(query.project_path, prd_name, story_id.clone())? {
+        return Err(ApiError::not_found(format!(
+            "Story not found: {}",
+            story_id
+        )));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Mark a story as passing or failing
+#[utoipa::path(
+    put,
+    path = "/api/v1/prds/{prd_name}/stories/{story_id}/passes",
+    tag = "stories",
+    params(
+        ("prd_name" = String, Path, description = "PRD name"),
+        ("story_id" = String, Path, description = "Story ID"),
+        ProjectQuery
+    ),
+    request_body = SetStoryPassesRequest,
+    responses(
+        (status = 200, body = RalphStory),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn set_story_passes(
+    Path((prd_name, story_id)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+    ApiJson(request): ApiJson<SetStoryPassesRequest>,
+) -> ApiResult<Json<RalphStory>> {
+    require_prd(&query.project_path, &prd_name)?;
+    let (project_path, prd, story) = (query.project_path, prd_name, story_id.clone());
+    find_story(
+        ralph_commands::get_ralph_prd(project_path.clone(), prd.clone())?,
+        &story_id,
+    )?;
+    if request.passes {
+        ralph_commands::mark_ralph_story_passing(project_path.clone(), prd.clone(), story)?;
+    } else {
+        ralph_commands::mark_ralph_story_failing(project_path.clone(), prd.clone(), story)?;
+    }
+    let prd = ralph_commands::get_ralph_prd(project_path, prd)?;
+    Ok(Json(find_story(prd, &story_id)?))
+}
+
+/// Get a PRD's learnings
+#[utoipa::path(
+    get,
+    path = "/api/v1/prds/{prd_name}/learnings",
+    tag = "learnings",
+    params(("prd_name" = String, Path, description = "PRD name"), ProjectQuery),
+    responses(
+        (status = 200, body = LearningsFile),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn list_learnings(
+    Path(prd_name): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<Json<LearningsFile>> {
+    require_prd(&query.project_path, &prd_name)?;
+    Ok(Json(ralph_commands::get_ralph_learnings(
+        query.project_path,
+        prd_name,
+    )?))
+}
+
+/// Add a manual learning
+#[utoipa::path(
+    post,
+    path = "/api/v1/prds/{prd_name}/learnings",
+    tag = "learnings",
+    params(("prd_name" = String, Path, description = "PRD name"), ProjectQuery),
+    request_body = AddLearningInput,
+    responses(
+        (status = 201, body = LearningEntry),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn add_learning(
+    Path(prd_name): Path<String>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+    ApiJson(input): ApiJson<AddLearningInput>,
+) -> ApiResult<(StatusCode, Json<LearningEntry>)> {
+    require_prd(&query.project_path, &prd_name)?;
+    let entry = ralph_commands::add_ralph_learning(query.project_path, prd_name, input)?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Update a learning
+#[utoipa::path(
+    patch,
+    path = "/api/v1/prds/{prd_name}/learnings/{learning_id}",
+    tag = "learnings",
+    params(
+        ("prd_name" = String, Path, description = "PRD name"),
+        ("learning_id" = String, Path, description = "Learning ID"),
+        ProjectQuery
+    ),
+    request_body = UpdateLearningRequest,
+    responses(
+        (status = 200, body = LearningEntry),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn update_learning(
+    Path((prd_name, learning_id)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+    ApiJson(request): ApiJson<UpdateLearningRequest>,
+) -> ApiResult<Json<LearningEntry>> {
+    require_prd(&query.project_path, &prd_name)?;
+    let learnings =
+        ralph_commands::get_ralph_learnings(query.project_path.clone(), prd_name.clone())?;
+    if !learnings.entries.iter().any(|e| e.id == learning_id) {
+        return Err(ApiError::not_found(format!(
+            "Learning not found: {}",
+            learning_id
+        )));
+    }
+    let input = UpdateLearningInput {
+        id: learning_id,
+        learning_type: request.learning_type,
+        content: request.content,
+        code_example: request.code_example,
+        story_id: request.story_id,
+    };
+    Ok(Json(ralph_commands::update_ralph_learning(
+        query.project_path,
+        prd_name,
+        input,
+    )?))
+}
+
+/// Delete a learning
+#[utoipa::path(
+    delete,
+    path = "/api/v1/prds/{prd_name}/learnings/{learning_id}",
+    tag = "learnings",
+    params(
+        ("prd_name" = String, Path, description = "PRD name"),
+        ("learning_id" = String, Path, description = "Learning ID"),
+        ProjectQuery
+    ),
+    responses(
+        (status = 204),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn delete_learning(
+    Path((prd_name, learning_id)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<ProjectQuery>,
+) -> ApiResult<StatusCode> {
+    require_prd(&query.project_path, &prd_name)?;
+    if !ralph_commands::delete_ralph_learning(query.project_path, prd_name, learning_id.clone())? {
+        return Err(ApiError::not_found(format!(
+            "Learning not found: {}",
+            learning_id
+        )));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,127 @@
+//! REST endpoints for registered projects
+
+use axum::{extract::Path, http::StatusCode, Json};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use super::{ApiError, ApiErrorBody, ApiJson, ApiResult};
+use crate::commands::projects as project_commands;
+use crate::file_storage::projects::{self as file_projects, ApiProject};
+
+/// Request to register a project folder
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterProjectRequest {
+    /// Absolute path of the project directory
+    pub path: String,
+    /// Display name (defaults to the folder name)
+    pub name: Option<String>,
+}
+
+/// Partial update of a project
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProjectRequest {
+    pub name: Option<String>,
+    pub is_favorite: Option<bool>,
+}
+
+fn find_project(project_id: &str) -> ApiResult<ApiProject> {
+    file_projects::get_project(project_id)?
+        .map(|entry| entry.to_api_project())
+        .ok_or_else(|| ApiError::not_found(format!("Project not found: {}", project_id)))
+}
+
+/// List registered projects
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects",
+    tag = "projects",
+    responses((status = 200, body = Vec<ApiProject>))
+)]
+pub async fn list_projects() -> ApiResult<Json<Vec<ApiProject>>> {
+    Ok(Json(project_commands::get_all_projects()?))
+}
+
+/// Register a project (returns the existing entry if already registered)
+#[utoipa::path(
+    post,
+    path = "/api/v1/projects",
+    tag = "projects",
+    request_body = RegisterProjectRequest,
+    responses(
+        (status = 201, body = ApiProject),
+        (status = 400, body = ApiErrorBody)
+    )
+)]
+pub async fn register_project(
+    ApiJson(request): ApiJson<RegisterProjectRequest>,
+) -> ApiResult<(StatusCode, Json<ApiProject>)> {
+    if !std::path::Path::new(&request.path).is_dir() {
+        return Err(ApiError::bad_request(format!(
+            "Project path is not a directory: {}",
+            request.path
+        )));
+    }
+    let project = project_commands::register_project(request.path, request.name)?;
+    Ok((StatusCode::CREATED, Json(project)))
+}
+
+/// Get a project by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{project_id}",
+    tag = "projects",
+    params(("project_id" = String, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = ApiProject),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn get_project(Path(project_id): Path<String>) -> ApiResult<Json<ApiProject>> {
+    Ok(Json(find_project(&project_id)?))
+}
+
+/// Rename a project or change its favorite flag
+#[utoipa::path(
+    patch,
+    path = "/api/v1/projects/{project_id}",
+    tag = "projects",
+    params(("project_id" = String, Path, description = "Project ID")),
+    request_body = UpdateProjectRequest,
+    responses(
+        (status = 200, body = ApiProject),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn update_project(
+    Path(project_id): Path<String>,
+    ApiJson(request): ApiJson<UpdateProjectRequest>,
+) -> ApiResult<Json<ApiProject>> {
+    // Fail with 404 before applying a partial update
+    find_project(&project_id)?;
+    if let Some(name) = request.name {
+        project_commands::update_project_name(project_id.clone(), name)?;
+    }
+    if let Some(is_favorite) = request.is_favorite {
+        project_commands::set_project_favorite(project_id.clone(), is_favorite)?;
+    }
+    Ok(Json(find_project(&project_id)?))
+}
+
+/// Unregister a project (project files are left untouched)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/projects/{project_id}",
+    tag = "projects",
+    params(("project_id" = String, Path, description = "Project ID")),
+    responses(
+        (status = 204),
+        (status = 404, body = ApiErrorBody)
+    )
+)]
+pub async fn delete_project(Path(project_id): Path<String>) -> ApiResult<StatusCode> {
+    find_project(&project_id)?;
+    project_commands::delete_project(project_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -4,7 +4,7 @@
 //! get_active_agents, get_all_active_agents, update_agent_metrics, update_agent_process_id,
 //! delete_agent, get_agent_pty_history, update_agent_status, add_agent_log, get_agent_logs,
 //! cleanup_stale_agents, agent_has_pty, get_agent_pty_id, process_agent_pty_data,
-//! notify_agent_pty_exit
+//! notify_agent_pty_exit, get_rate_limits, clear_rate_limit
 
 use crate::commands;
 use crate::models::*;
@@ -37,6 +37,18 @@ pub async fn route_agent_command(
             serde_json::to_value(result).map_err(|e| e.to_string())
         }
 
+        "get_rate_limits" => {
+            let result = commands::agents::get_rate_limits();
+            serde_json::to_value(result).map_err(|e| e.to_string())
+        }
+
+        "clear_rate_limit" => {
+            let agent_type: AgentType = get_arg(&args, "agentType")?;
+            let model: Option<String> = get_opt_arg(&args, "model")?;
+            let removed = commands::agents::clear_rate_limit(agent_type, model);
+            Ok(serde_json::json!({ "removed": removed }))
+        }
+
         "create_agent" => {
             let agent: Agent = get_arg(&args, "agent")?;
             let project_path: String = get_arg(&args, "projectPath")?;
@@ -208,6 +220,8 @@ pub fn is_agent_command(cmd: &str) -> bool {
     matches!(
         cmd,
         "get_all_agents_status"
+            | "get_rate_limits"
+            | "clear_rate_limit"
             | "create_agent"
             | "get_agent"
             | "get_agents_for_session"
@@ -0,0 +1,40 @@
+//! API token management command routing
+//!
+//! Handles: create_api_token, revoke_api_token, list_api_tokens
+
+use crate::commands;
+use crate::file_storage::api_tokens::NewApiToken;
+use serde_json::Value;
+
+use super::{get_arg, route_sync, ServerAppState};
+
+/// Route API token commands
+pub async fn route_api_token_command(
+    cmd: &str,
+    args: Value,
+    _state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "create_api_token" => {
+            let request: NewApiToken = get_arg(&args, "request")?;
+            route_sync!(commands::api_tokens::create_api_token(request))
+        }
+
+        "revoke_api_token" => {
+            let token_id: String = get_arg(&args, "tokenId")?;
+            route_sync!(commands::api_tokens::revoke_api_token(token_id))
+        }
+
+        "list_api_tokens" => route_sync!(commands::api_tokens::list_api_tokens()),
+
+        _ => Err(format!("Unknown API token command: {}", cmd)),
+    }
+}
+
+/// Check if a command is an API token command
+pub fn is_api_token_command(cmd: &str) -> bool {
+    matches!(
+        cmd,
+        "create_api_token" | "revoke_api_token" | "list_api_tokens"
+    )
+}
@@ -0,0 +1,30 @@
+//! Audit log command routing
+//!
+//! Handles: query_audit_log
+
+use crate::commands;
+use crate::file_storage::audit::AuditFilter;
+use serde_json::Value;
+
+use super::{get_opt_arg, route_sync, ServerAppState};
+
+/// Route audit log commands
+pub async fn route_audit_command(
+    cmd: &str,
+    args: Value,
+    _state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "query_audit_log" => {
+            let filter: Option<AuditFilter> = get_opt_arg(&args, "filter")?;
+            route_sync!(commands::audit::query_audit_log(filter))
+        }
+
+        _ => Err(format!("Unknown audit command: {}", cmd)),
+    }
+}
+
+/// Check if a command is an audit log command
+pub fn is_audit_command(cmd: &str) -> bool {
+    matches!(cmd, "query_audit_log")
+}
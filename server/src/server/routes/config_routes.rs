@@ -8,19 +8,23 @@
 //! Also handles project commands: register_project, get_project, get_project_by_path,
 //! get_all_projects, get_recent_projects, get_favorite_projects, update_project_name,
 //! toggle_project_favorite, set_project_favorite, touch_project, delete_project,
-//! list_directory, get_home_directory
+//! list_directory, get_home_directory, export_project_state (base64 `.tar.gz`),
+//! import_project_state
 //!
 //! Also handles template commands: list_templates, list_builtin_templates,
 //! get_template_content, save_template, delete_template, preview_template,
 //! render_template, render_task_prompt
 //!
-//! Also handles mission control commands: get_activity_feed, get_global_stats
+//! Also handles mission control commands: get_activity_feed, get_global_stats,
+//! list_fleet_executions (aggregated over federation peers unless `federated: false`),
+//! get_activity_page (this server only; cursors are per server)
 //!
 //! Also handles model discovery: get_available_models, refresh_models
 //!
 //! Also handles recovery commands: check_stale_sessions, recover_stale_session,
 //! recover_all_stale_sessions, acquire_session_lock, release_session_lock,
-//! get_session_lock_info, refresh_session_lock
+//! get_session_lock_info, refresh_session_lock, get_migration_report (dry run),
+//! run_migrations
 //!
 //! Also handles terminal commands: save_project_commands, load_project_commands,
 //! save_global_commands, load_global_commands
@@ -213,6 +217,28 @@ pub async fn route_config_command(
             route_unit!(commands::projects::delete_project(project_id))
         }
 
+        "export_project_state" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let options = get_opt_arg(&args, "options")?;
+            route_sync!(commands::projects::export_project_state(
+                project_path,
+                options
+            ))
+        }
+
+        "import_project_state" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let data: String = get_arg(&args, "data")?;
+            let mode = get_opt_arg(&args, "mode")?;
+            let dry_run: bool = get_opt_arg(&args, "dryRun")?.unwrap_or(false);
+            route_sync!(commands::projects::import_project_state(
+                project_path,
+                data,
+                mode,
+                dry_run
+            ))
+        }
+
         "list_directory" => {
             let path: Option<String> = get_opt_arg(&args, "path")?;
             route_sync!(commands::projects::list_directory(path))
@@ -248,10 +274,39 @@ pub async fn route_config_command(
         "get_activity_feed" => {
             let limit: Option<i32> = get_opt_arg(&args, "limit")?;
             let offset: Option<i32> = get_opt_arg(&args, "offset")?;
-            route_sync!(commands::mission_control::get_activity_feed(limit, offset))
+            if get_opt_arg(&args, "federated")?.unwrap_or(true) {
+                route_async!(
+                    cmd,
+                    commands::mission_control::get_fleet_activity_feed(limit, offset)
+                )
+            } else {
+                route_sync!(commands::mission_control::get_activity_feed(limit, offset))
+            }
         }
 
-        "get_global_stats" => route_sync!(commands::mission_control::get_global_stats()),
+        "get_activity_page" => {
+            let query = get_opt_arg(&args, "query")?;
+            route_sync!(commands::mission_control::get_activity_page(query))
+        }
+
+        "get_global_stats" => {
+            if get_opt_arg(&args, "federated")?.unwrap_or(true) {
+                route_async!(cmd, commands::mission_control::get_fleet_global_stats())
+            } else {
+                route_sync!(commands::mission_control::get_global_stats())
+            }
+        }
+
+        "list_fleet_executions" => {
+            let federated: bool = get_opt_arg(&args, "federated")?.unwrap_or(true);
+            let local = commands::ralph_loop::list_ralph_loop_executions_with_details(
+                &state.ralph_loop_state,
+            )?;
+            route_async!(
+                cmd,
+                commands::mission_control::list_fleet_executions(local, federated)
+            )
+        }
 
         // Template Commands
         "list_templates" => {
@@ -469,6 +524,16 @@ pub async fn route_config_command(
             )
         }
 
+        "get_migration_report" => {
+            let project_path: Option<String> = get_opt_arg(&args, "projectPath")?;
+            route_async!(cmd, commands::recovery::get_migration_report(project_path))
+        }
+
+        "run_migrations" => {
+            let project_path: Option<String> = get_opt_arg(&args, "projectPath")?;
+            route_async!(cmd, commands::recovery::run_migrations(project_path))
+        }
+
         // Terminal Commands
         "save_project_commands" => {
             let project_path: String = get_arg(&args, "projectPath")?;
@@ -618,6 +683,8 @@ pub fn is_config_command(cmd: &str) -> bool {
             | "set_project_favorite"
             | "touch_project"
             | "delete_project"
+            | "export_project_state"
+            | "import_project_state"
             | "list_directory"
             | "get_home_directory"
             | "create_folder"
@@ -626,7 +693,9 @@ pub fn is_config_command(cmd: &str) -> bool {
             | "create_filesystem_directory"
             // Mission control
             | "get_activity_feed"
+            | "get_activity_page"
             | "get_global_stats"
+            | "list_fleet_executions"
             // Templates
             | "list_templates"
             | "list_builtin_templates"
@@ -655,6 +724,8 @@ pub fn is_config_command(cmd: &str) -> bool {
             | "release_session_lock"
             | "get_session_lock_info"
             | "refresh_session_lock"
+            | "get_migration_report"
+            | "run_migrations"
             // Terminal
             | "save_project_commands"
             | "load_project_commands"
@@ -0,0 +1,48 @@
+//! Federation peer command routing
+//!
+//! Handles: list_federation_peers, add_federation_peer, remove_federation_peer,
+//! get_federation_status
+
+use crate::commands;
+use crate::federation::NewPeer;
+use serde_json::Value;
+
+use super::{get_arg, route_async, route_sync, ServerAppState};
+
+/// Route federation commands
+pub async fn route_federation_command(
+    cmd: &str,
+    args: Value,
+    _state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "list_federation_peers" => route_sync!(commands::federation::list_federation_peers()),
+
+        "add_federation_peer" => {
+            let request: NewPeer = get_arg(&args, "request")?;
+            route_sync!(commands::federation::add_federation_peer(request))
+        }
+
+        "remove_federation_peer" => {
+            let peer_id: String = get_arg(&args, "peerId")?;
+            route_sync!(commands::federation::remove_federation_peer(peer_id))
+        }
+
+        "get_federation_status" => {
+            route_async!(cmd, commands::federation::get_federation_status())
+        }
+
+        _ => Err(format!("Unknown federation command: {}", cmd)),
+    }
+}
+
+/// Check if a command is a federation command
+pub fn is_federation_command(cmd: &str) -> bool {
+    matches!(
+        cmd,
+        "list_federation_peers"
+            | "add_federation_peer"
+            | "remove_federation_peer"
+            | "get_federation_status"
+    )
+}
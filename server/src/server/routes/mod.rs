@@ -10,23 +10,41 @@
 //! - prd_workflow_routes: PRD workflow commands (centralized PRD creation system)
 //! - config_routes: Configuration and misc commands
 //! - parallel_routes: Parallel execution commands (reserved for future use)
+//! - transcript_routes: Persisted agent transcript commands
+//! - search_routes: Full-text search commands
+//! - api_token_routes: Named API token management commands
+//! - share_link_routes: Read-only execution share links
+//! - audit_routes: Audit log queries
+//! - webhook_routes: Outbound webhook management
+//! - trigger_routes: Inbound webhook trigger management
+//! - recording_routes: Asciicast terminal recordings
+//! - federation_routes: Federation peer management
 
 pub mod agent_routes;
+pub mod api_token_routes;
+pub mod audit_routes;
 pub mod chat_command_routes;
 pub mod config_routes;
 pub mod context_routes;
+pub mod federation_routes;
 pub mod git_routes;
 pub mod parallel_routes;
 pub mod prd_routes;
 pub mod prd_workflow_routes;
 pub mod ralph_loop_routes;
+pub mod recording_routes;
+pub mod search_routes;
 pub mod session_routes;
+pub mod share_link_routes;
 pub mod task_routes;
+pub mod transcript_routes;
+pub mod trigger_routes;
+pub mod webhook_routes;
 
 use serde::Serialize;
 use serde_json::Value;
 
-use super::ServerAppState;
+use super::{AuthIdentity, ServerAppState};
 
 // =============================================================================
 // Re-export helper functions for use by route modules
@@ -116,11 +134,13 @@ pub use route_unit_async;
 // Main Command Dispatcher
 // =============================================================================
 
-/// Route a command to its implementation by dispatching to the appropriate sub-router
+/// Route a command to its implementation by dispatching to the appropriate sub-router.
+/// `identity` is the authenticated caller (None for trusted local callers).
 pub async fn route_command(
     cmd: &str,
     args: Value,
     state: &ServerAppState,
+    identity: Option<&AuthIdentity>,
 ) -> Result<Value, String> {
     // Dispatch to the appropriate sub-router based on command name/prefix
 
@@ -141,7 +161,7 @@ pub async fn route_command(
     }
 
     if ralph_loop_routes::is_ralph_loop_command(cmd) {
-        return ralph_loop_routes::route_ralph_loop_command(cmd, args, state).await;
+        return ralph_loop_routes::route_ralph_loop_command(cmd, args, state, identity).await;
     }
 
     if prd_routes::is_prd_command(cmd) {
@@ -168,6 +188,42 @@ pub async fn route_command(
         return context_routes::route_context_command(cmd, args, state).await;
     }
 
+    if transcript_routes::is_transcript_command(cmd) {
+        return transcript_routes::route_transcript_command(cmd, args, state).await;
+    }
+
+    if recording_routes::is_recording_command(cmd) {
+        return recording_routes::route_recording_command(cmd, args, state).await;
+    }
+
+    if search_routes::is_search_command(cmd) {
+        return search_routes::route_search_command(cmd, args, state).await;
+    }
+
+    if api_token_routes::is_api_token_command(cmd) {
+        return api_token_routes::route_api_token_command(cmd, args, state).await;
+    }
+
+    if share_link_routes::is_share_link_command(cmd) {
+        return share_link_routes::route_share_link_command(cmd, args, state).await;
+    }
+
+    if audit_routes::is_audit_command(cmd) {
+        return audit_routes::route_audit_command(cmd, args, state).await;
+    }
+
+    if webhook_routes::is_webhook_command(cmd) {
+        return webhook_routes::route_webhook_command(cmd, args, state).await;
+    }
+
+    if federation_routes::is_federation_command(cmd) {
+        return federation_routes::route_federation_command(cmd, args, state).await;
+    }
+
+    if trigger_routes::is_trigger_command(cmd) {
+        return trigger_routes::route_trigger_command(cmd, args, state).await;
+    }
+
     Err(format!("Unknown command: {}", cmd))
 }
 
@@ -176,6 +232,6 @@ pub async fn route_command(
 // =============================================================================
 
 pub use super::proxy::{
-    regenerate_ralph_prd_stories_server, send_prd_chat_message_server, start_ralph_loop_server,
-    stop_ralph_loop_server,
+    checkpoint_ralph_loops_server, regenerate_ralph_prd_stories_server, resume_ralph_loop_server,
+    send_prd_chat_message_server, start_ralph_loop_server, stop_ralph_loop_server,
 };
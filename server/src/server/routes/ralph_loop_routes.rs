@@ -5,15 +5,46 @@
 
 use crate::commands;
 use crate::commands::ralph_loop::RalphStoryInput;
-use crate::ralph_loop::{ExecutionSnapshot, RalphConfig};
+use crate::ralph_loop::{ExecutionSnapshot, RalphConfig, ToolAnalyticsFilter};
 use serde::Serialize;
 use serde_json::Value;
 
-use super::{get_arg, get_opt_arg, route_sync, route_unit, ServerAppState};
+use super::{get_arg, get_opt_arg, route_sync, route_unit, AuthIdentity, ServerAppState};
+use crate::server::auth::ExecutionAccessError;
+use crate::server::permissions;
+
+/// Check the caller may use an execution of the project it runs in, and that
+/// it's the project the caller named (if any)
+fn authorize_execution(
+    state: &ServerAppState,
+    identity: Option<&AuthIdentity>,
+    cmd: &str,
+    args: &Value,
+    execution_id: &str,
+) -> Result<(), String> {
+    let Some(identity) = identity else {
+        return Ok(());
+    };
+    let named_project: Option<String> = get_opt_arg(args, "projectPath")?;
+    let execution_project = state.ralph_loop_state.execution_project(execution_id);
+    identity
+        .authorize_execution(
+            cmd,
+            permissions::command_permission(cmd),
+            execution_project.as_deref(),
+            named_project.as_deref(),
+        )
+        .map_err(|e| match e {
+            ExecutionAccessError::NotFound => format!("Execution {} not found", execution_id),
+            ExecutionAccessError::Forbidden(e) => format!("Forbidden: {}", e),
+        })
+}
 
 /// Extract a field from an ExecutionSnapshot by execution ID
 fn get_snapshot_field<T, F>(
     state: &ServerAppState,
+    identity: Option<&AuthIdentity>,
+    cmd: &str,
     args: &Value,
     extractor: F,
 ) -> Result<Value, String>
@@ -22,6 +53,7 @@ where
     F: FnOnce(&ExecutionSnapshot) -> T,
 {
     let execution_id: String = super::get_arg(args, "executionId")?;
+    authorize_execution(state, identity, cmd, args, &execution_id)?;
     match state.ralph_loop_state.get_snapshot(&execution_id) {
         Some(snapshot) => serde_json::to_value(extractor(&snapshot)).map_err(|e| e.to_string()),
         None => Err(format!("Execution {} not found", execution_id)),
@@ -33,6 +65,7 @@ pub async fn route_ralph_loop_command(
     cmd: &str,
     args: Value,
     state: &ServerAppState,
+    identity: Option<&AuthIdentity>,
 ) -> Result<Value, String> {
     match cmd {
         "get_ralph_prd" => {
@@ -267,6 +300,35 @@ pub async fn route_ralph_loop_command(
             ))
         }
 
+        "list_ralph_loop_checkpoints" => {
+            let project_path: Option<String> = get_opt_arg(&args, "projectPath")?;
+            route_sync!(commands::ralph_loop::list_ralph_loop_checkpoints(
+                project_path
+            ))
+        }
+
+        "list_ralph_loop_leases" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            route_sync!(commands::ralph_loop::list_ralph_loop_leases(project_path))
+        }
+
+        "resume_ralph_loop" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let execution_id: String = get_arg(&args, "executionId")?;
+            let execution_id =
+                super::resume_ralph_loop_server(project_path, execution_id, state).await?;
+            serde_json::to_value(execution_id).map_err(|e| e.to_string())
+        }
+
+        "discard_ralph_loop_checkpoint" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let execution_id: String = get_arg(&args, "executionId")?;
+            route_unit!(commands::ralph_loop::discard_ralph_loop_checkpoint(
+                project_path,
+                execution_id
+            ))
+        }
+
         "cleanup_ralph_iteration_history" => {
             let project_path: String = get_arg(&args, "projectPath")?;
             let days_to_keep: Option<i64> = get_opt_arg(&args, "daysToKeep")?;
@@ -426,6 +488,15 @@ pub async fn route_ralph_loop_command(
             ))
         }
 
+        "get_ralph_tool_analytics" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let filter: Option<ToolAnalyticsFilter> = get_opt_arg(&args, "filter")?;
+            route_sync!(commands::ralph_loop::get_ralph_tool_analytics(
+                project_path,
+                filter
+            ))
+        }
+
         "delete_ralph_iteration_history" => {
             let project_path: String = get_arg(&args, "projectPath")?;
             let execution_id: String = get_arg(&args, "executionId")?;
@@ -465,24 +536,41 @@ pub async fn route_ralph_loop_command(
             serde_json::to_value(execution_id).map_err(|e| e.to_string())
         }
 
+        "run_ralph_preflight" => {
+            let request: commands::ralph_loop::RalphPreflightRequest = get_arg(&args, "request")?;
+            let report = commands::ralph_loop::run_ralph_preflight(
+                request,
+                &state.config_state,
+                state.model_cache_state.clone(),
+            )
+            .await?;
+            serde_json::to_value(report).map_err(|e| e.to_string())
+        }
+
         "stop_ralph_loop" => {
             let execution_id: String = get_arg(&args, "executionId")?;
+            authorize_execution(state, identity, cmd, &args, &execution_id)?;
             super::stop_ralph_loop_server(execution_id, state).await?;
             Ok(serde_json::Value::Null)
         }
 
-        "get_ralph_loop_state" => get_snapshot_field(state, &args, |s| s.state.clone()),
-        "get_ralph_loop_metrics" => get_snapshot_field(state, &args, |s| s.metrics.clone()),
+        "get_ralph_loop_state" => {
+            get_snapshot_field(state, identity, cmd, &args, |s| s.state.clone())
+        }
+        "get_ralph_loop_metrics" => {
+            get_snapshot_field(state, identity, cmd, &args, |s| s.metrics.clone())
+        }
         "get_ralph_loop_current_agent" => {
-            get_snapshot_field(state, &args, |s| s.current_agent_id.clone())
+            get_snapshot_field(state, identity, cmd, &args, |s| s.current_agent_id.clone())
         }
         "get_ralph_loop_worktree_path" => {
-            get_snapshot_field(state, &args, |s| s.worktree_path.clone())
+            get_snapshot_field(state, identity, cmd, &args, |s| s.worktree_path.clone())
         }
 
         "get_ralph_loop_snapshot" => {
             let execution_id: String = get_arg(&args, "executionId")?;
             let project_path: String = get_arg(&args, "projectPath")?;
+            authorize_execution(state, identity, cmd, &args, &execution_id)?;
 
             // Try in-memory snapshot first
             if let Some(snapshot) = state.ralph_loop_state.get_snapshot(&execution_id) {
@@ -530,6 +618,7 @@ pub fn is_ralph_loop_command(cmd: &str) -> bool {
         || matches!(
             cmd,
             "has_ralph_files"
+                | "get_all_ralph_iterations"
                 | "init_ralph_prd"
                 | "init_ralph_config"
                 | "remove_ralph_story"
@@ -538,7 +627,10 @@ pub fn is_ralph_loop_command(cmd: &str) -> bool {
                 | "regenerate_ralph_prd_stories"
                 | "analyze_ralph_prd_stories"
                 | "start_ralph_loop"
+                | "run_ralph_preflight"
                 | "stop_ralph_loop"
+                | "resume_ralph_loop"
+                | "discard_ralph_loop_checkpoint"
                 | "manual_assign_ralph_story"
                 | "release_ralph_story_assignment"
                 | "regenerate_ralph_brief"
@@ -0,0 +1,47 @@
+//! Terminal recording command routing
+//!
+//! Handles: list_recordings, get_recording, delete_recording, prune_recordings
+
+use crate::commands;
+use crate::file_storage::recordings::RecordingFilter;
+use serde_json::Value;
+
+use super::{get_arg, get_opt_arg, route_sync, route_unit, ServerAppState};
+
+/// Route recording-related commands
+pub async fn route_recording_command(
+    cmd: &str,
+    args: Value,
+    state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "list_recordings" => {
+            let filter: Option<RecordingFilter> = get_opt_arg(&args, "filter")?;
+            route_sync!(commands::recordings::list_recordings(filter))
+        }
+
+        "get_recording" => {
+            let recording_id: String = get_arg(&args, "recordingId")?;
+            route_sync!(commands::recordings::get_recording(recording_id))
+        }
+
+        "delete_recording" => {
+            let recording_id: String = get_arg(&args, "recordingId")?;
+            route_unit!(commands::recordings::delete_recording(recording_id))
+        }
+
+        "prune_recordings" => {
+            route_sync!(commands::recordings::prune_recordings(&state.config_state))
+        }
+
+        _ => Err(format!("Unknown recording command: {}", cmd)),
+    }
+}
+
+/// Check if a command is a recording command
+pub fn is_recording_command(cmd: &str) -> bool {
+    matches!(
+        cmd,
+        "list_recordings" | "get_recording" | "delete_recording" | "prune_recordings"
+    )
+}
@@ -0,0 +1,39 @@
+//! Full-text search command routing
+//!
+//! Handles: search, update_search_index
+
+use crate::commands;
+use crate::search::SearchQuery;
+use serde_json::Value;
+
+use super::{get_arg, get_opt_arg, route_async, ServerAppState};
+
+/// Route search-related commands
+pub async fn route_search_command(
+    cmd: &str,
+    args: Value,
+    _state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "search" => {
+            let query: SearchQuery = get_arg(&args, "query")?;
+            route_async!(cmd, commands::search::search(query))
+        }
+
+        "update_search_index" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let rebuild: bool = get_opt_arg(&args, "rebuild")?.unwrap_or(false);
+            route_async!(
+                cmd,
+                commands::search::update_search_index(project_path, rebuild)
+            )
+        }
+
+        _ => Err(format!("Unknown search command: {}", cmd)),
+    }
+}
+
+/// Check if a command is a search command
+pub fn is_search_command(cmd: &str) -> bool {
+    matches!(cmd, "search" | "update_search_index")
+}
@@ -0,0 +1,43 @@
+//! Execution share link command routing
+//!
+//! Handles: create_share_link, revoke_share_link, list_share_links
+
+use crate::commands;
+use crate::file_storage::share_links::NewShareLink;
+use serde_json::Value;
+
+use super::{get_arg, get_opt_arg, route_sync, ServerAppState};
+
+/// Route share link commands
+pub async fn route_share_link_command(
+    cmd: &str,
+    args: Value,
+    _state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "create_share_link" => {
+            let request: NewShareLink = get_arg(&args, "request")?;
+            route_sync!(commands::share_links::create_share_link(request))
+        }
+
+        "revoke_share_link" => {
+            let link_id: String = get_arg(&args, "linkId")?;
+            route_sync!(commands::share_links::revoke_share_link(link_id))
+        }
+
+        "list_share_links" => {
+            let project_path: Option<String> = get_opt_arg(&args, "projectPath")?;
+            route_sync!(commands::share_links::list_share_links(project_path))
+        }
+
+        _ => Err(format!("Unknown share link command: {}", cmd)),
+    }
+}
+
+/// Check if a command is a share link command
+pub fn is_share_link_command(cmd: &str) -> bool {
+    matches!(
+        cmd,
+        "create_share_link" | "revoke_share_link" | "list_share_links"
+    )
+}
@@ -0,0 +1,101 @@
+//! Agent transcript command routing
+//!
+//! Handles: list_transcripts, get_transcript_page, get_transcript_tool_calls,
+//! download_transcript, export_chrome_trace, prune_transcripts
+
+use crate::commands;
+use crate::file_storage::transcripts::{TranscriptFilter, TranscriptPart};
+use serde_json::Value;
+
+use super::{get_arg, get_opt_arg, route_sync, ServerAppState};
+
+/// Route transcript-related commands
+pub async fn route_transcript_command(
+    cmd: &str,
+    args: Value,
+    state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "list_transcripts" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let filter: Option<TranscriptFilter> = get_opt_arg(&args, "filter")?;
+            route_sync!(commands::transcripts::list_transcripts(
+                project_path,
+                filter
+            ))
+        }
+
+        "get_transcript_page" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let execution_id: String = get_arg(&args, "executionId")?;
+            let iteration: u32 = get_arg(&args, "iteration")?;
+            let offset: Option<usize> = get_opt_arg(&args, "offset")?;
+            let limit: Option<usize> = get_opt_arg(&args, "limit")?;
+            route_sync!(commands::transcripts::get_transcript_page(
+                project_path,
+                execution_id,
+                iteration,
+                offset,
+                limit
+            ))
+        }
+
+        "get_transcript_tool_calls" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let execution_id: String = get_arg(&args, "executionId")?;
+            let iteration: u32 = get_arg(&args, "iteration")?;
+            route_sync!(commands::transcripts::get_transcript_tool_calls(
+                project_path,
+                execution_id,
+                iteration
+            ))
+        }
+
+        "download_transcript" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let execution_id: String = get_arg(&args, "executionId")?;
+            let iteration: u32 = get_arg(&args, "iteration")?;
+            let part: Option<TranscriptPart> = get_opt_arg(&args, "part")?;
+            route_sync!(commands::transcripts::download_transcript(
+                project_path,
+                execution_id,
+                iteration,
+                part
+            ))
+        }
+
+        "export_chrome_trace" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            let execution_id: String = get_arg(&args, "executionId")?;
+            let iteration: Option<u32> = get_opt_arg(&args, "iteration")?;
+            route_sync!(commands::transcripts::export_chrome_trace(
+                project_path,
+                execution_id,
+                iteration
+            ))
+        }
+
+        "prune_transcripts" => {
+            let project_path: String = get_arg(&args, "projectPath")?;
+            route_sync!(commands::transcripts::prune_transcripts(
+                project_path,
+                &state.config_state
+            ))
+        }
+
+        _ => Err(format!("Unknown transcript command: {}", cmd)),
+    }
+}
+
+/// Check if a command is a transcript command
+pub fn is_transcript_command(cmd: &str) -> bool {
+    matches!(
+        cmd,
+        "list_transcripts"
+            | "get_transcript_page"
+            | "get_transcript_tool_calls"
+            | "download_transcript"
+            | "export_chrome_trace"
+            | "prune_transcripts"
+    )
+}
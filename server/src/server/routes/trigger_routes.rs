@@ -0,0 +1,46 @@
+//! Inbound trigger command routing
+//!
+//! Handles: list_triggers, create_trigger, update_trigger, delete_trigger
+
+use crate::commands;
+use crate::triggers::{NewTrigger, TriggerUpdate};
+use serde_json::Value;
+
+use super::{get_arg, route_sync, ServerAppState};
+
+/// Route inbound trigger commands
+pub async fn route_trigger_command(
+    cmd: &str,
+    args: Value,
+    _state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "list_triggers" => route_sync!(commands::triggers::list_triggers()),
+
+        "create_trigger" => {
+            let request: NewTrigger = get_arg(&args, "request")?;
+            route_sync!(commands::triggers::create_trigger(request))
+        }
+
+        "update_trigger" => {
+            let trigger_id: String = get_arg(&args, "triggerId")?;
+            let update: TriggerUpdate = get_arg(&args, "update")?;
+            route_sync!(commands::triggers::update_trigger(trigger_id, update))
+        }
+
+        "delete_trigger" => {
+            let trigger_id: String = get_arg(&args, "triggerId")?;
+            route_sync!(commands::triggers::delete_trigger(trigger_id))
+        }
+
+        _ => Err(format!("Unknown trigger command: {}", cmd)),
+    }
+}
+
+/// Check if a command is an inbound trigger command
+pub fn is_trigger_command(cmd: &str) -> bool {
+    matches!(
+        cmd,
+        "list_triggers" | "create_trigger" | "update_trigger" | "delete_trigger"
+    )
+}
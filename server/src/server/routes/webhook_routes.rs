@@ -0,0 +1,65 @@
+//! Outbound webhook command routing
+//!
+//! Handles: list_webhooks, create_webhook, update_webhook, delete_webhook,
+//! test_webhook, list_webhook_dead_letters
+
+use crate::commands;
+use crate::webhooks::{NewWebhook, WebhookUpdate};
+use serde_json::Value;
+
+use super::{get_arg, get_opt_arg, route_async, route_sync, ServerAppState};
+
+/// Route webhook commands
+pub async fn route_webhook_command(
+    cmd: &str,
+    args: Value,
+    _state: &ServerAppState,
+) -> Result<Value, String> {
+    match cmd {
+        "list_webhooks" => route_sync!(commands::webhooks::list_webhooks()),
+
+        "create_webhook" => {
+            let request: NewWebhook = get_arg(&args, "request")?;
+            route_sync!(commands::webhooks::create_webhook(request))
+        }
+
+        "update_webhook" => {
+            let webhook_id: String = get_arg(&args, "webhookId")?;
+            let update: WebhookUpdate = get_arg(&args, "update")?;
+            route_sync!(commands::webhooks::update_webhook(webhook_id, update))
+        }
+
+        "delete_webhook" => {
+            let webhook_id: String = get_arg(&args, "webhookId")?;
+            route_sync!(commands::webhooks::delete_webhook(webhook_id))
+        }
+
+        "test_webhook" => {
+            let webhook_id: String = get_arg(&args, "webhookId")?;
+            route_async!(cmd, commands::webhooks::test_webhook(webhook_id))
+        }
+
+        "list_webhook_dead_letters" => {
+            let webhook_id: Option<String> = get_opt_arg(&args, "webhookId")?;
+            let limit: Option<usize> = get_opt_arg(&args, "limit")?;
+            route_sync!(commands::webhooks::list_webhook_dead_letters(
+                webhook_id, limit
+            ))
+        }
+
+        _ => Err(format!("Unknown webhook command: {}", cmd)),
+    }
+}
+
+/// Check if a command is a webhook command
+pub fn is_webhook_command(cmd: &str) -> bool {
+    matches!(
+        cmd,
+        "list_webhooks"
+            | "create_webhook"
+            | "update_webhook"
+            | "delete_webhook"
+            | "test_webhook"
+            | "list_webhook_dead_letters"
+    )
+}
@@ -11,6 +11,7 @@ use crate::commands::push::PushNotificationState;
 use crate::commands::ralph_loop::RalphLoopManagerState;
 use crate::plugins::PluginRegistry;
 use crate::shutdown::ShutdownState;
+use crate::webhooks::WebhookDispatcher;
 use std::sync::Arc;
 
 /// Shared state for the server, containing all the managers and state
@@ -79,6 +80,7 @@ impl ServerAppState {
 
         // Wire up the push notifier to the broadcaster for background notifications
         broadcaster_inner.set_push_notifier(push_state.get_notifier());
+        broadcaster_inner.set_webhook_dispatcher(Arc::new(WebhookDispatcher::new()));
 
         let broadcaster = Arc::new(broadcaster_inner);
         let file_watcher = Arc::new(ServerFileWatcher::new(broadcaster.clone()));
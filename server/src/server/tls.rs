@@ -0,0 +1,229 @@
+//! HTTPS support for server mode
+//!
+//! Serves the API and WebSocket upgrades over TLS with either a user-provided
+//! certificate or a self-signed one generated for this machine's hostnames and
+//! IPs. Self-signed certificates are persisted in `~/.ralph-ui/tls/` so the
+//! fingerprint clients pin stays stable across restarts.
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::file_storage::{ensure_dir, get_global_ralph_ui_dir, read_json, write_json};
+
+const SELF_SIGNED_CERT_FILE: &str = "self-signed-cert.pem";
+const SELF_SIGNED_KEY_FILE: &str = "self-signed-key.pem";
+/// Subject alternative names the persisted certificate was issued for
+const SELF_SIGNED_NAMES_FILE: &str = "self-signed-names.json";
+
+/// Where the server's certificate comes from
+#[derive(Debug, Clone)]
+pub enum TlsConfig {
+    /// PEM certificate chain and private key supplied by the user
+    Provided {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+    },
+    /// Generated (once) for this machine's hostnames and IPs
+    SelfSigned,
+}
+
+/// TLS settings ready to serve
+pub struct LoadedTls {
+    pub server_config: Arc<ServerConfig>,
+    /// SHA-256 fingerprint of the leaf certificate (`AB:CD:...`)
+    pub fingerprint: String,
+    pub cert_path: PathBuf,
+}
+
+/// Load (or generate) the certificate and build the rustls server config
+pub fn load_tls(config: &TlsConfig) -> Result<LoadedTls, String> {
+    let (cert_path, key_path) = match config {
+        TlsConfig::Provided {
+            cert_path,
+            key_path,
+        } => (cert_path.clone(), key_path.clone()),
+        TlsConfig::SelfSigned => ensure_self_signed_cert_in(&get_tls_dir(), &machine_names())?,
+    };
+
+    let certs = CertificateDer::pem_file_iter(&cert_path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to read certificate {:?}: {}", cert_path, e))?;
+    let leaf = certs
+        .first()
+        .ok_or_else(|| format!("No certificate found in {:?}", cert_path))?;
+    let fingerprint = certificate_fingerprint(leaf);
+
+    let key = PrivateKeyDer::from_pem_file(&key_path)
+        .map_err(|e| format!("Failed to read private key {:?}: {}", key_path, e))?;
+
+    let mut server_config =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Invalid TLS configuration: {}", e))?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| format!("Invalid certificate or key: {}", e))?;
+    // WebSocket upgrades need HTTP/1.1; browsers use it when h2 isn't offered
+    server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    Ok(LoadedTls {
+        server_config: Arc::new(server_config),
+        fingerprint,
+        cert_path,
+    })
+}
+
+/// Get the directory holding generated certificates
+pub fn get_tls_dir() -> PathBuf {
+    get_global_ralph_ui_dir().join("tls")
+}
+
+/// Hostnames and IP addresses this machine can be reached at
+pub fn machine_names() -> Vec<String> {
+    let mut names = vec![
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+        "::1".to_string(),
+    ];
+
+    if let Some(host) = sysinfo::System::host_name() {
+        names.push(host.clone());
+        if !host.contains('.') {
+            // mDNS name used on most LANs
+            names.push(format!("{}.local", host));
+        }
+    }
+
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    for (_, data) in networks.iter() {
+        for network in data.ip_networks() {
+            let ip = network.addr;
+            if !ip.is_loopback() && !ip.is_unspecified() {
+                names.push(ip.to_string());
+            }
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    names.retain(|n| seen.insert(n.to_lowercase()));
+    names
+}
+
+/// Reuse the persisted self-signed certificate if it covers `names`,
+/// otherwise issue a new one for the union of old and new names
+pub fn ensure_self_signed_cert_in(
+    tls_dir: &Path,
+    names: &[String],
+) -> Result<(PathBuf, PathBuf), String> {
+    let cert_path = tls_dir.join(SELF_SIGNED_CERT_FILE);
+    let key_path = tls_dir.join(SELF_SIGNED_KEY_FILE);
+    let names_path = tls_dir.join(SELF_SIGNED_NAMES_FILE);
+
+    let existing: Vec<String> = if cert_path.exists() && key_path.exists() {
+        read_json(&names_path).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    if !existing.is_empty() && names.iter().all(|n| existing.contains(n)) {
+        return Ok((cert_path, key_path));
+    }
+
+    let mut all_names = existing;
+    for name in names {
+        if !all_names.contains(name) {
+            all_names.push(name.clone());
+        }
+    }
+
+    let certified = rcgen::generate_simple_self_signed(all_names.clone())
+        .map_err(|e| format!("Failed to generate self-signed certificate: {}", e))?;
+
+    ensure_dir(tls_dir)?;
+    std::fs::write(&cert_path, certified.cert.pem())
+        .map_err(|e| format!("Failed to write {:?}: {}", cert_path, e))?;
+    write_private_key(&key_path, &certified.key_pair.serialize_pem())?;
+    write_json(&names_path, &all_names)?;
+
+    log::info!(
+        "[TLS] Generated self-signed certificate for {}",
+        all_names.join(", ")
+    );
+    Ok((cert_path, key_path))
+}
+
+fn write_private_key(path: &Path, pem: &str) -> Result<(), String> {
+    std::fs::write(path, pem).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {:?}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+/// SHA-256 fingerprint of a DER certificate, as colon-separated hex
+pub fn certificate_fingerprint(cert: &CertificateDer<'_>) -> String {
+    Sha256::digest(cert.as_ref())
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_self_signed_cert_is_reused_until_names_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let names = vec!["localhost".to_string(), "192.168.1.20".to_string()];
+
+        let (cert_path, _) = ensure_self_signed_cert_in(dir, &names).unwrap();
+        let first = std::fs::read_to_string(&cert_path).unwrap();
+
+        // Same (or fewer) names keep the certificate and its fingerprint
+        ensure_self_signed_cert_in(dir, &names[..1]).unwrap();
+        assert_eq!(std::fs::read_to_string(&cert_path).unwrap(), first);
+
+        // A new IP reissues the certificate, keeping the old names
+        ensure_self_signed_cert_in(dir, &["10.0.0.5".to_string()]).unwrap();
+        assert_ne!(std::fs::read_to_string(&cert_path).unwrap(), first);
+        let stored: Vec<String> = read_json(&dir.join(SELF_SIGNED_NAMES_FILE)).unwrap();
+        assert_eq!(stored, vec!["localhost", "192.168.1.20", "10.0.0.5"]);
+    }
+
+    #[test]
+    fn test_load_provided_certificate() {
+        let temp_dir = TempDir::new().unwrap();
+        let (cert_path, key_path) =
+            ensure_self_signed_cert_in(temp_dir.path(), &["localhost".to_string()]).unwrap();
+
+        let loaded = load_tls(&TlsConfig::Provided {
+            cert_path,
+            key_path,
+        })
+        .unwrap();
+        assert_eq!(loaded.fingerprint.len(), 32 * 3 - 1);
+        assert_eq!(
+            loaded.server_config.alpn_protocols,
+            vec![b"http/1.1".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_missing_certificate_is_an_error() {
+        let result = load_tls(&TlsConfig::Provided {
+            cert_path: PathBuf::from("/nonexistent/cert.pem"),
+            key_path: PathBuf::from("/nonexistent/key.pem"),
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! HTTP download endpoints for persisted agent transcripts
+//!
+//! Serves the stored gzip files as-is so large transcripts can be downloaded
+//! without decompressing them on the server, and Chrome trace exports that
+//! can be opened directly in Perfetto or chrome://tracing.
+
+use axum::{
+    extract::{Path, Query},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::commands::transcripts::export_chrome_trace;
+use crate::file_storage::transcripts::{get_transcript_file_path, TranscriptPart};
+use crate::utils::as_path;
+
+/// Path parameters for the transcript download endpoint
+#[derive(Debug, Deserialize)]
+pub struct TranscriptDownloadPath {
+    execution_id: String,
+    iteration: u32,
+    part: String,
+}
+
+/// Query parameters for the transcript download endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptDownloadQuery {
+    project_path: String,
+}
+
+/// GET /api/transcripts/:execution_id/:iteration/:part?projectPath=...
+pub async fn transcript_download_handler(
+    Path(path): Path<TranscriptDownloadPath>,
+    Query(query): Query<TranscriptDownloadQuery>,
+) -> Response {
+    let part: TranscriptPart = match path.part.parse() {
+        Ok(part) => part,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let file_path = match get_transcript_file_path(
+        as_path(&query.project_path),
+        &path.execution_id,
+        path.iteration,
+        part,
+    ) {
+        Ok(file_path) => file_path,
+        Err(e) => return (StatusCode::NOT_FOUND, e).into_response(),
+    };
+
+    match tokio::fs::read(&file_path).await {
+        Ok(data) => {
+            let file_name = file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let disposition = format!(
+                "attachment; filename=\"{}-{}-{}\"",
+                path.execution_id, path.iteration, file_name
+            );
+            (
+                [
+                    (CONTENT_TYPE, "application/gzip".to_string()),
+                    (CONTENT_DISPOSITION, disposition),
+                ],
+                data,
+            )
+                .into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read transcript: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+/// Query parameters for the trace export endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceExportQuery {
+    project_path: String,
+    iteration: Option<u32>,
+}
+
+/// GET /api/transcripts/:execution_id/trace?projectPath=...&iteration=...
+pub async fn trace_export_handler(
+    Path(execution_id): Path<String>,
+    Query(query): Query<TraceExportQuery>,
+) -> Response {
+    let file_name = match query.iteration {
+        Some(iteration) => format!("{}-{}-trace.json", execution_id, iteration),
+        None => format!("{}-trace.json", execution_id),
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        export_chrome_trace(query.project_path, execution_id, query.iteration)
+    })
+    .await;
+
+    let trace = match result {
+        Ok(Ok(trace)) => trace,
+        Ok(Err(e)) => return (StatusCode::NOT_FOUND, e).into_response(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Trace export failed: {}", e),
+            )
+                .into_response()
+        }
+    };
+
+    match serde_json::to_vec(&trace) {
+        Ok(body) => (
+            [
+                (CONTENT_TYPE, "application/json".to_string()),
+                (
+                    CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", file_name),
+                ),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to serialize trace: {}", e),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,450 @@
+//! Inbound webhook endpoint (`POST /hooks/:trigger_id`)
+//!
+//! Deliveries don't use Bearer tokens: each is verified against its trigger's
+//! secret instead, either GitHub's `X-Hub-Signature-256` or, for generic
+//! senders, `X-Ralph-Signature` (HMAC-SHA256 of `<X-Ralph-Timestamp>.<body>`,
+//! like outbound webhooks) or the plain `X-Ralph-Trigger-Secret` header.
+//! Every delivery is recorded in the audit log.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use super::audit;
+use super::auth::AuthIdentity;
+use super::permissions::ApiTokenRole;
+use super::proxy::start_ralph_loop_server;
+use super::ServerAppState;
+use crate::file_storage::audit::AuditStatus;
+use crate::ralph_loop::{PrdExecutor, RalphLoopState};
+use crate::triggers::rules;
+use crate::triggers::{
+    get_trigger, InboundTrigger, TriggerAction, TriggerActionResult, TriggerDeliveryResponse,
+    TriggerSource,
+};
+use crate::utils::{constant_time_eq, decode_hex};
+
+/// How far a generic delivery's `X-Ralph-Timestamp` may be from the server's
+/// clock, in seconds; older signed deliveries can't be replayed
+const SIGNATURE_WINDOW_SECS: i64 = 300;
+
+/// POST /hooks/:trigger_id
+pub async fn trigger_handler(
+    State(state): State<ServerAppState>,
+    Path(trigger_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let trigger = match get_trigger(&trigger_id) {
+        Ok(Some(trigger)) if trigger.enabled => trigger,
+        Ok(_) => return (StatusCode::NOT_FOUND, "Unknown trigger").into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+    let identity = trigger_identity(&trigger);
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    let delivery_id = header("x-github-delivery")
+        .or_else(|| header("x-ralph-delivery"))
+        .map(String::from)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let reject = |status: StatusCode, audit_status: AuditStatus, message: String| {
+        log::warn!(
+            "[Triggers] Rejected delivery {} to '{}': {}",
+            delivery_id,
+            trigger.name,
+            message
+        );
+        audit::record(
+            &identity,
+            "trigger_delivery",
+            Some(trigger.project_path.clone()),
+            &json!({ "deliveryId": delivery_id, "triggerId": trigger.id, "error": message }),
+            audit_status,
+            Some(status.as_u16()),
+        );
+        (status, message).into_response()
+    };
+
+    if let Err(e) = verify_delivery(&trigger, &headers, &body, chrono::Utc::now().timestamp()) {
+        return reject(StatusCode::UNAUTHORIZED, AuditStatus::Denied, e);
+    }
+
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return reject(
+                StatusCode::BAD_REQUEST,
+                AuditStatus::Failure,
+                format!("Invalid JSON payload: {}", e),
+            )
+        }
+    };
+
+    let base_event = match trigger.source {
+        TriggerSource::Github => header("x-github-event").map(String::from),
+        TriggerSource::Generic => header("x-ralph-event")
+            .map(String::from)
+            .or_else(|| payload.get("event")?.as_str().map(String::from)),
+    };
+    let Some(base_event) = base_event else {
+        return reject(
+            StatusCode::BAD_REQUEST,
+            AuditStatus::Failure,
+            "Missing event type".to_string(),
+        );
+    };
+    let event = rules::event_name(&base_event, &payload);
+
+    let mut results = Vec::new();
+    for (index, rule) in trigger.rules.iter().enumerate() {
+        if rules::rule_matches(rule, &event, &payload) {
+            let mut result = run_action(&state, &trigger, &rule.action, &payload).await;
+            result.rule = index;
+            results.push(result);
+        }
+    }
+
+    log::info!(
+        "[Triggers] Delivery {} ({}) to '{}' matched {} rule(s)",
+        delivery_id,
+        event,
+        trigger.name,
+        results.len()
+    );
+
+    let response = TriggerDeliveryResponse {
+        delivery_id,
+        trigger_id: trigger.id.clone(),
+        event,
+        results,
+    };
+    let status = if response.results.iter().any(|r| r.error.is_some()) {
+        AuditStatus::Failure
+    } else {
+        AuditStatus::Success
+    };
+    audit::record(
+        &identity,
+        "trigger_delivery",
+        Some(trigger.project_path.clone()),
+        &serde_json::to_value(&response).unwrap_or_default(),
+        status,
+        Some(StatusCode::OK.as_u16()),
+    );
+
+    Json(response).into_response()
+}
+
+/// Audit identity of a trigger's deliveries
+fn trigger_identity(trigger: &InboundTrigger) -> AuthIdentity {
+    AuthIdentity {
+        token_id: None,
+        name: format!("trigger:{}", trigger.name),
+        role: ApiTokenRole::Operator,
+        projects: Some(vec![trigger.project_path.clone()]),
+        share_link: None,
+    }
+}
+
+/// Check a delivery's signature (or shared secret) against the trigger,
+/// `now` being the current Unix time in seconds
+fn verify_delivery(
+    trigger: &InboundTrigger,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: i64,
+) -> Result<(), String> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    // Generic signatures cover the timestamp, so a captured delivery can't be
+    // resent once it leaves the window
+    let mut signed_prefix = None;
+    let signature = match trigger.source {
+        TriggerSource::Github => header("x-hub-signature-256"),
+        TriggerSource::Generic => {
+            if let Some(secret) = header("x-ralph-trigger-secret") {
+                return if constant_time_eq(secret.as_bytes(), trigger.secret.as_bytes()) {
+                    Ok(())
+                } else {
+                    Err("Invalid trigger secret".to_string())
+                };
+            }
+            let signature = header("x-ralph-signature");
+            if signature.is_some() {
+                let timestamp = header("x-ralph-timestamp").ok_or("Missing timestamp")?;
+                let seconds: i64 = timestamp.parse().map_err(|_| "Malformed timestamp")?;
+                if now.abs_diff(seconds) > SIGNATURE_WINDOW_SECS as u64 {
+                    return Err("Timestamp outside the allowed window".to_string());
+                }
+                signed_prefix = Some(format!("{}.", timestamp));
+            }
+            signature
+        }
+    };
+
+    let signature = signature.ok_or("Missing signature")?;
+    let digest = signature
+        .strip_prefix("sha256=")
+        .and_then(decode_hex)
+        .ok_or("Malformed signature")?;
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(trigger.secret.as_bytes()).map_err(|e| e.to_string())?;
+    if let Some(prefix) = signed_prefix {
+        mac.update(prefix.as_bytes());
+    }
+    mac.update(body);
+    mac.verify_slice(&digest)
+        .map_err(|_| "Signature mismatch".to_string())
+}
+
+async fn run_action(
+    state: &ServerAppState,
+    trigger: &InboundTrigger,
+    action: &TriggerAction,
+    payload: &Value,
+) -> TriggerActionResult {
+    let mut result = TriggerActionResult {
+        action: action.name().to_string(),
+        ..Default::default()
+    };
+    if let Err(e) = apply_action(state, trigger, action, payload, &mut result).await {
+        log::warn!(
+            "[Triggers] {} for '{}' failed: {}",
+            result.action,
+            trigger.name,
+            e
+        );
+        result.error = Some(e);
+    }
+    result
+}
+
+async fn apply_action(
+    state: &ServerAppState,
+    trigger: &InboundTrigger,
+    action: &TriggerAction,
+    payload: &Value,
+    result: &mut TriggerActionResult,
+) -> Result<(), String> {
+    let project_path = &trigger.project_path;
+
+    match action {
+        TriggerAction::ImportIssue {
+            prd_name,
+            start_loop,
+            agent_type,
+        } => {
+            let issue = rules::issue_from_payload(payload).ok_or("Event has no issue")?;
+            let prd_name = prd_name
+                .clone()
+                .unwrap_or_else(|| format!("issue-{}", issue.number));
+            let story = rules::issue_story(&issue);
+
+            result.prd_name = Some(prd_name.clone());
+            result.story_id = Some(story.id.clone());
+            result.story_created =
+                rules::add_story_to_prd(project_path, &prd_name, &issue.title, story)?;
+
+            if *start_loop {
+                let (execution_id, started) =
+                    ensure_loop(state, project_path, &prd_name, agent_type.clone()).await?;
+                result.execution_id = Some(execution_id);
+                result.loop_started = started;
+            }
+        }
+        TriggerAction::AddStory { prd_name } => {
+            let story = rules::comment_story(payload).ok_or("Event has no comment")?;
+            let prd_name = match prd_name {
+                Some(name) => name.clone(),
+                None => {
+                    let branch = rules::pull_request_branch(payload)
+                        .ok_or("Event has no pull request branch")?;
+                    rules::find_prd_by_branch(project_path, &branch)
+                        .ok_or_else(|| format!("No PRD works on branch {}", branch))?
+                }
+            };
+
+            result.prd_name = Some(prd_name.clone());
+            result.story_id = Some(story.id.clone());
+            result.story_created =
+                rules::add_story_to_prd(project_path, &prd_name, &prd_name, story)?;
+        }
+    }
+    Ok(())
+}
+
+/// Start a loop for the PRD unless one is active; active loops pick up new
+/// stories on their next iteration. Returns the execution ID and whether it
+/// was started.
+async fn ensure_loop(
+    state: &ServerAppState,
+    project_path: &str,
+    prd_name: &str,
+    agent_type: Option<String>,
+) -> Result<(String, bool), String> {
+    let prd = PrdExecutor::new(std::path::Path::new(project_path), prd_name).read_prd()?;
+    let active = prd.metadata.and_then(|m| m.last_execution_id).filter(|id| {
+        let snapshot = state.ralph_loop_state.get_snapshot(id);
+        matches!(
+            snapshot.and_then(|s| s.state),
+            Some(
+                RalphLoopState::Idle
+                    | RalphLoopState::Running { .. }
+                    | RalphLoopState::Retrying { .. }
+                    | RalphLoopState::Paused { .. }
+            )
+        )
+    });
+    if let Some(execution_id) = active {
+        return Ok((execution_id, false));
+    }
+
+    let agent_type = agent_type
+        .or_else(|| {
+            state
+                .config_state
+                .get_config()
+                .ok()
+                .map(|c| c.execution.agent_type)
+        })
+        .unwrap_or_else(|| "claude".to_string());
+    let request = serde_json::from_value(json!({
+        "projectPath": project_path,
+        "prdName": prd_name,
+        "agentType": agent_type,
+    }))
+    .map_err(|e| e.to_string())?;
+
+    let execution_id = start_ralph_loop_server(request, state).await?;
+    log::info!(
+        "[Triggers] Started loop {} for PRD {}",
+        execution_id,
+        prd_name
+    );
+    Ok((execution_id, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use chrono::Utc;
+
+    fn trigger(source: TriggerSource) -> InboundTrigger {
+        InboundTrigger {
+            id: "t1".to_string(),
+            name: "issues".to_string(),
+            secret: "It's a Secret to Everybody".to_string(),
+            source,
+            project_path: "/work/app".to_string(),
+            enabled: true,
+            rules: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_verify_github_signature() {
+        // Example from GitHub's webhook validation docs
+        let github = trigger(TriggerSource::Github);
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+        let valid = headers(&[("x-hub-signature-256", signature)]);
+        let now = Utc::now().timestamp();
+        assert!(verify_delivery(&github, &valid, b"Hello, World!", now).is_ok());
+        assert!(verify_delivery(&github, &valid, b"Hello, World?", now).is_err());
+        assert!(verify_delivery(&github, &HeaderMap::new(), b"Hello, World!", now).is_err());
+        // GitHub triggers don't accept the shared-secret header
+        let shared = headers(&[("x-ralph-trigger-secret", "It's a Secret to Everybody")]);
+        assert!(verify_delivery(&github, &shared, b"Hello, World!", now).is_err());
+    }
+
+    /// `X-Ralph-Signature` of a generic delivery sent at `timestamp`
+    /// (the scheme outbound webhooks sign with)
+    fn generic_signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+        format!(
+            "sha256={}",
+            crate::webhooks::dispatcher::sign(secret, timestamp, body)
+        )
+    }
+
+    #[test]
+    fn test_verify_generic_delivery() {
+        let generic = trigger(TriggerSource::Generic);
+        let now = 1_760_000_000;
+        let shared = headers(&[("x-ralph-trigger-secret", "It's a Secret to Everybody")]);
+        assert!(verify_delivery(&generic, &shared, b"{}", now).is_ok());
+        let wrong = headers(&[("x-ralph-trigger-secret", "guess")]);
+        assert!(verify_delivery(&generic, &wrong, b"{}", now).is_err());
+
+        let signed_at = |timestamp: i64| {
+            let signature = generic_signature(&generic.secret, timestamp, b"Hello, World!");
+            headers(&[
+                ("x-ralph-signature", signature.as_str()),
+                ("x-ralph-timestamp", timestamp.to_string().as_str()),
+            ])
+        };
+        assert!(verify_delivery(&generic, &signed_at(now - 10), b"Hello, World!", now).is_ok());
+        assert!(verify_delivery(&generic, &signed_at(now - 10), b"Hello, World?", now).is_err());
+        let malformed = headers(&[
+            ("x-ralph-signature", "sha256=zz"),
+            ("x-ralph-timestamp", now.to_string().as_str()),
+        ]);
+        assert!(verify_delivery(&generic, &malformed, b"Hello, World!", now).is_err());
+    }
+
+    #[test]
+    fn test_generic_signature_needs_a_recent_timestamp() {
+        let generic = trigger(TriggerSource::Generic);
+        let now = 1_760_000_000;
+        let body = b"Hello, World!";
+
+        // Replayed deliveries fall outside the window, in either direction
+        for timestamp in [
+            now - SIGNATURE_WINDOW_SECS - 1,
+            now + SIGNATURE_WINDOW_SECS + 1,
+        ] {
+            let signature = generic_signature(&generic.secret, timestamp, body);
+            let replayed = headers(&[
+                ("x-ralph-signature", signature.as_str()),
+                ("x-ralph-timestamp", timestamp.to_string().as_str()),
+            ]);
+            assert!(verify_delivery(&generic, &replayed, body, now).is_err());
+        }
+
+        // The timestamp is required and covered by the signature
+        let signature = generic_signature(&generic.secret, now, body);
+        let untimed = headers(&[("x-ralph-signature", signature.as_str())]);
+        assert!(verify_delivery(&generic, &untimed, body, now).is_err());
+        let moved = headers(&[
+            ("x-ralph-signature", signature.as_str()),
+            ("x-ralph-timestamp", (now + 1).to_string().as_str()),
+        ]);
+        assert!(verify_delivery(&generic, &moved, body, now).is_err());
+
+        // A body-only signature (the old scheme) no longer verifies
+        let body_only = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+        let old = headers(&[
+            ("x-ralph-signature", body_only),
+            ("x-ralph-timestamp", now.to_string().as_str()),
+        ]);
+        assert!(verify_delivery(&generic, &old, body, now).is_err());
+    }
+}
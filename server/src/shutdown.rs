@@ -64,6 +64,8 @@ impl Default for ShutdownState {
 pub struct ShutdownResult {
     /// Number of agents that were stopped
     pub agents_stopped: usize,
+    /// Number of loops checkpointed for resuming on restart
+    pub loops_checkpointed: usize,
     /// Number of worktrees that were cleaned up
     pub worktrees_cleaned: usize,
     /// Branches that were preserved (committed but not yet merged)
@@ -76,6 +78,7 @@ impl ShutdownResult {
     pub fn new() -> Self {
         Self {
             agents_stopped: 0,
+            loops_checkpointed: 0,
             worktrees_cleaned: 0,
             preserved_branches: Vec::new(),
             errors: Vec::new(),
@@ -304,6 +307,7 @@ mod tests {
         let result = handler.handle_shutdown(|| {
             Ok(ShutdownResult {
                 agents_stopped: 3,
+                loops_checkpointed: 1,
                 worktrees_cleaned: 2,
                 preserved_branches: vec!["feature/task-1".to_string()],
                 errors: vec![],
@@ -326,6 +330,7 @@ mod tests {
         let result = handler.handle_shutdown(|| {
             Ok(ShutdownResult {
                 agents_stopped: 1,
+                loops_checkpointed: 0,
                 worktrees_cleaned: 0,
                 preserved_branches: vec![],
                 errors: vec!["Failed to clean worktree".to_string()],
@@ -0,0 +1,16 @@
+//! Inbound webhook triggers for Ralph UI
+//!
+//! Lets issue trackers start work: deliveries to `POST /hooks/<trigger-id>`
+//! are verified against the trigger's secret and mapped to actions (import an
+//! issue as a story, add a story for a review comment, start a loop) by the
+//! trigger's rules.
+
+pub mod rules;
+pub mod storage;
+pub mod types;
+
+pub use storage::{create_trigger, delete_trigger, get_trigger, get_triggers, update_trigger};
+pub use types::{
+    CreatedTrigger, InboundTrigger, NewTrigger, TriggerAction, TriggerActionResult,
+    TriggerDeliveryResponse, TriggerInfo, TriggerRule, TriggerSource, TriggerUpdate,
+};
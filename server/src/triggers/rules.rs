@@ -0,0 +1,304 @@
+//! Rule matching and story creation for inbound deliveries
+//!
+//! Payloads use GitHub's webhook shapes (`issue`, `label`, `comment`,
+//! `pull_request`); generic senders can post the same fields.
+
+use super::types::TriggerRule;
+use crate::github::issue_converter::{issue_to_story, IssueImportOptions};
+use crate::github::Issue;
+use crate::ralph_loop::{PrdExecutor, RalphPrd, RalphStory};
+use serde_json::Value;
+use std::path::Path;
+
+/// Longest review comment excerpt used in a story title
+const MAX_TITLE_EXCERPT: usize = 80;
+
+/// Full event name: `<event>.<action>` when the payload has an action
+pub fn event_name(event: &str, payload: &Value) -> String {
+    match payload.get("action").and_then(|a| a.as_str()) {
+        Some(action) if !event.contains('.') => format!("{}.{}", event, action),
+        _ => event.to_string(),
+    }
+}
+
+/// Whether a rule applies to an event
+pub fn rule_matches(rule: &TriggerRule, event: &str, payload: &Value) -> bool {
+    let event_matches = rule.event == event
+        || event
+            .strip_prefix(rule.event.as_str())
+            .is_some_and(|rest| rest.starts_with('.'));
+    if !event_matches {
+        return false;
+    }
+
+    let Some(label) = &rule.label else {
+        return true;
+    };
+    // Labeling events must apply the rule's label; others must carry it
+    match payload.pointer("/label/name").and_then(|n| n.as_str()) {
+        Some(applied) => applied.eq_ignore_ascii_case(label),
+        None => payload
+            .get("issue")
+            .map(|issue| label_names(issue.get("labels")))
+            .unwrap_or_default()
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(label)),
+    }
+}
+
+/// Label names from a list of label objects (`{"name": ...}`) or strings
+fn label_names(labels: Option<&Value>) -> Vec<String> {
+    labels
+        .and_then(|l| l.as_array())
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|l| l.as_str().or_else(|| l.get("name")?.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The issue an event is about
+pub fn issue_from_payload(payload: &Value) -> Option<Issue> {
+    let issue = payload.get("issue")?;
+    Some(Issue {
+        number: issue.get("number")?.as_u64()? as u32,
+        title: issue.get("title")?.as_str()?.to_string(),
+        body: issue.get("body").and_then(|b| b.as_str()).map(String::from),
+        state: str_field(issue, "state"),
+        html_url: str_field(issue, "html_url"),
+        labels: label_names(issue.get("labels")),
+        created_at: str_field(issue, "created_at"),
+        updated_at: str_field(issue, "updated_at"),
+    })
+}
+
+/// Story for an issue, as produced by the GitHub issue import
+pub fn issue_story(issue: &Issue) -> RalphStory {
+    issue_to_story(issue, &IssueImportOptions::default())
+}
+
+/// Story asking the agent to address a (review) comment
+pub fn comment_story(payload: &Value) -> Option<RalphStory> {
+    let comment = payload.get("comment")?;
+    let comment_id = comment.get("id")?.as_u64()?;
+    let body = comment.get("body")?.as_str()?.trim();
+    let number = payload
+        .pointer("/pull_request/number")
+        .or_else(|| payload.pointer("/issue/number"))?
+        .as_u64()?;
+
+    let first_line = body.lines().next().unwrap_or_default();
+    let mut excerpt: String = first_line.chars().take(MAX_TITLE_EXCERPT).collect();
+    if first_line.chars().count() > MAX_TITLE_EXCERPT {
+        excerpt.push('…');
+    }
+
+    let mut description = body.to_string();
+    if let Some(path) = comment.get("path").and_then(|p| p.as_str()) {
+        match comment.get("line").and_then(|l| l.as_u64()) {
+            Some(line) => description.push_str(&format!("\n\nFile: {}:{}", path, line)),
+            None => description.push_str(&format!("\n\nFile: {}", path)),
+        }
+    }
+    if let Some(author) = comment.pointer("/user/login").and_then(|l| l.as_str()) {
+        description.push_str(&format!("\nComment by @{}", author));
+    }
+
+    let url = str_field(comment, "html_url");
+    let acceptance = if url.is_empty() {
+        "The review comment is addressed".to_string()
+    } else {
+        format!("The review comment is addressed: {}", url)
+    };
+
+    let mut story = RalphStory::new(
+        format!("pr{}-c{}", number, comment_id),
+        format!("Address review comment on #{}: {}", number, excerpt),
+        acceptance,
+    );
+    story.description = Some(description);
+    story.tags = vec!["review".to_string()];
+    Some(story)
+}
+
+/// Head branch of the pull request an event is about
+pub fn pull_request_branch(payload: &Value) -> Option<String> {
+    payload
+        .pointer("/pull_request/head/ref")
+        .and_then(|r| r.as_str())
+        .map(String::from)
+}
+
+/// Name of the PRD working on `branch`
+pub fn find_prd_by_branch(project_path: &str, branch: &str) -> Option<String> {
+    std::fs::read_dir(crate::utils::prds_dir(project_path))
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_str()?.strip_suffix(".json")?.to_string();
+            Some(name)
+        })
+        .find(|name| {
+            PrdExecutor::new(Path::new(project_path), name)
+                .read_prd()
+                .is_ok_and(|prd| prd.branch == branch)
+        })
+}
+
+/// Add a story to a PRD, creating the PRD if needed.
+/// Returns whether the story was added (false if it already existed).
+pub fn add_story_to_prd(
+    project_path: &str,
+    prd_name: &str,
+    prd_title: &str,
+    story: RalphStory,
+) -> Result<bool, String> {
+    let executor = PrdExecutor::new(Path::new(project_path), prd_name);
+    if !executor.prd_exists() {
+        log::info!("[Triggers] Creating PRD {} in {}", prd_name, project_path);
+        let mut prd = RalphPrd::new(prd_title, format!("ralph-{}", prd_name));
+        prd.stories.push(story);
+        executor.write_prd(&prd)?;
+        return Ok(true);
+    }
+    executor.add_story_if_missing(story)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::triggers::types::TriggerAction;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn rule(event: &str, label: Option<&str>) -> TriggerRule {
+        TriggerRule {
+            event: event.to_string(),
+            label: label.map(String::from),
+            action: TriggerAction::AddStory { prd_name: None },
+        }
+    }
+
+    fn labeled_issue(label: &str) -> Value {
+        json!({
+            "action": "labeled",
+            "label": { "name": label },
+            "issue": {
+                "number": 42,
+                "title": "Add dark mode",
+                "body": "## Acceptance Criteria\n- [ ] Toggle in settings",
+                "state": "open",
+                "html_url": "https://github.com/o/r/issues/42",
+                "labels": [{ "name": "ralph" }, { "name": "enhancement" }]
+            }
+        })
+    }
+
+    #[test]
+    fn test_rule_matching() {
+        let payload = labeled_issue("ralph");
+        let event = event_name("issues", &payload);
+        assert_eq!(event, "issues.labeled");
+
+        assert!(rule_matches(
+            &rule("issues.labeled", Some("ralph")),
+            &event,
+            &payload
+        ));
+        assert!(rule_matches(&rule("issues", None), &event, &payload));
+        assert!(!rule_matches(&rule("issue", None), &event, &payload));
+        assert!(!rule_matches(
+            &rule("issues.opened", None),
+            &event,
+            &payload
+        ));
+
+        // Another label applied to an issue that already has "ralph" doesn't match
+        let other = labeled_issue("enhancement");
+        assert!(!rule_matches(
+            &rule("issues.labeled", Some("ralph")),
+            &event,
+            &other
+        ));
+
+        // Without a label event, the issue's labels are checked
+        let mut edited = labeled_issue("ralph");
+        edited["action"] = json!("edited");
+        edited.as_object_mut().unwrap().remove("label");
+        let event = event_name("issues", &edited);
+        assert!(rule_matches(
+            &rule("issues", Some("RALPH")),
+            &event,
+            &edited
+        ));
+        assert!(!rule_matches(&rule("issues", Some("bug")), &event, &edited));
+    }
+
+    #[test]
+    fn test_issue_and_comment_stories() {
+        let issue = issue_from_payload(&labeled_issue("ralph")).unwrap();
+        assert_eq!(issue.labels, vec!["ralph", "enhancement"]);
+        let story = issue_story(&issue);
+        assert_eq!(story.id, "gh-42");
+        assert_eq!(story.title, "Add dark mode");
+
+        let payload = json!({
+            "action": "created",
+            "comment": {
+                "id": 7,
+                "body": "Please handle the empty list case\nIt panics today.",
+                "path": "src/list.rs",
+                "line": 12,
+                "html_url": "https://github.com/o/r/pull/5#discussion_r7",
+                "user": { "login": "alice" }
+            },
+            "pull_request": { "number": 5, "head": { "ref": "ralph-dark-mode" } }
+        });
+        let story = comment_story(&payload).unwrap();
+        assert_eq!(story.id, "pr5-c7");
+        assert_eq!(
+            story.title,
+            "Address review comment on #5: Please handle the empty list case"
+        );
+        let description = story.description.unwrap();
+        assert!(description.contains("File: src/list.rs:12"));
+        assert!(description.contains("@alice"));
+        assert_eq!(pull_request_branch(&payload).unwrap(), "ralph-dark-mode");
+    }
+
+    #[test]
+    fn test_add_story_to_prd_and_find_by_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().to_str().unwrap();
+        let issue = issue_from_payload(&labeled_issue("ralph")).unwrap();
+
+        assert!(
+            add_story_to_prd(project, "issue-42", "Add dark mode", issue_story(&issue)).unwrap()
+        );
+        // Redelivery of the same issue doesn't duplicate the story
+        assert!(
+            !add_story_to_prd(project, "issue-42", "Add dark mode", issue_story(&issue)).unwrap()
+        );
+
+        let prd = PrdExecutor::new(temp_dir.path(), "issue-42")
+            .read_prd()
+            .unwrap();
+        assert_eq!(prd.stories.len(), 1);
+        assert_eq!(
+            find_prd_by_branch(project, "ralph-issue-42").as_deref(),
+            Some("issue-42")
+        );
+        assert!(find_prd_by_branch(project, "main").is_none());
+    }
+}
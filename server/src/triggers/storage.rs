@@ -0,0 +1,208 @@
+//! Storage for inbound triggers
+//!
+//! Triggers are stored globally in `~/.ralph-ui/triggers.json`.
+
+use super::types::{CreatedTrigger, InboundTrigger, NewTrigger, TriggerInfo, TriggerUpdate};
+use crate::file_storage::migrations::{read_versioned, write_versioned, TRIGGERS};
+use crate::file_storage::{ensure_dir, get_global_ralph_ui_dir};
+use crate::utils::lock_mutex_recover;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes read-modify-write of the triggers file
+static TRIGGERS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Storage format for the triggers file
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct TriggersFile {
+    #[serde(default)]
+    triggers: Vec<InboundTrigger>,
+}
+
+fn get_triggers_path_in(base_dir: &Path) -> PathBuf {
+    base_dir.join("triggers.json")
+}
+
+fn load_triggers_in(base_dir: &Path) -> Result<TriggersFile, String> {
+    let path = get_triggers_path_in(base_dir);
+    if !path.exists() {
+        return Ok(TriggersFile::default());
+    }
+    read_versioned(&path, &TRIGGERS)
+}
+
+fn save_triggers_in(base_dir: &Path, data: &TriggersFile) -> Result<(), String> {
+    ensure_dir(base_dir)?;
+    write_versioned(&get_triggers_path_in(base_dir), data, &TRIGGERS)
+}
+
+/// Get all triggers (including secrets)
+pub fn get_triggers() -> Result<Vec<InboundTrigger>, String> {
+    get_triggers_in(&get_global_ralph_ui_dir())
+}
+
+/// Get all triggers in a specific directory (for testing)
+pub fn get_triggers_in(base_dir: &Path) -> Result<Vec<InboundTrigger>, String> {
+    Ok(load_triggers_in(base_dir)?.triggers)
+}
+
+/// Get a trigger by ID
+pub fn get_trigger(trigger_id: &str) -> Result<Option<InboundTrigger>, String> {
+    Ok(get_triggers()?.into_iter().find(|t| t.id == trigger_id))
+}
+
+/// Create a trigger
+pub fn create_trigger(new_trigger: NewTrigger) -> Result<CreatedTrigger, String> {
+    create_trigger_in(&get_global_ralph_ui_dir(), new_trigger)
+}
+
+/// Create a trigger in a specific directory (for testing)
+pub fn create_trigger_in(
+    base_dir: &Path,
+    new_trigger: NewTrigger,
+) -> Result<CreatedTrigger, String> {
+    let name = new_trigger.name.trim();
+    if name.is_empty() {
+        return Err("Trigger name is required".to_string());
+    }
+    if new_trigger.project_path.trim().is_empty() {
+        return Err("Trigger project path is required".to_string());
+    }
+
+    let now = Utc::now();
+    let trigger = InboundTrigger {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        secret: new_trigger
+            .secret
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(crate::server::generate_auth_token),
+        source: new_trigger.source,
+        project_path: new_trigger.project_path,
+        enabled: true,
+        rules: new_trigger.rules,
+        created_at: now,
+        updated_at: now,
+    };
+    let created = CreatedTrigger {
+        secret: trigger.secret.clone(),
+        info: TriggerInfo::from(&trigger),
+    };
+
+    let _guard = lock_mutex_recover(&TRIGGERS_LOCK);
+    let mut data = load_triggers_in(base_dir)?;
+    data.triggers.push(trigger);
+    save_triggers_in(base_dir, &data)?;
+
+    Ok(created)
+}
+
+/// Update a trigger
+pub fn update_trigger(trigger_id: &str, update: TriggerUpdate) -> Result<TriggerInfo, String> {
+    update_trigger_in(&get_global_ralph_ui_dir(), trigger_id, update)
+}
+
+/// Update a trigger in a specific directory (for testing)
+pub fn update_trigger_in(
+    base_dir: &Path,
+    trigger_id: &str,
+    update: TriggerUpdate,
+) -> Result<TriggerInfo, String> {
+    let _guard = lock_mutex_recover(&TRIGGERS_LOCK);
+    let mut data = load_triggers_in(base_dir)?;
+    let trigger = data
+        .triggers
+        .iter_mut()
+        .find(|t| t.id == trigger_id)
+        .ok_or_else(|| format!("Trigger not found: {}", trigger_id))?;
+
+    if let Some(name) = update.name.filter(|n| !n.trim().is_empty()) {
+        trigger.name = name.trim().to_string();
+    }
+    if let Some(secret) = update.secret.filter(|s| !s.is_empty()) {
+        trigger.secret = secret;
+    }
+    if let Some(enabled) = update.enabled {
+        trigger.enabled = enabled;
+    }
+    if let Some(rules) = update.rules {
+        trigger.rules = rules;
+    }
+    trigger.updated_at = Utc::now();
+    let info = TriggerInfo::from(&*trigger);
+
+    save_triggers_in(base_dir, &data)?;
+    Ok(info)
+}
+
+/// Delete a trigger
+pub fn delete_trigger(trigger_id: &str) -> Result<bool, String> {
+    delete_trigger_in(&get_global_ralph_ui_dir(), trigger_id)
+}
+
+/// Delete a trigger in a specific directory (for testing)
+pub fn delete_trigger_in(base_dir: &Path, trigger_id: &str) -> Result<bool, String> {
+    let _guard = lock_mutex_recover(&TRIGGERS_LOCK);
+    let mut data = load_triggers_in(base_dir)?;
+    let before = data.triggers.len();
+    data.triggers.retain(|t| t.id != trigger_id);
+
+    let removed = data.triggers.len() != before;
+    if removed {
+        save_triggers_in(base_dir, &data)?;
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::triggers::types::{TriggerAction, TriggerRule, TriggerSource};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_update_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        let created = create_trigger_in(
+            dir,
+            NewTrigger {
+                name: "issues".to_string(),
+                source: TriggerSource::Github,
+                project_path: "/work/app".to_string(),
+                secret: None,
+                rules: Vec::new(),
+            },
+        )
+        .unwrap();
+        assert_eq!(created.info.endpoint, format!("/hooks/{}", created.info.id));
+
+        let rule = TriggerRule {
+            event: "issues.labeled".to_string(),
+            label: Some("ralph".to_string()),
+            action: TriggerAction::ImportIssue {
+                prd_name: None,
+                start_loop: true,
+                agent_type: None,
+            },
+        };
+        let info = update_trigger_in(
+            dir,
+            &created.info.id,
+            TriggerUpdate {
+                rules: Some(vec![rule.clone()]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(info.rules, vec![rule]);
+
+        let stored = get_triggers_in(dir).unwrap();
+        assert_eq!(stored[0].secret, created.secret);
+
+        assert!(delete_trigger_in(dir, &created.info.id).unwrap());
+        assert!(get_triggers_in(dir).unwrap().is_empty());
+    }
+}
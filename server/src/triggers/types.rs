@@ -0,0 +1,185 @@
+//! Types for inbound webhook triggers
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Who sends deliveries to a trigger, which decides how they are verified
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerSource {
+    /// GitHub repository webhook, verified with `X-Hub-Signature-256`
+    #[default]
+    Github,
+    /// Any sender; verified with `X-Ralph-Timestamp` and
+    /// `X-Ralph-Signature: sha256=<HMAC of "<timestamp>.<body>">`
+    /// or the shared secret in `X-Ralph-Trigger-Secret`
+    Generic,
+}
+
+/// What a matching rule does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum TriggerAction {
+    /// Convert the event's issue into a story of a PRD (created if missing)
+    ImportIssue {
+        /// Target PRD (default: `issue-<number>`)
+        #[serde(default)]
+        prd_name: Option<String>,
+        /// Start a loop for the PRD unless one is already running
+        #[serde(default)]
+        start_loop: bool,
+        /// Agent for started loops (default: the configured agent type)
+        #[serde(default)]
+        agent_type: Option<String>,
+    },
+    /// Add a story for a review comment
+    AddStory {
+        /// Target PRD (default: the PRD whose branch is the pull request's head)
+        #[serde(default)]
+        prd_name: Option<String>,
+    },
+}
+
+impl TriggerAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TriggerAction::ImportIssue { .. } => "import_issue",
+            TriggerAction::AddStory { .. } => "add_story",
+        }
+    }
+}
+
+/// Maps an event to an action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerRule {
+    /// `event` or `event.action`, e.g. "issues.labeled",
+    /// "pull_request_review_comment.created" or "issues" for any action
+    pub event: String,
+    /// Only match when this label was just applied (or, for events that
+    /// don't apply labels, is on the issue)
+    #[serde(default)]
+    pub label: Option<String>,
+    pub action: TriggerAction,
+}
+
+/// An inbound trigger endpoint (`POST /hooks/<id>`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundTrigger {
+    pub id: String,
+    pub name: String,
+    /// Secret used to verify deliveries
+    pub secret: String,
+    #[serde(default)]
+    pub source: TriggerSource,
+    /// Project the trigger's actions run in
+    pub project_path: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub rules: Vec<TriggerRule>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Trigger details safe to show (no secret)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerInfo {
+    pub id: String,
+    pub name: String,
+    pub source: TriggerSource,
+    pub project_path: String,
+    pub enabled: bool,
+    pub rules: Vec<TriggerRule>,
+    /// Path deliveries are sent to
+    pub endpoint: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&InboundTrigger> for TriggerInfo {
+    fn from(trigger: &InboundTrigger) -> Self {
+        Self {
+            id: trigger.id.clone(),
+            name: trigger.name.clone(),
+            source: trigger.source,
+            project_path: trigger.project_path.clone(),
+            enabled: trigger.enabled,
+            rules: trigger.rules.clone(),
+            endpoint: format!("/hooks/{}", trigger.id),
+            created_at: trigger.created_at,
+            updated_at: trigger.updated_at,
+        }
+    }
+}
+
+/// A newly created trigger; `secret` is only returned here
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedTrigger {
+    pub secret: String,
+    pub info: TriggerInfo,
+}
+
+/// Options for creating a trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTrigger {
+    pub name: String,
+    #[serde(default)]
+    pub source: TriggerSource,
+    pub project_path: String,
+    /// Verification secret (generated when omitted)
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<TriggerRule>,
+}
+
+/// Fields to change on an existing trigger
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerUpdate {
+    pub name: Option<String>,
+    pub secret: Option<String>,
+    pub enabled: Option<bool>,
+    pub rules: Option<Vec<TriggerRule>>,
+}
+
+/// Outcome of one matched rule
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerActionResult {
+    /// Index of the rule in the trigger's rules
+    pub rule: usize,
+    pub action: String,
+    pub prd_name: Option<String>,
+    pub story_id: Option<String>,
+    /// False when the story already existed
+    pub story_created: bool,
+    /// Loop started for the PRD, or the running loop that will pick the story up
+    pub execution_id: Option<String>,
+    pub loop_started: bool,
+    pub error: Option<String>,
+}
+
+/// Response to a delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerDeliveryResponse {
+    /// Sender's delivery ID (`X-GitHub-Delivery`) or a generated one
+    pub delivery_id: String,
+    pub trigger_id: String,
+    pub event: String,
+    pub results: Vec<TriggerActionResult>,
+}
@@ -206,6 +206,36 @@ pub fn format_cost(tokens: i32, cost_per_million: f64) -> f64 {
     (tokens as f64 / 1_000_000.0) * cost_per_million
 }
 
+/// Compare two secrets in time that depends only on their length
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Decode a hex string (either case); None if it isn't valid hex
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Check that a URL the server will call (webhook, federation peer) is an
+/// http(s) URL with a host. `kind` names it in errors, e.g. "webhook".
+pub fn validate_http_url(url: &str, kind: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid {} URL: {}", kind, e))?;
+    match parsed.scheme() {
+        "http" | "https" => {}
+        scheme => return Err(format!("Unsupported {} URL scheme: {}", kind, scheme)),
+    }
+    if parsed.host_str().map_or(true, str::is_empty) {
+        return Err(format!("Invalid {} URL: missing host", kind));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +254,28 @@ mod tests {
         assert_eq!(cost, 3.0);
     }
 
+    #[test]
+    fn test_constant_time_eq_and_decode_hex() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+
+        assert_eq!(decode_hex("00ffAB"), Some(vec![0x00, 0xff, 0xab]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+
+    #[test]
+    fn test_validate_http_url() {
+        assert!(validate_http_url("https://ralph.example.com:8420", "peer").is_ok());
+        assert!(validate_http_url("http://127.0.0.1/hooks", "webhook").is_ok());
+        assert_eq!(
+            validate_http_url("file:///etc/passwd", "webhook").unwrap_err(),
+            "Unsupported webhook URL scheme: file"
+        );
+        assert!(validate_http_url("not a url", "peer").is_err());
+    }
+
     #[test]
     fn test_as_path() {
         let project_path = "/home/user/project";
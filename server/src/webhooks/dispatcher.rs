@@ -0,0 +1,529 @@
+//! Webhook delivery
+//!
+//! Matches broadcast events against the configured webhooks and delivers them
+//! in background tasks. Each request is signed with the webhook's secret:
+//!
+//! ```text
+//! X-Ralph-Timestamp: <unix seconds>
+//! X-Ralph-Signature: sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">
+//! ```
+//!
+//! Failed deliveries are retried with exponential backoff and written to the
+//! dead-letter log once retries are exhausted.
+
+use super::storage::{append_dead_letter, get_webhooks, get_webhooks_path};
+use super::types::{DeadLetter, DeliveryResult, WebhookConfig, WebhookFormat};
+use crate::events::{
+    AgentFailedPayload, MergeConflictDetectedPayload, RalphLoopCompletedPayload,
+    RalphLoopErrorPayload, RateLimitDetectedPayload, EVENT_AGENT_FAILED,
+    EVENT_MERGE_CONFLICT_DETECTED, EVENT_RALPH_LOOP_COMPLETED, EVENT_RALPH_LOOP_ERROR,
+    EVENT_RATE_LIMIT_DETECTED,
+};
+use crate::server::ServerEvent;
+use crate::utils::lock_mutex_recover;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Attempts per delivery before it is dead-lettered
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry; doubled for each further attempt
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Longest delay between retries
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Per-request timeout
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the webhooks file is checked for changes
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Event type of test deliveries
+pub const EVENT_WEBHOOK_TEST: &str = "webhook:test";
+
+/// Webhook configurations, reloaded when the webhooks file changes
+#[derive(Default)]
+struct WebhookCache {
+    checked_at: Option<Instant>,
+    modified: Option<SystemTime>,
+    webhooks: Arc<Vec<WebhookConfig>>,
+}
+
+/// Delivers broadcast events to configured webhooks
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    cache: Mutex<WebhookCache>,
+    /// Runtime deliveries are spawned on when an event is broadcast from a
+    /// thread outside it (PTY readers, file watchers, agent threads)
+    runtime: Option<tokio::runtime::Handle>,
+}
+
+impl WebhookDispatcher {
+    /// Create a new dispatcher delivering on the current tokio runtime
+    pub fn new() -> Self {
+        Self::with_runtime(tokio::runtime::Handle::try_current().ok())
+    }
+
+    /// Create a new dispatcher delivering on a given runtime
+    pub fn with_runtime(runtime: Option<tokio::runtime::Handle>) -> Self {
+        Self {
+            client: http_client(),
+            cache: Mutex::new(WebhookCache::default()),
+            runtime,
+        }
+    }
+
+    /// Current webhook configurations (file changes are picked up within
+    /// [`CONFIG_CHECK_INTERVAL`])
+    fn webhooks(&self) -> Arc<Vec<WebhookConfig>> {
+        let mut cache = lock_mutex_recover(&self.cache);
+        if cache
+            .checked_at
+            .is_some_and(|at| at.elapsed() < CONFIG_CHECK_INTERVAL)
+        {
+            return cache.webhooks.clone();
+        }
+        cache.checked_at = Some(Instant::now());
+
+        let modified = std::fs::metadata(get_webhooks_path())
+            .and_then(|m| m.modified())
+            .ok();
+        if modified != cache.modified {
+            cache.modified = modified;
+            cache.webhooks = Arc::new(get_webhooks().unwrap_or_else(|e| {
+                log::warn!("[Webhooks] Failed to read webhooks: {}", e);
+                Vec::new()
+            }));
+        }
+        cache.webhooks.clone()
+    }
+
+    /// Deliver an event to every matching webhook in the background
+    pub fn dispatch(&self, event: &ServerEvent) {
+        let webhooks = self.webhooks();
+        if webhooks.is_empty() {
+            return;
+        }
+        let matching: Vec<&WebhookConfig> = webhooks.iter().filter(|w| w.matches(event)).collect();
+        if matching.is_empty() {
+            return;
+        }
+        let Some(runtime) = tokio::runtime::Handle::try_current()
+            .ok()
+            .or_else(|| self.runtime.clone())
+        else {
+            log::warn!(
+                "[Webhooks] No async runtime to deliver {} (seq {}) to {} webhook(s)",
+                event.event,
+                event.seq,
+                matching.len()
+            );
+            return;
+        };
+
+        for webhook in matching {
+            let client = self.client.clone();
+            let webhook = webhook.clone();
+            let event = event.clone();
+            runtime.spawn(async move {
+                deliver_with_retries(&client, &webhook, &event).await;
+            });
+        }
+    }
+}
+
+impl Default for WebhookDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("ralph-ui-webhooks/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Send a synthetic `webhook:test` event once, without retries
+pub async fn send_test_delivery(webhook: &WebhookConfig) -> DeliveryResult {
+    let event = ServerEvent {
+        seq: 0,
+        event: EVENT_WEBHOOK_TEST.to_string(),
+        payload: json!({
+            "webhookId": webhook.id,
+            "webhookName": webhook.name,
+            "message": "Test delivery from Ralph UI",
+        }),
+        project_path: None,
+    };
+    let delivery_id = uuid::Uuid::new_v4().to_string();
+    let body = build_body(webhook.format, &event, &delivery_id);
+    deliver_once(&http_client(), webhook, &event.event, &delivery_id, &body).await
+}
+
+async fn deliver_with_retries(
+    client: &reqwest::Client,
+    webhook: &WebhookConfig,
+    event: &ServerEvent,
+) {
+    let delivery_id = uuid::Uuid::new_v4().to_string();
+    let body = build_body(webhook.format, event, &delivery_id);
+
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let result = deliver_once(client, webhook, &event.event, &delivery_id, &body).await;
+        if result.success {
+            log::debug!(
+                "[Webhooks] Delivered {} #{} to '{}'",
+                event.event,
+                event.seq,
+                webhook.name
+            );
+            return;
+        }
+
+        if attempt >= MAX_ATTEMPTS || !is_retryable(&result) {
+            log::warn!(
+                "[Webhooks] Giving up on {} #{} to '{}' after {} attempt(s): {}",
+                event.event,
+                event.seq,
+                webhook.name,
+                attempt,
+                result.error.as_deref().unwrap_or("unknown error")
+            );
+            let dead_letter = DeadLetter {
+                delivery_id,
+                webhook_id: webhook.id.clone(),
+                webhook_name: webhook.name.clone(),
+                event: event.event.clone(),
+                seq: event.seq,
+                failed_at: chrono::Utc::now(),
+                attempts: attempt,
+                last_status: result.status_code,
+                last_error: result.error,
+                body,
+            };
+            if let Err(e) = append_dead_letter(&dead_letter) {
+                log::error!("[Webhooks] Failed to record dead letter: {}", e);
+            }
+            return;
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
+
+async fn deliver_once(
+    client: &reqwest::Client,
+    webhook: &WebhookConfig,
+    event_type: &str,
+    delivery_id: &str,
+    body: &Value,
+) -> DeliveryResult {
+    let started = Instant::now();
+    let bytes = serde_json::to_vec(body).unwrap_or_default();
+    let timestamp = chrono::Utc::now().timestamp();
+
+    let response = client
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Ralph-Event", event_type)
+        .header("X-Ralph-Delivery", delivery_id)
+        .header("X-Ralph-Timestamp", timestamp.to_string())
+        .header(
+            "X-Ralph-Signature",
+            format!("sha256={}", sign(&webhook.secret, timestamp, &bytes)),
+        )
+        .body(bytes)
+        .send()
+        .await;
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            DeliveryResult {
+                success: status.is_success(),
+                status_code: Some(status.as_u16()),
+                error: (!status.is_success()).then(|| format!("HTTP {}", status)),
+                duration_ms,
+            }
+        }
+        Err(e) => DeliveryResult {
+            success: false,
+            status_code: None,
+            error: Some(e.to_string()),
+            duration_ms,
+        },
+    }
+}
+
+/// Network errors, timeouts, throttling and server errors are worth retrying
+fn is_retryable(result: &DeliveryResult) -> bool {
+    match result.status_code {
+        None => true,
+        Some(status) => status == 408 || status == 429 || status >= 500,
+    }
+}
+
+/// Hex HMAC-SHA256 of `"<timestamp>.<body>"`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Request body for an event in the webhook's format
+pub fn build_body(format: WebhookFormat, event: &ServerEvent, delivery_id: &str) -> Value {
+    match format {
+        WebhookFormat::Json => json!({
+            "id": delivery_id,
+            "event": event.event,
+            "seq": event.seq,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "projectPath": event.project_path,
+            "payload": event.payload,
+        }),
+        WebhookFormat::Slack => json!({ "text": summarize(event) }),
+        WebhookFormat::Discord => json!({ "content": summarize(event) }),
+    }
+}
+
+/// One-line human-readable description of an event, for chat tools
+pub fn summarize(event: &ServerEvent) -> String {
+    let payload = event.payload.clone();
+    let message = match event.event.as_str() {
+        EVENT_RALPH_LOOP_COMPLETED => {
+            serde_json::from_value(payload)
+                .ok()
+                .map(|p: RalphLoopCompletedPayload| {
+                    format!(
+                        "Ralph loop completed for {}: {}/{} stories in {} iterations ({}, ${:.2})",
+                        p.prd_name,
+                        p.completed_stories,
+                        p.total_stories,
+                        p.total_iterations,
+                        format_duration(p.duration_secs),
+                        p.total_cost
+                    )
+                })
+        }
+        EVENT_RALPH_LOOP_ERROR => {
+            serde_json::from_value(payload)
+                .ok()
+                .map(|p: RalphLoopErrorPayload| {
+                    format!(
+                        "Ralph loop error in {} ({:?}, iteration {}): {}",
+                        p.prd_name, p.error_type, p.iteration, p.message
+                    )
+                })
+        }
+        EVENT_RATE_LIMIT_DETECTED => {
+            serde_json::from_value(payload)
+                .ok()
+                .map(|p: RateLimitDetectedPayload| {
+                    let retry = p
+                        .retry_after_ms
+                        .map(|ms| format!(", retry after {}", format_duration(ms as f64 / 1000.0)))
+                        .unwrap_or_default();
+                    format!(
+                        "Rate limit ({}) hit by agent {}{}",
+                        p.limit_type, p.agent_id, retry
+                    )
+                })
+        }
+        EVENT_MERGE_CONFLICT_DETECTED => {
+            serde_json::from_value(payload)
+                .ok()
+                .map(|p: MergeConflictDetectedPayload| {
+                    format!(
+                        "Merge conflict in {} (iteration {}): {}",
+                        p.prd_name,
+                        p.iteration,
+                        p.conflicting_files.join(", ")
+                    )
+                })
+        }
+        EVENT_AGENT_FAILED => serde_json::from_value(payload)
+            .ok()
+            .map(|p: AgentFailedPayload| {
+                format!(
+                    "Agent {} failed on task {}: {}",
+                    p.agent_id, p.task_id, p.error
+                )
+            }),
+        EVENT_WEBHOOK_TEST => Some("Test delivery from Ralph UI".to_string()),
+        _ => None,
+    }
+    .unwrap_or_else(|| format!("Ralph UI event: {}", event.event));
+
+    match event.project_path.as_deref().and_then(project_name) {
+        Some(project) => format!("[{}] {}", project, message),
+        None => message,
+    }
+}
+
+fn project_name(path: &str) -> Option<&str> {
+    std::path::Path::new(path).file_name()?.to_str()
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.max(0.0).round() as u64;
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn event(event_type: &str, payload: Value, project: Option<&str>) -> ServerEvent {
+        ServerEvent {
+            seq: 7,
+            event: event_type.to_string(),
+            payload,
+            project_path: project.map(String::from),
+        }
+    }
+
+    fn webhook(event_types: &[&str], projects: Option<Vec<String>>) -> WebhookConfig {
+        WebhookConfig {
+            id: "w1".to_string(),
+            name: "ci".to_string(),
+            url: "https://example.com/hook".to_string(),
+            secret: "s3cret".to_string(),
+            enabled: true,
+            format: WebhookFormat::Json,
+            event_types: event_types.iter().map(|s| s.to_string()).collect(),
+            projects,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_sign_matches_known_vector() {
+        // HMAC-SHA256("key", "The quick brown fox jumps over the lazy dog")
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+        mac.update(b"The quick brown fox jumps over the lazy dog");
+        let expected: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(
+            expected,
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+
+        let signature = sign("s3cret", 1700000000, br#"{"a":1}"#);
+        assert_eq!(signature.len(), 64);
+        assert_eq!(signature, sign("s3cret", 1700000000, br#"{"a":1}"#));
+        assert_ne!(signature, sign("s3cret", 1700000001, br#"{"a":1}"#));
+        assert_ne!(signature, sign("other", 1700000000, br#"{"a":1}"#));
+    }
+
+    #[test]
+    fn test_event_filters() {
+        let error = event(EVENT_RALPH_LOOP_ERROR, json!({}), Some("/work/app"));
+        let progress = event("ralph:progress", json!({}), Some("/work/app"));
+
+        // Defaults cover the notable events only
+        let defaults = webhook(&[], None);
+        assert!(defaults.matches(&error));
+        assert!(!defaults.matches(&progress));
+
+        // Prefix filters
+        let ralph = webhook(&["ralph:"], None);
+        assert!(ralph.matches(&progress));
+        assert!(!ralph.matches(&event(EVENT_AGENT_FAILED, json!({}), None)));
+        assert!(webhook(&["*"], None).matches(&progress));
+
+        // Project filters exclude other and unattributed events
+        let scoped = webhook(&[], Some(vec!["/work/app".to_string()]));
+        assert!(scoped.matches(&error));
+        assert!(!scoped.matches(&event(
+            EVENT_RALPH_LOOP_ERROR,
+            json!({}),
+            Some("/work/other")
+        )));
+        assert!(!scoped.matches(&event(EVENT_RALPH_LOOP_ERROR, json!({}), None)));
+
+        let mut disabled = webhook(&[], None);
+        disabled.enabled = false;
+        assert!(!disabled.matches(&error));
+    }
+
+    #[test]
+    fn test_body_templates() {
+        let completed = event(
+            EVENT_RALPH_LOOP_COMPLETED,
+            json!({
+                "executionId": "e1",
+                "prdName": "auth",
+                "totalIterations": 4,
+                "completedStories": 3,
+                "totalStories": 3,
+                "durationSecs": 125.0,
+                "totalCost": 1.5,
+                "timestamp": "2024-01-01T00:00:00Z"
+            }),
+            Some("/work/app"),
+        );
+
+        let envelope = build_body(WebhookFormat::Json, &completed, "d1");
+        assert_eq!(envelope["id"], "d1");
+        assert_eq!(envelope["event"], EVENT_RALPH_LOOP_COMPLETED);
+        assert_eq!(envelope["seq"], 7);
+        assert_eq!(envelope["projectPath"], "/work/app");
+        assert_eq!(envelope["payload"]["prdName"], "auth");
+
+        let slack = build_body(WebhookFormat::Slack, &completed, "d1");
+        assert_eq!(
+            slack["text"],
+            "[app] Ralph loop completed for auth: 3/3 stories in 4 iterations (2m 5s, $1.50)"
+        );
+        let discord = build_body(WebhookFormat::Discord, &completed, "d1");
+        assert_eq!(discord["content"], slack["text"]);
+
+        // Unknown or malformed payloads fall back to the event type
+        let other = event("task:status_changed", json!({}), None);
+        assert_eq!(summarize(&other), "Ralph UI event: task:status_changed");
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        let result = |status: Option<u16>| DeliveryResult {
+            success: false,
+            status_code: status,
+            error: None,
+            duration_ms: 0,
+        };
+        assert!(is_retryable(&result(None)));
+        assert!(is_retryable(&result(Some(503))));
+        assert!(is_retryable(&result(Some(429))));
+        assert!(!is_retryable(&result(Some(404))));
+        assert!(!is_retryable(&result(Some(400))));
+    }
+}
@@ -0,0 +1,18 @@
+//! Outbound webhook module for Ralph UI
+//!
+//! Delivers selected server events (loop completions, failures, rate limits,
+//! merge conflicts, ...) to chat tools and internal automation as signed JSON
+//! or Slack/Discord-compatible messages.
+
+pub mod dispatcher;
+pub mod storage;
+pub mod types;
+
+pub use dispatcher::{send_test_delivery, WebhookDispatcher};
+pub use storage::{
+    create_webhook, delete_webhook, get_webhook, get_webhooks, list_dead_letters, update_webhook,
+};
+pub use types::{
+    CreatedWebhook, DeadLetter, DeliveryResult, NewWebhook, WebhookConfig, WebhookFormat,
+    WebhookInfo, WebhookUpdate, DEFAULT_WEBHOOK_EVENTS,
+};
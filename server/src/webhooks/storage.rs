@@ -0,0 +1,328 @@
+//! Storage for webhook configurations and dead letters
+//!
+//! Webhooks are stored globally in `~/.ralph-ui/webhooks.json`; deliveries that
+//! exhaust their retries are appended to `~/.ralph-ui/webhooks/dead-letters.jsonl`.
+
+use super::types::{
+    CreatedWebhook, DeadLetter, NewWebhook, WebhookConfig, WebhookInfo, WebhookUpdate,
+};
+use crate::file_storage::migrations::{read_versioned, write_versioned, WEBHOOKS};
+use crate::file_storage::{ensure_dir, get_global_ralph_ui_dir};
+use crate::utils::{lock_mutex_recover, validate_http_url};
+use chrono::Utc;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const DEAD_LETTER_FILE: &str = "dead-letters.jsonl";
+
+/// Default number of dead letters returned by a listing
+const DEFAULT_DEAD_LETTER_LIMIT: usize = 100;
+
+/// Serializes read-modify-write of the webhooks file
+static WEBHOOKS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Serializes dead-letter appends
+static DEAD_LETTER_LOCK: Mutex<()> = Mutex::new(());
+
+/// Storage format for the webhooks file
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct WebhooksFile {
+    #[serde(default)]
+    webhooks: Vec<WebhookConfig>,
+}
+
+/// Get the path to the webhooks file
+pub fn get_webhooks_path() -> PathBuf {
+    get_webhooks_path_in(&get_global_ralph_ui_dir())
+}
+
+fn get_webhooks_path_in(base_dir: &Path) -> PathBuf {
+    base_dir.join("webhooks.json")
+}
+
+fn get_dead_letter_path_in(base_dir: &Path) -> PathBuf {
+    base_dir.join("webhooks").join(DEAD_LETTER_FILE)
+}
+
+fn load_webhooks_in(base_dir: &Path) -> Result<WebhooksFile, String> {
+    let path = get_webhooks_path_in(base_dir);
+    if !path.exists() {
+        return Ok(WebhooksFile::default());
+    }
+    read_versioned(&path, &WEBHOOKS)
+}
+
+fn save_webhooks_in(base_dir: &Path, data: &WebhooksFile) -> Result<(), String> {
+    ensure_dir(base_dir)?;
+    write_versioned(&get_webhooks_path_in(base_dir), data, &WEBHOOKS)
+}
+
+fn generate_secret() -> String {
+    format!("whsec_{}", crate::server::generate_auth_token())
+}
+
+/// Get all webhook configurations (including secrets)
+pub fn get_webhooks() -> Result<Vec<WebhookConfig>, String> {
+    get_webhooks_in(&get_global_ralph_ui_dir())
+}
+
+/// Get all webhook configurations in a specific directory (for testing)
+pub fn get_webhooks_in(base_dir: &Path) -> Result<Vec<WebhookConfig>, String> {
+    Ok(load_webhooks_in(base_dir)?.webhooks)
+}
+
+/// Get a webhook configuration by ID
+pub fn get_webhook(webhook_id: &str) -> Result<WebhookConfig, String> {
+    get_webhooks()?
+        .into_iter()
+        .find(|w| w.id == webhook_id)
+        .ok_or_else(|| format!("Webhook not found: {}", webhook_id))
+}
+
+/// Create a webhook
+pub fn create_webhook(new_webhook: NewWebhook) -> Result<CreatedWebhook, String> {
+    create_webhook_in(&get_global_ralph_ui_dir(), new_webhook)
+}
+
+/// Create a webhook in a specific directory (for testing)
+pub fn create_webhook_in(
+    base_dir: &Path,
+    new_webhook: NewWebhook,
+) -> Result<CreatedWebhook, String> {
+    let name = new_webhook.name.trim();
+    if name.is_empty() {
+        return Err("Webhook name is required".to_string());
+    }
+    validate_http_url(&new_webhook.url, "webhook")?;
+
+    let now = Utc::now();
+    let webhook = WebhookConfig {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        url: new_webhook.url,
+        secret: new_webhook
+            .secret
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(generate_secret),
+        enabled: true,
+        format: new_webhook.format,
+        event_types: new_webhook.event_types,
+        projects: new_webhook.projects.filter(|p| !p.is_empty()),
+        created_at: now,
+        updated_at: now,
+    };
+    let created = CreatedWebhook {
+        secret: webhook.secret.clone(),
+        info: WebhookInfo::from(&webhook),
+    };
+
+    let _guard = lock_mutex_recover(&WEBHOOKS_LOCK);
+    let mut data = load_webhooks_in(base_dir)?;
+    data.webhooks.push(webhook);
+    save_webhooks_in(base_dir, &data)?;
+
+    Ok(created)
+}
+
+/// Update a webhook
+pub fn update_webhook(webhook_id: &str, update: WebhookUpdate) -> Result<WebhookInfo, String> {
+    update_webhook_in(&get_global_ralph_ui_dir(), webhook_id, update)
+}
+
+/// Update a webhook in a specific directory (for testing)
+pub fn update_webhook_in(
+    base_dir: &Path,
+    webhook_id: &str,
+    update: WebhookUpdate,
+) -> Result<WebhookInfo, String> {
+    if let Some(url) = &update.url {
+        validate_http_url(url, "webhook")?;
+    }
+
+    let _guard = lock_mutex_recover(&WEBHOOKS_LOCK);
+    let mut data = load_webhooks_in(base_dir)?;
+    let webhook = data
+        .webhooks
+        .iter_mut()
+        .find(|w| w.id == webhook_id)
+        .ok_or_else(|| format!("Webhook not found: {}", webhook_id))?;
+
+    if let Some(name) = update.name.filter(|n| !n.trim().is_empty()) {
+        webhook.name = name.trim().to_string();
+    }
+    if let Some(url) = update.url {
+        webhook.url = url;
+    }
+    if let Some(secret) = update.secret.filter(|s| !s.is_empty()) {
+        webhook.secret = secret;
+    }
+    if let Some(enabled) = update.enabled {
+        webhook.enabled = enabled;
+    }
+    if let Some(format) = update.format {
+        webhook.format = format;
+    }
+    if let Some(event_types) = update.event_types {
+        webhook.event_types = event_types;
+    }
+    if let Some(projects) = update.projects {
+        webhook.projects = Some(projects).filter(|p| !p.is_empty());
+    }
+    webhook.updated_at = Utc::now();
+    let info = WebhookInfo::from(&*webhook);
+
+    save_webhooks_in(base_dir, &data)?;
+    Ok(info)
+}
+
+/// Delete a webhook
+pub fn delete_webhook(webhook_id: &str) -> Result<bool, String> {
+    delete_webhook_in(&get_global_ralph_ui_dir(), webhook_id)
+}
+
+/// Delete a webhook in a specific directory (for testing)
+pub fn delete_webhook_in(base_dir: &Path, webhook_id: &str) -> Result<bool, String> {
+    let _guard = lock_mutex_recover(&WEBHOOKS_LOCK);
+    let mut data = load_webhooks_in(base_dir)?;
+    let before = data.webhooks.len();
+    data.webhooks.retain(|w| w.id != webhook_id);
+
+    let removed = data.webhooks.len() != before;
+    if removed {
+        save_webhooks_in(base_dir, &data)?;
+    }
+    Ok(removed)
+}
+
+/// Record a delivery that exhausted its retries
+pub fn append_dead_letter(dead_letter: &DeadLetter) -> Result<(), String> {
+    append_dead_letter_in(&get_global_ralph_ui_dir(), dead_letter)
+}
+
+/// Record a dead letter in a specific directory (for testing)
+pub fn append_dead_letter_in(base_dir: &Path, dead_letter: &DeadLetter) -> Result<(), String> {
+    let line = serde_json::to_string(dead_letter)
+        .map_err(|e| format!("Failed to serialize dead letter: {}", e))?;
+    let path = get_dead_letter_path_in(base_dir);
+
+    let _guard = lock_mutex_recover(&DEAD_LETTER_LOCK);
+    if let Some(parent) = path.parent() {
+        ensure_dir(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open dead-letter log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write dead letter: {}", e))
+}
+
+/// List dead letters, newest first, optionally for one webhook
+pub fn list_dead_letters(
+    webhook_id: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<DeadLetter>, String> {
+    list_dead_letters_in(&get_global_ralph_ui_dir(), webhook_id, limit)
+}
+
+/// List dead letters in a specific directory (for testing)
+pub fn list_dead_letters_in(
+    base_dir: &Path,
+    webhook_id: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<DeadLetter>, String> {
+    let Ok(file) = File::open(get_dead_letter_path_in(base_dir)) else {
+        return Ok(Vec::new());
+    };
+
+    let mut dead_letters: Vec<DeadLetter> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .filter(|d: &DeadLetter| webhook_id.map_or(true, |id| d.webhook_id == id))
+        .collect();
+    dead_letters.reverse();
+    dead_letters.truncate(limit.unwrap_or(DEFAULT_DEAD_LETTER_LIMIT));
+    Ok(dead_letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhooks::types::WebhookFormat;
+    use tempfile::TempDir;
+
+    fn new_webhook(name: &str, url: &str) -> NewWebhook {
+        NewWebhook {
+            name: name.to_string(),
+            url: url.to_string(),
+            secret: None,
+            format: WebhookFormat::Json,
+            event_types: Vec::new(),
+            projects: None,
+        }
+    }
+
+    #[test]
+    fn test_create_update_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        let created =
+            create_webhook_in(dir, new_webhook("ci", "https://example.com/hook")).unwrap();
+        assert!(created.secret.starts_with("whsec_"));
+        assert!(create_webhook_in(dir, new_webhook("bad", "ftp://example.com")).is_err());
+
+        let info = update_webhook_in(
+            dir,
+            &created.info.id,
+            WebhookUpdate {
+                enabled: Some(false),
+                format: Some(WebhookFormat::Slack),
+                projects: Some(vec!["/work/app".to_string()]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(!info.enabled);
+        assert_eq!(info.format, WebhookFormat::Slack);
+
+        let stored = get_webhooks_in(dir).unwrap();
+        assert_eq!(stored[0].secret, created.secret);
+        assert_eq!(stored[0].projects, Some(vec!["/work/app".to_string()]));
+
+        assert!(delete_webhook_in(dir, &created.info.id).unwrap());
+        assert!(!delete_webhook_in(dir, &created.info.id).unwrap());
+        assert!(get_webhooks_in(dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dead_letters_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        for (seq, webhook_id) in [(1, "a"), (2, "b"), (3, "a")] {
+            let dead_letter = DeadLetter {
+                delivery_id: format!("d{}", seq),
+                webhook_id: webhook_id.to_string(),
+                webhook_name: webhook_id.to_string(),
+                event: "ralph:loop_error".to_string(),
+                seq,
+                failed_at: Utc::now(),
+                attempts: 5,
+                last_status: Some(500),
+                last_error: None,
+                body: serde_json::json!({}),
+            };
+            append_dead_letter_in(dir, &dead_letter).unwrap();
+        }
+
+        let all = list_dead_letters_in(dir, None, None).unwrap();
+        assert_eq!(all.iter().map(|d| d.seq).collect::<Vec<_>>(), vec![3, 2, 1]);
+        let for_a = list_dead_letters_in(dir, Some("a"), Some(1)).unwrap();
+        assert_eq!(for_a.len(), 1);
+        assert_eq!(for_a[0].seq, 3);
+    }
+}
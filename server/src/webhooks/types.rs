@@ -0,0 +1,181 @@
+//! Types for outbound webhooks
+
+use crate::events::{
+    EVENT_AGENT_FAILED, EVENT_MERGE_CONFLICT_DETECTED, EVENT_RALPH_LOOP_COMPLETED,
+    EVENT_RALPH_LOOP_ERROR, EVENT_RATE_LIMIT_DETECTED,
+};
+use crate::server::permissions;
+use crate::server::ServerEvent;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Events delivered to webhooks that don't configure `eventTypes`
+pub const DEFAULT_WEBHOOK_EVENTS: &[&str] = &[
+    EVENT_RALPH_LOOP_COMPLETED,
+    EVENT_RALPH_LOOP_ERROR,
+    EVENT_RATE_LIMIT_DETECTED,
+    EVENT_MERGE_CONFLICT_DETECTED,
+    EVENT_AGENT_FAILED,
+];
+
+/// Body format of a delivery
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// Signed JSON envelope with the full event payload
+    #[default]
+    Json,
+    /// Slack incoming webhook (`{"text": ...}`)
+    Slack,
+    /// Discord webhook (`{"content": ...}`)
+    Discord,
+}
+
+/// A configured webhook endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    /// HMAC-SHA256 signing secret
+    pub secret: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Event type prefixes to deliver (empty = [`DEFAULT_WEBHOOK_EVENTS`])
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// Only deliver events of these projects (None = all projects)
+    #[serde(default)]
+    pub projects: Option<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl WebhookConfig {
+    /// Whether this webhook should receive an event
+    pub fn matches(&self, event: &ServerEvent) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let type_matches = if self.event_types.is_empty() {
+            DEFAULT_WEBHOOK_EVENTS.contains(&event.event.as_str())
+        } else {
+            self.event_types
+                .iter()
+                .any(|prefix| prefix == "*" || event.event.starts_with(prefix.as_str()))
+        };
+
+        type_matches
+            && self.projects.as_ref().map_or(true, |scopes| {
+                event
+                    .project_path
+                    .as_ref()
+                    .is_some_and(|p| permissions::path_in_scope(p, scopes))
+            })
+    }
+}
+
+/// Webhook details safe to show (no secret)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookInfo {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub enabled: bool,
+    pub format: WebhookFormat,
+    pub event_types: Vec<String>,
+    pub projects: Option<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&WebhookConfig> for WebhookInfo {
+    fn from(webhook: &WebhookConfig) -> Self {
+        Self {
+            id: webhook.id.clone(),
+            name: webhook.name.clone(),
+            url: webhook.url.clone(),
+            enabled: webhook.enabled,
+            format: webhook.format,
+            event_types: webhook.event_types.clone(),
+            projects: webhook.projects.clone(),
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+/// A newly created webhook; `secret` is only returned here
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedWebhook {
+    pub secret: String,
+    pub info: WebhookInfo,
+}
+
+/// Options for creating a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewWebhook {
+    pub name: String,
+    pub url: String,
+    /// Signing secret (generated when omitted)
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    #[serde(default)]
+    pub projects: Option<Vec<String>>,
+}
+
+/// Fields to change on an existing webhook
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookUpdate {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub enabled: Option<bool>,
+    pub format: Option<WebhookFormat>,
+    pub event_types: Option<Vec<String>>,
+    /// `Some([])` removes the project filter
+    pub projects: Option<Vec<String>>,
+}
+
+/// Outcome of a single delivery attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryResult {
+    pub success: bool,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// A delivery that failed after all retries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetter {
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub webhook_name: String,
+    pub event: String,
+    pub seq: u64,
+    pub failed_at: DateTime<Utc>,
+    pub attempts: u32,
+    pub last_status: Option<u16>,
+    pub last_error: Option<String>,
+    /// Request body that could not be delivered
+    pub body: serde_json::Value,
+}
@@ -9,6 +9,7 @@ import type {
   ConfigPaths,
   StaleLockInfo,
   RecoveryResult,
+  MigrationReport,
   SubagentEvent,
   SubagentTree,
   SubagentTreeSummary,
@@ -204,6 +205,21 @@ export const recoveryApi = {
   refreshLock: async (projectPath: string, sessionId: string): Promise<void> => {
     return await invoke('refresh_session_lock', { projectPath, sessionId })
   },
+
+  /**
+   * Report stored documents that need a schema migration (dry run).
+   * Without a project, covers global documents and all registered projects.
+   */
+  getMigrationReport: async (projectPath?: string): Promise<MigrationReport[]> => {
+    return await invoke('get_migration_report', { projectPath })
+  },
+
+  /**
+   * Migrate outdated stored documents, backing up the originals
+   */
+  runMigrations: async (projectPath?: string): Promise<MigrationReport[]> => {
+    return await invoke('run_migrations', { projectPath })
+  },
 }
 
 // ============================================================================
@@ -2,7 +2,14 @@
 // Re-exports all domain-specific API modules for convenient imports
 
 // Types from types.ts
-export type { AgentAvailabilityResult, WatchFileResponse, ActivityEvent, GlobalStats } from './types'
+export type {
+  AgentAvailabilityResult,
+  WatchFileResponse,
+  ActivityEvent,
+  ActivityPage,
+  ActivityQuery,
+  GlobalStats,
+} from './types'
 
 // Session API
 export { sessionApi } from './session-api'
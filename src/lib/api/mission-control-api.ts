@@ -1,7 +1,7 @@
 // Mission Control API wrappers
 
 import { invoke } from '../invoke'
-import type { ActivityEvent, GlobalStats } from './types'
+import type { ActivityEvent, ActivityPage, ActivityQuery, GlobalStats } from './types'
 
 export const missionControlApi = {
   /** Get activity feed for Mission Control dashboard */
@@ -9,6 +9,11 @@ export const missionControlApi = {
     return await invoke('get_activity_feed', { limit, offset })
   },
 
+  /** Get one page of the activity feed, continuing from `query.cursor` */
+  getActivityPage: async (query?: ActivityQuery): Promise<ActivityPage> => {
+    return await invoke('get_activity_page', { query })
+  },
+
   /** Get global statistics for Mission Control dashboard */
   getGlobalStats: async (): Promise<GlobalStats> => {
     return await invoke('get_global_stats')
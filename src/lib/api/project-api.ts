@@ -1,6 +1,14 @@
 // Project API wrappers
 
-import type { Project, ProjectFolder, DirectoryEntry } from '@/types'
+import type {
+  Project,
+  ProjectFolder,
+  DirectoryEntry,
+  ExportOptions,
+  ExportedProjectState,
+  ImportMode,
+  ImportReport,
+} from '@/types'
 import { invoke } from '../invoke'
 
 export const projectApi = {
@@ -78,4 +86,22 @@ export const projectApi = {
   createFilesystemDirectory: async (path: string): Promise<DirectoryEntry> => {
     return await invoke('create_filesystem_directory', { path })
   },
+
+  /** Export a project's .ralph-ui state as a base64-encoded .tar.gz */
+  exportState: async (
+    projectPath: string,
+    options?: ExportOptions
+  ): Promise<ExportedProjectState> => {
+    return await invoke('export_project_state', { projectPath, options })
+  },
+
+  /** Import an exported archive (base64) into a project and register it */
+  importState: async (
+    projectPath: string,
+    data: string,
+    mode: ImportMode = 'merge',
+    dryRun = false
+  ): Promise<ImportReport> => {
+    return await invoke('import_project_state', { projectPath, data, mode, dryRun })
+  },
 }
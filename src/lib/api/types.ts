@@ -25,12 +25,34 @@ export interface ActivityEvent {
     | 'agent_spawned'
     | 'session_started'
     | 'session_completed'
+    | 'loop_started'
+    | 'loop_completed'
+    | 'loop_failed'
+    | 'loop_cancelled'
+    | 'story_completed'
+    | 'chat_started'
+    | 'branch_merged'
   projectPath: string
   projectName: string
   sessionName: string
   description: string
 }
 
+export interface ActivityQuery {
+  cursor?: string
+  limit?: number
+  projectPath?: string
+  eventTypes?: ActivityEvent['eventType'][]
+  sessionName?: string
+  since?: string
+  until?: string
+}
+
+export interface ActivityPage {
+  events: ActivityEvent[]
+  nextCursor: string | null
+}
+
 export interface GlobalStats {
   activeAgentsCount: number
   tasksInProgress: number
@@ -24,6 +24,8 @@ import {
  * Event payload from the server
  */
 interface ServerEvent {
+  /** Sequence number (0 for control messages like pong) */
+  seq?: number
   event: string
   payload: unknown
 }
@@ -56,6 +58,8 @@ class EventsClient {
   private keepaliveInterval: ReturnType<typeof setInterval> | null = null
   private lastPongTime: number = 0
   private visibilityHandler: (() => void) | null = null
+  // Last event sequence received, used to replay missed events after reconnecting
+  private lastSeq = 0
 
   /**
    * Connect to the WebSocket server
@@ -95,6 +99,13 @@ class EventsClient {
           console.log('[EventsClient] WebSocket connected')
           this.isConnecting = false
 
+          // Resume the stream: the server replays events we missed while disconnected
+          if (this.lastSeq > 0) {
+            this.ws?.send(
+              JSON.stringify({ event: 'subscribe', payload: { since: this.lastSeq } })
+            )
+          }
+
           useConnectionStore.getState().markConnected()
           this.startKeepalive()
           this.startVisibilityListener()
@@ -111,6 +122,10 @@ class EventsClient {
               return
             }
 
+            if (data.seq && data.seq > this.lastSeq) {
+              this.lastSeq = data.seq
+            }
+
             this.dispatchEvent(data.event, data.payload)
           } catch (e) {
             console.warn('[EventsClient] Failed to parse WebSocket message:', e)
@@ -78,6 +78,94 @@ export interface RecoveryResult {
   message: string
 }
 
+export interface DocumentMigration {
+  /** Path relative to the .ralph-ui directory */
+  path: string
+  kind: string
+  fromVersion: number
+  toVersion: number
+  steps: string[]
+}
+
+export interface MigrationError {
+  path: string
+  kind: string
+  error: string
+}
+
+export interface MigrationReport {
+  baseDir: string
+  scope: 'project' | 'global'
+  dryRun: boolean
+  checked: number
+  migrated: DocumentMigration[]
+  errors: MigrationError[]
+  /** Where originals of migrated documents were copied */
+  backupDir: string | null
+}
+
+export type ArchiveSection =
+  | 'prds'
+  | 'sessions'
+  | 'executions'
+  | 'learnings'
+  | 'chats'
+  | 'context'
+  | 'workflows'
+  | 'transcripts'
+  | 'attachments'
+
+export interface ExportOptions {
+  /** Sections to export (default: all but transcripts and attachments) */
+  sections?: ArchiveSection[]
+  includeTranscripts?: boolean
+  includeAttachments?: boolean
+}
+
+export interface ArchiveFile {
+  /** Path relative to `.ralph-ui/` */
+  path: string
+  size: number
+  sha256: string
+}
+
+export interface ArchiveManifest {
+  formatVersion: number
+  ralphUiVersion: string
+  exportedAt: string
+  /** Path of the exported project (rewritten on import) */
+  projectPath: string
+  projectName: string
+  sections: ArchiveSection[]
+  files: ArchiveFile[]
+}
+
+export interface ExportedProjectState {
+  fileName: string
+  manifest: ArchiveManifest
+  /** The `.tar.gz` archive, base64-encoded */
+  data: string
+}
+
+export type ImportMode = 'merge' | 'replace'
+
+export interface ImportReport {
+  projectPath: string
+  mode: ImportMode
+  dryRun: boolean
+  manifest: ArchiveManifest
+  added: string[]
+  unchanged: number
+  /** Files that differ: kept when merging, replaced when replacing */
+  conflicts: string[]
+  /** Files of the archived sections missing from the archive (removed when replacing) */
+  removed: string[]
+  /** JSON files whose project paths were rewritten */
+  rewritten: string[]
+  /** Where originals of replaced and removed files were copied */
+  backupDir: string | null
+}
+
 // ============================================================================
 // Subagent Trace Types
 // ============================================================================
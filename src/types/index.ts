@@ -13,6 +13,16 @@ export type {
   RateLimitEvent,
   StaleLockInfo,
   RecoveryResult,
+  DocumentMigration,
+  MigrationError,
+  MigrationReport,
+  ArchiveSection,
+  ExportOptions,
+  ArchiveFile,
+  ArchiveManifest,
+  ExportedProjectState,
+  ImportMode,
+  ImportReport,
   SubagentEventType,
   SubagentEvent,
   SubagentTree,
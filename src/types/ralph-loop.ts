@@ -352,6 +352,8 @@ export interface StartRalphLoopRequest {
   executionMode?: RalphExecutionMode
   /** Maximum parallel agents when using parallel execution mode (default: 3) */
   maxParallel?: number
+  /** Start even if preflight diagnostics report hard failures (default: false) */
+  force?: boolean
 }
 
 /** Request to convert a database PRD to Ralph format */